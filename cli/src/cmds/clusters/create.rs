@@ -163,6 +163,12 @@ pub fn generate_query_config() -> QueryConfig {
     if !portpicker::is_free(config.query.clickhouse_handler_port) {
         config.query.clickhouse_handler_port = portpicker::pick_unused_port().unwrap();
     }
+    if !portpicker::is_free(config.query.clickhouse_http_handler_port) {
+        config.query.clickhouse_http_handler_port = portpicker::pick_unused_port().unwrap();
+    }
+    if !portpicker::is_free(config.query.postgres_handler_port) {
+        config.query.postgres_handler_port = portpicker::pick_unused_port().unwrap();
+    }
     if !portpicker::is_free(config.query.http_handler_port) {
         config.query.http_handler_port = portpicker::pick_unused_port().unwrap();
     }
@@ -207,7 +213,7 @@ pub async fn provision_local_query_service(
             );
             writer.write_ok(
                 format!(
-                    "To process HTTP REST queries, run: curl --location --request POST '{}:{}/v1/statement/' --header 'Content-Type: text/plain' --data-raw 'your SQL'",
+                    "To process HTTP REST queries, run: curl --location --request POST '{}:{}/v1/statement/' --user root: --header 'Content-Type: text/plain' --data-raw 'your SQL'",
                     query_config.config.query.http_handler_host,
                     query_config.config.query.http_handler_port
                 )
@@ -239,6 +239,8 @@ pub async fn execute_query_json(
 )> {
     let ans = cli
         .post(url)
+        // the local cluster is of the root user without password
+        .basic_auth("root", None::<&str>)
         .body(query.clone())
         .send()
         .await
@@ -460,6 +460,26 @@ impl LocalRuntime for LocalQueryConfig {
                 databend_query::configs::config_query::QUERY_CLICKHOUSE_HANDLER_PORT,
                 conf.query.clickhouse_handler_port.to_string(),
             )
+            .env(
+                databend_query::configs::config_query::QUERY_CLICKHOUSE_HTTP_HANDLER_HOST,
+                conf.query.clickhouse_http_handler_host,
+            )
+            .env(
+                databend_query::configs::config_query::QUERY_CLICKHOUSE_HTTP_HANDLER_PORT,
+                conf.query.clickhouse_http_handler_port.to_string(),
+            )
+            .env(
+                databend_query::configs::config_query::QUERY_POSTGRES_HANDLER_HOST,
+                conf.query.postgres_handler_host,
+            )
+            .env(
+                databend_query::configs::config_query::QUERY_POSTGRES_HANDLER_PORT,
+                conf.query.postgres_handler_port.to_string(),
+            )
+            .env(
+                databend_query::configs::config_query::QUERY_POSTGRES_HANDLER_AUTH_METHOD,
+                conf.query.postgres_handler_auth_method,
+            )
             .env(
                 databend_query::configs::config_query::QUERY_MYSQL_HANDLER_HOST,
                 conf.query.mysql_handler_host,
@@ -16,6 +16,8 @@ use std::io::Read;
 use std::io::Seek;
 use std::sync::Arc;
 
+use chrono::DateTime;
+use chrono::Utc;
 use common_exception::Result;
 use futures::stream::Stream;
 use futures::AsyncRead;
@@ -31,6 +33,16 @@ impl<T> AsyncSeekableReader for T where T: AsyncRead + AsyncSeek {}
 
 pub type InputStream = Box<dyn AsyncSeekableReader + Send + Unpin>;
 
+/// An object listed by `DataAccessor::list_objects`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectMeta {
+    pub location: String,
+    /// Size of the object in bytes
+    pub size: u64,
+    /// None if it is not told by the storage
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
 pub trait SeekableReader: Read + Seek {}
 
 impl<T> SeekableReader for T where T: Read + Seek {}
@@ -55,6 +67,19 @@ pub trait DataAccessor: Send + Sync {
         stream_len: usize,
     ) -> Result<()>;
 
+    /// Lists all the objects whose location starts with `prefix`, along with their sizes and
+    /// the time they were last modified.
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<ObjectMeta>>;
+
+    /// Lists the locations of all the objects, whose location starts with `prefix`.
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let objects = self.list_objects(prefix).await?;
+        Ok(objects.into_iter().map(|object| object.location).collect())
+    }
+
+    /// Removes the object at `path`, removing an object that does not exist is not an error.
+    async fn delete(&self, path: &str) -> Result<()>;
+
     async fn read(&self, location: &str) -> Result<Vec<u8>> {
         let mut input_stream = self.get_input_stream(location, None)?;
         let mut buffer = vec![];
@@ -15,6 +15,8 @@
 
 use std::str::FromStr;
 
+use chrono::DateTime;
+use chrono::Utc;
 use common_base::tokio::io::AsyncReadExt;
 use common_exception::ErrorCode;
 use common_exception::Result;
@@ -26,7 +28,10 @@ use rusoto_core::ByteStream;
 use rusoto_core::Client;
 use rusoto_core::HttpClient;
 use rusoto_core::Region;
+use rusoto_core::RusotoError;
+use rusoto_s3::DeleteObjectRequest;
 use rusoto_s3::GetObjectRequest;
+use rusoto_s3::ListObjectsV2Request;
 use rusoto_s3::PutObjectRequest;
 use rusoto_s3::S3Client;
 use rusoto_s3::S3 as RusotoS3;
@@ -34,6 +39,7 @@ use rusoto_s3::S3 as RusotoS3;
 use crate::Bytes;
 use crate::DataAccessor;
 use crate::InputStream;
+use crate::ObjectMeta;
 use crate::S3InputStream;
 
 pub struct S3 {
@@ -182,4 +188,59 @@ impl DataAccessor for S3 {
         self.put_byte_stream(path, ByteStream::new_with_size(s, stream_len))
             .await
     }
+
+    async fn list_objects(&self, prefix: &str) -> common_exception::Result<Vec<ObjectMeta>> {
+        let mut locations = vec![];
+        let mut continuation_token = None;
+        loop {
+            let req = ListObjectsV2Request {
+                bucket: self.bucket.to_string(),
+                prefix: Some(prefix.to_string()),
+                continuation_token,
+                ..Default::default()
+            };
+            let output = self
+                .client
+                .list_objects_v2(req)
+                .await
+                .map_err(|e| ErrorCode::DALTransportError(e.to_string()))?;
+            let objects = output.contents.unwrap_or_default();
+            locations.extend(objects.into_iter().filter_map(|obj| {
+                // in the format of RFC 3339, e.g. "2021-11-08T09:30:00.000Z"
+                let last_modified = obj
+                    .last_modified
+                    .and_then(|time| DateTime::parse_from_rfc3339(&time).ok())
+                    .map(|time| time.with_timezone(&Utc));
+                let size = obj.size.unwrap_or_default() as u64;
+                obj.key.map(|location| ObjectMeta {
+                    location,
+                    size,
+                    last_modified,
+                })
+            }));
+
+            // at most 1000 keys are returned per request
+            match output.next_continuation_token {
+                Some(token) if output.is_truncated == Some(true) => {
+                    continuation_token = Some(token)
+                }
+                _ => break,
+            }
+        }
+        Ok(locations)
+    }
+
+    async fn delete(&self, path: &str) -> common_exception::Result<()> {
+        let req = DeleteObjectRequest {
+            key: path.to_string(),
+            bucket: self.bucket.to_string(),
+            ..Default::default()
+        };
+        match self.client.delete_object(req).await {
+            // S3 itself answers 204 anyway, some compatible storages answer 404
+            Err(RusotoError::Unknown(resp)) if resp.status.as_u16() == 404 => Ok(()),
+            Err(e) => Err(ErrorCode::DALTransportError(e.to_string())),
+            Ok(_) => Ok(()),
+        }
+    }
 }
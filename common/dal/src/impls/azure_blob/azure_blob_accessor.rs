@@ -15,6 +15,7 @@
 
 use std::sync::Arc;
 
+use azure_core_mirror::errors::AzureError;
 use azure_core_mirror::HttpClient;
 use azure_storage_mirror::clients::StorageAccountClient;
 use azure_storage_mirror::core::prelude::*;
@@ -27,6 +28,7 @@ use crate::AzureBlobInputStream;
 use crate::Bytes;
 use crate::DataAccessor;
 use crate::InputStream;
+use crate::ObjectMeta;
 
 pub struct AzureBlobAccessor {
     client: Arc<StorageClient>,
@@ -161,4 +163,62 @@ impl DataAccessor for AzureBlobAccessor {
         }
         self.put_blob(path, data).await
     }
+
+    async fn list_objects(&self, prefix: &str) -> common_exception::Result<Vec<ObjectMeta>> {
+        let container = self.client.as_container_client(&self.container);
+        let mut locations = vec![];
+        let mut next_marker = None;
+        loop {
+            let mut request = container.list_blobs().prefix(prefix);
+            if let Some(marker) = next_marker {
+                request = request.next_marker(marker);
+            }
+            let response = request.execute().await.map_err(|e| {
+                ErrorCode::DALTransportError(format!(
+                    "Failed on azure blob list operation, {}",
+                    e.to_string()
+                ))
+            })?;
+            locations.extend(response.blobs.blobs.into_iter().map(|blob| ObjectMeta {
+                location: blob.name,
+                size: blob.properties.content_length,
+                last_modified: Some(blob.properties.last_modified),
+            }));
+
+            match response.next_marker {
+                Some(marker) => next_marker = Some(marker),
+                None => break,
+            }
+        }
+        Ok(locations)
+    }
+
+    async fn delete(&self, path: &str) -> common_exception::Result<()> {
+        let blob = self
+            .client
+            .as_container_client(&self.container)
+            .as_blob_client(path);
+
+        match blob.delete().execute().await {
+            // removing an object that does not exist is not an error
+            Err(e) if is_not_found(e.as_ref()) => Ok(()),
+            Err(e) => Err(ErrorCode::DALTransportError(format!(
+                "Failed on azure blob delete operation, {}",
+                e.to_string()
+            ))),
+            Ok(_) => Ok(()),
+        }
+    }
+}
+
+/// Whether the request failed with 404, i.e. the blob does not exist.
+fn is_not_found(e: &(dyn std::error::Error + 'static)) -> bool {
+    let mut cause = Some(e);
+    while let Some(e) = cause {
+        if let Some(AzureError::UnexpectedHTTPResult(result)) = e.downcast_ref::<AzureError>() {
+            return result.status_code().as_u16() == 404;
+        }
+        cause = e.source();
+    }
+    false
 }
@@ -20,6 +20,8 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use async_compat::CompatExt;
+use chrono::DateTime;
+use chrono::Utc;
 use common_base::tokio;
 use common_exception::ErrorCode;
 use common_exception::Result;
@@ -31,6 +33,7 @@ use tokio::io::AsyncWriteExt;
 use crate::Bytes;
 use crate::DataAccessor;
 use crate::InputStream;
+use crate::ObjectMeta;
 
 pub struct Local {
     root: PathBuf,
@@ -118,6 +121,69 @@ impl DataAccessor for Local {
         new_file.flush().await?;
         Ok(())
     }
+
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<ObjectMeta>> {
+        let path = self.prefix_with_root(prefix)?;
+        // the prefix may end with a partial file name, e.g. "dir/file_"
+        let dir = if prefix.is_empty() || prefix.ends_with('/') {
+            path
+        } else {
+            match path.parent() {
+                Some(parent) => parent.to_path_buf(),
+                None => return Ok(vec![]),
+            }
+        };
+
+        let mut locations = vec![];
+        // an empty root is the working directory
+        let mut dirs = match dir.as_os_str().is_empty() {
+            true => vec![PathBuf::from(".")],
+            false => vec![dir],
+        };
+        while let Some(dir) = dirs.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let entry_path = entry.path();
+                if entry.file_type().await?.is_dir() {
+                    dirs.push(entry_path);
+                    continue;
+                }
+                let location = entry_path
+                    .strip_prefix(&self.root)
+                    .map_err(|e| ErrorCode::UnknownException(e.to_string()))?
+                    .components()
+                    .filter(|c| *c != Component::CurDir)
+                    // the root of an absolute path is kept as a leading "/"
+                    .map(|c| match c {
+                        Component::RootDir => "".into(),
+                        c => c.as_os_str().to_string_lossy(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/");
+                if location.starts_with(prefix) {
+                    let metadata = entry.metadata().await?;
+                    locations.push(ObjectMeta {
+                        location,
+                        size: metadata.len(),
+                        last_modified: Some(DateTime::<Utc>::from(metadata.modified()?)),
+                    });
+                }
+            }
+        }
+        Ok(locations)
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        let path = self.prefix_with_root(path)?;
+        match tokio::fs::remove_file(path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
 }
 
 // from cargo::util::path
@@ -18,6 +18,7 @@ pub use data_accessor::Bytes;
 pub use data_accessor::DataAccessor;
 pub use data_accessor::DataAccessorBuilder;
 pub use data_accessor::InputStream;
+pub use data_accessor::ObjectMeta;
 pub use data_accessor::SeekableReader;
 pub use impls::aws_s3::S3InputStream;
 pub use impls::aws_s3::S3;
@@ -115,3 +115,19 @@ async fn test_s3_input_stream_seek_api() -> common_exception::Result<()> {
     assert!(r.is_err());
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[ignore]
+async fn test_s3_list_and_delete_api() -> common_exception::Result<()> {
+    let test_key = "test_s3_list_and_delete/obj".to_string();
+    let fixture = TestFixture::new(1024, test_key.clone());
+    fixture.gen_test_obj().await?;
+
+    let s3 = fixture.data_accessor()?;
+    let locations = s3.list("test_s3_list_and_delete/").await?;
+    assert_eq!(locations, vec![test_key.clone()]);
+
+    s3.delete(&test_key).await?;
+    assert!(s3.list("test_s3_list_and_delete/").await?.is_empty());
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use common_base::tokio;
+use common_dal::DataAccessor;
+use common_dal::Local;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_local_list_and_delete() -> common_exception::Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let local = Local::with_path(tmp_dir.path().to_owned());
+
+    for path in ["1/_b/a", "1/_sg/b", "12/_b/c", "d"] {
+        local.put(path, vec![1, 2, 3]).await?;
+    }
+
+    // list
+    let mut locations = local.list("1/").await?;
+    locations.sort();
+    assert_eq!(locations, vec!["1/_b/a".to_string(), "1/_sg/b".to_string()]);
+
+    let mut locations = local.list("1").await?;
+    locations.sort();
+    assert_eq!(locations, vec![
+        "1/_b/a".to_string(),
+        "1/_sg/b".to_string(),
+        "12/_b/c".to_string()
+    ]);
+
+    assert!(local.list("not_exist/").await?.is_empty());
+
+    // the objects are just written
+    let objects = local.list_objects("1/").await?;
+    let since = chrono::Utc::now() - chrono::Duration::minutes(1);
+    assert!(objects
+        .iter()
+        .all(|object| matches!(object.last_modified, Some(time) if time > since)));
+    assert!(objects.iter().all(|object| object.size == 3));
+
+    // delete
+    local.delete("1/_b/a").await?;
+    assert_eq!(local.list("1/").await?, vec!["1/_sg/b".to_string()]);
+    assert!(local.get("1/_b/a").await.is_err());
+
+    // deleting an object that does not exist is fine
+    local.delete("1/_b/a").await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_local_list_absolute_path() -> common_exception::Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let dir = tmp_dir.path().to_str().unwrap().to_string();
+    // an empty root takes the paths as they are
+    let local = Local::new("");
+
+    local.put(&format!("{}/a/b", dir), vec![1, 2, 3]).await?;
+
+    assert_eq!(local.list(&format!("{}/", dir)).await?, vec![format!(
+        "{}/a/b",
+        dir
+    )]);
+    assert!(local.list("").await.is_ok());
+
+    Ok(())
+}
@@ -21,11 +21,12 @@ use common_arrow::arrow::datatypes::Schema as ArrowSchema;
 use common_arrow::arrow::datatypes::SchemaRef as ArrowSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_macros::MallocSizeOf;
 
 use crate::DataField;
 
 /// memory layout.
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, MallocSizeOf)]
 pub struct DataSchema {
     pub(crate) fields: Vec<DataField>,
     pub(crate) metadata: HashMap<String, String>,
@@ -30,6 +30,10 @@ pub struct UserInfo {
     pub auth_type: AuthType,
     pub privileges: UserPrivilege,
     pub quota: UserQuota,
+    /// The SCRAM-SHA-256 verifier of the password, to authenticate the users with hashed
+    /// passwords by the PostgreSQL protocol.
+    #[serde(default)]
+    pub scram_verifier: Option<String>,
 }
 
 impl UserInfo {
@@ -45,6 +49,7 @@ impl UserInfo {
             auth_type,
             privileges,
             quota,
+            scram_verifier: None,
         }
     }
 
@@ -72,6 +77,7 @@ pub trait UserMgrApi: Sync + Send {
         hostname: String,
         new_password: Option<Vec<u8>>,
         new_auth: Option<AuthType>,
+        new_scram_verifier: Option<String>,
         seq: Option<u64>,
     ) -> Result<Option<u64>>;
 
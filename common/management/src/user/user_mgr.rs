@@ -117,6 +117,7 @@ impl UserMgrApi for UserMgr {
         hostname: String,
         new_password: Option<Vec<u8>>,
         new_auth: Option<AuthType>,
+        new_scram_verifier: Option<String>,
         seq: Option<u64>,
     ) -> Result<Option<u64>> {
         if new_password.is_none() && new_auth.is_none() {
@@ -125,6 +126,11 @@ impl UserMgrApi for UserMgr {
         let user_val_seq = self.get_user(username.clone(), hostname.clone(), seq);
         let user_info = user_val_seq.await?.data;
 
+        // The verifier is derived from the password, it is replaced only with the password.
+        let new_scram_verifier = match new_password {
+            Some(_) => new_scram_verifier,
+            None => user_info.scram_verifier,
+        };
         let mut new_user_info = UserInfo::new(
             username.clone(),
             hostname.clone(),
@@ -132,6 +138,7 @@ impl UserMgrApi for UserMgr {
             new_auth.unwrap_or(user_info.auth_type),
         );
         new_user_info.set_privileges(user_info.privileges);
+        new_user_info.scram_verifier = new_scram_verifier;
 
         let user_key = format_user_key(&new_user_info.name, &new_user_info.hostname);
         let key = format!("{}/{}", self.user_prefix, user_key);
@@ -528,6 +528,7 @@ mod update {
             test_hostname.to_string(),
             Some(new_user_info.password),
             None,
+            None,
             test_seq,
         );
 
@@ -596,6 +597,7 @@ mod update {
             test_hostname.to_string(),
             Some(new_user_info.password),
             Some(new_auth_type),
+            None,
             test_seq,
         );
         assert!(res.await.is_ok());
@@ -619,6 +621,7 @@ mod update {
             new_password,
             None,
             None,
+            None,
         );
         assert!(res.await.is_ok());
         Ok(())
@@ -650,6 +653,7 @@ mod update {
             test_hostname.to_string(),
             Some(Vec::from("new_pass".as_bytes())),
             None,
+            None,
             test_seq,
         );
         assert_eq!(
@@ -706,6 +710,7 @@ mod update {
             test_hostname.to_string(),
             Some(Vec::from("new_pass".as_bytes())),
             Some(AuthType::Sha256),
+            None,
             test_seq,
         );
         assert_eq!(
@@ -24,6 +24,7 @@ use common_meta_types::MetaVersion;
 use common_meta_types::TableIdent;
 use common_meta_types::TableInfo;
 use common_meta_types::TableMeta;
+use common_meta_types::TableOptionsUpsert;
 use common_meta_types::UpsertTableOptionReply;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
@@ -62,5 +63,14 @@ pub trait MetaApi: Send + Sync {
         option_value: String,
     ) -> Result<UpsertTableOptionReply>;
 
+    /// Upserts options of several tables in one shot.
+    ///
+    /// Either all of the tables are updated, or none is,
+    /// if any of them is no longer of the version specified.
+    async fn upsert_multi_table_options(
+        &self,
+        upserts: Vec<TableOptionsUpsert>,
+    ) -> Result<UpsertTableOptionReply>;
+
     fn name(&self) -> String;
 }
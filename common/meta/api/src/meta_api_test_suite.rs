@@ -22,11 +22,13 @@ use common_meta_types::CreateDatabaseReply;
 use common_meta_types::TableIdent;
 use common_meta_types::TableInfo;
 use common_meta_types::TableMeta;
+use common_meta_types::TableOptionsUpsert;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
 use common_planners::DropDatabasePlan;
 use common_planners::DropTablePlan;
 use common_tracing::tracing;
+use maplit::hashmap;
 
 use crate::MetaApi;
 
@@ -317,6 +319,45 @@ impl MetaApiTestSuite {
                     let table = mt.get_table("db1", "tb2").await.unwrap();
                     assert_eq!(table.options().get("key1"), Some(&"val1".into()));
                 }
+
+                tracing::info!("--- upsert multi table options with mismatched version");
+                {
+                    let table = mt.get_table("db1", "tb2").await.unwrap();
+
+                    let got = mt
+                        .upsert_multi_table_options(vec![TableOptionsUpsert {
+                            table_id: table.ident.table_id,
+                            table_version: table.ident.version - 1,
+                            options: hashmap! {"key1".into() => "val2".into()},
+                        }])
+                        .await;
+
+                    let got = got.unwrap_err();
+                    assert_eq!(ErrorCode::TableVersionMissMatch("").code(), got.code());
+
+                    // table is not affected.
+                    let table = mt.get_table("db1", "tb2").await.unwrap();
+                    assert_eq!(table.options().get("key1"), Some(&"val1".into()));
+                }
+
+                tracing::info!("--- upsert multi table options with key1=val2, key2=val2");
+                {
+                    let table = mt.get_table("db1", "tb2").await.unwrap();
+
+                    mt.upsert_multi_table_options(vec![TableOptionsUpsert {
+                        table_id: table.ident.table_id,
+                        table_version: table.ident.version,
+                        options: hashmap! {
+                            "key1".into() => "val2".into(),
+                            "key2".into() => "val2".into(),
+                        },
+                    }])
+                    .await?;
+
+                    let table = mt.get_table("db1", "tb2").await.unwrap();
+                    assert_eq!(table.options().get("key1"), Some(&"val2".into()));
+                    assert_eq!(table.options().get("key2"), Some(&"val2".into()));
+                }
             }
 
             tracing::info!("--- drop table with if_exists = false");
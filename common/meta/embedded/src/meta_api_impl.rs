@@ -32,6 +32,7 @@ use common_meta_types::MetaVersion;
 use common_meta_types::TableIdent;
 use common_meta_types::TableInfo;
 use common_meta_types::TableMeta;
+use common_meta_types::TableOptionsUpsert;
 use common_meta_types::UpsertTableOptionReply;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
@@ -266,6 +267,46 @@ impl MetaApi for MetaEmbedded {
         Ok(())
     }
 
+    async fn upsert_multi_table_options(
+        &self,
+        upserts: Vec<TableOptionsUpsert>,
+    ) -> Result<UpsertTableOptionReply> {
+        let sm = self.inner.lock().await;
+
+        let tables = upserts
+            .iter()
+            .map(|upsert| {
+                let table_options = upsert
+                    .options
+                    .iter()
+                    .map(|(k, v)| (k.clone(), Some(v.clone())))
+                    .collect();
+                (
+                    upsert.table_id,
+                    MatchSeq::Exact(upsert.table_version),
+                    table_options,
+                )
+            })
+            .collect();
+        let cmd = Cmd::UpsertMultiTableOptions { tables };
+
+        let res = sm.apply_cmd(&cmd).await?;
+        if !res.changed() {
+            let chs: Vec<Change<TableMeta>> = res.try_into().unwrap();
+            for (upsert, ch) in upserts.iter().zip(chs.into_iter()) {
+                let (prev, _result) = ch.unwrap();
+                if prev.seq != upsert.table_version {
+                    return Err(ErrorCode::TableVersionMissMatch(format!(
+                        "table_id {} targeting version {}, current version {}",
+                        upsert.table_id, upsert.table_version, prev.seq,
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn name(&self) -> String {
         "meta-embedded".to_string()
     }
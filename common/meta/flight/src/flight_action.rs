@@ -27,6 +27,7 @@ use common_meta_types::MetaId;
 use common_meta_types::MetaVersion;
 use common_meta_types::PrefixListReply;
 use common_meta_types::TableInfo;
+use common_meta_types::TableOptionsUpsert;
 use common_meta_types::UpsertKVAction;
 use common_meta_types::UpsertKVActionReply;
 use common_meta_types::UpsertTableOptionReply;
@@ -57,6 +58,7 @@ pub enum MetaFlightAction {
     GetTables(GetTablesAction),
     GetDatabases(GetDatabasesAction),
     CommitTable(UpsertTableOptionReq),
+    CommitTables(UpsertMultiTableOptionsReq),
 
     // general purpose kv
     UpsertKV(UpsertKVAction),
@@ -214,6 +216,14 @@ impl RequestFor for UpsertTableOptionReq {
     type Reply = UpsertTableOptionReply;
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct UpsertMultiTableOptionsReq {
+    pub upserts: Vec<TableOptionsUpsert>,
+}
+impl RequestFor for UpsertMultiTableOptionsReq {
+    type Reply = UpsertTableOptionReply;
+}
+
 // - get tables
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct GetTablesAction {
@@ -24,6 +24,7 @@ use common_meta_types::MetaVersion;
 use common_meta_types::TableIdent;
 use common_meta_types::TableInfo;
 use common_meta_types::TableMeta;
+use common_meta_types::TableOptionsUpsert;
 use common_meta_types::UpsertTableOptionReply;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
@@ -40,6 +41,7 @@ use crate::GetTableAction;
 use crate::GetTableExtReq;
 use crate::GetTablesAction;
 use crate::MetaFlightClient;
+use crate::UpsertMultiTableOptionsReq;
 use crate::UpsertTableOptionReq;
 
 #[async_trait::async_trait]
@@ -122,6 +124,13 @@ impl MetaApi for MetaFlightClient {
         .await
     }
 
+    async fn upsert_multi_table_options(
+        &self,
+        upserts: Vec<TableOptionsUpsert>,
+    ) -> common_exception::Result<UpsertTableOptionReply> {
+        self.do_action(UpsertMultiTableOptionsReq { upserts }).await
+    }
+
     fn name(&self) -> String {
         "MetaFlightClient".to_string()
     }
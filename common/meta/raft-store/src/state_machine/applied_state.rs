@@ -40,6 +40,8 @@ pub enum AppliedState {
 
     TableMeta(Change<TableMeta>),
 
+    TableMetas(Vec<Change<TableMeta>>),
+
     TableIdent {
         prev: Option<TableIdent>,
         result: Option<TableIdent>,
@@ -89,6 +91,7 @@ impl AppliedState {
             } => prev != result,
             AppliedState::DatabaseId(ref ch) => ch.changed(),
             AppliedState::TableMeta(ref ch) => ch.changed(),
+            AppliedState::TableMetas(ref chs) => chs.iter().any(|ch| ch.changed()),
             AppliedState::TableIdent { prev, result } => prev != result,
             AppliedState::KV(ref ch) => ch.changed(),
             AppliedState::None => false,
@@ -117,6 +120,7 @@ impl AppliedState {
             AppliedState::Node { ref prev, .. } => prev.is_none(),
             AppliedState::DatabaseId(Change { ref prev, .. }) => prev.is_none(),
             AppliedState::TableMeta(Change { ref prev, .. }) => prev.is_none(),
+            AppliedState::TableMetas(ref chs) => chs.iter().any(|ch| ch.prev.is_none()),
             AppliedState::TableIdent { ref prev, .. } => prev.is_none(),
             AppliedState::KV(Change { ref prev, .. }) => prev.is_none(),
             AppliedState::None => true,
@@ -129,6 +133,7 @@ impl AppliedState {
             AppliedState::Node { ref result, .. } => result.is_none(),
             AppliedState::DatabaseId(Change { ref result, .. }) => result.is_none(),
             AppliedState::TableMeta(Change { ref result, .. }) => result.is_none(),
+            AppliedState::TableMetas(ref chs) => chs.iter().any(|ch| ch.result.is_none()),
             AppliedState::TableIdent { ref result, .. } => result.is_none(),
             AppliedState::KV(Change { ref result, .. }) => result.is_none(),
             AppliedState::None => true,
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
@@ -476,50 +477,95 @@ impl StateMachine {
                 ref seq,
                 ref table_options,
             } => {
-                let prev = self.tables().get(table_id)?;
-
-                // Unlike other Cmd, prev to be None is not allowed for upsert-options.
-                let prev = match prev {
-                    None => {
-                        return Err(ErrorCode::UnknownTableId(format!("table_id:{}", table_id)))
-                    }
-                    Some(x) => x,
-                };
+                let prev = self.get_table_to_upsert(table_id)?;
 
                 if seq.match_seq(&prev).is_err() {
                     let res = AppliedState::TableMeta(Change::new(Some(prev.clone()), Some(prev)));
                     return Ok(res);
                 }
 
-                let meta = prev.meta.clone();
-                let mut table_meta = prev.data.clone();
-                let opts = &mut table_meta.options;
+                let sv = self
+                    .upsert_table_options(table_id, prev.clone(), table_options)
+                    .await?;
 
-                for (k, opt_v) in table_options {
-                    match opt_v {
-                        None => {
-                            opts.remove(k);
-                        }
-                        Some(v) => {
-                            opts.insert(k.to_string(), v.to_string());
-                        }
-                    }
+                Ok(AppliedState::TableMeta(Change::new(Some(prev), Some(sv))))
+            }
+
+            Cmd::UpsertMultiTableOptions { ref tables } => {
+                let mut prevs = Vec::with_capacity(tables.len());
+                for (table_id, _, _) in tables {
+                    prevs.push(self.get_table_to_upsert(table_id)?);
                 }
 
-                let new_seq = self.incr_seq(Tables::NAME).await?;
-                let sv = SeqV {
-                    seq: new_seq,
-                    meta,
-                    data: table_meta,
-                };
+                // All or nothing: if any of the tables has been changed, none of them is updated.
+                let all_matched = tables
+                    .iter()
+                    .zip(prevs.iter())
+                    .all(|((_, seq, _), prev)| seq.match_seq(prev).is_ok());
+
+                if !all_matched {
+                    let unchanged = prevs
+                        .into_iter()
+                        .map(|prev| Change::new(Some(prev.clone()), Some(prev)))
+                        .collect();
+                    return Ok(AppliedState::TableMetas(unchanged));
+                }
 
-                self.tables().insert(table_id, &sv).await?;
+                let mut changes = Vec::with_capacity(tables.len());
+                for ((table_id, _, table_options), prev) in tables.iter().zip(prevs.into_iter()) {
+                    let sv = self
+                        .upsert_table_options(table_id, prev.clone(), table_options)
+                        .await?;
+                    changes.push(Change::new(Some(prev), Some(sv)));
+                }
 
-                Ok(AppliedState::TableMeta(Change::new(Some(prev), Some(sv))))
+                Ok(AppliedState::TableMetas(changes))
             }
         }
     }
 
+    /// Unlike other Cmd, prev to be None is not allowed for upsert-options.
+    fn get_table_to_upsert(&self, table_id: &u64) -> common_exception::Result<SeqV<TableMeta>> {
+        match self.tables().get(table_id)? {
+            None => Err(ErrorCode::UnknownTableId(format!("table_id:{}", table_id))),
+            Some(x) => Ok(x),
+        }
+    }
+
+    /// Applies `table_options` to a table, which is supposed to be `prev`, and bumps its seq.
+    async fn upsert_table_options(
+        &self,
+        table_id: &u64,
+        prev: SeqV<TableMeta>,
+        table_options: &HashMap<String, Option<String>>,
+    ) -> common_exception::Result<SeqV<TableMeta>> {
+        let meta = prev.meta;
+        let mut table_meta = prev.data;
+        let opts = &mut table_meta.options;
+
+        for (k, opt_v) in table_options {
+            match opt_v {
+                None => {
+                    opts.remove(k);
+                }
+                Some(v) => {
+                    opts.insert(k.to_string(), v.to_string());
+                }
+            }
+        }
+
+        let new_seq = self.incr_seq(Tables::NAME).await?;
+        let sv = SeqV {
+            seq: new_seq,
+            meta,
+            data: table_meta,
+        };
+
+        self.tables().insert(table_id, &sv).await?;
+
+        Ok(sv)
+    }
+
     async fn sub_tree_upsert<'s, V, KS>(
         &'s self,
         sub_tree: AsKeySpace<'s, KS>,
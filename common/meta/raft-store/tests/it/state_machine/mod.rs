@@ -333,6 +333,129 @@ async fn test_state_machine_apply_upsert_table_option() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_state_machine_apply_upsert_multi_table_options() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_raft_store_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_raft_test_context();
+    let m = StateMachine::open(&tc.raft_config, 1).await?;
+
+    tracing::info!("--- prepare two tables");
+    m.apply_cmd(&Cmd::CreateDatabase {
+        name: "db1".to_string(),
+    })
+    .await?;
+
+    let mut idents = vec![];
+    for table_name in ["tb1", "tb2"] {
+        let resp = m
+            .apply_cmd(&Cmd::CreateTable {
+                db_name: "db1".to_string(),
+                table_name: table_name.to_string(),
+                table_meta: Default::default(),
+            })
+            .await?;
+
+        match resp {
+            AppliedState::TableIdent { result, .. } => {
+                let r = result.unwrap();
+                idents.push((r.table_id, r.version));
+            }
+            _ => {
+                panic!("expect AppliedState::TableIdent")
+            }
+        }
+    }
+    let (id1, version1) = idents[0];
+    let (id2, version2) = idents[1];
+
+    tracing::info!("--- update with invalid table_id");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::UpsertMultiTableOptions {
+                tables: vec![
+                    (id1, MatchSeq::Exact(version1), hashmap! {}),
+                    (0, MatchSeq::Exact(version2), hashmap! {}),
+                ],
+            })
+            .await;
+
+        let err = resp.unwrap_err();
+
+        assert_eq!(ErrorCode::UnknownTableIdCode(), err.code());
+    }
+
+    tracing::info!("--- one mismatched seq wont update any table");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::UpsertMultiTableOptions {
+                tables: vec![
+                    (id1, MatchSeq::Exact(version1), hashmap! {
+                        "a".to_string() => Some("A".to_string()),
+                    }),
+                    (id2, MatchSeq::Exact(version2 - 1), hashmap! {
+                        "b".to_string() => Some("B".to_string()),
+                    }),
+                ],
+            })
+            .await?;
+
+        assert!(!resp.changed());
+        let chs: Vec<Change<TableMeta>> = resp.try_into().unwrap();
+        assert_eq!(2, chs.len());
+        for ch in chs {
+            let (prev, result) = ch.unwrap();
+            assert_eq!(prev, result);
+        }
+
+        let got = m.get_table_by_id(&id1)?.unwrap();
+        assert_eq!(version1, got.seq);
+        assert_eq!(HashMap::new(), got.data.options);
+    }
+
+    tracing::info!("--- update OK");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::UpsertMultiTableOptions {
+                tables: vec![
+                    (id1, MatchSeq::Exact(version1), hashmap! {
+                        "a".to_string() => Some("A".to_string()),
+                    }),
+                    (id2, MatchSeq::Exact(version2), hashmap! {
+                        "b".to_string() => Some("B".to_string()),
+                    }),
+                ],
+            })
+            .await?;
+
+        assert!(resp.changed());
+        let chs: Vec<Change<TableMeta>> = resp.try_into().unwrap();
+        assert_eq!(2, chs.len());
+
+        tracing::info!("--- check tables are updated");
+        let got = m.get_table_by_id(&id1)?.unwrap();
+        assert!(got.seq > version1);
+        assert_eq!(
+            hashmap! {
+                "a".to_string() => "A".to_string()
+            },
+            got.data.options
+        );
+
+        let got = m.get_table_by_id(&id2)?.unwrap();
+        assert!(got.seq > version2);
+        assert_eq!(
+            hashmap! {
+                "b".to_string() => "B".to_string()
+            },
+            got.data.options
+        );
+    }
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_state_machine_apply_non_dup_generic_kv_upsert_get() -> anyhow::Result<()> {
     let (_log_guards, ut_span) = init_raft_store_ut!();
@@ -72,6 +72,18 @@ pub enum Cmd {
         table_options: HashMap<String, Option<String>>,
     },
 
+    /// Update, remove or insert options of several tables at once.
+    ///
+    /// All of the tables have to be present, otherwise an `UnknownTableId` is returned.
+    ///
+    /// If the seq of any table mismatches, none of them is updated
+    /// and it returns the unchanged states of all the tables.
+    /// Otherwise it returns the TableMeta of every table before and after update.
+    UpsertMultiTableOptions {
+        /// (table_id, the exact version of the table, options to add or remove)
+        tables: Vec<(u64, MatchSeq, HashMap<String, Option<String>>)>,
+    },
+
     /// Update or insert a general purpose kv store
     UpsertKV {
         key: String,
@@ -141,6 +153,17 @@ impl fmt::Display for Cmd {
                     table_id, seq, table_options
                 )
             }
+            Cmd::UpsertMultiTableOptions { tables } => {
+                write!(f, "upsert-multi-table-options:")?;
+                for (table_id, seq, table_options) in tables {
+                    write!(
+                        f,
+                        " table-id:{}({:?}) = {:?};",
+                        table_id, seq, table_options
+                    )?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -13,4 +13,17 @@
 //  limitations under the License.
 //
 
+use std::collections::HashMap;
+
+use crate::MetaId;
+use crate::MetaVersion;
+
 pub type UpsertTableOptionReply = ();
+
+/// Options to set on a table, on condition that the table is still of `table_version`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TableOptionsUpsert {
+    pub table_id: MetaId,
+    pub table_version: MetaVersion,
+    pub options: HashMap<String, String>,
+}
@@ -20,6 +20,7 @@ pub use cluster::Node;
 pub use cluster::NodeInfo;
 pub use cluster::Slot;
 pub use cmd::Cmd;
+pub use commit_table_reply::TableOptionsUpsert;
 pub use commit_table_reply::UpsertTableOptionReply;
 pub use database_info::DatabaseInfo;
 pub use database_reply::CreateDatabaseReply;
@@ -15,11 +15,13 @@
 use std::sync::Arc;
 
 use common_datablocks::DataBlock;
+use common_datavalues::compare_coercion;
 use common_datavalues::DataField;
 use common_datavalues::DataSchema;
 use common_datavalues::DataSchemaRef;
 use common_datavalues::DataSchemaRefExt;
 use common_datavalues::DataType;
+use common_exception::ErrorCode;
 use common_exception::Result;
 
 use crate::col;
@@ -34,13 +36,18 @@ use crate::Expression;
 use crate::ExpressionPlan;
 use crate::FilterPlan;
 use crate::HavingPlan;
+use crate::JoinPlan;
+use crate::JoinType;
 use crate::LimitByPlan;
 use crate::LimitPlan;
 use crate::PlanNode;
 use crate::ProjectionPlan;
 use crate::RewriteHelper;
 use crate::SelectPlan;
+use crate::SetOperationPlan;
+use crate::SetOperator;
 use crate::SortPlan;
+use crate::WindowPlan;
 
 pub enum AggregateMode {
     Partial,
@@ -226,6 +233,23 @@ impl PlanBuilder {
         })))
     }
 
+    /// Apply window functions, they must have the same PARTITION BY and ORDER BY.
+    pub fn window(&self, exprs: &[Expression]) -> Result<Self> {
+        let input_schema = self.plan.schema();
+        let mut fields = input_schema.fields().clone();
+        for field in RewriteHelper::exprs_to_fields(exprs, &input_schema)? {
+            if !fields.iter().any(|x| x.name() == field.name()) {
+                fields.push(field);
+            }
+        }
+
+        Ok(Self::from(&PlanNode::Window(WindowPlan {
+            window_funcs: exprs.to_vec(),
+            input: Arc::new(self.plan.clone()),
+            schema: DataSchemaRefExt::create(fields),
+        })))
+    }
+
     /// Apply a limit
     pub fn limit(&self, n: usize) -> Result<Self> {
         Ok(Self::from(&PlanNode::Limit(LimitPlan {
@@ -252,6 +276,111 @@ impl PlanBuilder {
         })))
     }
 
+    /// Apply a hash join, the current plan is the left(probe) side.
+    /// The equi-join keys are coerced to a common type pairwise.
+    pub fn join(
+        &self,
+        right: &PlanNode,
+        join_type: JoinType,
+        left_keys: &[Expression],
+        right_keys: &[Expression],
+        filter: Option<Expression>,
+    ) -> Result<Self> {
+        if left_keys.len() != right_keys.len() {
+            return Result::Err(ErrorCode::BadArguments(format!(
+                "Join keys must be paired, left keys: {:?}, right keys: {:?}",
+                left_keys, right_keys
+            )));
+        }
+
+        let left_schema = self.plan.schema();
+        let right_schema = right.schema();
+        for field in left_schema.fields() {
+            if right_schema.field_with_name(field.name()).is_ok() {
+                return Result::Err(ErrorCode::SyntaxException(format!(
+                    "Duplicate column '{}' in join, the tables should be qualified by different names",
+                    field.name()
+                )));
+            }
+        }
+
+        let mut coerced_left_keys = Vec::with_capacity(left_keys.len());
+        let mut coerced_right_keys = Vec::with_capacity(right_keys.len());
+        for (left_key, right_key) in left_keys.iter().zip(right_keys) {
+            validate_expression(left_key)?;
+            validate_expression(right_key)?;
+
+            let left_type = left_key.to_data_type(&left_schema)?;
+            let right_type = right_key.to_data_type(&right_schema)?;
+            let key_type = compare_coercion(&left_type, &right_type)?;
+            coerced_left_keys.push(Self::cast_join_key(left_key, &left_type, &key_type));
+            coerced_right_keys.push(Self::cast_join_key(right_key, &right_type, &key_type));
+        }
+
+        if let Some(filter) = &filter {
+            validate_expression(filter)?;
+        }
+
+        Ok(Self::from(&PlanNode::Join(JoinPlan {
+            join_type,
+            left_keys: coerced_left_keys,
+            right_keys: coerced_right_keys,
+            filter,
+            left: Arc::new(self.plan.clone()),
+            right: Arc::new(right.clone()),
+            schema: JoinPlan::join_schema(join_type, &left_schema, &right_schema),
+        })))
+    }
+
+    fn cast_join_key(key: &Expression, key_type: &DataType, target: &DataType) -> Expression {
+        match key_type == target {
+            true => key.clone(),
+            false => Expression::Cast {
+                expr: Box::new(key.clone()),
+                data_type: target.clone(),
+            },
+        }
+    }
+
+    /// Apply a set operation, the columns of both inputs are coerced to the output schema by position.
+    pub fn set_operation(&self, right: &PlanNode, op: SetOperator) -> Result<Self> {
+        let schema = SetOperationPlan::set_operation_schema(&self.plan.schema(), &right.schema())?;
+        let left = Self::coerce_set_operation_input(&self.plan, &schema)?;
+        let right = Self::coerce_set_operation_input(right, &schema)?;
+
+        Ok(Self::from(&PlanNode::SetOperation(SetOperationPlan {
+            op,
+            left: Arc::new(left),
+            right: Arc::new(right),
+            schema,
+        })))
+    }
+
+    fn coerce_set_operation_input(input: &PlanNode, schema: &DataSchemaRef) -> Result<PlanNode> {
+        let input_schema = input.schema();
+        let fields = input_schema.fields().iter().zip(schema.fields());
+        if fields
+            .clone()
+            .all(|(f, target)| f.name() == target.name() && f.data_type() == target.data_type())
+        {
+            return Ok(input.clone());
+        }
+
+        let exprs = fields
+            .map(|(f, target)| match f.data_type() == target.data_type() {
+                true if f.name() == target.name() => col(f.name()),
+                true => col(f.name()).alias(target.name()),
+                false => Expression::Cast {
+                    expr: Box::new(col(f.name())),
+                    data_type: target.data_type().clone(),
+                }
+                .alias(target.name()),
+            })
+            .collect::<Vec<_>>();
+
+        Self::from(input).project(&exprs)?.build()
+    }
+
     pub fn select(&self) -> Result<Self> {
         Ok(Self::from(&PlanNode::Select(SelectPlan {
             input: Arc::new(self.plan.clone()),
@@ -20,10 +20,12 @@ use crate::AggregatorFinalPlan;
 use crate::AggregatorPartialPlan;
 use crate::CreateDatabasePlan;
 use crate::CreateTablePlan;
+use crate::DeletePlan;
 use crate::DropDatabasePlan;
 use crate::DropTablePlan;
 use crate::Expression;
 use crate::ExpressionPlan;
+use crate::JoinPlan;
 use crate::LimitPlan;
 use crate::PlanNode;
 use crate::ProjectionPlan;
@@ -31,6 +33,7 @@ use crate::ReadDataSourcePlan;
 use crate::SortPlan;
 use crate::StagePlan;
 use crate::SubQueriesSetPlan;
+use crate::UpdatePlan;
 
 pub struct PlanNodeIndentFormatDisplay<'a> {
     indent: usize,
@@ -64,13 +67,19 @@ impl<'a> fmt::Display for PlanNodeIndentFormatDisplay<'a> {
             PlanNode::Filter(plan) => write!(f, "Filter: {:?}", plan.predicate),
             PlanNode::Having(plan) => write!(f, "Having: {:?}", plan.predicate),
             PlanNode::Sort(plan) => Self::format_sort(f, plan),
+            PlanNode::Window(plan) => write!(f, "Window: {:?}", plan.window_funcs),
             PlanNode::Limit(plan) => Self::format_limit(f, plan),
             PlanNode::SubQueryExpression(plan) => Self::format_subquery_expr(f, plan),
             PlanNode::ReadSource(plan) => Self::format_read_source(f, plan),
+            PlanNode::Join(plan) => Self::format_join(f, plan),
+            PlanNode::SetOperation(plan) => write!(f, "SetOperation: {}", plan.op),
+            PlanNode::CteScan(plan) => write!(f, "CteScan: {}", plan.name),
             PlanNode::CreateDatabase(plan) => Self::format_create_database(f, plan),
             PlanNode::DropDatabase(plan) => Self::format_drop_database(f, plan),
             PlanNode::CreateTable(plan) => Self::format_create_table(f, plan),
             PlanNode::DropTable(plan) => Self::format_drop_table(f, plan),
+            PlanNode::Delete(plan) => Self::format_delete(f, plan),
+            PlanNode::Update(plan) => Self::format_update(f, plan),
             _ => {
                 let mut printed = true;
 
@@ -213,6 +222,18 @@ impl<'a> PlanNodeIndentFormatDisplay<'a> {
             plan.statistics.read_bytes,
         )?;
 
+        if let Some(pruning) = &plan.statistics.pruning {
+            write!(
+                f,
+                ", pruning: [segments_total: {}, pruned_segments: {}, partitions_total: {}, pruned_by_range: {}, pruned_by_bloom_filter: {}]",
+                pruning.segments_total,
+                pruning.pruned_segments,
+                pruning.partitions_total,
+                pruning.pruned_by_range,
+                pruning.pruned_by_bloom_filter,
+            )?;
+        }
+
         if let Some(p) = &plan.push_downs {
             if p.limit.is_some() || p.projection.is_some() {
                 write!(f, ", push_downs: [")?;
@@ -237,6 +258,26 @@ impl<'a> PlanNodeIndentFormatDisplay<'a> {
         Ok(())
     }
 
+    fn format_join(f: &mut Formatter, plan: &JoinPlan) -> fmt::Result {
+        write!(f, "Join: {}", plan.join_type)?;
+
+        if !plan.left_keys.is_empty() {
+            write!(f, ", on: [")?;
+            for (i, (left, right)) in plan.left_keys.iter().zip(&plan.right_keys).enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{:?} = {:?}", left, right)?;
+            }
+            write!(f, "]")?;
+        }
+
+        if let Some(filter) = &plan.filter {
+            write!(f, ", filter: {:?}", filter)?;
+        }
+        Ok(())
+    }
+
     fn format_create_database(f: &mut Formatter, plan: &CreateDatabasePlan) -> fmt::Result {
         write!(f, "Create database {:},", plan.db)?;
         write!(f, " if_not_exists:{:},", plan.if_not_exists)?;
@@ -248,6 +289,28 @@ impl<'a> PlanNodeIndentFormatDisplay<'a> {
         write!(f, " if_exists:{:}", plan.if_exists)
     }
 
+    fn format_delete(f: &mut Formatter, plan: &DeletePlan) -> fmt::Result {
+        write!(f, "Delete from {:}.{:}", plan.db_name, plan.tbl_name)?;
+        match &plan.selection {
+            None => Ok(()),
+            Some(selection) => write!(f, ", selection: {:?}", selection),
+        }
+    }
+
+    fn format_update(f: &mut Formatter, plan: &UpdatePlan) -> fmt::Result {
+        write!(f, "Update {:}.{:} set ", plan.db_name, plan.tbl_name)?;
+        for (i, (column, value)) in plan.assignments.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:} = {:?}", column, value)?;
+        }
+        match &plan.selection {
+            None => Ok(()),
+            Some(selection) => write!(f, ", selection: {:?}", selection),
+        }
+    }
+
     fn format_create_table(f: &mut Formatter, plan: &CreateTablePlan) -> fmt::Result {
         write!(f, "Create table {:}.{:}", plan.db, plan.table)?;
         write!(f, " {:},", plan.schema())?;
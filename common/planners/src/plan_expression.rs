@@ -28,6 +28,7 @@ use common_functions::scalars::FunctionFactory;
 use lazy_static::lazy_static;
 
 use crate::PlanNode;
+use crate::WindowFrame;
 
 lazy_static! {
     static ref OP_SET: HashSet<&'static str> = ["database", "version",].iter().copied().collect();
@@ -91,6 +92,19 @@ pub enum Expression {
         args: Vec<Expression>,
     },
 
+    /// WindowFunction evaluated over a window of the rows in the partition.
+    /// Such as "rank() over (partition by a order by b)".
+    WindowFunction {
+        op: String,
+        params: Vec<DataValue>,
+        args: Vec<Expression>,
+        partition_by: Vec<Expression>,
+        /// The sort expressions
+        order_by: Vec<Expression>,
+        /// None if the frame is not specified
+        window_frame: Option<WindowFrame>,
+    },
+
     /// A sort expression, that can be used to sort values.
     Sort {
         /// The expression to sort on
@@ -296,6 +310,20 @@ impl Expression {
                 let func = self.to_aggregate_function(input_schema)?;
                 func.return_type()
             }
+            Expression::WindowFunction { op, args, .. } => match op.to_lowercase().as_str() {
+                "row_number" | "rank" | "dense_rank" => Ok(DataType::UInt64),
+                "lag" | "lead" | "first_value" | "last_value" => match args.first() {
+                    Some(arg) => arg.to_data_type(input_schema),
+                    None => Result::Err(ErrorCode::NumberArgumentsNotMatch(format!(
+                        "Window function {} expect at least 1 argument",
+                        op
+                    ))),
+                },
+                _ => {
+                    let func = self.to_aggregate_function(input_schema)?;
+                    func.return_type()
+                }
+            },
             Expression::Wildcard => Result::Err(ErrorCode::IllegalDataType(
                 "Wildcard expressions are not valid to get return type",
             )),
@@ -323,6 +351,15 @@ impl Expression {
                 }
                 AggregateFunctionFactory::instance().get(&func_name, params.clone(), fields)
             }
+            Expression::WindowFunction {
+                op, params, args, ..
+            } => {
+                let mut fields = Vec::with_capacity(args.len());
+                for arg in args.iter() {
+                    fields.push(arg.to_data_field(schema)?);
+                }
+                AggregateFunctionFactory::instance().get(op, params.clone(), fields)
+            }
             _ => Err(ErrorCode::LogicalError(
                 "Expression must be aggregated function",
             )),
@@ -331,7 +368,8 @@ impl Expression {
 
     pub fn to_aggregate_function_names(&self) -> Result<Vec<String>> {
         match self {
-            Expression::AggregateFunction { args, .. } => {
+            Expression::AggregateFunction { args, .. }
+            | Expression::WindowFunction { args, .. } => {
                 let mut names = Vec::with_capacity(args.len());
                 for arg in args.iter() {
                     names.push(arg.column_name());
@@ -417,6 +455,53 @@ impl fmt::Debug for Expression {
                 Ok(())
             }
 
+            Expression::WindowFunction {
+                op,
+                params,
+                args,
+                partition_by,
+                order_by,
+                window_frame,
+            } => {
+                let args_column_name = args.iter().map(Expression::column_name).collect::<Vec<_>>();
+                let params_name = params
+                    .iter()
+                    .map(|v| DataValue::custom_display(v, true))
+                    .collect::<Vec<_>>();
+
+                if params.is_empty() {
+                    write!(f, "{}", op)?;
+                } else {
+                    write!(f, "{}({})", op, params_name.join(", "))?;
+                };
+                write!(f, "({}) over (", args_column_name.join(", "))?;
+
+                let mut clauses = vec![];
+                if !partition_by.is_empty() {
+                    let partition_by = partition_by
+                        .iter()
+                        .map(|expr| format!("{:?}", expr))
+                        .collect::<Vec<_>>();
+                    clauses.push(format!("partition by {}", partition_by.join(", ")));
+                }
+                if !order_by.is_empty() {
+                    let order_by = order_by
+                        .iter()
+                        .map(|expr| match expr {
+                            Expression::Sort {
+                                expr, asc: false, ..
+                            } => format!("{:?} desc", expr),
+                            _ => format!("{:?}", expr),
+                        })
+                        .collect::<Vec<_>>();
+                    clauses.push(format!("order by {}", order_by.join(", ")));
+                }
+                if let Some(window_frame) = window_frame {
+                    clauses.push(window_frame.to_string());
+                }
+                write!(f, "{})", clauses.join(" "))
+            }
+
             Expression::Sort { expr, .. } => write!(f, "{:?}", expr),
             Expression::Wildcard => write!(f, "*"),
             Expression::Cast { expr, data_type } => {
@@ -67,6 +67,37 @@ pub fn expand_aggregate_arg_exprs(exprs: &[Expression]) -> Vec<Expression> {
     res
 }
 
+/// Collect all deeply nested `Expression::WindowFunction`. They are returned in order
+/// of occurrence (depth first), with duplicates omitted.
+pub fn find_window_exprs(exprs: &[Expression]) -> Vec<Expression> {
+    find_exprs_in_exprs(exprs, &|nest_exprs| {
+        matches!(nest_exprs, Expression::WindowFunction { .. })
+    })
+}
+
+/// Collect the arguments, partition keys and order keys of window functions
+/// [WindowFunction(rank() over (partition by a order by b))] ---> [ColumnExpr(a), ColumnExpr(b)]
+pub fn expand_window_arg_exprs(exprs: &[Expression]) -> Vec<Expression> {
+    let mut res = vec![];
+    for expr in exprs {
+        if let Expression::WindowFunction {
+            args,
+            partition_by,
+            order_by,
+            ..
+        } = expr
+        {
+            let order_by = order_by.iter().map(sort_to_inner_expr).collect::<Vec<_>>();
+            for arg in args.iter().chain(partition_by).chain(order_by.iter()) {
+                if !res.contains(arg) {
+                    res.push(arg.clone());
+                }
+            }
+        }
+    }
+    res
+}
+
 /// Collect all deeply nested `Expression::Column`'s. They are returned in order of
 /// appearance (depth first), with duplicates omitted.
 pub fn find_column_exprs(exprs: &[Expression]) -> Vec<Expression> {
@@ -297,6 +328,31 @@ where F: Fn(&Expression) -> Result<Option<Expression>> {
                     .collect::<Result<Vec<Expression>>>()?,
             }),
 
+            Expression::WindowFunction {
+                op,
+                params,
+                args,
+                partition_by,
+                order_by,
+                window_frame,
+            } => Ok(Expression::WindowFunction {
+                op: op.clone(),
+                params: params.clone(),
+                args: args
+                    .iter()
+                    .map(|e| clone_with_replacement(e, replacement_fn))
+                    .collect::<Result<Vec<Expression>>>()?,
+                partition_by: partition_by
+                    .iter()
+                    .map(|e| clone_with_replacement(e, replacement_fn))
+                    .collect::<Result<Vec<Expression>>>()?,
+                order_by: order_by
+                    .iter()
+                    .map(|e| clone_with_replacement(e, replacement_fn))
+                    .collect::<Result<Vec<Expression>>>()?,
+                window_frame: *window_frame,
+            }),
+
             Expression::Sort {
                 expr: nested_expr,
                 asc,
@@ -0,0 +1,111 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+
+use crate::Expression;
+use crate::PlanNode;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+    Full,
+    Cross,
+}
+
+impl JoinType {
+    /// Whether the rows of the left input without a match are kept, padded with NULLs.
+    pub fn keep_left_unmatched(&self) -> bool {
+        matches!(self, JoinType::Left | JoinType::Full)
+    }
+
+    /// Whether the rows of the right input without a match are kept, padded with NULLs.
+    pub fn keep_right_unmatched(&self) -> bool {
+        matches!(self, JoinType::Right | JoinType::Full)
+    }
+}
+
+impl fmt::Display for JoinType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            JoinType::Inner => write!(f, "INNER"),
+            JoinType::Left => write!(f, "LEFT OUTER"),
+            JoinType::Right => write!(f, "RIGHT OUTER"),
+            JoinType::Full => write!(f, "FULL OUTER"),
+            JoinType::Cross => write!(f, "CROSS"),
+        }
+    }
+}
+
+/// Hash join of two inputs.
+/// The right input is the build side, the left input is the probe side.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct JoinPlan {
+    pub join_type: JoinType,
+    /// Equi-join keys, evaluated against the left input.
+    pub left_keys: Vec<Expression>,
+    /// Equi-join keys, evaluated against the right input.
+    pub right_keys: Vec<Expression>,
+    /// The residual (non-equi) condition, evaluated against the joined rows.
+    pub filter: Option<Expression>,
+    pub left: Arc<PlanNode>,
+    pub right: Arc<PlanNode>,
+    /// Output schema: left fields followed by right fields.
+    pub schema: DataSchemaRef,
+}
+
+impl JoinPlan {
+    pub fn join_schema(
+        join_type: JoinType,
+        left: &DataSchemaRef,
+        right: &DataSchemaRef,
+    ) -> DataSchemaRef {
+        let nullable_fields = |schema: &DataSchemaRef, nullable: bool| {
+            schema
+                .fields()
+                .iter()
+                .map(|f| {
+                    DataField::new(f.name(), f.data_type().clone(), nullable || f.is_nullable())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut fields = nullable_fields(left, join_type.keep_right_unmatched());
+        fields.extend(nullable_fields(right, join_type.keep_left_unmatched()));
+        DataSchemaRefExt::create(fields)
+    }
+
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    pub fn get_inputs(&self) -> Vec<Arc<PlanNode>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    pub fn set_inputs(&mut self, inputs: Vec<&PlanNode>) {
+        assert_eq!(inputs.len(), 2);
+        self.left = Arc::new(inputs[0].clone());
+        self.right = Arc::new(inputs[1].clone());
+        self.schema = Self::join_schema(self.join_type, &self.left.schema(), &self.right.schema());
+    }
+}
@@ -22,10 +22,13 @@ use crate::plan_broadcast::BroadcastPlan;
 use crate::plan_subqueries_set::SubQueriesSetPlan;
 use crate::AggregatorFinalPlan;
 use crate::AggregatorPartialPlan;
+use crate::AlterTablePlan;
 use crate::AlterUserPlan;
 use crate::CreateDatabasePlan;
 use crate::CreateTablePlan;
 use crate::CreateUserPlan;
+use crate::CteScanPlan;
+use crate::DeletePlan;
 use crate::DescribeTablePlan;
 use crate::DropDatabasePlan;
 use crate::DropTablePlan;
@@ -36,19 +39,25 @@ use crate::FilterPlan;
 use crate::GrantPrivilegePlan;
 use crate::HavingPlan;
 use crate::InsertIntoPlan;
+use crate::JoinPlan;
 use crate::KillPlan;
 use crate::LimitByPlan;
 use crate::LimitPlan;
+use crate::OptimizeTablePlan;
 use crate::ProjectionPlan;
 use crate::ReadDataSourcePlan;
 use crate::RemotePlan;
 use crate::SelectPlan;
+use crate::SetOperationPlan;
 use crate::SettingPlan;
 use crate::ShowCreateTablePlan;
 use crate::SortPlan;
 use crate::StagePlan;
+use crate::TransactionPlan;
 use crate::TruncateTablePlan;
+use crate::UpdatePlan;
 use crate::UseDatabasePlan;
+use crate::WindowPlan;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
@@ -64,9 +73,13 @@ pub enum PlanNode {
     Filter(FilterPlan),
     Having(HavingPlan),
     Sort(SortPlan),
+    Window(WindowPlan),
     Limit(LimitPlan),
     LimitBy(LimitByPlan),
     ReadSource(ReadDataSourcePlan),
+    Join(JoinPlan),
+    SetOperation(SetOperationPlan),
+    CteScan(CteScanPlan),
     Select(SelectPlan),
     Explain(ExplainPlan),
     CreateDatabase(CreateDatabasePlan),
@@ -75,15 +88,20 @@ pub enum PlanNode {
     DescribeTable(DescribeTablePlan),
     DropTable(DropTablePlan),
     TruncateTable(TruncateTablePlan),
+    OptimizeTable(OptimizeTablePlan),
+    AlterTable(AlterTablePlan),
     UseDatabase(UseDatabasePlan),
     SetVariable(SettingPlan),
     InsertInto(InsertIntoPlan),
+    Delete(DeletePlan),
+    Update(UpdatePlan),
     ShowCreateTable(ShowCreateTablePlan),
     SubQueryExpression(SubQueriesSetPlan),
     Kill(KillPlan),
     CreateUser(CreateUserPlan),
     AlterUser(AlterUserPlan),
     GrantPrivilege(GrantPrivilegePlan),
+    Transaction(TransactionPlan),
 }
 
 impl PlanNode {
@@ -103,6 +121,9 @@ impl PlanNode {
             PlanNode::Limit(v) => v.schema(),
             PlanNode::LimitBy(v) => v.schema(),
             PlanNode::ReadSource(v) => v.schema(),
+            PlanNode::Join(v) => v.schema(),
+            PlanNode::SetOperation(v) => v.schema(),
+            PlanNode::CteScan(v) => v.schema(),
             PlanNode::Select(v) => v.schema(),
             PlanNode::Explain(v) => v.schema(),
             PlanNode::CreateDatabase(v) => v.schema(),
@@ -111,16 +132,22 @@ impl PlanNode {
             PlanNode::DropTable(v) => v.schema(),
             PlanNode::DescribeTable(v) => v.schema(),
             PlanNode::TruncateTable(v) => v.schema(),
+            PlanNode::OptimizeTable(v) => v.schema(),
+            PlanNode::AlterTable(v) => v.schema(),
             PlanNode::SetVariable(v) => v.schema(),
             PlanNode::Sort(v) => v.schema(),
+            PlanNode::Window(v) => v.schema(),
             PlanNode::UseDatabase(v) => v.schema(),
             PlanNode::InsertInto(v) => v.schema(),
+            PlanNode::Delete(v) => v.schema(),
+            PlanNode::Update(v) => v.schema(),
             PlanNode::ShowCreateTable(v) => v.schema(),
             PlanNode::SubQueryExpression(v) => v.schema(),
             PlanNode::Kill(v) => v.schema(),
             PlanNode::CreateUser(v) => v.schema(),
             PlanNode::AlterUser(v) => v.schema(),
             PlanNode::GrantPrivilege(v) => v.schema(),
+            PlanNode::Transaction(v) => v.schema(),
         }
     }
 
@@ -139,6 +166,9 @@ impl PlanNode {
             PlanNode::Limit(_) => "LimitPlan",
             PlanNode::LimitBy(_) => "LimitByPlan",
             PlanNode::ReadSource(_) => "ReadSourcePlan",
+            PlanNode::Join(_) => "JoinPlan",
+            PlanNode::SetOperation(_) => "SetOperationPlan",
+            PlanNode::CteScan(_) => "CteScanPlan",
             PlanNode::Select(_) => "SelectPlan",
             PlanNode::Explain(_) => "ExplainPlan",
             PlanNode::CreateDatabase(_) => "CreateDatabasePlan",
@@ -147,16 +177,22 @@ impl PlanNode {
             PlanNode::DescribeTable(_) => "DescribeTablePlan",
             PlanNode::DropTable(_) => "DropTablePlan",
             PlanNode::TruncateTable(_) => "TruncateTablePlan",
+            PlanNode::OptimizeTable(_) => "OptimizeTablePlan",
+            PlanNode::AlterTable(_) => "AlterTablePlan",
             PlanNode::SetVariable(_) => "SetVariablePlan",
             PlanNode::Sort(_) => "SortPlan",
+            PlanNode::Window(_) => "WindowPlan",
             PlanNode::UseDatabase(_) => "UseDatabasePlan",
             PlanNode::InsertInto(_) => "InsertIntoPlan",
+            PlanNode::Delete(_) => "DeletePlan",
+            PlanNode::Update(_) => "UpdatePlan",
             PlanNode::ShowCreateTable(_) => "ShowCreateTablePlan",
             PlanNode::SubQueryExpression(_) => "CreateSubQueriesSets",
             PlanNode::Kill(_) => "KillQuery",
             PlanNode::CreateUser(_) => "CreateUser",
             PlanNode::AlterUser(_) => "AlterUser",
             PlanNode::GrantPrivilege(_) => "GrantPrivilegePlan",
+            PlanNode::Transaction(_) => "TransactionPlan",
         }
     }
 
@@ -174,7 +210,11 @@ impl PlanNode {
             PlanNode::Explain(v) => vec![v.input.clone()],
             PlanNode::Select(v) => vec![v.input.clone()],
             PlanNode::Sort(v) => vec![v.input.clone()],
+            PlanNode::Window(v) => vec![v.input.clone()],
             PlanNode::SubQueryExpression(v) => v.get_inputs(),
+            PlanNode::Join(v) => v.get_inputs(),
+            PlanNode::SetOperation(v) => v.get_inputs(),
+            PlanNode::CteScan(v) => vec![v.input.clone()],
 
             _ => vec![],
         }
@@ -202,7 +242,11 @@ impl PlanNode {
             PlanNode::Explain(v) => v.set_input(inputs[0]),
             PlanNode::Select(v) => v.set_input(inputs[0]),
             PlanNode::Sort(v) => v.set_input(inputs[0]),
+            PlanNode::Window(v) => v.set_input(inputs[0]),
             PlanNode::SubQueryExpression(v) => v.set_inputs(inputs),
+            PlanNode::Join(v) => v.set_inputs(inputs),
+            PlanNode::SetOperation(v) => v.set_inputs(inputs),
+            PlanNode::CteScan(v) => v.set_input(inputs[0]),
             _ => {
                 return Err(ErrorCode::UnImplement(format!(
                     "UnImplement set_inputs for {:?}",
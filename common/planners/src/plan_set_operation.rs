@@ -0,0 +1,101 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use common_datavalues::compare_coercion;
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::PlanNode;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SetOperator {
+    UnionAll,
+    Union,
+    Intersect,
+    Except,
+}
+
+impl fmt::Display for SetOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SetOperator::UnionAll => write!(f, "UNION ALL"),
+            SetOperator::Union => write!(f, "UNION"),
+            SetOperator::Intersect => write!(f, "INTERSECT"),
+            SetOperator::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
+/// Set operation of two inputs, the columns are matched by position.
+/// The output columns are named after the left input.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct SetOperationPlan {
+    pub op: SetOperator,
+    pub left: Arc<PlanNode>,
+    pub right: Arc<PlanNode>,
+    pub schema: DataSchemaRef,
+}
+
+impl SetOperationPlan {
+    /// The output schema, the types of the columns at the same position are coerced to a common type.
+    pub fn set_operation_schema(
+        left: &DataSchemaRef,
+        right: &DataSchemaRef,
+    ) -> Result<DataSchemaRef> {
+        if left.fields().len() != right.fields().len() {
+            return Result::Err(ErrorCode::BadArguments(format!(
+                "Set operation requires the same number of columns, left: {}, right: {}",
+                left.fields().len(),
+                right.fields().len()
+            )));
+        }
+
+        let fields = left
+            .fields()
+            .iter()
+            .zip(right.fields())
+            .map(|(l, r)| {
+                let data_type = compare_coercion(l.data_type(), r.data_type())?;
+                Ok(DataField::new(
+                    l.name(),
+                    data_type,
+                    l.is_nullable() || r.is_nullable(),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DataSchemaRefExt::create(fields))
+    }
+
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    pub fn get_inputs(&self) -> Vec<Arc<PlanNode>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    pub fn set_inputs(&mut self, inputs: Vec<&PlanNode>) {
+        assert_eq!(inputs.len(), 2);
+        self.left = Arc::new(inputs[0].clone());
+        self.right = Arc::new(inputs[1].clone());
+    }
+}
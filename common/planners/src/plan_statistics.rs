@@ -20,6 +20,26 @@ pub struct Statistics {
     pub read_bytes: usize,
     /// Is the statistics exact.
     pub is_exact: bool,
+    /// How the partitions are pruned, for the tables with indexes.
+    #[serde(default)]
+    pub pruning: Option<PruningStatistics>,
+}
+
+/// Partitions (e.g. the blocks of fuse tables) pruned by the indexes of the table.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct PruningStatistics {
+    /// Segments (groups of partitions) before pruning.
+    #[serde(default)]
+    pub segments_total: usize,
+    /// Segments pruned as a whole by the min/max statistics of them.
+    #[serde(default)]
+    pub pruned_segments: usize,
+    /// Partitions before pruning.
+    pub partitions_total: usize,
+    /// Partitions pruned by the min/max statistics.
+    pub pruned_by_range: usize,
+    /// Partitions pruned by the bloom filters.
+    pub pruned_by_bloom_filter: usize,
 }
 
 impl Statistics {
@@ -28,6 +48,7 @@ impl Statistics {
             read_rows,
             read_bytes,
             is_exact: false,
+            pruning: None,
         }
     }
 
@@ -36,6 +57,7 @@ impl Statistics {
             read_rows,
             read_bytes,
             is_exact: true,
+            pruning: None,
         }
     }
 
@@ -43,3 +65,19 @@ impl Statistics {
         *self = Self::default();
     }
 }
+
+impl PruningStatistics {
+    /// Partitions pruned by any of the indexes.
+    pub fn pruned_partitions(&self) -> usize {
+        self.pruned_by_range + self.pruned_by_bloom_filter
+    }
+
+    /// Accumulates the pruning of another scan, e.g. of another table of the same query.
+    pub fn merge(&mut self, other: &PruningStatistics) {
+        self.segments_total += other.segments_total;
+        self.pruned_segments += other.pruned_segments;
+        self.partitions_total += other.partitions_total;
+        self.pruned_by_range += other.pruned_by_range;
+        self.pruned_by_bloom_filter += other.pruned_by_bloom_filter;
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use common_datavalues::DataSchemaRef;
+
+use crate::Expression;
+use crate::PlanNode;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum WindowFrameUnits {
+    Rows,
+    Range,
+}
+
+impl fmt::Display for WindowFrameUnits {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            WindowFrameUnits::Rows => write!(f, "ROWS"),
+            WindowFrameUnits::Range => write!(f, "RANGE"),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum WindowFrameBound {
+    /// `UNBOUNDED PRECEDING` or `<N> PRECEDING`
+    Preceding(Option<u64>),
+    CurrentRow,
+    /// `UNBOUNDED FOLLOWING` or `<N> FOLLOWING`
+    Following(Option<u64>),
+}
+
+impl fmt::Display for WindowFrameBound {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            WindowFrameBound::Preceding(None) => write!(f, "UNBOUNDED PRECEDING"),
+            WindowFrameBound::Preceding(Some(n)) => write!(f, "{} PRECEDING", n),
+            WindowFrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            WindowFrameBound::Following(None) => write!(f, "UNBOUNDED FOLLOWING"),
+            WindowFrameBound::Following(Some(n)) => write!(f, "{} FOLLOWING", n),
+        }
+    }
+}
+
+/// The rows of the partition that an aggregate window function works on, relative to the current row.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start_bound: WindowFrameBound,
+    pub end_bound: WindowFrameBound,
+}
+
+impl WindowFrame {
+    /// The frame when it is not specified: from the start of the partition to the
+    /// last peer of the current row if there is ORDER BY, else the whole partition.
+    pub fn default_frame(has_order_by: bool) -> WindowFrame {
+        WindowFrame {
+            units: WindowFrameUnits::Range,
+            start_bound: WindowFrameBound::Preceding(None),
+            end_bound: match has_order_by {
+                true => WindowFrameBound::CurrentRow,
+                false => WindowFrameBound::Following(None),
+            },
+        }
+    }
+}
+
+impl fmt::Display for WindowFrame {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} BETWEEN {} AND {}",
+            self.units, self.start_bound, self.end_bound
+        )
+    }
+}
+
+/// Evaluates the window functions with the same PARTITION BY and ORDER BY,
+/// the results are appended to the input columns.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct WindowPlan {
+    /// The window functions, all of them are Expression::WindowFunction
+    pub window_funcs: Vec<Expression>,
+    /// The logical plan
+    pub input: Arc<PlanNode>,
+    /// Output data schema
+    pub schema: DataSchemaRef,
+}
+
+impl WindowPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    pub fn set_input(&mut self, node: &PlanNode) {
+        self.input = Arc::new(node.clone());
+    }
+
+    pub fn partition_by(&self) -> &[Expression] {
+        match self.window_funcs.first() {
+            Some(Expression::WindowFunction { partition_by, .. }) => partition_by,
+            _ => &[],
+        }
+    }
+
+    pub fn order_by(&self) -> &[Expression] {
+        match self.window_funcs.first() {
+            Some(Expression::WindowFunction { order_by, .. }) => order_by,
+            _ => &[],
+        }
+    }
+
+    /// The input is sorted by the partition keys and then the order keys.
+    pub fn sort_exprs(&self) -> Vec<Expression> {
+        self.partition_by()
+            .iter()
+            .map(|expr| Expression::Sort {
+                expr: Box::new(expr.clone()),
+                asc: true,
+                nulls_first: true,
+                origin_expr: Box::new(expr.clone()),
+            })
+            .chain(self.order_by().iter().cloned())
+            .collect()
+    }
+}
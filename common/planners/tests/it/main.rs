@@ -14,6 +14,7 @@
 
 mod plan_aggregator;
 mod plan_builder;
+mod plan_cte_scan;
 mod plan_describe_table;
 mod plan_display;
 mod plan_explain;
@@ -21,8 +22,11 @@ mod plan_expression;
 mod plan_extras;
 mod plan_filter;
 mod plan_having;
+mod plan_join;
 mod plan_limit;
 mod plan_projection;
 mod plan_rewriter;
 mod plan_select;
+mod plan_set_operation;
+mod plan_window;
 mod test;
@@ -0,0 +1,47 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_planners::*;
+use pretty_assertions::assert_eq;
+
+use crate::test::Test;
+
+#[test]
+fn test_cte_scan_plan() -> Result<()> {
+    let source = Test::create().generate_source_plan_for_test(10000)?;
+    let input = PlanBuilder::from(&source)
+        .project(&[col("number").alias("c")])?
+        .build()?;
+
+    let plan = PlanNode::CteScan(CteScanPlan {
+        cte_id: "_cte_t_0".to_string(),
+        name: "t".to_string(),
+        input: Arc::new(input),
+    });
+
+    let expect = "\
+    CteScan: t\
+    \n  Projection: number as c:UInt64\
+    \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10000, read_bytes: 80000]";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let schema = plan.schema();
+    assert_eq!(schema.fields().len(), 1);
+    assert_eq!(schema.field(0).name(), "c");
+    Ok(())
+}
@@ -0,0 +1,104 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::Result;
+use common_planners::*;
+use pretty_assertions::assert_eq;
+
+use crate::test::Test;
+
+fn qualified_source(qualifier: &str, total: usize) -> Result<PlanNode> {
+    let source = Test::create().generate_source_plan_for_test(total)?;
+    PlanBuilder::from(&source)
+        .project(&[col("number").alias(&format!("{}.number", qualifier))])?
+        .build()
+}
+
+#[test]
+fn test_join_plan() -> Result<()> {
+    let left = qualified_source("a", 10000)?;
+    let right = qualified_source("b", 100)?;
+    let plan = PlanBuilder::from(&left)
+        .join(
+            &right,
+            JoinType::Inner,
+            &[col("a.number")],
+            &[col("b.number")],
+            Some(col("a.number").gt(lit(1u64))),
+        )?
+        .project(&[col("a.number"), col("b.number")])?
+        .build()?;
+
+    let expect = "\
+    Projection: a.number:UInt64, b.number:UInt64\
+    \n  Join: INNER, on: [a.number = b.number], filter: (a.number > 1)\
+    \n    Projection: number as a.number:UInt64\
+    \n      ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10000, read_bytes: 80000]\
+    \n    Projection: number as b.number:UInt64\
+    \n      ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 100, read_bytes: 800]";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[test]
+fn test_join_plan_outer_schema() -> Result<()> {
+    let left = qualified_source("a", 10)?;
+    let right = qualified_source("b", 10)?;
+
+    let tests = vec![
+        (JoinType::Inner, false, false),
+        (JoinType::Left, false, true),
+        (JoinType::Right, true, false),
+        (JoinType::Full, true, true),
+        (JoinType::Cross, false, false),
+    ];
+
+    for (join_type, left_nullable, right_nullable) in tests {
+        let plan = PlanBuilder::from(&left)
+            .join(&right, join_type, &[], &[], None)?
+            .build()?;
+
+        let schema = plan.schema();
+        assert_eq!(
+            schema.field(0).is_nullable(),
+            left_nullable,
+            "{}",
+            join_type
+        );
+        assert_eq!(
+            schema.field(1).is_nullable(),
+            right_nullable,
+            "{}",
+            join_type
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_join_plan_error() -> Result<()> {
+    let source = Test::create().generate_source_plan_for_test(10)?;
+    let qualified = qualified_source("b", 10)?;
+
+    let result = PlanBuilder::from(&source).join(&source, JoinType::Inner, &[], &[], None);
+    let actual = result.err().unwrap();
+    let expect = "Code: 5, displayText = Duplicate column 'number' in join, the tables should be qualified by different names.";
+    assert_eq!(expect, format!("{}", actual));
+
+    let result =
+        PlanBuilder::from(&source).join(&qualified, JoinType::Inner, &[col("number")], &[], None);
+    assert!(result.is_err());
+    Ok(())
+}
@@ -0,0 +1,66 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::DataType;
+use common_exception::Result;
+use common_planners::*;
+use pretty_assertions::assert_eq;
+
+use crate::test::Test;
+
+#[test]
+fn test_set_operation_plan() -> Result<()> {
+    let left = Test::create().generate_source_plan_for_test(10000)?;
+    let right = Test::create().generate_source_plan_for_test(100)?;
+    let right = PlanBuilder::from(&right)
+        .project(&[Expression::Cast {
+            expr: Box::new(col("number")),
+            data_type: DataType::UInt8,
+        }
+        .alias("c")])?
+        .build()?;
+
+    let plan = PlanBuilder::from(&left)
+        .set_operation(&right, SetOperator::UnionAll)?
+        .build()?;
+
+    let expect = "\
+    SetOperation: UNION ALL\
+    \n  ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10000, read_bytes: 80000]\
+    \n  Projection: cast(c as UInt64) as number:UInt64\
+    \n    Projection: cast(number as UInt8) as c:UInt8\
+    \n      ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 100, read_bytes: 800]";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let schema = plan.schema();
+    assert_eq!(schema.fields().len(), 1);
+    assert_eq!(schema.field(0).name(), "number");
+    assert_eq!(schema.field(0).data_type(), &DataType::UInt64);
+    Ok(())
+}
+
+#[test]
+fn test_set_operation_plan_error() -> Result<()> {
+    let left = Test::create().generate_source_plan_for_test(10)?;
+    let right = PlanBuilder::from(&left)
+        .project(&[col("number"), col("number").alias("c")])?
+        .build()?;
+
+    let result = PlanBuilder::from(&left).set_operation(&right, SetOperator::Union);
+    let actual = result.err().unwrap();
+    let expect = "Code: 6, displayText = Set operation requires the same number of columns, left: 1, right: 2.";
+    assert_eq!(expect, format!("{}", actual));
+    Ok(())
+}
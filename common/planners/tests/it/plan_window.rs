@@ -0,0 +1,70 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::DataType;
+use common_exception::Result;
+use common_planners::*;
+use pretty_assertions::assert_eq;
+
+use crate::test::Test;
+
+#[test]
+fn test_window_plan() -> Result<()> {
+    let source = Test::create().generate_source_plan_for_test(10000)?;
+    let window_func = Expression::WindowFunction {
+        op: "sum".to_string(),
+        params: vec![],
+        args: vec![col("number")],
+        partition_by: vec![],
+        order_by: vec![sort("number", false, false)],
+        window_frame: Some(WindowFrame {
+            units: WindowFrameUnits::Rows,
+            start_bound: WindowFrameBound::Preceding(Some(1)),
+            end_bound: WindowFrameBound::CurrentRow,
+        }),
+    };
+    let plan = PlanBuilder::from(&source).window(&[window_func])?.build()?;
+
+    let expect = "\
+    Window: [sum(number) over (order by number desc ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)]\
+    \n  ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10000, read_bytes: 80000]";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let schema = plan.schema();
+    assert_eq!(schema.fields().len(), 2);
+    assert_eq!(schema.field(0).name(), "number");
+    assert_eq!(
+        schema.field(1).name(),
+        "sum(number) over (order by number desc ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)"
+    );
+    assert_eq!(schema.field(1).data_type(), &DataType::UInt64);
+    Ok(())
+}
+
+#[test]
+fn test_window_default_frame() -> Result<()> {
+    let frame = WindowFrame::default_frame(true);
+    assert_eq!(
+        frame.to_string(),
+        "RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW"
+    );
+
+    let frame = WindowFrame::default_frame(false);
+    assert_eq!(
+        frame.to_string(),
+        "RANGE BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING"
+    );
+    Ok(())
+}
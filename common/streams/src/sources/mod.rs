@@ -14,11 +14,13 @@
 
 mod source;
 mod source_csv;
+mod source_ndjson;
 mod source_parquet;
 mod source_values;
 
 pub use source::FormatSettings;
 pub use source::Source;
 pub use source_csv::CsvSource;
+pub use source_ndjson::NDJsonSource;
 pub use source_parquet::ParquetSource;
 pub use source_values::ValueSource;
@@ -33,11 +33,23 @@ pub struct CsvSource<R> {
 }
 
 impl<R> CsvSource<R>
-where R: tokio::io::AsyncRead + Unpin + Send + Sync
+where R: tokio::io::AsyncRead + Unpin + Send
 {
     pub fn new(reader: R, schema: DataSchemaRef, header: bool, block_size: usize) -> Self {
+        Self::with_delimiter(reader, schema, header, block_size, b',')
+    }
+
+    /// A source of the delimiter separated values, e.g. `b'\t'` for TSV.
+    pub fn with_delimiter(
+        reader: R,
+        schema: DataSchemaRef,
+        header: bool,
+        block_size: usize,
+        delimiter: u8,
+    ) -> Self {
         let reader = AsyncReaderBuilder::new()
             .has_headers(header)
+            .delimiter(delimiter)
             .create_reader(reader);
 
         Self {
@@ -51,7 +63,7 @@ where R: tokio::io::AsyncRead + Unpin + Send + Sync
 
 #[async_trait]
 impl<R> Source for CsvSource<R>
-where R: tokio::io::AsyncRead + Unpin + Send + Sync
+where R: tokio::io::AsyncRead + Unpin + Send
 {
     async fn read(&mut self) -> Result<Option<DataBlock>> {
         let mut desers = self
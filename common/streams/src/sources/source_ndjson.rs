@@ -0,0 +1,114 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use common_base::tokio;
+use common_base::tokio::io::AsyncBufReadExt;
+use common_base::tokio::io::BufReader;
+use common_base::tokio::io::Lines;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use serde_json::Value;
+
+use crate::Source;
+
+/// Newline delimited JSON, each line is an object of the columns by name, the missing ones
+/// are NULL.
+pub struct NDJsonSource<R> {
+    lines: Lines<BufReader<R>>,
+    schema: DataSchemaRef,
+    block_size: usize,
+    rows: usize,
+}
+
+impl<R> NDJsonSource<R>
+where R: tokio::io::AsyncRead + Unpin + Send
+{
+    pub fn new(reader: R, schema: DataSchemaRef, block_size: usize) -> Self {
+        Self {
+            lines: BufReader::new(reader).lines(),
+            schema,
+            block_size,
+            rows: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl<R> Source for NDJsonSource<R>
+where R: tokio::io::AsyncRead + Unpin + Send
+{
+    async fn read(&mut self) -> Result<Option<DataBlock>> {
+        let mut desers = self
+            .schema
+            .fields()
+            .iter()
+            .map(|f| f.data_type().create_serializer(self.block_size))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut rows = 0;
+        while rows < self.block_size {
+            let line = self
+                .lines
+                .next_line()
+                .await
+                .map_err_to_code(ErrorCode::BadBytes, || {
+                    format!("Read ndjson error at line {}", self.rows)
+                })?;
+            let line = match line {
+                Some(line) if line.trim().is_empty() => continue,
+                Some(line) => line,
+                None => break,
+            };
+
+            let value: Value = serde_json::from_str(&line)
+                .map_err_to_code(ErrorCode::BadBytes, || {
+                    format!("Parse ndjson error at line {}", self.rows)
+                })?;
+            let object = value.as_object().ok_or_else(|| {
+                ErrorCode::BadBytes(format!(
+                    "Parse ndjson error at line {}, expect an object",
+                    self.rows
+                ))
+            })?;
+
+            for (field, deser) in self.schema.fields().iter().zip(desers.iter_mut()) {
+                match object.get(field.name()) {
+                    None | Some(Value::Null) => deser.de_null(),
+                    Some(Value::String(v)) => deser.de_text(v.as_bytes())?,
+                    Some(v) => deser.de_text(v.to_string().as_bytes())?,
+                }
+            }
+            rows += 1;
+            self.rows += 1;
+        }
+
+        if rows == 0 {
+            return Ok(None);
+        }
+
+        let series = desers
+            .iter_mut()
+            .map(|deser| deser.finish_to_series())
+            .collect::<Vec<_>>();
+
+        Ok(Some(DataBlock::create_by_array(
+            self.schema.clone(),
+            series,
+        )))
+    }
+}
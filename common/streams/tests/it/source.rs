@@ -18,6 +18,7 @@ use common_datavalues::DataField;
 use common_datavalues::DataSchemaRefExt;
 use common_datavalues::DataType;
 use common_streams::CsvSource;
+use common_streams::NDJsonSource;
 use common_streams::Source;
 use common_streams::ValueSource;
 
@@ -78,3 +79,62 @@ async fn test_parse_csvs() {
     let block = csv_source.read().await.unwrap();
     assert!(block.is_none());
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_parse_tsvs() {
+    let buffer = "a\tb\n1\t1-1\n2\t2,2\n";
+
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int8, false),
+        DataField::new("b", DataType::String, false),
+    ]);
+    let mut tsv_source = CsvSource::with_delimiter(buffer.as_bytes(), schema, true, 10, b'\t');
+    let block = tsv_source.read().await.unwrap().unwrap();
+    assert_blocks_eq(
+        vec![
+            "+---+-----+",
+            "| a | b   |",
+            "+---+-----+",
+            "| 1 | 1-1 |",
+            "| 2 | 2,2 |",
+            "+---+-----+",
+        ],
+        &[block],
+    );
+
+    let block = tsv_source.read().await.unwrap();
+    assert!(block.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_parse_ndjsons() {
+    let buffer = "{\"a\": 1, \"b\": \"1\", \"c\": 1.11}\n\n{\"b\": \"2\", \"a\": 2}\n{\"a\": 3, \"b\": \"3\", \"c\": 3}\n";
+
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int8, false),
+        DataField::new("b", DataType::String, false),
+        DataField::new("c", DataType::Float64, true),
+    ]);
+    let mut ndjson_source = NDJsonSource::new(buffer.as_bytes(), schema.clone(), 2);
+    let block = ndjson_source.read().await.unwrap().unwrap();
+    assert_eq!(block.num_rows(), 2);
+    let block_2 = ndjson_source.read().await.unwrap().unwrap();
+    assert_blocks_eq(
+        vec![
+            "+---+---+------+",
+            "| a | b | c    |",
+            "+---+---+------+",
+            "| 1 | 1 | 1.11 |",
+            "| 2 | 2 | NULL |",
+            "| 3 | 3 | 3    |",
+            "+---+---+------+",
+        ],
+        &[block, block_2],
+    );
+
+    let block = ndjson_source.read().await.unwrap();
+    assert!(block.is_none());
+
+    let mut ndjson_source = NDJsonSource::new("[1, 2]\n".as_bytes(), schema, 2);
+    assert!(ndjson_source.read().await.is_err());
+}
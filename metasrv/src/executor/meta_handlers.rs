@@ -26,6 +26,7 @@ use common_meta_flight::GetDatabasesAction;
 use common_meta_flight::GetTableAction;
 use common_meta_flight::GetTableExtReq;
 use common_meta_flight::GetTablesAction;
+use common_meta_flight::UpsertMultiTableOptionsReq;
 use common_meta_flight::UpsertTableOptionReq;
 use common_meta_raft_store::state_machine::AppliedState;
 use common_meta_types::Change;
@@ -33,6 +34,7 @@ use common_meta_types::Cmd::CreateDatabase;
 use common_meta_types::Cmd::CreateTable;
 use common_meta_types::Cmd::DropDatabase;
 use common_meta_types::Cmd::DropTable;
+use common_meta_types::Cmd::UpsertMultiTableOptions;
 use common_meta_types::Cmd::UpsertTableOptions;
 use common_meta_types::CreateDatabaseReply;
 use common_meta_types::CreateTableReply;
@@ -346,3 +348,54 @@ impl RequestHandler<UpsertTableOptionReq> for ActionHandler {
         Ok(())
     }
 }
+
+#[async_trait::async_trait]
+impl RequestHandler<UpsertMultiTableOptionsReq> for ActionHandler {
+    async fn handle(
+        &self,
+        req: UpsertMultiTableOptionsReq,
+    ) -> common_exception::Result<UpsertTableOptionReply> {
+        let tables = req
+            .upserts
+            .iter()
+            .map(|upsert| {
+                let table_options = upsert
+                    .options
+                    .iter()
+                    .map(|(k, v)| (k.clone(), Some(v.clone())))
+                    .collect();
+                (
+                    upsert.table_id,
+                    MatchSeq::Exact(upsert.table_version),
+                    table_options,
+                )
+            })
+            .collect();
+
+        let cr = LogEntry {
+            txid: None,
+            cmd: UpsertMultiTableOptions { tables },
+        };
+
+        let res = self
+            .meta_node
+            .write(cr)
+            .await
+            .map_err(|e| ErrorCode::MetaNodeInternalError(e.to_string()))?;
+
+        if !res.changed() {
+            let chs: Vec<Change<TableMeta>> = res.try_into().unwrap();
+            for (upsert, ch) in req.upserts.iter().zip(chs.into_iter()) {
+                let (prev, _result) = ch.unwrap();
+                if prev.seq != upsert.table_version {
+                    return Err(ErrorCode::TableVersionMissMatch(format!(
+                        "table_id {} targeting version {}, current version {}",
+                        upsert.table_id, upsert.table_version, prev.seq,
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -24,8 +24,10 @@ use databend_query::api::RpcService;
 use databend_query::configs::Config;
 use databend_query::metrics::MetricService;
 use databend_query::servers::ClickHouseHandler;
+use databend_query::servers::ClickHouseHttpHandler;
 use databend_query::servers::HttpHandler;
 use databend_query::servers::MySQLHandler;
+use databend_query::servers::PostgresHandler;
 use databend_query::servers::Server;
 use databend_query::servers::ShutdownHandle;
 use databend_query::sessions::SessionManager;
@@ -101,6 +103,38 @@ async fn main(_global_tracker: Arc<RuntimeTracker>) -> common_exception::Result<
             listening.port(),
         );
     }
+    // ClickHouse HTTP handler.
+    {
+        let hostname = conf.query.clickhouse_http_handler_host.clone();
+        let listening = format!("{}:{}", hostname, conf.query.clickhouse_http_handler_port);
+
+        let mut srv = ClickHouseHttpHandler::create(session_manager.clone());
+        let listening = srv.start(listening.parse()?).await?;
+        shutdown_handle.add_service(srv);
+
+        info!(
+            "ClickHouse HTTP handler listening on {}, Usage: curl 'http://{}/?query=SELECT%201'",
+            listening, listening,
+        );
+    }
+
+    // PostgreSQL handler.
+    {
+        let hostname = conf.query.postgres_handler_host.clone();
+        let listening = format!("{}:{}", hostname, conf.query.postgres_handler_port);
+
+        let mut srv = PostgresHandler::create(session_manager.clone());
+        let listening = srv.start(listening.parse()?).await?;
+        shutdown_handle.add_service(srv);
+
+        info!(
+            "PostgreSQL handler listening on {}, Usage: psql -h {} -p {} -U root",
+            listening,
+            listening.ip(),
+            listening.port(),
+        );
+    }
+
     // HTTP handler.
     {
         let hostname = conf.query.http_handler_host.clone();
@@ -26,6 +26,7 @@ use common_meta_types::MetaVersion;
 use common_meta_types::TableIdent;
 use common_meta_types::TableInfo;
 use common_meta_types::TableMeta;
+use common_meta_types::TableOptionsUpsert;
 use common_meta_types::UpsertTableOptionReply;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
@@ -135,6 +136,14 @@ impl MetaApi for MetaRemote {
         .await
     }
 
+    async fn upsert_multi_table_options(
+        &self,
+        upserts: Vec<TableOptionsUpsert>,
+    ) -> Result<UpsertTableOptionReply> {
+        self.query_backend(move |cli| async move { cli.upsert_multi_table_options(upserts).await })
+            .await
+    }
+
     fn name(&self) -> String {
         "meta-remote".to_owned()
     }
@@ -21,6 +21,7 @@ use common_meta_types::MetaVersion;
 use common_meta_types::TableIdent;
 use common_meta_types::TableInfo;
 use common_meta_types::TableMeta;
+use common_meta_types::TableOptionsUpsert;
 use common_meta_types::UpsertTableOptionReply;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
@@ -76,6 +77,12 @@ pub trait Catalog {
         table_option_value: String,
     ) -> common_exception::Result<UpsertTableOptionReply>;
 
+    /// Upserts options of several tables at once, all or nothing.
+    async fn upsert_multi_table_options(
+        &self,
+        upserts: Vec<TableOptionsUpsert>,
+    ) -> common_exception::Result<UpsertTableOptionReply>;
+
     // Operation with database.
     async fn create_database(&self, plan: CreateDatabasePlan) -> Result<CreateDatabaseReply>;
 
@@ -30,6 +30,7 @@ use common_meta_types::MetaVersion;
 use common_meta_types::TableIdent;
 use common_meta_types::TableInfo;
 use common_meta_types::TableMeta;
+use common_meta_types::TableOptionsUpsert;
 use common_meta_types::UpsertTableOptionReply;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
@@ -44,6 +45,7 @@ use crate::catalogs::Table;
 use crate::common::MetaClientProvider;
 use crate::configs::Config;
 use crate::datasources::database::default::default_database::DefaultDatabase;
+use crate::datasources::table::fuse::FuseTableOptions;
 use crate::datasources::table::register_prelude_tbl_engines;
 use crate::datasources::table_engine_registry::TableEngineRegistry;
 
@@ -100,7 +102,7 @@ impl MetaStoreCatalog {
 
         let table_engine_registry = Arc::new(TableEngineRegistry::new());
 
-        register_prelude_tbl_engines(&table_engine_registry)?;
+        register_prelude_tbl_engines(&table_engine_registry, &conf.storage)?;
 
         let plan = CreateDatabasePlan {
             if_not_exists: true,
@@ -179,8 +181,18 @@ impl Catalog for MetaStoreCatalog {
             .await
     }
 
+    async fn upsert_multi_table_options(
+        &self,
+        upserts: Vec<TableOptionsUpsert>,
+    ) -> Result<UpsertTableOptionReply> {
+        self.meta.upsert_multi_table_options(upserts).await
+    }
+
     async fn create_table(&self, plan: CreateTablePlan) -> common_exception::Result<()> {
         // TODO validate table parameters by using TableFactory
+        if plan.table_meta.engine.eq_ignore_ascii_case("FUSE") {
+            FuseTableOptions::validate(&plan.table_meta)?;
+        }
         self.meta.create_table(plan).await?;
         Ok(())
     }
@@ -23,6 +23,7 @@ use common_meta_types::MetaVersion;
 use common_meta_types::TableIdent;
 use common_meta_types::TableInfo;
 use common_meta_types::TableMeta;
+use common_meta_types::TableOptionsUpsert;
 use common_meta_types::UpsertTableOptionReply;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
@@ -186,6 +187,14 @@ impl Catalog for OverlaidCatalog {
             .await
     }
 
+    async fn upsert_multi_table_options(
+        &self,
+        upserts: Vec<TableOptionsUpsert>,
+    ) -> common_exception::Result<UpsertTableOptionReply> {
+        // upsert table options in BOTTOM layer only
+        self.bottom.upsert_multi_table_options(upserts).await
+    }
+
     async fn create_database(
         &self,
         plan: CreateDatabasePlan,
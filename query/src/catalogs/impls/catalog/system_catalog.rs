@@ -23,6 +23,7 @@ use common_meta_types::MetaVersion;
 use common_meta_types::TableIdent;
 use common_meta_types::TableInfo;
 use common_meta_types::TableMeta;
+use common_meta_types::TableOptionsUpsert;
 use common_meta_types::UpsertTableOptionReply;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
@@ -151,6 +152,15 @@ impl Catalog for SystemCatalog {
         )))
     }
 
+    async fn upsert_multi_table_options(
+        &self,
+        _upserts: Vec<TableOptionsUpsert>,
+    ) -> Result<UpsertTableOptionReply> {
+        Err(ErrorCode::UnImplement(
+            "commit tables not allowed for system catalog",
+        ))
+    }
+
     async fn create_table(&self, _plan: CreateTablePlan) -> Result<()> {
         unimplemented!("programming error: SystemCatalog does not support create table")
     }
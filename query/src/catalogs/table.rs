@@ -16,20 +16,26 @@ use std::any::Any;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use chrono::DateTime;
+use chrono::Utc;
 use common_context::TableIOContext;
 use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_meta_types::MetaId;
 use common_meta_types::TableInfo;
+use common_planners::AlterTablePlan;
+use common_planners::DeletePlan;
 use common_planners::Expression;
 use common_planners::Extras;
 use common_planners::InsertIntoPlan;
+use common_planners::OptimizeTablePlan;
 use common_planners::Part;
 use common_planners::Partitions;
 use common_planners::ReadDataSourcePlan;
 use common_planners::Statistics;
 use common_planners::TruncateTablePlan;
+use common_planners::UpdatePlan;
 use common_streams::SendableDataBlockStream;
 
 #[async_trait::async_trait]
@@ -79,6 +85,18 @@ pub trait Table: Sync + Send {
         None
     }
 
+    /// Returns the table as it was at the given point of its history.
+    fn navigate_to(
+        &self,
+        _io_ctx: Arc<TableIOContext>,
+        _point: &NavigationPoint,
+    ) -> Result<Arc<dyn Table>> {
+        Err(ErrorCode::UnImplement(format!(
+            "time travel for table {} is not supported",
+            self.name()
+        )))
+    }
+
     // Read block data from the underling.
     async fn read(
         &self,
@@ -109,10 +127,49 @@ pub trait Table: Sync + Send {
             self.name()
         )))
     }
+
+    async fn delete(&self, _io_ctx: Arc<TableIOContext>, _delete_plan: DeletePlan) -> Result<()> {
+        Err(ErrorCode::UnImplement(format!(
+            "delete for local table {} is not implemented",
+            self.name()
+        )))
+    }
+
+    async fn update(&self, _io_ctx: Arc<TableIOContext>, _update_plan: UpdatePlan) -> Result<()> {
+        Err(ErrorCode::UnImplement(format!(
+            "update for local table {} is not implemented",
+            self.name()
+        )))
+    }
+
+    async fn optimize(
+        &self,
+        _io_ctx: Arc<TableIOContext>,
+        _optimize_plan: OptimizeTablePlan,
+    ) -> Result<()> {
+        Err(ErrorCode::UnImplement(format!(
+            "optimize for local table {} is not implemented",
+            self.name()
+        )))
+    }
+
+    async fn alter(&self, _io_ctx: Arc<TableIOContext>, _alter_plan: AlterTablePlan) -> Result<()> {
+        Err(ErrorCode::UnImplement(format!(
+            "alter for local table {} is not implemented",
+            self.name()
+        )))
+    }
 }
 
 pub type TablePtr = Arc<dyn Table>;
 
+/// A point in the history of a table, used by `AT (SNAPSHOT => ...)` and `AT (TIMESTAMP => ...)`
+#[derive(Clone, Debug, PartialEq)]
+pub enum NavigationPoint {
+    SnapshotID(String),
+    TimePoint(DateTime<Utc>),
+}
+
 pub trait ToReadDataSourcePlan {
     /// Real read_plan to access partitions/push_downs
     fn read_plan(
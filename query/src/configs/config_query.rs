@@ -26,12 +26,20 @@ pub const QUERY_MYSQL_HANDLER_PORT: &str = "QUERY_MYSQL_HANDLER_PORT";
 pub const QUERY_MAX_ACTIVE_SESSIONS: &str = "QUERY_MAX_ACTIVE_SESSIONS";
 pub const QUERY_CLICKHOUSE_HANDLER_HOST: &str = "QUERY_CLICKHOUSE_HANDLER_HOST";
 pub const QUERY_CLICKHOUSE_HANDLER_PORT: &str = "QUERY_CLICKHOUSE_HANDLER_PORT";
+pub const QUERY_CLICKHOUSE_HTTP_HANDLER_HOST: &str = "QUERY_CLICKHOUSE_HTTP_HANDLER_HOST";
+pub const QUERY_CLICKHOUSE_HTTP_HANDLER_PORT: &str = "QUERY_CLICKHOUSE_HTTP_HANDLER_PORT";
+pub const QUERY_POSTGRES_HANDLER_HOST: &str = "QUERY_POSTGRES_HANDLER_HOST";
+pub const QUERY_POSTGRES_HANDLER_PORT: &str = "QUERY_POSTGRES_HANDLER_PORT";
+pub const QUERY_POSTGRES_HANDLER_AUTH_METHOD: &str = "QUERY_POSTGRES_HANDLER_AUTH_METHOD";
 pub const QUERY_HTTP_HANDLER_HOST: &str = "QUERY_HTTP_HANDLER_HOST";
 pub const QUERY_HTTP_HANDLER_PORT: &str = "QUERY_HTTP_HANDLER_PORT";
 pub const QUERY_FLIGHT_API_ADDRESS: &str = "QUERY_FLIGHT_API_ADDRESS";
 pub const QUERY_HTTP_API_ADDRESS: &str = "QUERY_HTTP_API_ADDRESS";
 pub const QUERY_METRICS_API_ADDRESS: &str = "QUERY_METRIC_API_ADDRESS";
 pub const QUERY_WAIT_TIMEOUT_MILLS: &str = "QUERY_WAIT_TIMEOUT_MILLS";
+pub const QUERY_TABLE_META_CACHE_MB_SIZE: &str = "QUERY_TABLE_META_CACHE_MB_SIZE";
+pub const QUERY_TABLE_DISK_CACHE_ROOT: &str = "QUERY_TABLE_DISK_CACHE_ROOT";
+pub const QUERY_TABLE_DISK_CACHE_MB_SIZE: &str = "QUERY_TABLE_DISK_CACHE_MB_SIZE";
 const QUERY_API_TLS_SERVER_CERT: &str = "QUERY_API_TLS_SERVER_CERT";
 const QUERY_API_TLS_SERVER_KEY: &str = "QUERY_API_TLS_SERVER_KEY";
 const QUERY_API_TLS_SERVER_ROOT_CA_CERT: &str = "QUERY_API_TLS_SERVER_ROOT_CA_CERT";
@@ -95,6 +103,47 @@ pub struct QueryConfig {
     #[serde(default)]
     pub clickhouse_handler_port: u16,
 
+    #[structopt(
+    long,
+    env = QUERY_CLICKHOUSE_HTTP_HANDLER_HOST,
+    default_value = "127.0.0.1"
+    )]
+    #[serde(default)]
+    pub clickhouse_http_handler_host: String,
+
+    #[structopt(
+    long,
+    env = QUERY_CLICKHOUSE_HTTP_HANDLER_PORT,
+    default_value = "8123"
+    )]
+    #[serde(default)]
+    pub clickhouse_http_handler_port: u16,
+
+    #[structopt(
+    long,
+    env = QUERY_POSTGRES_HANDLER_HOST,
+    default_value = "127.0.0.1"
+    )]
+    #[serde(default)]
+    pub postgres_handler_host: String,
+
+    #[structopt(
+    long,
+    env = QUERY_POSTGRES_HANDLER_PORT,
+    default_value = "5432"
+    )]
+    #[serde(default)]
+    pub postgres_handler_port: u16,
+
+    #[structopt(
+        long,
+        env = QUERY_POSTGRES_HANDLER_AUTH_METHOD,
+        default_value = "scram-sha-256",
+        help = "Authentication of the users with plaintext passwords by the PostgreSQL handler, md5 or scram-sha-256"
+    )]
+    #[serde(default)]
+    pub postgres_handler_auth_method: String,
+
     #[structopt(
     long,
     env = QUERY_HTTP_HANDLER_HOST,
@@ -188,6 +237,33 @@ pub struct QueryConfig {
         )]
     #[serde(default)]
     pub wait_timeout_mills: u64,
+
+    #[structopt(
+        long,
+        env = QUERY_TABLE_META_CACHE_MB_SIZE,
+        default_value = "256",
+        help = "Size of the in-memory cache of the fuse table snapshots, segments and bloom filters, 0 to disable"
+    )]
+    #[serde(default)]
+    pub table_meta_cache_mb_size: u64,
+
+    #[structopt(
+        long,
+        env = QUERY_TABLE_DISK_CACHE_ROOT,
+        default_value = "./_cache",
+        help = "Directory of the on-disk cache of the fuse table blocks"
+    )]
+    #[serde(default)]
+    pub table_disk_cache_root: String,
+
+    #[structopt(
+        long,
+        env = QUERY_TABLE_DISK_CACHE_MB_SIZE,
+        default_value = "0",
+        help = "Size of the on-disk cache of the fuse table blocks, 0 to disable"
+    )]
+    #[serde(default)]
+    pub table_disk_cache_mb_size: u64,
 }
 
 impl QueryConfig {
@@ -201,6 +277,11 @@ impl QueryConfig {
             max_active_sessions: 256,
             clickhouse_handler_host: "127.0.0.1".to_string(),
             clickhouse_handler_port: 9000,
+            clickhouse_http_handler_host: "127.0.0.1".to_string(),
+            clickhouse_http_handler_port: 8123,
+            postgres_handler_host: "127.0.0.1".to_string(),
+            postgres_handler_port: 5432,
+            postgres_handler_auth_method: "scram-sha-256".to_string(),
             http_handler_host: "127.0.0.1".to_string(),
             http_handler_port: 8000,
             flight_api_address: "127.0.0.1:9090".to_string(),
@@ -214,6 +295,9 @@ impl QueryConfig {
             rpc_tls_query_server_root_ca_cert: "".to_string(),
             rpc_tls_query_service_domain_name: "localhost".to_string(),
             wait_timeout_mills: 5000,
+            table_meta_cache_mb_size: 256,
+            table_disk_cache_root: "./_cache".to_string(),
+            table_disk_cache_mb_size: 0,
         }
     }
 
@@ -256,6 +340,41 @@ impl QueryConfig {
             u16,
             QUERY_CLICKHOUSE_HANDLER_PORT
         );
+        env_helper!(
+            mut_config,
+            query,
+            clickhouse_http_handler_host,
+            String,
+            QUERY_CLICKHOUSE_HTTP_HANDLER_HOST
+        );
+        env_helper!(
+            mut_config,
+            query,
+            clickhouse_http_handler_port,
+            u16,
+            QUERY_CLICKHOUSE_HTTP_HANDLER_PORT
+        );
+        env_helper!(
+            mut_config,
+            query,
+            postgres_handler_host,
+            String,
+            QUERY_POSTGRES_HANDLER_HOST
+        );
+        env_helper!(
+            mut_config,
+            query,
+            postgres_handler_port,
+            u16,
+            QUERY_POSTGRES_HANDLER_PORT
+        );
+        env_helper!(
+            mut_config,
+            query,
+            postgres_handler_auth_method,
+            String,
+            QUERY_POSTGRES_HANDLER_AUTH_METHOD
+        );
         env_helper!(
             mut_config,
             query,
@@ -335,5 +454,28 @@ impl QueryConfig {
             u64,
             QUERY_WAIT_TIMEOUT_MILLS
         );
+
+        // for the caches of the fuse tables
+        env_helper!(
+            mut_config,
+            query,
+            table_meta_cache_mb_size,
+            u64,
+            QUERY_TABLE_META_CACHE_MB_SIZE
+        );
+        env_helper!(
+            mut_config,
+            query,
+            table_disk_cache_root,
+            String,
+            QUERY_TABLE_DISK_CACHE_ROOT
+        );
+        env_helper!(
+            mut_config,
+            query,
+            table_disk_cache_mb_size,
+            u64,
+            QUERY_TABLE_DISK_CACHE_MB_SIZE
+        );
     }
 }
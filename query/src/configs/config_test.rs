@@ -45,6 +45,11 @@ mysql_handler_port = 3307
 max_active_sessions = 256
 clickhouse_handler_host = \"127.0.0.1\"
 clickhouse_handler_port = 9000
+clickhouse_http_handler_host = \"127.0.0.1\"
+clickhouse_http_handler_port = 8123
+postgres_handler_host = \"127.0.0.1\"
+postgres_handler_port = 5432
+postgres_handler_auth_method = \"scram-sha-256\"
 http_handler_host = \"127.0.0.1\"
 http_handler_port = 8000
 flight_api_address = \"127.0.0.1:9090\"
@@ -58,6 +63,9 @@ rpc_tls_server_key = \"\"
 rpc_tls_query_server_root_ca_cert = \"\"
 rpc_tls_query_service_domain_name = \"localhost\"
 wait_timeout_mills = 5000
+table_meta_cache_mb_size = 256
+table_disk_cache_root = \"./_cache\"
+table_disk_cache_mb_size = 0
 
 [log]
 log_level = \"INFO\"
@@ -108,6 +116,11 @@ fn test_env_config() -> Result<()> {
     std::env::set_var("QUERY_MAX_ACTIVE_SESSIONS", "255");
     std::env::set_var("QUERY_CLICKHOUSE_HANDLER_HOST", "1.2.3.4");
     std::env::set_var("QUERY_CLICKHOUSE_HANDLER_PORT", "9000");
+    std::env::set_var("QUERY_CLICKHOUSE_HTTP_HANDLER_HOST", "1.2.3.4");
+    std::env::set_var("QUERY_CLICKHOUSE_HTTP_HANDLER_PORT", "8124");
+    std::env::set_var("QUERY_POSTGRES_HANDLER_HOST", "1.2.3.4");
+    std::env::set_var("QUERY_POSTGRES_HANDLER_PORT", "5433");
+    std::env::set_var("QUERY_POSTGRES_HANDLER_AUTH_METHOD", "md5");
     std::env::set_var("QUERY_FLIGHT_API_ADDRESS", "1.2.3.4:9091");
     std::env::set_var("QUERY_HTTP_API_ADDRESS", "1.2.3.4:8081");
     std::env::set_var("QUERY_METRIC_API_ADDRESS", "1.2.3.4:7071");
@@ -131,6 +144,11 @@ fn test_env_config() -> Result<()> {
     assert_eq!(255, configured.query.max_active_sessions);
     assert_eq!("1.2.3.4", configured.query.clickhouse_handler_host);
     assert_eq!(9000, configured.query.clickhouse_handler_port);
+    assert_eq!("1.2.3.4", configured.query.clickhouse_http_handler_host);
+    assert_eq!(8124, configured.query.clickhouse_http_handler_port);
+    assert_eq!("1.2.3.4", configured.query.postgres_handler_host);
+    assert_eq!(5433, configured.query.postgres_handler_port);
+    assert_eq!("md5", configured.query.postgres_handler_auth_method);
 
     assert_eq!("1.2.3.4:9091", configured.query.flight_api_address);
     assert_eq!("1.2.3.4:8081", configured.query.http_api_address);
@@ -156,6 +174,11 @@ fn test_env_config() -> Result<()> {
     std::env::remove_var("QUERY_CLICKHOUSE_HANDLER_HOST");
     std::env::remove_var("QUERY_CLICKHOUSE_HANDLER_PORT");
     std::env::remove_var("QUERY_CLICKHOUSE_HANDLER_THREAD_NUM");
+    std::env::remove_var("QUERY_CLICKHOUSE_HTTP_HANDLER_HOST");
+    std::env::remove_var("QUERY_CLICKHOUSE_HTTP_HANDLER_PORT");
+    std::env::remove_var("QUERY_POSTGRES_HANDLER_HOST");
+    std::env::remove_var("QUERY_POSTGRES_HANDLER_PORT");
+    std::env::remove_var("QUERY_POSTGRES_HANDLER_AUTH_METHOD");
     std::env::remove_var("QUERY_FLIGHT_API_ADDRESS");
     std::env::remove_var("QUERY_HTTP_API_ADDRESS");
     std::env::remove_var("QUERY_METRIC_API_ADDRESS");
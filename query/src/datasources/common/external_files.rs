@@ -0,0 +1,106 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use common_dal::DataAccessor;
+use common_dal::DataAccessorBuilder;
+use common_dal::Local;
+use common_dal::ObjectMeta;
+use common_dal::StorageScheme;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use glob::MatchOptions;
+use glob::Pattern;
+
+use crate::configs::StorageConfig;
+use crate::datasources::common::ContextDalBuilder;
+
+const GLOB_CHARS: &[char] = &['*', '?', '['];
+
+/// The data accessor of the files of the external tables (the CSV and Parquet engines).
+///
+/// On the disk storage, the locations are local paths, relative to `temp_data_path`; on the
+/// other storages, they are the keys of the objects in the bucket (container) of the storage.
+pub fn external_data_accessor(storage_conf: &StorageConfig) -> Result<Arc<dyn DataAccessor>> {
+    match StorageScheme::from_str(&storage_conf.storage_type)? {
+        StorageScheme::LocalFs => Ok(Arc::new(Local::new(&storage_conf.disk.temp_data_path))),
+        _ => ContextDalBuilder::new(storage_conf.clone()).build(),
+    }
+}
+
+/// The files of an external table, sorted by location, `location` is one of
+///
+/// - a file, e.g. `data/sample.csv`
+/// - a prefix (directory), e.g. `data/` or `data`, all the files under it
+/// - a glob pattern, e.g. `data/*.csv` or `data/**/2021-*.parquet`, `*` and `?` do not
+///   match `/`, while `**` matches any directories
+pub async fn list_external_files(da: &dyn DataAccessor, location: &str) -> Result<Vec<ObjectMeta>> {
+    let location = normalize_location(location);
+    let location = location.as_str();
+    let mut files = match location.find(GLOB_CHARS) {
+        None => {
+            let dir = match location.is_empty() || location.ends_with('/') {
+                true => location.to_string(),
+                false => format!("{}/", location),
+            };
+            da.list_objects(location)
+                .await?
+                .into_iter()
+                .filter(|file| file.location == location || file.location.starts_with(&dir))
+                .collect::<Vec<_>>()
+        }
+        Some(pos) => {
+            let pattern = Pattern::new(location).map_err(|e| {
+                ErrorCode::BadOption(format!("Invalid location pattern {}: {}", location, e))
+            })?;
+            let options = MatchOptions {
+                require_literal_separator: true,
+                ..MatchOptions::default()
+            };
+            // the directory before the first wildcard
+            let prefix = match location[..pos].rfind('/') {
+                Some(slash) => &location[..=slash],
+                None => "",
+            };
+            da.list_objects(prefix)
+                .await?
+                .into_iter()
+                .filter(|file| pattern.matches_with(&file.location, options))
+                .collect::<Vec<_>>()
+        }
+    };
+    files.sort_by(|a, b| a.location.cmp(&b.location));
+    Ok(files)
+}
+
+/// Resolves the `.` and `..` segments, the listed locations never contain them.
+fn normalize_location(location: &str) -> String {
+    let mut segments: Vec<&str> = vec![];
+    for segment in location.split('/') {
+        match segment {
+            "." => {}
+            ".." if matches!(segments.last(), Some(s) if !s.is_empty() && *s != "..") => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    let mut normalized = segments.join("/");
+    if location.ends_with('/') && !normalized.ends_with('/') {
+        normalized.push('/');
+    }
+    normalized
+}
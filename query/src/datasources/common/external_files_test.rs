@@ -0,0 +1,87 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use common_base::tokio;
+use common_dal::DataAccessor;
+use common_dal::Local;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+use crate::datasources::common::list_external_files;
+
+#[tokio::test]
+async fn test_list_external_files() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let da = Local::with_path(tmp_dir.path().to_owned());
+
+    for path in [
+        "data/a.csv",
+        "data/b.csv",
+        "data/b.parquet",
+        "data/2021/c.csv",
+        "data_1/d.csv",
+    ] {
+        da.put(path, path.as_bytes().to_vec()).await?;
+    }
+
+    let da = &da;
+    let list = |location: &'static str| async move {
+        let files = list_external_files(da, location).await?;
+        Result::Ok(files.into_iter().map(|f| f.location).collect::<Vec<_>>())
+    };
+
+    // a file
+    assert_eq!(list("data/a.csv").await?, vec!["data/a.csv"]);
+    assert_eq!(list("data/./2021/../a.csv").await?, vec!["data/a.csv"]);
+    assert!(list("data/not_exists.csv").await?.is_empty());
+
+    // a prefix, with or without the trailing slash
+    let all = vec![
+        "data/2021/c.csv",
+        "data/a.csv",
+        "data/b.csv",
+        "data/b.parquet",
+    ];
+    assert_eq!(list("data").await?, all);
+    assert_eq!(list("data/").await?, all);
+
+    // glob patterns
+    assert_eq!(list("data/*.csv").await?, vec!["data/a.csv", "data/b.csv"]);
+    assert_eq!(list("data/**/*.csv").await?, vec![
+        "data/2021/c.csv",
+        "data/a.csv",
+        "data/b.csv"
+    ]);
+    assert_eq!(list("data*/?.csv").await?, vec![
+        "data/a.csv",
+        "data/b.csv",
+        "data_1/d.csv"
+    ]);
+    assert_eq!(list("data/[ab].parquet").await?, vec!["data/b.parquet"]);
+
+    match list("data/[a.csv").await {
+        Err(e) => assert_eq!(e.code(), ErrorCode::BadOption("").code()),
+        Ok(_) => panic!("expect an invalid pattern"),
+    }
+
+    // along with the sizes
+    let files = list_external_files(da, "data/2021").await?;
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].size, "data/2021/c.csv".len() as u64);
+
+    Ok(())
+}
@@ -14,16 +14,21 @@
 //
 
 pub use dal_builder::ContextDalBuilder;
+pub use external_files::external_data_accessor;
+pub use external_files::list_external_files;
 pub use line::count_lines;
 pub use part::generate_parts;
 
 #[cfg(test)]
 mod dal_builder_test;
 #[cfg(test)]
+mod external_files_test;
+#[cfg(test)]
 mod line_test;
 #[cfg(test)]
 mod part_test;
 
 mod dal_builder;
+mod external_files;
 mod line;
 mod part;
@@ -40,7 +40,7 @@ async fn test_configs_table() -> Result<()> {
     let result = stream.try_collect::<Vec<_>>().await?;
     let block = &result[0];
     assert_eq!(block.num_columns(), 4);
-    assert_eq!(block.num_rows(), 30);
+    assert_eq!(block.num_rows(), 38);
 
     let expected = vec![
         "+-----------------------------------+------------------+-------+-------------+",
@@ -51,6 +51,8 @@ async fn test_configs_table() -> Result<()> {
         "| api_tls_server_root_ca_cert       |                  | query |             |",
         "| clickhouse_handler_host           | 127.0.0.1        | query |             |",
         "| clickhouse_handler_port           | 9000             | query |             |",
+        "| clickhouse_http_handler_host      | 127.0.0.1        | query |             |",
+        "| clickhouse_http_handler_port      | 8123             | query |             |",
         "| cluster_id                        |                  | query |             |",
         "| flight_api_address                | 127.0.0.1:9090   | query |             |",
         "| http_api_address                  | 127.0.0.1:8080   | query |             |",
@@ -68,12 +70,18 @@ async fn test_configs_table() -> Result<()> {
         "| mysql_handler_host                | 127.0.0.1        | query |             |",
         "| mysql_handler_port                | 3307             | query |             |",
         "| num_cpus                          | 8                | query |             |",
+        "| postgres_handler_auth_method      | scram-sha-256    | query |             |",
+        "| postgres_handler_host             | 127.0.0.1        | query |             |",
+        "| postgres_handler_port             | 5432             | query |             |",
         "| rpc_tls_meta_server_root_ca_cert  |                  | meta  |             |",
         "| rpc_tls_meta_service_domain_name  | localhost        | meta  |             |",
         "| rpc_tls_query_server_root_ca_cert |                  | query |             |",
         "| rpc_tls_query_service_domain_name | localhost        | query |             |",
         "| rpc_tls_server_cert               |                  | query |             |",
         "| rpc_tls_server_key                |                  | query |             |",
+        "| table_disk_cache_mb_size          | 0                | query |             |",
+        "| table_disk_cache_root             | ./_cache         | query |             |",
+        "| table_meta_cache_mb_size          | 256              | query |             |",
         "| tenant_id                         |                  | query |             |",
         "| wait_timeout_mills                | 5000             | query |             |",
         "+-----------------------------------+------------------+-------+-------------+",
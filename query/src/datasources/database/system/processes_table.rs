@@ -49,6 +49,8 @@ impl ProcessesTable {
             DataField::new("database", DataType::String, false),
             DataField::new("extra_info", DataType::String, true),
             DataField::new("memory_usage", DataType::UInt64, true),
+            DataField::new("pruned_segments", DataType::UInt64, true),
+            DataField::new("pruned_partitions", DataType::UInt64, true),
         ]);
 
         let table_info = TableInfo {
@@ -107,6 +109,8 @@ impl Table for ProcessesTable {
         let mut processes_database = Vec::with_capacity(processes_info.len());
         let mut processes_extra_info = Vec::with_capacity(processes_info.len());
         let mut processes_memory_usage = Vec::with_capacity(processes_info.len());
+        let mut processes_pruned_segments = Vec::with_capacity(processes_info.len());
+        let mut processes_pruned_partitions = Vec::with_capacity(processes_info.len());
 
         for process_info in &processes_info {
             processes_id.push(process_info.id.clone().into_bytes());
@@ -116,6 +120,9 @@ impl Table for ProcessesTable {
             processes_host.push(ProcessesTable::process_host(process_info));
             processes_extra_info.push(ProcessesTable::process_extra_info(process_info));
             processes_memory_usage.push(process_info.memory_usage);
+            let pruning = process_info.pruning.as_ref();
+            processes_pruned_segments.push(pruning.map(|p| p.pruned_segments as u64));
+            processes_pruned_partitions.push(pruning.map(|p| p.pruned_partitions() as u64));
         }
 
         let schema = self.table_info.schema();
@@ -127,6 +134,8 @@ impl Table for ProcessesTable {
             Series::new(processes_database),
             Series::new(processes_extra_info),
             Series::new(processes_memory_usage),
+            Series::new(processes_pruned_segments),
+            Series::new(processes_pruned_partitions),
         ]);
 
         Ok(Box::pin(DataBlockStream::create(schema, None, vec![block])))
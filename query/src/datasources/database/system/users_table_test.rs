@@ -40,6 +40,7 @@ async fn test_users_table() -> Result<()> {
             auth_type: AuthType::None,
             privileges: UserPrivilege::empty(),
             quota: UserQuota::no_limit(),
+            scram_verifier: None,
         })
         .await?;
     ctx.get_sessions_manager()
@@ -51,6 +52,7 @@ async fn test_users_table() -> Result<()> {
             auth_type: AuthType::PlainText,
             privileges: UserPrivilege::empty(),
             quota: UserQuota::no_limit(),
+            scram_verifier: None,
         })
         .await?;
 
@@ -74,6 +74,16 @@ fn test_range_filter() -> Result<()> {
             expr: col("a").eq(lit(1)).and(col("b").not_eq(lit(3))),
             expect: true,
         },
+        Test {
+            name: "a in (21, 30)",
+            expr: col("a").eq(lit(21)).or(col("a").eq(lit(30))),
+            expect: false,
+        },
+        Test {
+            name: "b is null",
+            expr: Expression::create_scalar_function("isNull", vec![col("b")]),
+            expect: false,
+        },
         Test {
             name: "a is null",
             expr: Expression::create_scalar_function("isNull", vec![col("a")]),
@@ -165,6 +175,16 @@ fn test_build_verifiable_function() -> Result<()> {
             expr: col("a").eq(lit(1)).and(col("b").not_eq(lit(3))),
             expect: "(((min_a <= 1) and (max_a >= 1)) and ((min_b != 3) or (max_b != 3)))",
         },
+        Test {
+            name: "a between 1 and 5",
+            expr: col("a").gt_eq(lit(1)).and(col("a").lt_eq(lit(5))),
+            expect: "((max_a >= 1) and (min_a <= 5))",
+        },
+        Test {
+            name: "a in (1, 5)",
+            expr: col("a").eq(lit(1)).or(col("a").eq(lit(5))),
+            expect: "(((min_a <= 1) and (max_a >= 1)) or ((min_a <= 5) and (max_a >= 5)))",
+        },
         Test {
             name: "a is null",
             expr: Expression::create_scalar_function("isNull", vec![col("a")]),
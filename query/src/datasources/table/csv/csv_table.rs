@@ -14,15 +14,16 @@
 //
 
 use std::any::Any;
-use std::fs::File;
 use std::sync::Arc;
 
+use async_compat::CompatExt;
 use async_stream::stream;
-use common_base::tokio;
+use common_base::BlockingWait;
 use common_context::DataContext;
 use common_context::IOContext;
 use common_context::TableIOContext;
-use common_dal::Local;
+use common_dal::DataAccessor;
+use common_dal::ObjectMeta;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_meta_types::TableInfo;
@@ -34,26 +35,35 @@ use common_planners::Statistics;
 use common_streams::CsvSource;
 use common_streams::SendableDataBlockStream;
 use common_streams::Source;
+use futures::AsyncReadExt;
 
 use crate::catalogs::Table;
 use crate::datasources::common::count_lines;
+use crate::datasources::common::external_data_accessor;
+use crate::datasources::common::list_external_files;
 use crate::sessions::DatabendQueryContext;
 
 pub struct CsvTable {
     table_info: TableInfo,
-    // TODO: support s3 protocol && support gob matcher files
-    file: String,
+    // a file, a prefix or a glob pattern, see `list_external_files`
+    location: String,
     has_header: bool,
+    // the files are on the disk of each node, rather than shared by the cluster
+    local_storage: bool,
 }
 
+/// Bytes read from the head of the first file, to estimate the rows of the table.
+const ROWS_SAMPLE_BYTES: u64 = 64 * 1024;
+
 impl CsvTable {
     pub fn try_create(
         table_info: TableInfo,
         _data_ctx: Arc<dyn DataContext<u64>>,
+        local_storage: bool,
     ) -> Result<Box<dyn Table>> {
         let options = table_info.options();
         let has_header = options.get("has_header").is_some();
-        let file = match options.get("location") {
+        let location = match options.get("location") {
             None => {
                 return Result::Err(ErrorCode::BadOption(
                     "CSV Engine must contains file location options",
@@ -64,14 +74,19 @@ impl CsvTable {
 
         Ok(Box::new(Self {
             table_info,
-            file,
+            location,
             has_header,
+            local_storage,
         }))
     }
 }
 
 #[async_trait::async_trait]
 impl Table for CsvTable {
+    fn is_local(&self) -> bool {
+        self.local_storage
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -82,18 +97,32 @@ impl Table for CsvTable {
 
     fn read_partitions(
         &self,
-        _io_ctx: Arc<TableIOContext>,
+        io_ctx: Arc<TableIOContext>,
         _push_downs: Option<Extras>,
     ) -> Result<(Statistics, Partitions)> {
-        let file = &self.file;
-        let lines_count = count_lines(File::open(file.clone())?)?;
-        let bytes = File::open(file.clone())?.metadata()?.len() as usize;
-
-        let parts = vec![Part {
-            name: file.clone(),
-            version: 0,
-        }];
-        Ok((Statistics::new_estimated(lines_count, bytes), parts))
+        let ctx: Arc<DatabendQueryContext> = io_ctx
+            .get_user_data()?
+            .expect("DatabendQueryContext should not be None");
+        let da = external_data_accessor(&ctx.get_config().storage)?;
+        let location = self.location.clone();
+        let has_header = self.has_header;
+        let (files, rows) = async move {
+            let files = list_external_files(da.as_ref(), &location).await?;
+            let rows = estimate_rows(da.as_ref(), &files, has_header).await?;
+            Result::Ok((files, rows))
+        }
+        .wait_in(&io_ctx.get_runtime(), None)??;
+
+        let bytes = files.iter().map(|file| file.size as usize).sum();
+        // one partition per file
+        let parts = files
+            .into_iter()
+            .map(|file| Part {
+                name: file.location,
+                version: 0,
+            })
+            .collect();
+        Ok((Statistics::new_estimated(rows, bytes), parts))
     }
 
     async fn read(
@@ -105,8 +134,7 @@ impl Table for CsvTable {
             .get_user_data()?
             .expect("DatabendQueryContext should not be None");
 
-        let conf = ctx.get_config().storage.disk;
-        let local = Local::new(conf.temp_data_path.as_str());
+        let da = external_data_accessor(&ctx.get_config().storage)?;
 
         let ctx_clone = ctx.clone();
         let schema = plan.schema();
@@ -122,13 +150,9 @@ impl Table for CsvTable {
                             break;
                         }
 
-                        /// TODO use dal, but inputstream is not send which is need for csv-async reader
                         let part = partitions.get(0).unwrap();
-                        let file = part.name.clone();
-
-                        let path = local.prefix_with_root(&file)?;
-                        let std_file = std::fs::File::open(path)?;
-                        let reader = tokio::fs::File::from_std(std_file);
+                        let input = da.get_input_stream(&part.name, None)?;
+                        let reader = input.compat();
 
                         let mut source = CsvSource::new(reader, schema.clone(), has_header, block_size);
 
@@ -148,3 +172,37 @@ impl Table for CsvTable {
         Ok(Box::pin(s))
     }
 }
+
+/// Estimates the rows of the files by the average length of the lines at the head of the
+/// first file, which is exact for a single file no larger than `ROWS_SAMPLE_BYTES`.
+async fn estimate_rows(
+    da: &dyn DataAccessor,
+    files: &[ObjectMeta],
+    has_header: bool,
+) -> Result<usize> {
+    let first = match files.first() {
+        Some(first) => first,
+        None => return Ok(0),
+    };
+    let mut sample = vec![];
+    da.get_input_stream(&first.location, Some(first.size))?
+        .take(ROWS_SAMPLE_BYTES)
+        .read_to_end(&mut sample)
+        .await?;
+
+    let mut lines = count_lines(sample.as_slice())?;
+    // the last line of the file, or the line cut by the sample
+    if matches!(sample.last(), Some(b) if *b != b'\n') {
+        lines += 1;
+    }
+    if lines == 0 {
+        return Ok(0);
+    }
+
+    let total_bytes: u64 = files.iter().map(|file| file.size).sum();
+    let rows = (total_bytes as u128 * lines as u128 / sample.len() as u128) as usize;
+    match has_header {
+        true => Ok(rows.saturating_sub(files.len())),
+        false => Ok(rows),
+    }
+}
@@ -59,11 +59,15 @@ async fn test_csv_table() -> Result<()> {
             },
         },
         Arc::new(TableDataContext::default()),
+        true,
     )?;
 
     let io_ctx = ctx.get_cluster_table_io_context()?;
     let io_ctx = Arc::new(io_ctx);
     let source_plan = table.read_plan(io_ctx.clone(), Some(Extras::default()))?;
+    // the whole file is sampled, 6 lines in 96 bytes
+    assert_eq!(source_plan.statistics, Statistics::new_estimated(6, 96));
+    assert!(table.is_local());
     ctx.try_set_partitions(source_plan.parts.clone())?;
 
     let stream = table.read(io_ctx, &source_plan).await?;
@@ -120,6 +124,7 @@ async fn test_csv_table_parse_error() -> Result<()> {
             },
         },
         Arc::new(TableDataContext::default()),
+        true,
     )?;
 
     let io_ctx = ctx.get_cluster_table_io_context()?;
@@ -0,0 +1,56 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::sync::Arc;
+
+use common_context::TableIOContext;
+use common_exception::Result;
+use common_planners::AlterTableOperation;
+use common_planners::AlterTablePlan;
+
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_COLUMNS;
+use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table::fuse::FuseTableOptions;
+
+impl FuseTable {
+    /// Commits the altered columns of the table, the blocks are NOT rewritten.
+    #[inline]
+    pub async fn do_alter(
+        &self,
+        io_ctx: Arc<TableIOContext>,
+        alter_plan: AlterTablePlan,
+    ) -> Result<()> {
+        let columns = match alter_plan.operation {
+            AlterTableOperation::AddColumn {
+                field,
+                default_value,
+            } => self.table_columns.add_column(field, default_value)?,
+            AlterTableOperation::DropColumn { name } => self.table_columns.drop_column(&name)?,
+            AlterTableOperation::RenameColumn { old_name, new_name } => {
+                self.table_columns.rename_column(&old_name, &new_name)?
+            }
+        };
+
+        // options refer to columns by names, e.g. the dictionary columns
+        FuseTableOptions::try_create(self.table_info.options(), columns.schema.as_ref())?;
+
+        self.commit_option(
+            &io_ctx,
+            TBL_OPT_KEY_COLUMNS,
+            serde_json::to_string(&columns)?,
+        )
+        .await
+    }
+}
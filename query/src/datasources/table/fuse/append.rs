@@ -15,23 +15,32 @@
 
 use std::sync::Arc;
 
+use chrono::Utc;
 use common_context::IOContext;
 use common_context::TableIOContext;
-use common_datavalues::DataSchema;
+use common_dal::DataAccessor;
 use common_exception::Result;
 use common_meta_types::MetaId;
-use common_meta_types::MetaVersion;
 use common_planners::InsertIntoPlan;
 use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
 use uuid::Uuid;
 
+use crate::datasources::table::fuse::commit::commit_backoff;
+use crate::datasources::table::fuse::commit::current_transaction;
+use crate::datasources::table::fuse::commit::is_commit_conflict;
+use crate::datasources::table::fuse::commit::is_version_mismatch;
+use crate::datasources::table::fuse::commit::remove_rejected;
+use crate::datasources::table::fuse::commit::try_commit;
+use crate::datasources::table::fuse::encode_meta;
+use crate::datasources::table::fuse::io;
 use crate::datasources::table::fuse::util;
 use crate::datasources::table::fuse::util::TBL_OPT_KEY_SNAPSHOT_LOC;
 use crate::datasources::table::fuse::BlockAppender;
 use crate::datasources::table::fuse::FuseTable;
 use crate::datasources::table::fuse::SegmentInfo;
+use crate::datasources::table::fuse::TableColumns;
 use crate::datasources::table::fuse::TableSnapshot;
-use crate::sessions::DatabendQueryContext;
 
 impl FuseTable {
     #[inline]
@@ -43,86 +52,184 @@ impl FuseTable {
     ) -> Result<()> {
         // 1. get da
         let da = io_ctx.get_data_accessor()?;
+        let reader = io::MetaReader::create(&io_ctx)?;
 
         // 2. Append blocks to storage
-        let segment_info =
-            BlockAppender::append_blocks(da.clone(), stream, self.table_info.schema().as_ref())
-                .await?;
+        let segment_info = BlockAppender::append_blocks(
+            da.clone(),
+            &self.meta_location_generator,
+            &self.table_options,
+            stream,
+            &self.table_columns,
+        )
+        .await?;
 
         // 3. save segment info
-        let seg_loc = util::gen_segment_info_location();
-        let bytes = serde_json::to_vec(&segment_info)?;
+        let seg_loc = self.meta_location_generator.gen_segment_info_location();
+        let bytes = encode_meta(&segment_info)?;
         da.put(&seg_loc, bytes).await?;
 
-        // 4. new snapshot
-        let prev_snapshot = self.table_snapshot(io_ctx.as_ref()).await?;
+        let mut written = vec![];
+        for block_meta in &segment_info.blocks {
+            written.extend(block_meta.object_locations());
+        }
+        written.push(seg_loc.clone());
 
-        // TODO backoff retry this block
-        {
-            let new_snapshot = merge_snapshot(
-                self.table_info.schema().as_ref(),
-                prev_snapshot,
-                (segment_info, seg_loc),
-            )?;
+        // inside an explicit transaction, the new snapshot is staged rather than committed,
+        // and everything written here is removed if the transaction is rolled back
+        let table_id = insert_plan.tbl_id;
+        if let Some(txn) = current_transaction(&io_ctx)? {
+            let prev_snapshot = self.table_snapshot(io_ctx.as_ref()).await?;
+            let (snapshot_loc, _) = self
+                .write_next_snapshot(
+                    da.clone(),
+                    &reader,
+                    prev_snapshot,
+                    (&segment_info, &seg_loc),
+                )
+                .await?;
 
+            written.push(snapshot_loc.clone());
+            txn.stage(
+                table_id,
+                self.table_info.ident.version,
+                TBL_OPT_KEY_SNAPSHOT_LOC,
+                snapshot_loc,
+                written,
+            );
+            return Ok(());
+        }
+
+        // 4. commit, the blocks and the segment are referenced by nothing if it is given up
+        let result = self
+            .commit_append(&io_ctx, table_id, &reader, (&segment_info, &seg_loc))
+            .await;
+        let num_segments = match result {
+            Err(cause) if is_commit_conflict(&cause) => {
+                remove_rejected(&io_ctx, &written).await?;
+                return Err(cause);
+            }
+            result => result?,
+        };
+
+        // 6. compact in the background, if there are too many segments
+        self.try_background_compact(
+            io_ctx,
+            insert_plan.db_name,
+            insert_plan.tbl_name,
+            num_segments,
+        )?;
+        Ok(())
+    }
+
+    /// Commits a new snapshot that appends the segment, rebased onto the latest one and
+    /// retried, if the table is committed by others meanwhile. Fails with
+    /// `TableCommitConflict` after `MAX_COMMIT_RETRIES`, or if it can not be rebased.
+    ///
+    /// Returns the number of segments of the committed snapshot.
+    async fn commit_append(
+        &self,
+        io_ctx: &TableIOContext,
+        table_id: MetaId,
+        reader: &io::MetaReader,
+        (segment_info, seg_loc): (&SegmentInfo, &str),
+    ) -> Result<usize> {
+        let da = io_ctx.get_data_accessor()?;
+        let mut table_version = self.table_info.ident.version;
+        let mut prev_snapshot = self.table_snapshot(io_ctx).await?;
+        let mut retry = 0;
+        loop {
             // 4.1 save the new snapshot
-            let uuid = new_snapshot.snapshot_id;
-            let snapshot_loc = util::snapshot_location(uuid.to_simple().to_string().as_str());
-            let bytes = serde_json::to_vec(&new_snapshot)?;
-            da.put(&snapshot_loc, bytes).await?;
+            let (snapshot_loc, num_segments) = self
+                .write_next_snapshot(da.clone(), reader, prev_snapshot, (segment_info, seg_loc))
+                .await?;
 
             // 5. commit
-            let table_id = insert_plan.tbl_id;
-            commit(
-                &io_ctx,
+            let result = try_commit(
+                io_ctx,
                 table_id,
-                self.table_info.ident.version,
-                snapshot_loc,
+                table_version,
+                TBL_OPT_KEY_SNAPSHOT_LOC,
+                snapshot_loc.clone(),
             )
-            .await?;
+            .await;
+            match result {
+                Ok(_) => return Ok(num_segments),
+                Err(cause) if is_version_mismatch(&cause) => {
+                    // the snapshot will never be referenced
+                    da.delete(&snapshot_loc).await?;
+                    if retry >= util::MAX_COMMIT_RETRIES {
+                        return Err(self.commit_conflict(format!(
+                            "gave up after {} retries, {}",
+                            retry,
+                            cause.message()
+                        )));
+                    }
+                    commit_backoff(retry).await;
+                    retry += 1;
+
+                    let (version, latest) = self.latest_snapshot(io_ctx).await?;
+                    tracing::debug!(
+                        "rebasing the append onto version {} of table {}, retry {}",
+                        version,
+                        self.table_info.desc,
+                        retry
+                    );
+                    table_version = version;
+                    prev_snapshot = latest;
+                }
+                Err(cause) => return Err(cause),
+            }
         }
-        Ok(())
+    }
+
+    /// Saves the snapshot that appends the segment to `prev_snapshot`, returns the location
+    /// of it and the number of segments it references.
+    async fn write_next_snapshot(
+        &self,
+        da: Arc<dyn DataAccessor>,
+        reader: &io::MetaReader,
+        prev_snapshot: Option<TableSnapshot>,
+        (segment_info, seg_loc): (&SegmentInfo, &str),
+    ) -> Result<(String, usize)> {
+        let mut new_snapshot = merge_snapshot(
+            &self.table_columns,
+            prev_snapshot,
+            (segment_info, seg_loc.to_string()),
+        )?;
+        new_snapshot.clustering = self
+            .clustering_of_segments(reader, &new_snapshot.segments)
+            .await?;
+
+        let snapshot_loc = self
+            .meta_location_generator
+            .snapshot_location_from_uuid(&new_snapshot.snapshot_id);
+        let bytes = encode_meta(&new_snapshot)?;
+        da.put(&snapshot_loc, bytes).await?;
+        Ok((snapshot_loc, new_snapshot.segments.len()))
     }
 }
 
 fn merge_snapshot(
-    schema: &DataSchema,
+    columns: &TableColumns,
     pre: Option<TableSnapshot>,
-    (seg_info, loc): (SegmentInfo, String),
+    (seg_info, loc): (&SegmentInfo, String),
 ) -> Result<TableSnapshot> {
     if let Some(s) = pre {
-        let mut new_snapshot = s.append_segment(loc);
-        let new_stat = util::merge_stats(schema, &new_snapshot.summary, &seg_info.summary)?;
+        let mut new_snapshot = s.into_next(columns).append_segment(loc);
+        let new_stat = util::merge_stats(columns, &new_snapshot.summary, &seg_info.summary)?;
         new_snapshot.summary = new_stat;
         Ok(new_snapshot)
     } else {
         Ok(TableSnapshot {
             snapshot_id: Uuid::new_v4(),
             prev_snapshot_id: None,
-            schema: schema.clone(),
-            summary: seg_info.summary,
+            timestamp: Some(Utc::now()),
+            schema: columns.schema.as_ref().clone(),
+            column_ids: columns.column_ids.clone(),
+            summary: seg_info.summary.clone(),
             segments: vec![loc],
+            clustering: None,
         })
     }
 }
-
-async fn commit(
-    io_ctx: &TableIOContext,
-    table_id: MetaId,
-    table_version: MetaVersion,
-    new_snapshot_location: String,
-) -> Result<()> {
-    use crate::catalogs::Catalog;
-    let ctx: Arc<DatabendQueryContext> = io_ctx
-        .get_user_data()?
-        .expect("DatabendQueryContext should not be None");
-    let catalog = ctx.get_catalog();
-    catalog
-        .upsert_table_option(
-            table_id,
-            table_version,
-            TBL_OPT_KEY_SNAPSHOT_LOC.to_string(),
-            new_snapshot_location,
-        )
-        .await
-}
@@ -0,0 +1,192 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use common_context::IOContext;
+use common_context::TableIOContext;
+use common_datablocks::DataBlock;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_planners::Part;
+
+use crate::datasources::table::fuse::encode_meta;
+use crate::datasources::table::fuse::io;
+use crate::datasources::table::fuse::util;
+use crate::datasources::table::fuse::BlockAppender;
+use crate::datasources::table::fuse::BlockMeta;
+use crate::datasources::table::fuse::ClusteringStatistics;
+use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table::fuse::Location;
+use crate::datasources::table::fuse::SegmentInfo;
+use crate::datasources::table::fuse::Stats;
+
+impl FuseTable {
+    /// Rewrites the blocks whose ranges of the cluster keys overlap with the most of the
+    /// other blocks (at most `DEFAULT_RECLUSTER_BLOCKS` of them), into blocks sorted by the
+    /// cluster keys that no longer overlap with each other.
+    ///
+    /// Nothing is committed if none of the blocks overlap.
+    #[inline]
+    pub async fn do_recluster(&self, io_ctx: Arc<TableIOContext>) -> Result<()> {
+        if self.table_options.cluster_keys.is_empty() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Table {} has no cluster keys",
+                self.table_info.name
+            )));
+        }
+        let prev_snapshot = match self.table_snapshot(&io_ctx).await? {
+            None => return Ok(()),
+            Some(snapshot) => snapshot,
+        };
+
+        let da = io_ctx.get_data_accessor()?;
+        let reader = io::MetaReader::create(&io_ctx)?;
+        let schema = self.table_info.schema();
+        let columns = &self.table_columns;
+        let cluster_keys = &self.table_options.cluster_keys;
+        let key_types = util::cluster_key_types(cluster_keys, &schema)?;
+
+        // 1. collect the blocks of all the segments
+        let mut block_metas = vec![];
+        for seg_loc in prev_snapshot.segments.iter() {
+            let segment_info = reader.read_segment(seg_loc).await?;
+            block_metas.extend(segment_info.blocks.iter().cloned());
+        }
+
+        // 2. pick the most overlapping blocks, the ones of unknown ranges come first
+        let overlaps = util::block_overlaps(&block_metas, &key_types)?;
+        let mut candidates = overlaps
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, overlaps)| match overlaps {
+                None => Some((idx, usize::MAX)),
+                Some(0) => None,
+                Some(n) => Some((idx, *n)),
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|l, r| r.1.cmp(&l.1));
+        candidates.truncate(util::DEFAULT_RECLUSTER_BLOCKS);
+        if candidates.is_empty() {
+            return Ok(());
+        }
+        let picked = candidates
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect::<HashSet<_>>();
+
+        // 3. merge the picked blocks sorted by the cluster keys, then split them into blocks
+        // of the target size
+        let projection = (0..schema.fields().len()).collect::<Vec<_>>();
+        let mut reclustered = Vec::with_capacity(block_metas.len());
+        let mut blocks = Vec::with_capacity(picked.len());
+        for (idx, block_meta) in block_metas.into_iter().enumerate() {
+            if !picked.contains(&idx) {
+                reclustered.push(block_meta);
+                continue;
+            }
+            let part = Part {
+                name: block_meta.location.location.clone(),
+                version: 0,
+            };
+            blocks.push(io::do_read(part, da.clone(), projection.clone(), columns.clone()).await?);
+        }
+        let merged = DataBlock::concat_blocks(&blocks)?;
+        let (sorted, _) = util::sort_by_cluster_keys(merged, cluster_keys)?;
+        let target_rows = self.table_options.target_block_rows();
+        // the objects written, which are removed if the commit is rejected
+        let mut written = vec![];
+        for block in DataBlock::split_block_by_size(&sorted, target_rows)? {
+            let block = DataBlock::create(schema.clone(), block.columns().to_vec());
+            let block_meta = BlockAppender::append_block(
+                da.clone(),
+                &self.meta_location_generator,
+                &self.table_options,
+                columns,
+                block,
+            )
+            .await?;
+            written.extend(block_meta.object_locations());
+            reclustered.push(block_meta);
+        }
+
+        // 4. new segments and snapshot, chained to the previous one
+        let mut segments = vec![];
+        let mut summaries = vec![];
+        for chunk in reclustered.chunks(util::DEFAULT_SEGMENT_BLOCKS) {
+            let segment_info = SegmentInfo {
+                summary: util::reduce_block_metas(chunk, columns)?,
+                blocks: chunk.to_vec(),
+            };
+            let seg_loc = self.meta_location_generator.gen_segment_info_location();
+            let bytes = encode_meta(&segment_info)?;
+            da.put(&seg_loc, bytes).await?;
+            written.push(seg_loc.clone());
+            segments.push(seg_loc);
+            summaries.push(segment_info.summary);
+        }
+
+        let mut new_snapshot = prev_snapshot.into_next(columns);
+        new_snapshot.segments = segments;
+        new_snapshot.summary = summaries
+            .iter()
+            .try_fold(Stats::default(), |acc, summary| {
+                util::merge_stats(columns, &acc, summary)
+            })?;
+        new_snapshot.clustering = self.clustering_of_blocks(&reclustered)?;
+
+        let new_snapshot_loc = self
+            .meta_location_generator
+            .snapshot_location_from_uuid(&new_snapshot.snapshot_id);
+        let bytes = encode_meta(&new_snapshot)?;
+        da.put(&new_snapshot_loc, bytes).await?;
+
+        // 5. commit
+        self.commit_snapshot(&io_ctx, new_snapshot_loc, written)
+            .await
+    }
+
+    /// Clustering statistics of the blocks, None if the table has no cluster keys
+    pub(crate) fn clustering_of_blocks(
+        &self,
+        block_metas: &[BlockMeta],
+    ) -> Result<Option<ClusteringStatistics>> {
+        let cluster_keys = &self.table_options.cluster_keys;
+        if cluster_keys.is_empty() {
+            return Ok(None);
+        }
+        let key_types = util::cluster_key_types(cluster_keys, &self.table_info.schema())?;
+        util::clustering_statistics(block_metas, &key_types).map(Some)
+    }
+
+    /// Clustering statistics of the blocks of the segments, None if the table has no cluster
+    /// keys (in which case the segments are not read at all)
+    pub(crate) async fn clustering_of_segments(
+        &self,
+        reader: &io::MetaReader,
+        segments: &[Location],
+    ) -> Result<Option<ClusteringStatistics>> {
+        if self.table_options.cluster_keys.is_empty() {
+            return Ok(None);
+        }
+        let mut block_metas = vec![];
+        for seg_loc in segments {
+            let segment_info = reader.read_segment(seg_loc).await?;
+            block_metas.extend(segment_info.blocks.iter().cloned());
+        }
+        self.clustering_of_blocks(&block_metas)
+    }
+}
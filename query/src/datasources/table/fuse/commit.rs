@@ -0,0 +1,214 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::cmp::min;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::tokio::time::sleep;
+use common_context::IOContext;
+use common_context::TableIOContext;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_types::MetaId;
+use common_meta_types::MetaVersion;
+use common_tracing::tracing;
+use rand::thread_rng;
+use rand::Rng;
+
+use crate::catalogs::Catalog;
+use crate::catalogs::Table;
+use crate::datasources::table::fuse::io::MetaReader;
+use crate::datasources::table::fuse::util;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_COLUMNS;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_SNAPSHOT_LOC;
+use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table::fuse::TableSnapshot;
+use crate::sessions::DatabendQueryContext;
+use crate::sessions::Transaction;
+
+impl FuseTable {
+    /// Points the table to the new snapshot, see `commit_option`.
+    ///
+    /// `written` are the other objects written for the new snapshot (blocks, bloom filters
+    /// and segments). Nothing refers to them if the commit is rejected for a conflict, or the
+    /// transaction it is staged in is rolled back, they are removed along with the new
+    /// snapshot then.
+    pub(crate) async fn commit_snapshot(
+        &self,
+        io_ctx: &TableIOContext,
+        new_snapshot_loc: String,
+        written: Vec<String>,
+    ) -> Result<()> {
+        if let Some(txn) = current_transaction(io_ctx)? {
+            // everything written is removed, if the transaction is rolled back
+            let mut objects = written;
+            objects.push(new_snapshot_loc.clone());
+            txn.stage(
+                self.get_id(),
+                self.table_info.ident.version,
+                TBL_OPT_KEY_SNAPSHOT_LOC,
+                new_snapshot_loc,
+                objects,
+            );
+            return Ok(());
+        }
+
+        let result = self
+            .commit_option(io_ctx, TBL_OPT_KEY_SNAPSHOT_LOC, new_snapshot_loc.clone())
+            .await;
+        if matches!(&result, Err(cause) if is_commit_conflict(cause)) {
+            let mut written = written;
+            written.push(new_snapshot_loc);
+            remove_rejected(io_ctx, &written).await?;
+        }
+        result
+    }
+
+    /// Commits the table option, on condition that the table is not changed since it was got
+    /// from the catalog, i.e. the seq of the table meta still matches the version of the table.
+    ///
+    /// It is for the operations that can not be rebased onto the concurrent commits (e.g.
+    /// truncate, compaction and deletion), which fail with `TableCommitConflict` if the table
+    /// is changed concurrently.
+    ///
+    /// Inside an explicit transaction, the option is staged instead, and committed on COMMIT.
+    pub(crate) async fn commit_option(
+        &self,
+        io_ctx: &TableIOContext,
+        key: &str,
+        value: String,
+    ) -> Result<()> {
+        if let Some(txn) = current_transaction(io_ctx)? {
+            txn.stage(
+                self.get_id(),
+                self.table_info.ident.version,
+                key,
+                value,
+                vec![],
+            );
+            return Ok(());
+        }
+
+        try_commit(
+            io_ctx,
+            self.get_id(),
+            self.table_info.ident.version,
+            key,
+            value,
+        )
+        .await
+        .map_err(|cause| match is_version_mismatch(&cause) {
+            true => self.commit_conflict(cause.message()),
+            false => cause,
+        })
+    }
+
+    /// The latest version and snapshot of the table, it might have been changed since it was
+    /// got from the catalog. Fails with `TableCommitConflict` if the columns have been altered,
+    /// the blocks written against the previous columns can not be committed any more.
+    pub(crate) async fn latest_snapshot(
+        &self,
+        io_ctx: &TableIOContext,
+    ) -> Result<(MetaVersion, Option<TableSnapshot>)> {
+        let ctx: Arc<DatabendQueryContext> = io_ctx
+            .get_user_data()?
+            .expect("DatabendQueryContext should not be None");
+        let (ident, table_meta) = ctx
+            .get_catalog()
+            .get_table_meta_by_id(self.get_id())
+            .await?;
+
+        if table_meta.options.get(TBL_OPT_KEY_COLUMNS)
+            != self.table_info.options().get(TBL_OPT_KEY_COLUMNS)
+        {
+            return Err(self.commit_conflict("the columns have been altered"));
+        }
+
+        let snapshot = match table_meta.options.get(TBL_OPT_KEY_SNAPSHOT_LOC) {
+            None => None,
+            Some(loc) => {
+                let reader = MetaReader::create(io_ctx)?;
+                Some(reader.read_snapshot(loc).await?.as_ref().clone())
+            }
+        };
+        Ok((ident.version, snapshot))
+    }
+
+    pub(crate) fn commit_conflict(&self, reason: impl std::fmt::Display) -> ErrorCode {
+        ErrorCode::TableCommitConflict(format!(
+            "Table {} has been changed by a concurrent commit: {}",
+            self.table_info.desc, reason
+        ))
+    }
+}
+
+/// Upserts the table option, if the seq of the table meta matches the version.
+pub(crate) async fn try_commit(
+    io_ctx: &TableIOContext,
+    table_id: MetaId,
+    table_version: MetaVersion,
+    key: &str,
+    value: String,
+) -> Result<()> {
+    let ctx: Arc<DatabendQueryContext> = io_ctx
+        .get_user_data()?
+        .expect("DatabendQueryContext should not be None");
+    let catalog = ctx.get_catalog();
+    catalog
+        .upsert_table_option(table_id, table_version, key.to_string(), value)
+        .await
+}
+
+/// The explicit transaction the query runs in, if any. Changes made in a transaction are
+/// staged in it, instead of being committed to the catalog.
+pub(crate) fn current_transaction(io_ctx: &TableIOContext) -> Result<Option<Arc<Transaction>>> {
+    let ctx: Arc<DatabendQueryContext> = io_ctx
+        .get_user_data()?
+        .expect("DatabendQueryContext should not be None");
+    Ok(ctx.get_transaction())
+}
+
+/// The commit is rejected, since the table has been committed by others
+pub(crate) fn is_version_mismatch(cause: &ErrorCode) -> bool {
+    cause.code() == ErrorCode::TableVersionMissMatch("").code()
+}
+
+pub(crate) fn is_commit_conflict(cause: &ErrorCode) -> bool {
+    cause.code() == ErrorCode::TableCommitConflict("").code()
+}
+
+/// Removes the objects written for a commit that is rejected, nothing refers to them.
+pub(crate) async fn remove_rejected(io_ctx: &TableIOContext, written: &[String]) -> Result<()> {
+    let da = io_ctx.get_data_accessor()?;
+    for location in written {
+        // the conflict is reported anyway, the leftovers are removed by purge
+        if let Err(e) = da.delete(location).await {
+            tracing::warn!("Failed to remove {} of a rejected commit: {}", location, e);
+        }
+    }
+    Ok(())
+}
+
+/// Sleeps before the n-th (starting from 0) retry of a commit, for an exponentially growing
+/// period with jitter, so that the concurrent writers are unlikely to collide again
+pub(crate) async fn commit_backoff(retry: usize) {
+    let max = min(
+        util::COMMIT_BACKOFF_BASE_MILLIS << min(retry, 16),
+        util::COMMIT_BACKOFF_MAX_MILLIS,
+    );
+    let millis = thread_rng().gen_range(max / 2..=max);
+    sleep(Duration::from_millis(millis)).await;
+}
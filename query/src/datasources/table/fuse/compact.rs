@@ -0,0 +1,206 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::sync::Arc;
+
+use common_base::TrySpawn;
+use common_context::IOContext;
+use common_context::TableIOContext;
+use common_datablocks::DataBlock;
+use common_exception::Result;
+use common_planners::Optimization;
+use common_planners::OptimizeTablePlan;
+use common_planners::Part;
+use common_tracing::tracing;
+
+use crate::catalogs::Catalog;
+use crate::catalogs::Table;
+use crate::datasources::table::fuse::encode_meta;
+use crate::datasources::table::fuse::io;
+use crate::datasources::table::fuse::util;
+use crate::datasources::table::fuse::BlockAppender;
+use crate::datasources::table::fuse::BlockMeta;
+use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table::fuse::SegmentInfo;
+use crate::datasources::table::fuse::Stats;
+use crate::sessions::DatabendQueryContext;
+
+impl FuseTable {
+    /// Re-packs the undersized blocks into blocks of the target size, and merges all the
+    /// segments into segments of at most `DEFAULT_SEGMENT_BLOCKS` blocks.
+    ///
+    /// Nothing is committed if the table is already compact.
+    #[inline]
+    pub async fn do_compact(&self, io_ctx: Arc<TableIOContext>) -> Result<()> {
+        let prev_snapshot = match self.table_snapshot(&io_ctx).await? {
+            None => return Ok(()),
+            Some(snapshot) => snapshot,
+        };
+
+        let da = io_ctx.get_data_accessor()?;
+        let reader = io::MetaReader::create(&io_ctx)?;
+        let schema = self.table_info.schema();
+        let columns = &self.table_columns;
+
+        // 1. collect the blocks of all the segments
+        let mut block_metas = vec![];
+        for seg_loc in prev_snapshot.segments.iter() {
+            let segment_info = reader.read_segment(seg_loc).await?;
+            block_metas.extend(segment_info.blocks.iter().cloned());
+        }
+
+        let target_rows = self.table_options.target_block_rows();
+        let target_bytes = self.table_options.target_block_bytes();
+        let (undersized, mut compacted): (Vec<BlockMeta>, Vec<BlockMeta>) =
+            block_metas.into_iter().partition(|block_meta| {
+                (block_meta.row_count as usize) < target_rows
+                    && (block_meta.block_size as usize) < target_bytes
+            });
+
+        let num_blocks = undersized.len() + compacted.len();
+        let expected_segments =
+            (num_blocks + util::DEFAULT_SEGMENT_BLOCKS - 1) / util::DEFAULT_SEGMENT_BLOCKS;
+        if undersized.len() <= 1 && prev_snapshot.segments.len() <= expected_segments {
+            return Ok(());
+        }
+
+        // 2. re-pack the undersized blocks, the objects written are removed if the commit
+        // is rejected
+        let mut written = vec![];
+        if undersized.len() <= 1 {
+            compacted.extend(undersized);
+        } else {
+            let projection = (0..schema.fields().len()).collect::<Vec<_>>();
+            let mut buffered = vec![];
+            let mut buffered_rows = 0;
+            let mut buffered_bytes = 0;
+            let last = undersized.len() - 1;
+            for (idx, block_meta) in undersized.into_iter().enumerate() {
+                let part = Part {
+                    name: block_meta.location.location.clone(),
+                    version: 0,
+                };
+                let block =
+                    io::do_read(part, da.clone(), projection.clone(), columns.clone()).await?;
+                buffered_rows += block.num_rows();
+                buffered_bytes += block.memory_size();
+                buffered.push(block);
+
+                let full = buffered_rows >= target_rows || buffered_bytes >= target_bytes;
+                if full || idx == last {
+                    let merged = DataBlock::concat_blocks(&buffered)?;
+                    for block in DataBlock::split_block_by_size(&merged, target_rows)? {
+                        let block = DataBlock::create(schema.clone(), block.columns().to_vec());
+                        let block_meta = BlockAppender::append_block(
+                            da.clone(),
+                            &self.meta_location_generator,
+                            &self.table_options,
+                            columns,
+                            block,
+                        )
+                        .await?;
+                        written.extend(block_meta.object_locations());
+                        compacted.push(block_meta);
+                    }
+                    buffered.clear();
+                    buffered_rows = 0;
+                    buffered_bytes = 0;
+                }
+            }
+        }
+
+        // 3. merge the segments
+        let mut segments = Vec::with_capacity(expected_segments);
+        let mut summaries = Vec::with_capacity(expected_segments);
+        for chunk in compacted.chunks(util::DEFAULT_SEGMENT_BLOCKS) {
+            let segment_info = SegmentInfo {
+                summary: util::reduce_block_metas(chunk, columns)?,
+                blocks: chunk.to_vec(),
+            };
+            let seg_loc = self.meta_location_generator.gen_segment_info_location();
+            let bytes = encode_meta(&segment_info)?;
+            da.put(&seg_loc, bytes).await?;
+            written.push(seg_loc.clone());
+            segments.push(seg_loc);
+            summaries.push(segment_info.summary);
+        }
+
+        // 4. new snapshot, chained to the previous one
+        let mut new_snapshot = prev_snapshot.into_next(columns);
+        new_snapshot.segments = segments;
+        new_snapshot.summary = summaries
+            .iter()
+            .try_fold(Stats::default(), |acc, summary| {
+                util::merge_stats(columns, &acc, summary)
+            })?;
+        new_snapshot.clustering = self
+            .clustering_of_segments(&reader, &new_snapshot.segments)
+            .await?;
+
+        let new_snapshot_loc = self
+            .meta_location_generator
+            .snapshot_location_from_uuid(&new_snapshot.snapshot_id);
+        let bytes = encode_meta(&new_snapshot)?;
+        da.put(&new_snapshot_loc, bytes).await?;
+
+        // 5. commit
+        self.commit_snapshot(&io_ctx, new_snapshot_loc, written)
+            .await
+    }
+
+    /// Compacts the table in the background, if the number of its segments exceeds the
+    /// setting `auto_compact_segments_threshold` (0 means never).
+    pub(crate) fn try_background_compact(
+        &self,
+        io_ctx: Arc<TableIOContext>,
+        db: String,
+        table: String,
+        num_segments: usize,
+    ) -> Result<()> {
+        let ctx: Arc<DatabendQueryContext> = io_ctx
+            .get_user_data()?
+            .expect("DatabendQueryContext should not be None");
+        let threshold = ctx.get_settings().get_auto_compact_segments_threshold()? as usize;
+        if threshold == 0 || num_segments <= threshold {
+            return Ok(());
+        }
+
+        let catalog = ctx.get_catalog();
+        let runtime = io_ctx.get_runtime();
+        runtime.try_spawn(async move {
+            // the latest version of the table is needed, which is committed by the insertion
+            let result = match catalog.get_table(&db, &table).await {
+                Ok(tbl) => {
+                    let plan = OptimizeTablePlan {
+                        db: db.clone(),
+                        table: table.clone(),
+                        operation: Optimization::Compact,
+                    };
+                    tbl.optimize(io_ctx, plan).await
+                }
+                Err(cause) => Err(cause),
+            };
+            if let Err(cause) = result {
+                tracing::warn!(
+                    "Background compaction of table {}.{} failed: {}",
+                    db,
+                    table,
+                    cause
+                );
+            }
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_context::TableIOContext;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_planners::DeletePlan;
+use common_planners::Expression;
+
+use crate::datasources::table::fuse::BlockMutation;
+use crate::datasources::table::fuse::BlockMutator;
+use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table::fuse::SelectionEvaluator;
+
+impl FuseTable {
+    #[inline]
+    pub async fn do_delete(
+        &self,
+        io_ctx: Arc<TableIOContext>,
+        delete_plan: DeletePlan,
+    ) -> Result<()> {
+        let deleter =
+            BlockDeleter::try_create(self.table_info.schema(), delete_plan.selection.clone())?;
+        self.mutate_blocks(io_ctx, delete_plan.selection.as_ref(), &deleter)
+            .await?;
+        Ok(())
+    }
+}
+
+struct BlockDeleter {
+    /// None if all the rows are deleted
+    evaluator: Option<SelectionEvaluator>,
+}
+
+impl BlockDeleter {
+    fn try_create(schema: DataSchemaRef, selection: Option<Expression>) -> Result<Self> {
+        let evaluator = selection
+            .map(|selection| SelectionEvaluator::try_create(schema, selection))
+            .transpose()?;
+        Ok(BlockDeleter { evaluator })
+    }
+}
+
+impl BlockMutator for BlockDeleter {
+    fn removes_all(&self) -> bool {
+        self.evaluator.is_none()
+    }
+
+    fn mutate(&self, block: DataBlock) -> Result<BlockMutation> {
+        let evaluator = match &self.evaluator {
+            None => return Ok(BlockMutation::Removed),
+            Some(evaluator) => evaluator,
+        };
+
+        let keep = evaluator
+            .eval(&block)?
+            .apply_with_idx_on_opt(|(_, matched)| matched.map(|matched| !matched));
+        let kept_block = DataBlock::filter_block(&block, keep.into_series())?;
+
+        match kept_block.num_rows() {
+            0 => Ok(BlockMutation::Removed),
+            rows if rows == block.num_rows() => Ok(BlockMutation::Untouched),
+            _ => Ok(BlockMutation::Replaced(kept_block)),
+        }
+    }
+}
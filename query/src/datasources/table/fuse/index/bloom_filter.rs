@@ -0,0 +1,298 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hasher;
+
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_datavalues::DataValue;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_macros::MallocSizeOf;
+use common_planners::Expression;
+use common_planners::Extras;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use serde::Deserialize;
+use serde::Serialize;
+use siphasher::sip::SipHasher13;
+
+use crate::datasources::table::fuse::io::MetaReader;
+use crate::datasources::table::fuse::BlockMeta;
+use crate::datasources::table::fuse::ColumnId;
+use crate::datasources::table::fuse::TableColumns;
+
+/// Target false positive rate of the bloom filters
+const FALSE_POSITIVE_RATE: f64 = 0.01;
+const MAX_NUM_HASHES: u32 = 16;
+
+/// A bloom filter of the hashes of the values of a column
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, MallocSizeOf)]
+pub struct BloomFilter {
+    num_hashes: u32,
+    bits: Vec<u64>,
+}
+
+impl BloomFilter {
+    /// Creates a bloom filter which is sized by the number of distinct hashes.
+    pub fn from_hashes(mut hashes: Vec<u64>) -> Self {
+        hashes.sort_unstable();
+        hashes.dedup();
+
+        let num_items = std::cmp::max(1, hashes.len()) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-num_items * FALSE_POSITIVE_RATE.ln() / (ln2 * ln2)).ceil() as usize;
+        let num_words = std::cmp::max(1, (num_bits + 63) / 64);
+        let num_hashes = ((num_words * 64) as f64 / num_items * ln2).round() as u32;
+
+        let mut filter = Self {
+            num_hashes: num_hashes.clamp(1, MAX_NUM_HASHES),
+            bits: vec![0; num_words],
+        };
+        for hash in hashes {
+            for pos in filter.bit_positions(hash) {
+                filter.bits[pos / 64] |= 1 << (pos % 64);
+            }
+        }
+        filter
+    }
+
+    /// False if the value of the hash is definitely not in the filter.
+    pub fn contains(&self, hash: u64) -> bool {
+        self.bit_positions(hash)
+            .all(|pos| self.bits[pos / 64] & (1 << (pos % 64)) != 0)
+    }
+
+    // double hashing, the two halves of the hash are taken as two independent hashes
+    fn bit_positions(&self, hash: u64) -> impl Iterator<Item = usize> {
+        let num_bits = (self.bits.len() * 64) as u64;
+        let h1 = hash & 0xffff_ffff;
+        let h2 = hash >> 32;
+        (0..self.num_hashes as u64)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits) as usize)
+    }
+}
+
+/// The hash function of the values, the hashes are persisted in the bloom filters.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, MallocSizeOf)]
+pub enum BloomFilterHash {
+    /// The std `DefaultHasher` of the early versions, which is unspecified and may change
+    /// across the Rust releases, the filters hashed by it are never checked
+    Unspecified,
+    /// SipHash-1-3 keyed by zeros, of the values serialized by `Series::serialize`
+    SipHash13,
+}
+
+impl Default for BloomFilterHash {
+    fn default() -> Self {
+        BloomFilterHash::Unspecified
+    }
+}
+
+/// Bloom filters of (some of) the columns of a block, by the ids of the columns.
+///
+/// It is kept in an object alongside the block, see `BlockMeta::bloom_filter_location`, and
+/// encoded with `encode_meta`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, MallocSizeOf)]
+pub struct BloomFilterIndex {
+    /// The indexes of the early versions have no hash, they were hashed by `Unspecified`
+    #[serde(default)]
+    pub hash: BloomFilterHash,
+    pub filters: HashMap<ColumnId, BloomFilter>,
+}
+
+impl BloomFilterIndex {
+    /// Builds the bloom filters of the given columns of the block, NULLs are not hashed.
+    pub fn try_create(
+        block: &DataBlock,
+        column_ids: &[ColumnId],
+        bloom_filter_columns: &HashSet<String>,
+    ) -> Result<Self> {
+        let mut filters = HashMap::with_capacity(bloom_filter_columns.len());
+        for (idx, field) in block.schema().fields().iter().enumerate() {
+            if !bloom_filter_columns.contains(field.name()) {
+                continue;
+            }
+            let series = block.column(idx).to_array()?;
+            let hashes = Self::hash_series(&series)?
+                .into_iter()
+                .enumerate()
+                .filter(|(row, _)| !series.is_null(*row))
+                .map(|(_, hash)| hash)
+                .collect::<Vec<_>>();
+            filters.insert(column_ids[idx], BloomFilter::from_hashes(hashes));
+        }
+        Ok(Self {
+            hash: BloomFilterHash::SipHash13,
+            filters,
+        })
+    }
+
+    /// False if there are definitely no rows of the block matching the expression.
+    ///
+    /// Only the equalities (and the conjunctions/disjunctions of them) between the columns
+    /// and the literals are checked, anything else may match.
+    pub fn may_match(&self, expr: &Expression, columns: &TableColumns) -> bool {
+        if self.hash != BloomFilterHash::SipHash13 {
+            return true;
+        }
+        match expr {
+            Expression::BinaryExpression { left, op, right } => match op.to_lowercase().as_str() {
+                "and" => self.may_match(left, columns) && self.may_match(right, columns),
+                "or" => self.may_match(left, columns) || self.may_match(right, columns),
+                "=" => match (left.as_ref(), right.as_ref()) {
+                    (Expression::Column(name), Expression::Literal { value, .. })
+                    | (Expression::Literal { value, .. }, Expression::Column(name)) => {
+                        self.may_contain(name, value, columns)
+                    }
+                    _ => true,
+                },
+                _ => true,
+            },
+            _ => true,
+        }
+    }
+
+    fn may_contain(&self, name: &str, value: &DataValue, columns: &TableColumns) -> bool {
+        let (idx, field) = match columns.schema.column_with_name(name) {
+            Some(column) => column,
+            None => return true,
+        };
+        let filter = match self.filters.get(&columns.column_id(idx)) {
+            Some(filter) => filter,
+            None => return true,
+        };
+        if value.is_null() {
+            // NULL equals to nothing
+            return false;
+        }
+        if !is_comparable(field.data_type(), &value.data_type()) {
+            return true;
+        }
+        // the value is hashed as the type of the column, in the same way as the values of the column
+        match Self::hash_value(value, field.data_type()) {
+            Ok(Some(hash)) => filter.contains(hash),
+            _ => true,
+        }
+    }
+
+    fn hash_value(value: &DataValue, data_type: &DataType) -> Result<Option<u64>> {
+        let series = value.to_series_with_size(1)?.cast_with_type(data_type)?;
+        if series.is_null(0) {
+            return Ok(None);
+        }
+        Ok(Self::hash_series(&series)?.into_iter().next())
+    }
+
+    // the hashes are persisted, thus the hash function must be specified, and not be seeded
+    // randomly, see `BloomFilterHash::SipHash13`
+    fn hash_series(series: &Series) -> Result<Vec<u64>> {
+        // -0.0 equals to 0.0 but is serialized differently, so are the NaNs with different bits
+        let series = match series.data_type() {
+            DataType::Float32 => series.f32()?.apply(normalize_f32).into_series(),
+            DataType::Float64 => series.f64()?.apply(normalize_f64).into_series(),
+            _ => series.clone(),
+        };
+        let mut values = vec![vec![]; series.len()];
+        series.serialize(&mut values)?;
+        Ok(values
+            .iter()
+            .map(|value| {
+                let mut hasher = SipHasher13::new_with_keys(0, 0);
+                hasher.write(value);
+                hasher.finish()
+            })
+            .collect())
+    }
+}
+
+fn normalize_f32(value: f32) -> f32 {
+    if value.is_nan() {
+        f32::NAN
+    } else if value == 0.0 {
+        0.0
+    } else {
+        value
+    }
+}
+
+fn normalize_f64(value: f64) -> f64 {
+    if value.is_nan() {
+        f64::NAN
+    } else if value == 0.0 {
+        0.0
+    } else {
+        value
+    }
+}
+
+/// Whether the columns of the type can have bloom filters.
+pub fn is_bloom_filter_supported(data_type: &DataType) -> bool {
+    is_numeric(data_type) || is_date_or_date_time(data_type) || data_type == &DataType::String
+}
+
+// Literals of other types may be compared in other ways, e.g. as strings
+fn is_comparable(column_type: &DataType, literal_type: &DataType) -> bool {
+    match column_type {
+        DataType::String => literal_type == &DataType::String,
+        t if is_numeric(t) => is_numeric(literal_type),
+        t if is_date_or_date_time(t) => {
+            is_numeric(literal_type) || is_date_or_date_time(literal_type)
+        }
+        _ => false,
+    }
+}
+
+/// Prunes the blocks by their bloom filters, blocks without bloom filters are kept.
+pub async fn bloom_filter_prune(
+    block_metas: Vec<BlockMeta>,
+    columns: &TableColumns,
+    push_down: &Option<Extras>,
+    reader: &MetaReader,
+) -> Result<Vec<BlockMeta>> {
+    let filters = match push_down {
+        Some(extras) if !extras.filters.is_empty() => &extras.filters,
+        _ => return Ok(block_metas),
+    };
+    if !block_metas
+        .iter()
+        .any(|meta| meta.bloom_filter_location.is_some())
+    {
+        return Ok(block_metas);
+    }
+
+    let num_blocks = block_metas.len();
+    let res = futures::stream::iter(block_metas)
+        .map(|block_meta| async {
+            let location = match &block_meta.bloom_filter_location {
+                None => return Ok::<_, ErrorCode>(Some(block_meta)),
+                Some(location) => location.clone(),
+            };
+            let index = reader.read_bloom_filter_index(&location).await?;
+            if filters.iter().all(|expr| index.may_match(expr, columns)) {
+                Ok(Some(block_meta))
+            } else {
+                Ok(None)
+            }
+        })
+        // configuration of the max size of buffered futures
+        .buffered(std::cmp::min(10, num_blocks))
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    Ok(res.into_iter().flatten().collect())
+}
@@ -0,0 +1,181 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::collections::HashSet;
+
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::SeriesFrom;
+use common_datavalues::series::Series;
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
+use common_datavalues::DataValue;
+use common_exception::Result;
+use common_planners::col;
+use common_planners::lit;
+use common_planners::Expression;
+
+use crate::datasources::table::fuse::decode_meta;
+use crate::datasources::table::fuse::encode_meta;
+use crate::datasources::table::fuse::index::is_bloom_filter_supported;
+use crate::datasources::table::fuse::index::BloomFilter;
+use crate::datasources::table::fuse::index::BloomFilterHash;
+use crate::datasources::table::fuse::index::BloomFilterIndex;
+use crate::datasources::table::fuse::TableColumns;
+
+#[test]
+fn test_bloom_filter() -> Result<()> {
+    let filter = BloomFilter::from_hashes((0..1000u64).map(|v| v * 7919).collect());
+
+    // no false negatives
+    assert!((0..1000u64).all(|v| filter.contains(v * 7919)));
+
+    // the false positive rate is about 1%
+    let false_positives = (1000..11000u64)
+        .filter(|v| filter.contains(v * 7919))
+        .count();
+    assert!(
+        false_positives < 300,
+        "false positives: {}",
+        false_positives
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bloom_filter_index() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("id", DataType::Int32, false),
+        DataField::new("name", DataType::String, true),
+        DataField::new("flag", DataType::UInt8, false),
+    ]);
+    let names = (0..100).map(|v| format!("user_{}", v)).collect::<Vec<_>>();
+    let block = DataBlock::create_by_array(schema.clone(), vec![
+        Series::new((0..100).collect::<Vec<i32>>()),
+        Series::new(
+            names
+                .iter()
+                .enumerate()
+                .map(|(v, name)| match v % 10 {
+                    0 => None,
+                    _ => Some(name.as_str()),
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Series::new(vec![1u8; 100]),
+    ]);
+    let columns = TableColumns::from_schema(schema);
+    let bloom_filter_columns = ["id", "name"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<HashSet<_>>();
+    let index = BloomFilterIndex::try_create(&block, &columns.column_ids, &bloom_filter_columns)?;
+    assert_eq!(index.filters.len(), 2);
+
+    // the values of the block may match
+    assert!((0..100).all(|v| index.may_match(&col("id").eq(lit(v)), &columns)));
+    assert!(index.may_match(&lit(42).eq(col("id")), &columns));
+    assert!(index.may_match(&col("name").eq(lit("user_42".as_bytes())), &columns));
+
+    // most of the others do not
+    let matched = (100..200)
+        .filter(|v| index.may_match(&col("id").eq(lit(*v)), &columns))
+        .count();
+    assert!(matched < 10, "matched: {}", matched);
+
+    // NULLs are not hashed, and NULL equals to nothing
+    let matched = names
+        .iter()
+        .filter(|name| index.may_match(&col("name").eq(lit(name.as_bytes())), &columns))
+        .count();
+    assert!((90..100).contains(&matched), "matched: {}", matched);
+    let null_literal = Expression::create_literal(DataValue::Null);
+    assert!(!index.may_match(&col("id").eq(null_literal), &columns));
+
+    // anything but the equalities of the columns with bloom filters may match
+    let absent = (100..1000)
+        .find(|v| !index.may_match(&col("id").eq(lit(*v)), &columns))
+        .unwrap();
+    let cases = vec![
+        (col("id").eq(lit(absent)), false),
+        (col("id").eq(lit(absent)).and(col("id").gt(lit(0))), false),
+        (col("id").eq(lit(absent)).or(col("id").eq(lit(1))), true),
+        (col("id").eq(lit(absent)).or(col("id").gt(lit(0))), true),
+        (col("id").gt(lit(absent)), true),
+        (col("id").not_eq(lit(1)), true),
+        (col("flag").eq(lit(absent)), true),
+        (col("id").eq(lit(absent.to_string().into_bytes())), true),
+        (col("id").eq(col("flag")), true),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(index.may_match(&expr, &columns), expected, "{:?}", expr);
+    }
+
+    // the index is persisted in the format of the metadata, along with its hash function
+    let decoded = decode_meta::<BloomFilterIndex>(&encode_meta(&index)?)?;
+    assert_eq!(decoded.hash, BloomFilterHash::SipHash13);
+    assert_eq!(decoded.filters, index.filters);
+    assert!(!decoded.may_match(&col("id").eq(lit(absent)), &columns));
+
+    // the indexes of the early versions are JSON encoded, and hashed by an unspecified function
+    let legacy = serde_json::to_vec(&serde_json::json!({ "filters": index.filters }))?;
+    let decoded = decode_meta::<BloomFilterIndex>(&legacy)?;
+    assert_eq!(decoded.hash, BloomFilterHash::Unspecified);
+    assert!(decoded.may_match(&col("id").eq(lit(absent)), &columns));
+    Ok(())
+}
+
+#[test]
+fn test_bloom_filter_index_of_floats() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("f32", DataType::Float32, false),
+        DataField::new("f64", DataType::Float64, false),
+    ]);
+    // a NaN with the bits other than the ones of `NAN`
+    let nan = f64::from_bits(f64::NAN.to_bits() | 1);
+    let block = DataBlock::create_by_array(schema.clone(), vec![
+        Series::new(vec![-0.0f32, 1.5, nan as f32]),
+        Series::new(vec![-0.0f64, 1.5, nan]),
+    ]);
+    let columns = TableColumns::from_schema(schema);
+    let bloom_filter_columns = ["f32", "f64"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<HashSet<_>>();
+    let index = BloomFilterIndex::try_create(&block, &columns.column_ids, &bloom_filter_columns)?;
+
+    // -0.0 equals to 0.0, and all the NaNs are hashed alike
+    for name in ["f32", "f64"] {
+        assert!(index.may_match(&col(name).eq(lit(0.0f64)), &columns));
+        assert!(index.may_match(&col(name).eq(lit(-0.0f64)), &columns));
+        assert!(index.may_match(&col(name).eq(lit(f64::NAN)), &columns));
+        assert!(index.may_match(&col(name).eq(lit(1.5f64)), &columns));
+        let matched = (100..200)
+            .filter(|v| index.may_match(&col(name).eq(lit(*v as f64 + 0.5)), &columns))
+            .count();
+        assert!(matched < 10, "{} matched: {}", name, matched);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_bloom_filter_supported_types() -> Result<()> {
+    assert!(is_bloom_filter_supported(&DataType::Int64));
+    assert!(is_bloom_filter_supported(&DataType::Float64));
+    assert!(is_bloom_filter_supported(&DataType::String));
+    assert!(is_bloom_filter_supported(&DataType::Date16));
+    assert!(!is_bloom_filter_supported(&DataType::Boolean));
+    Ok(())
+}
@@ -13,83 +13,82 @@
 //  limitations under the License.
 //
 
-use std::sync::Arc;
-
-use common_dal::DataAccessor;
-use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_planners::Extras;
 use futures::StreamExt;
 use futures::TryStreamExt;
 
 use crate::datasources::index::RangeFilter;
-use crate::datasources::table::fuse::util;
 use crate::datasources::table::fuse::util::BlockStats;
 use crate::datasources::table::fuse::BlockMeta;
-use crate::datasources::table::fuse::SegmentInfo;
+use crate::datasources::table::fuse::ColumnId;
+use crate::datasources::table::fuse::MetaReader;
+use crate::datasources::table::fuse::TableColumns;
 use crate::datasources::table::fuse::TableSnapshot;
 
 pub struct MinMaxIndex {
-    table_snapshot_loc: String,
-    da: Arc<dyn DataAccessor>,
+    segment_locs: Vec<String>,
+    reader: MetaReader,
 }
 
 impl MinMaxIndex {
-    pub fn new(table_snapshot: &TableSnapshot, da: Arc<dyn DataAccessor>) -> Self {
+    pub fn new(table_snapshot: &TableSnapshot, reader: MetaReader) -> Self {
         Self {
-            table_snapshot_loc: util::snapshot_location(
-                table_snapshot.snapshot_id.to_simple().to_string(),
-            ),
-            da,
+            segment_locs: table_snapshot.segments.clone(),
+            reader,
         }
     }
 
+    /// Returns the blocks which may match the filters, and the number of the segments pruned
+    /// as a whole (by their summaries)
     // Returns an iterator or stream would be better
     pub async fn apply(
         &self,
-        schema: DataSchemaRef,
+        columns: TableColumns,
         push_down: Option<Extras>,
-    ) -> common_exception::Result<Vec<BlockMeta>> {
+    ) -> common_exception::Result<(Vec<BlockMeta>, usize)> {
         type Pred =
             Box<dyn Fn(&BlockStats) -> common_exception::Result<bool> + Send + Sync + Unpin>;
         let pred_true: fn() -> Pred = || Box::new(|_: &BlockStats| Ok(true));
 
-        let block_pred: Pred = if let Some(exprs) = push_down {
-            if exprs.filters.is_empty() {
-                pred_true()
-            } else {
-                // for the time being, we only handle the first expr
-                let verifiable_expression = RangeFilter::try_create(&exprs.filters[0], schema)?;
-                Box::new(move |v: &BlockStats| verifiable_expression.eval(v))
+        // the rows should match all the filters, i.e. the conjunction of them
+        let conjunction = push_down.and_then(|extras| {
+            let mut filters = extras.filters.into_iter();
+            let first = filters.next()?;
+            Some(filters.fold(first, |acc, filter| acc.and(filter)))
+        });
+        let block_pred: Pred = match conjunction {
+            None => pred_true(),
+            Some(expr) => {
+                let verifiable_expression = RangeFilter::try_create(&expr, columns.schema.clone())?;
+                Box::new(move |v: &BlockStats| match stats_by_position(v, &columns) {
+                    Some(stats) => verifiable_expression.eval(&stats),
+                    // e.g. columns added after the block is written, which can not be pruned
+                    None => Ok(true),
+                })
             }
-        } else {
-            pred_true()
         };
 
-        let snapshot =
-            common_dal::read_obj::<TableSnapshot>(self.da.clone(), self.table_snapshot_loc.clone())
-                .await?;
-        let segment_num = snapshot.segments.len();
-        let segment_locs = snapshot.segments;
+        let segment_num = self.segment_locs.len();
+        let segment_locs = self.segment_locs.clone();
         if segment_locs.is_empty() {
-            return Ok(vec![]);
+            return Ok((vec![], 0));
         };
         let res = futures::stream::iter(segment_locs)
             .map(|seg_loc| async {
-                let segment_info =
-                    common_dal::read_obj::<SegmentInfo>(self.da.clone(), seg_loc).await?;
+                let segment_info = self.reader.read_segment(&seg_loc).await?;
                 let r = if block_pred(&segment_info.summary.col_stats)? {
-                    segment_info.blocks.into_iter().try_fold(
+                    Some(segment_info.blocks.iter().try_fold(
                         Vec::new(),
                         |mut acc, block_meta| {
                             if block_pred(&block_meta.col_stats)? {
-                                acc.push(block_meta)
+                                acc.push(block_meta.clone())
                             }
                             Ok::<_, ErrorCode>(acc)
                         },
-                    )?
+                    )?)
                 } else {
-                    vec![]
+                    None
                 };
                 Ok::<_, ErrorCode>(r)
             })
@@ -98,16 +97,32 @@ impl MinMaxIndex {
             .try_collect::<Vec<_>>()
             .await?;
 
-        Ok(res.into_iter().flatten().collect())
+        let pruned_segments = res.iter().filter(|blocks| blocks.is_none()).count();
+        Ok((
+            res.into_iter().flatten().flatten().collect(),
+            pruned_segments,
+        ))
     }
 }
 
 pub async fn range_filter(
     table_snapshot: &TableSnapshot,
-    schema: DataSchemaRef,
+    columns: TableColumns,
     push_down: Option<Extras>,
-    data_accessor: Arc<dyn DataAccessor>,
+    reader: MetaReader,
 ) -> common_exception::Result<Vec<BlockMeta>> {
-    let range_index = MinMaxIndex::new(table_snapshot, data_accessor);
-    range_index.apply(schema, push_down).await
+    let range_index = MinMaxIndex::new(table_snapshot, reader);
+    let (block_metas, _) = range_index.apply(columns, push_down).await?;
+    Ok(block_metas)
+}
+
+/// Stats are kept by the ids of the columns, while the range filter refers to the positions
+/// of the columns in the schema. None if the stats of some columns are missing.
+fn stats_by_position(stats: &BlockStats, columns: &TableColumns) -> Option<BlockStats> {
+    columns
+        .column_ids
+        .iter()
+        .enumerate()
+        .map(|(idx, column_id)| Some((idx as ColumnId, stats.get(column_id)?.clone())))
+        .collect()
 }
@@ -16,8 +16,6 @@
 use std::sync::Arc;
 
 use common_base::tokio;
-use common_context::IOContext;
-use common_dal::read_obj;
 use common_datablocks::DataBlock;
 use common_datavalues::prelude::SeriesFrom;
 use common_datavalues::series::Series;
@@ -36,6 +34,8 @@ use crate::catalogs::Catalog;
 use crate::datasources::table::fuse::index::min_max::range_filter;
 use crate::datasources::table::fuse::table_test_fixture::TestFixture;
 use crate::datasources::table::fuse::util::TBL_OPT_KEY_SNAPSHOT_LOC;
+use crate::datasources::table::fuse::MetaReader;
+use crate::datasources::table::fuse::TableColumns;
 
 #[tokio::test]
 async fn test_min_max_index() -> Result<()> {
@@ -88,7 +88,7 @@ async fn test_min_max_index() -> Result<()> {
         values_opt: None,
     };
     let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
-    let da = io_ctx.get_data_accessor()?;
+    let reader = MetaReader::create(&io_ctx)?;
     let stream = Box::pin(futures::stream::iter(blocks));
     table
         .append_data(io_ctx.clone(), insert_into_plan, stream)
@@ -104,15 +104,15 @@ async fn test_min_max_index() -> Result<()> {
         .options()
         .get(TBL_OPT_KEY_SNAPSHOT_LOC)
         .unwrap();
-    let snapshot = read_obj(da.clone(), snapshot_loc.clone()).await?;
+    let snapshot = reader.read_snapshot(snapshot_loc).await?;
 
     // no pruning
     let push_downs = None;
     let blocks = range_filter(
         &snapshot,
-        table.get_table_info().schema(),
+        TableColumns::from_schema(table.get_table_info().schema()),
         push_downs,
-        da.clone(),
+        reader.clone(),
     )
     .await?;
     let rows: u64 = blocks.iter().map(|b| b.row_count).sum();
@@ -126,9 +126,9 @@ async fn test_min_max_index() -> Result<()> {
 
     let blocks = range_filter(
         &snapshot,
-        table.get_table_info().schema(),
+        TableColumns::from_schema(table.get_table_info().schema()),
         Some(extra),
-        da.clone(),
+        reader.clone(),
     )
     .await?;
     assert_eq!(0, blocks.len());
@@ -138,7 +138,8 @@ async fn test_min_max_index() -> Result<()> {
     let pred = col("a").gt(lit(3)).and(col("b").gt(lit(3)));
     extra.filters = vec![pred];
 
-    let blocks = range_filter(&snapshot, table.get_table_info().schema(), Some(extra), da).await?;
+    let columns = TableColumns::from_schema(table.get_table_info().schema());
+    let blocks = range_filter(&snapshot, columns, Some(extra), reader).await?;
     assert_eq!(num - 1, blocks.len() as u64);
 
     Ok(())
@@ -13,9 +13,17 @@
 //  limitations under the License.
 //
 
+pub use bloom_filter::bloom_filter_prune;
+pub use bloom_filter::is_bloom_filter_supported;
+pub use bloom_filter::BloomFilter;
+pub use bloom_filter::BloomFilterHash;
+pub use bloom_filter::BloomFilterIndex;
 pub use min_max::range_filter;
 pub use min_max::MinMaxIndex;
 
+mod bloom_filter;
+#[cfg(test)]
+mod bloom_filter_test;
 mod min_max;
 #[cfg(test)]
 mod min_max_test;
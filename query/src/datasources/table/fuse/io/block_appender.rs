@@ -18,18 +18,25 @@ use common_arrow::arrow::datatypes::Schema as ArrowSchema;
 use common_arrow::arrow::io::parquet::write::WriteOptions;
 use common_arrow::arrow::io::parquet::write::*;
 use common_arrow::arrow::record_batch::RecordBatch;
+use common_arrow::parquet::metadata::KeyValue;
 use common_dal::DataAccessor;
 use common_datablocks::DataBlock;
-use common_datavalues::DataSchema;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_streams::SendableDataBlockStream;
 use futures::StreamExt;
 use rusoto_core::ByteStream;
 
+use crate::datasources::table::fuse::encode_meta;
+use crate::datasources::table::fuse::index::BloomFilterIndex;
 use crate::datasources::table::fuse::util;
+use crate::datasources::table::fuse::util::TableMetaLocationGenerator;
+use crate::datasources::table::fuse::BlockMeta;
+use crate::datasources::table::fuse::ColumnId;
+use crate::datasources::table::fuse::FuseTableOptions;
 use crate::datasources::table::fuse::SegmentInfo;
 use crate::datasources::table::fuse::Stats;
+use crate::datasources::table::fuse::TableColumns;
 
 /// dummy struct, namespace placeholder
 pub struct BlockAppender;
@@ -38,26 +45,58 @@ impl BlockAppender {
     // TODO should return a stream of SegmentInfo (batch blocks into segments)
     pub async fn append_blocks(
         data_accessor: Arc<dyn DataAccessor>,
+        locs: &TableMetaLocationGenerator,
+        table_options: &FuseTableOptions,
         mut stream: SendableDataBlockStream,
-        data_schema: &DataSchema,
+        columns: &TableColumns,
     ) -> Result<SegmentInfo> {
         let mut stats_acc = util::StatisticsAccumulator::new();
         let mut block_meta_acc = util::BlockMetaAccumulator::new();
 
+        // blocks are re-chunked only if the target size of block is specified
+        let rechunk =
+            table_options.row_per_block.is_some() || table_options.block_size_threshold.is_some();
+        let mut buffer = BlockBuffer::default();
+
         // accumulate the stats and save the blocks
         while let Some(block) = stream.next().await {
             let block = block?;
-            stats_acc.acc(&block)?;
-            let schema = block.schema().to_arrow();
-            let location = util::gen_unique_block_location();
-            let file_size = Self::save_block(&schema, block, &data_accessor, &location).await?;
-            block_meta_acc.acc(file_size, location, &mut stats_acc);
+            let blocks = if rechunk {
+                buffer.push(block);
+                buffer.take_full(table_options)?
+            } else {
+                vec![block]
+            };
+            for block in blocks {
+                Self::write_block(
+                    &data_accessor,
+                    locs,
+                    table_options,
+                    columns,
+                    block,
+                    &mut stats_acc,
+                    &mut block_meta_acc,
+                )
+                .await?;
+            }
+        }
+        if let Some(block) = buffer.take_all()? {
+            Self::write_block(
+                &data_accessor,
+                locs,
+                table_options,
+                columns,
+                block,
+                &mut stats_acc,
+                &mut block_meta_acc,
+            )
+            .await?;
         }
 
         // summary and give back a segment_info
         // we need to send back a stream of segment latter
         let block_metas = block_meta_acc.blocks_metas;
-        let summary = util::column_stats_reduce_with_schema(&stats_acc.blocks_stats, data_schema)?;
+        let summary = util::column_stats_reduce_with_schema(&stats_acc.blocks_stats, columns)?;
         let segment_info = SegmentInfo {
             blocks: block_metas,
             summary: Stats {
@@ -71,19 +110,96 @@ impl BlockAppender {
         Ok(segment_info)
     }
 
+    /// Save a single block, e.g. the rewritten one, gives back the meta of it
+    pub async fn append_block(
+        data_accessor: Arc<dyn DataAccessor>,
+        locs: &TableMetaLocationGenerator,
+        table_options: &FuseTableOptions,
+        columns: &TableColumns,
+        block: DataBlock,
+    ) -> Result<BlockMeta> {
+        let mut stats_acc = util::StatisticsAccumulator::new();
+        let mut block_meta_acc = util::BlockMetaAccumulator::new();
+        Self::write_block(
+            &data_accessor,
+            locs,
+            table_options,
+            columns,
+            block,
+            &mut stats_acc,
+            &mut block_meta_acc,
+        )
+        .await?;
+        Ok(block_meta_acc.blocks_metas.remove(0))
+    }
+
+    async fn write_block(
+        data_accessor: &Arc<dyn DataAccessor>,
+        locs: &TableMetaLocationGenerator,
+        table_options: &FuseTableOptions,
+        columns: &TableColumns,
+        block: DataBlock,
+        stats_acc: &mut util::StatisticsAccumulator,
+        block_meta_acc: &mut util::BlockMetaAccumulator,
+    ) -> Result<()> {
+        // the rows are sorted by the cluster keys (if any), so that the ranges of blocks are narrow
+        let (block, cluster_stats) =
+            util::sort_by_cluster_keys(block, &table_options.cluster_keys)?;
+        stats_acc.acc(&block, &columns.column_ids)?;
+        let bloom_filter_location = if table_options.bloom_filter_columns.is_empty() {
+            None
+        } else {
+            let index = BloomFilterIndex::try_create(
+                &block,
+                &columns.column_ids,
+                &table_options.bloom_filter_columns,
+            )?;
+            let location = locs.gen_bloom_filter_location();
+            data_accessor.put(&location, encode_meta(&index)?).await?;
+            Some(location)
+        };
+        let schema = block.schema().to_arrow();
+        let location = locs.gen_block_location();
+        let file_size = Self::save_block(
+            &schema,
+            block,
+            data_accessor,
+            &location,
+            table_options,
+            &columns.column_ids,
+        )
+        .await?;
+        block_meta_acc.acc(
+            file_size,
+            location,
+            bloom_filter_location,
+            cluster_stats,
+            stats_acc,
+        );
+        Ok(())
+    }
+
+    /// Saves the block as a parquet file, the ids of its columns are kept in the file metadata.
     pub(super) async fn save_block(
         arrow_schema: &ArrowSchema,
         block: DataBlock,
         data_accessor: impl AsRef<dyn DataAccessor>,
         location: &str,
+        table_options: &FuseTableOptions,
+        column_ids: &[ColumnId],
     ) -> Result<u64> {
         let data_accessor = data_accessor.as_ref();
         let options = WriteOptions {
             write_statistics: true,
-            compression: Compression::Lz4, // let's begin with lz4
+            compression: table_options.compression,
             version: Version::V2,
         };
         let batch = RecordBatch::try_from(block)?;
+        let (arrow_schema, batch) = util::dictionary_encode_columns(
+            arrow_schema,
+            batch,
+            &table_options.dictionary_columns,
+        )?;
         let encodings: Vec<_> = arrow_schema
             .fields()
             .iter()
@@ -92,7 +208,7 @@ impl BlockAppender {
 
         let iter = vec![Ok(batch)];
         let row_groups =
-            RowGroupIterator::try_new(iter.into_iter(), arrow_schema, options, encodings)?;
+            RowGroupIterator::try_new(iter.into_iter(), &arrow_schema, options, encodings)?;
         let parquet_schema = row_groups.parquet_schema().clone();
 
         // PutObject in S3 need to know the content-length in advance
@@ -103,13 +219,23 @@ impl BlockAppender {
         // we need a configuration of block size threshold here
         let mut writer = Vec::with_capacity(10 * 1024 * 1024).writer();
 
+        let key_value_metadata = vec![KeyValue {
+            key: util::BLOCK_META_KEY_COLUMN_IDS.to_string(),
+            value: Some(
+                column_ids
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        }];
         let len = common_arrow::parquet::write::write_file(
             &mut writer,
             row_groups,
             parquet_schema,
             options,
             None,
-            None,
+            Some(key_value_metadata),
         )
         .map_err(|e| ErrorCode::ParquetError(e.to_string()))?;
 
@@ -123,3 +249,57 @@ impl BlockAppender {
         Ok(len)
     }
 }
+
+/// Buffers the inserted blocks, and re-chunks them into blocks of the target size.
+#[derive(Default)]
+struct BlockBuffer {
+    blocks: Vec<DataBlock>,
+    rows: usize,
+    bytes: usize,
+}
+
+impl BlockBuffer {
+    fn push(&mut self, block: DataBlock) {
+        self.rows += block.num_rows();
+        self.bytes += block.memory_size();
+        self.blocks.push(block);
+    }
+
+    /// Takes the blocks of the target size out, the rest are kept in the buffer.
+    fn take_full(&mut self, table_options: &FuseTableOptions) -> Result<Vec<DataBlock>> {
+        let target_rows = table_options.target_block_rows();
+        let target_bytes = table_options.target_block_bytes();
+        if self.rows < target_rows && self.bytes < target_bytes {
+            return Ok(vec![]);
+        }
+
+        // the target bytes are converted to rows, by the average row size
+        let rows_of_bytes = (target_bytes as f64 * self.rows as f64 / self.bytes as f64) as usize;
+        let rows_per_block = std::cmp::max(1, std::cmp::min(target_rows, rows_of_bytes));
+
+        let merged = DataBlock::concat_blocks(&std::mem::take(&mut self.blocks))?;
+        let mut blocks = DataBlock::split_block_by_size(&merged, rows_per_block)?;
+        self.rows = 0;
+        self.bytes = 0;
+        if let Some(last) = blocks.pop() {
+            if last.num_rows() < rows_per_block {
+                self.push(last);
+            } else {
+                blocks.push(last);
+            }
+        }
+        Ok(blocks)
+    }
+
+    fn take_all(&mut self) -> Result<Option<DataBlock>> {
+        self.rows = 0;
+        self.bytes = 0;
+        match self.blocks.len() {
+            0 => Ok(None),
+            1 => Ok(self.blocks.pop()),
+            _ => Ok(Some(DataBlock::concat_blocks(&std::mem::take(
+                &mut self.blocks,
+            ))?)),
+        }
+    }
+}
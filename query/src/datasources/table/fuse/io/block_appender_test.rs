@@ -24,7 +24,10 @@ use common_datavalues::DataSchemaRefExt;
 use common_datavalues::DataType;
 use tempfile::TempDir;
 
+use crate::datasources::table::fuse::util::TableMetaLocationGenerator;
 use crate::datasources::table::fuse::BlockAppender;
+use crate::datasources::table::fuse::FuseTableOptions;
+use crate::datasources::table::fuse::TableColumns;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_fuse_table_block_appender() {
@@ -33,8 +36,52 @@ async fn test_fuse_table_block_appender() {
     let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int32, false)]);
     let block = DataBlock::create_by_array(schema.clone(), vec![Series::new(vec![1, 2, 3])]);
     let block_stream = futures::stream::iter(vec![Ok(block)]);
-    let r =
-        BlockAppender::append_blocks(Arc::new(local_fs), Box::pin(block_stream), schema.as_ref())
-            .await;
+    let locs = TableMetaLocationGenerator::with_prefix("_prefix".to_string());
+    let r = BlockAppender::append_blocks(
+        Arc::new(local_fs),
+        &locs,
+        &FuseTableOptions::default(),
+        Box::pin(block_stream),
+        &TableColumns::from_schema(schema),
+    )
+    .await;
     assert!(r.is_ok())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_fuse_table_block_appender_rechunk() -> common_exception::Result<()> {
+    let tmp_dir = TempDir::new().unwrap();
+    let local_fs = common_dal::Local::with_path(tmp_dir.path().to_owned());
+    let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int32, false)]);
+    let blocks = (0..10).map(|_| {
+        Ok(DataBlock::create_by_array(schema.clone(), vec![
+            Series::new(vec![1, 2, 3]),
+        ]))
+    });
+    let block_stream = futures::stream::iter(blocks.collect::<Vec<_>>());
+    let locs = TableMetaLocationGenerator::with_prefix("_prefix".to_string());
+    let table_options = FuseTableOptions {
+        row_per_block: Some(4),
+        ..Default::default()
+    };
+
+    let segment_info = BlockAppender::append_blocks(
+        Arc::new(local_fs),
+        &locs,
+        &table_options,
+        Box::pin(block_stream),
+        &TableColumns::from_schema(schema),
+    )
+    .await?;
+
+    // 30 rows are re-chunked into 7 blocks of 4 rows, and the last one of 2 rows
+    let rows = segment_info
+        .blocks
+        .iter()
+        .map(|b| b.row_count)
+        .collect::<Vec<_>>();
+    assert_eq!(rows, vec![4, 4, 4, 4, 4, 4, 4, 2]);
+    assert_eq!(segment_info.summary.row_count, 30);
+    assert_eq!(segment_info.summary.block_count, 8);
+    Ok(())
+}
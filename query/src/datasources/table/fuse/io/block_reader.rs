@@ -15,21 +15,26 @@
 
 use std::sync::Arc;
 
-use common_arrow::arrow::datatypes::Schema as ArrowSchema;
 use common_arrow::arrow::io::parquet::read::decompress;
 use common_arrow::arrow::io::parquet::read::page_stream_to_array;
 use common_arrow::arrow::io::parquet::read::read_metadata_async;
+use common_arrow::arrow::io::parquet::read::schema::FileMetaData;
 use common_arrow::parquet::read::get_page_stream;
 use common_dal::DataAccessor;
+use common_dal::InputStream;
 use common_datablocks::DataBlock;
 use common_datavalues::columns::DataColumn;
 use common_datavalues::prelude::IntoSeries;
-use common_datavalues::DataSchema;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_planners::Part;
 use futures::StreamExt;
 
+use super::TableCache;
+use crate::datasources::table::fuse::util;
+use crate::datasources::table::fuse::ColumnId;
+use crate::datasources::table::fuse::TableColumns;
+
 // TODO move these to a dedicated mod
 mod cache_keys {
     use std::sync::Arc;
@@ -56,16 +61,50 @@ mod cache_keys {
     pub type BlockMetaCache = Arc<Mutex<LruCache<BlockMetaCacheKey, Vec<u8>>>>;
 }
 
+/// Reads the projected columns of the block.
+///
+/// The columns of the block are resolved by their ids, the ones that the block does not have,
+/// i.e. added after the block is written, are filled with their default values.
 pub async fn do_read(
     part: Part,
     data_accessor: Arc<dyn DataAccessor>,
     projection: Vec<usize>,
-    arrow_schema: ArrowSchema,
+    columns: TableColumns,
+) -> Result<DataBlock> {
+    read_block(part, data_accessor, None, projection, columns).await
+}
+
+/// Reads the projected columns of the block like `do_read`, the whole block is read through
+/// the disk cache of `table_cache` if it is enabled.
+pub async fn do_read_cached(
+    part: Part,
+    data_accessor: Arc<dyn DataAccessor>,
+    table_cache: Arc<TableCache>,
+    projection: Vec<usize>,
+    columns: TableColumns,
+) -> Result<DataBlock> {
+    let block = table_cache.read_block(&data_accessor, &part.name).await?;
+    read_block(part, data_accessor, block, projection, columns).await
+}
+
+/// The block is read from `block` if it is already read, otherwise from the storage.
+async fn read_block(
+    part: Part,
+    data_accessor: Arc<dyn DataAccessor>,
+    block: Option<Arc<[u8]>>,
+    projection: Vec<usize>,
+    columns: TableColumns,
 ) -> Result<DataBlock> {
     let loc = &part.name;
     let col_num = projection.len();
-    // TODO pass in parquet file len
-    let mut reader = data_accessor.get_input_stream(loc, None)?;
+    let open = || -> Result<InputStream> {
+        match &block {
+            Some(block) => Ok(Box::new(futures::io::Cursor::new(block.clone()))),
+            // TODO pass in parquet file len
+            None => data_accessor.get_input_stream(loc, None),
+        }
+    };
+    let mut reader = open()?;
     // TODO cache parquet meta
     let metadata = read_metadata_async(&mut reader)
         .await
@@ -73,17 +112,37 @@ pub async fn do_read(
 
     // we only put one page in the a parquet file (reference xxx)
     let row_group = 0;
-    let cols = projection
-        .into_iter()
-        .map(|idx| (metadata.row_groups[row_group].column(idx).clone(), idx));
+    let num_rows = metadata.row_groups[row_group].num_rows() as usize;
+    let block_column_ids = block_column_ids(&metadata)?;
+    let cols = projection.into_iter().map(|idx| {
+        let column_id = columns.column_id(idx);
+        let col_meta = block_column_ids
+            .iter()
+            .position(|id| *id == column_id)
+            .map(|pos| metadata.row_groups[row_group].column(pos).clone());
+        (col_meta, idx)
+    });
 
+    let arrow_schema = columns.schema.to_arrow();
     let fields = arrow_schema.fields();
+    let columns = &columns;
 
     use futures::TryStreamExt;
     let stream = futures::stream::iter(cols).map(|(col_meta, idx)| {
-        let data_accessor = data_accessor.clone();
+        let open = &open;
         async move {
-            let mut reader = data_accessor.get_input_stream(loc, None)?;
+            let col_meta = match col_meta {
+                Some(col_meta) => col_meta,
+                None => {
+                    let default_value = columns.default_value(columns.column_id(idx));
+                    let data_type = columns.schema.field(idx).data_type();
+                    let series = default_value
+                        .to_series_with_size(num_rows)?
+                        .cast_with_type(data_type)?;
+                    return Ok(DataColumn::Array(series));
+                }
+            };
+            let mut reader = open()?;
             // TODO cache block column
             let col_pages = get_page_stream(&col_meta, &mut reader, vec![], Arc::new(|_, _| true))
                 .await
@@ -102,6 +161,32 @@ pub async fn do_read(
     let n = std::cmp::min(buffer_size, col_num);
     let data_cols = stream.buffered(n).try_collect().await?;
 
-    let block = DataBlock::create(Arc::new(DataSchema::from(arrow_schema)), data_cols);
+    let block = DataBlock::create(columns.schema.clone(), data_cols);
     Ok(block)
 }
+
+/// Ids of the columns of the block, which are kept in the parquet metadata.
+///
+/// Blocks of the early versions have no ids kept, the ids of which are the positions.
+fn block_column_ids(metadata: &FileMetaData) -> Result<Vec<ColumnId>> {
+    let column_ids = metadata
+        .key_value_metadata
+        .iter()
+        .flatten()
+        .find(|kv| kv.key == util::BLOCK_META_KEY_COLUMN_IDS)
+        .and_then(|kv| kv.value.as_ref());
+    match column_ids {
+        None => {
+            let num_columns = metadata.row_groups[0].columns().len() as ColumnId;
+            Ok((0..num_columns).collect())
+        }
+        Some(column_ids) => column_ids
+            .split(',')
+            .map(|id| {
+                id.parse::<ColumnId>().map_err(|e| {
+                    ErrorCode::ParquetError(format!("Invalid column id {} of block: {}", id, e))
+                })
+            })
+            .collect(),
+    }
+}
@@ -39,10 +39,13 @@ use common_datavalues::series::Series;
 use common_datavalues::DataField;
 use common_datavalues::DataSchemaRefExt;
 use common_datavalues::DataType;
+use common_datavalues::DataValue;
 use common_planners::Part;
 use tempfile::TempDir;
 
-use super::super::util;
+use super::super::util::TableMetaLocationGenerator;
+use super::super::FuseTableOptions;
+use super::super::TableColumns;
 use super::block_appender::BlockAppender;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -53,9 +56,19 @@ async fn test_block_reader_read() -> common_exception::Result<()> {
     let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int32, false)]);
     let block = DataBlock::create_by_array(schema.clone(), vec![Series::new(vec![1, 2, 3])]);
     let arrow_scheme = block.schema().to_arrow();
-    let location = util::gen_unique_block_location();
+    let locs = TableMetaLocationGenerator::with_prefix("_prefix".to_string());
+    let location = locs.gen_block_location();
 
-    let _r = BlockAppender::save_block(&arrow_scheme, block.clone(), &da, &location).await?;
+    let table_options = FuseTableOptions::default();
+    let _r = BlockAppender::save_block(
+        &arrow_scheme,
+        block.clone(),
+        &da,
+        &location,
+        &table_options,
+        &[0],
+    )
+    .await?;
 
     let part = Part {
         name: location.to_string(),
@@ -63,7 +76,8 @@ async fn test_block_reader_read() -> common_exception::Result<()> {
     };
 
     let proj = (0..arrow_scheme.fields().len()).collect();
-    let got = super::block_reader::do_read(part, da, proj, arrow_scheme).await;
+    let columns = TableColumns::from_schema(schema);
+    let got = super::block_reader::do_read(part, da, proj, columns).await;
     assert!(got.is_ok(), "{:?}", got);
 
     let input_block_as_string = pretty_format_blocks(&[block]).unwrap();
@@ -72,3 +86,105 @@ async fn test_block_reader_read() -> common_exception::Result<()> {
     assert_blocks_sorted_eq(lines_of_input_block, &[got.unwrap()]);
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_block_reader_read_with_table_options() -> common_exception::Result<()> {
+    let tmp_dir = TempDir::new().unwrap();
+    let local_fs = common_dal::Local::with_path(tmp_dir.path().to_owned());
+    let da: Arc<dyn DataAccessor> = Arc::new(local_fs);
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int32, false),
+        DataField::new("b", DataType::String, false),
+    ]);
+    let block = DataBlock::create_by_array(schema.clone(), vec![
+        Series::new(vec![1, 2, 3, 4]),
+        Series::new(vec!["x", "y", "x", "x"]),
+    ]);
+    let arrow_scheme = block.schema().to_arrow();
+    let locs = TableMetaLocationGenerator::with_prefix("_prefix".to_string());
+
+    for compression in ["none", "lz4", "zstd", "snappy"] {
+        let options = [
+            ("compression".to_string(), compression.to_string()),
+            ("dictionary_columns".to_string(), "b".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let table_options = FuseTableOptions::try_create(&options, schema.as_ref())?;
+        let location = locs.gen_block_location();
+        BlockAppender::save_block(
+            &arrow_scheme,
+            block.clone(),
+            &da,
+            &location,
+            &table_options,
+            &[0, 1],
+        )
+        .await?;
+
+        let part = Part {
+            name: location.to_string(),
+            version: 0,
+        };
+        let proj = (0..arrow_scheme.fields().len()).collect();
+        let columns = TableColumns::from_schema(schema.clone());
+        let got = super::block_reader::do_read(part, da.clone(), proj, columns).await?;
+        let input_block_as_string = pretty_format_blocks(&[block.clone()]).unwrap();
+        let lines_of_input_block = input_block_as_string.lines().collect();
+        assert_blocks_sorted_eq(lines_of_input_block, &[got]);
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_block_reader_read_altered_columns() -> common_exception::Result<()> {
+    let tmp_dir = TempDir::new().unwrap();
+    let local_fs = common_dal::Local::with_path(tmp_dir.path().to_owned());
+    let da: Arc<dyn DataAccessor> = Arc::new(local_fs);
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int32, false),
+        DataField::new("b", DataType::String, false),
+    ]);
+    let block = DataBlock::create_by_array(schema.clone(), vec![
+        Series::new(vec![1, 2, 3]),
+        Series::new(vec!["x", "y", "z"]),
+    ]);
+    let arrow_scheme = block.schema().to_arrow();
+    let locs = TableMetaLocationGenerator::with_prefix("_prefix".to_string());
+    let location = locs.gen_block_location();
+    let table_options = FuseTableOptions::default();
+    BlockAppender::save_block(&arrow_scheme, block, &da, &location, &table_options, &[
+        0, 1,
+    ])
+    .await?;
+
+    // the block is not rewritten, after the columns are altered
+    let columns = TableColumns::from_schema(schema)
+        .drop_column("a")?
+        .rename_column("b", "bb")?
+        .add_column(
+            DataField::new("c", DataType::Int32, false),
+            Some(DataValue::Int32(Some(7))),
+        )?
+        .add_column(DataField::new("d", DataType::String, false), None)?;
+    assert_eq!(columns.column_ids, vec![1, 2, 3]);
+
+    let part = Part {
+        name: location.to_string(),
+        version: 0,
+    };
+    let got = super::block_reader::do_read(part, da, vec![0, 1, 2], columns).await?;
+    assert_blocks_sorted_eq(
+        vec![
+            "+----+---+------+",
+            "| bb | c | d    |",
+            "+----+---+------+",
+            "| x  | 7 | NULL |",
+            "| y  | 7 | NULL |",
+            "| z  | 7 | NULL |",
+            "+----+---+------+",
+        ],
+        &[got],
+    );
+    Ok(())
+}
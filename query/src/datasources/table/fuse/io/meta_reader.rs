@@ -0,0 +1,63 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::sync::Arc;
+
+use common_context::IOContext;
+use common_context::TableIOContext;
+use common_dal::DataAccessor;
+use common_exception::Result;
+
+use super::TableCache;
+use crate::datasources::table::fuse::index::BloomFilterIndex;
+use crate::datasources::table::fuse::SegmentInfo;
+use crate::datasources::table::fuse::TableSnapshot;
+use crate::sessions::DatabendQueryContext;
+
+/// Reads the snapshots, segments and bloom filter indexes of the fuse tables, through the
+/// table cache.
+#[derive(Clone)]
+pub struct MetaReader {
+    da: Arc<dyn DataAccessor>,
+    cache: Arc<TableCache>,
+}
+
+impl MetaReader {
+    pub fn create(io_ctx: &TableIOContext) -> Result<MetaReader> {
+        let ctx: Arc<DatabendQueryContext> = io_ctx
+            .get_user_data()?
+            .expect("DatabendQueryContext should not be None");
+        Ok(MetaReader {
+            da: io_ctx.get_data_accessor()?,
+            cache: ctx.get_table_cache(),
+        })
+    }
+
+    pub async fn read_snapshot(&self, location: &str) -> Result<Arc<TableSnapshot>> {
+        self.cache.read_meta(&self.da, location).await
+    }
+
+    pub async fn read_segment(&self, location: &str) -> Result<Arc<SegmentInfo>> {
+        self.cache.read_meta(&self.da, location).await
+    }
+
+    pub async fn read_bloom_filter_index(&self, location: &str) -> Result<Arc<BloomFilterIndex>> {
+        self.cache.read_meta(&self.da, location).await
+    }
+
+    pub fn evict(&self, location: &str) {
+        self.cache.evict(location)
+    }
+}
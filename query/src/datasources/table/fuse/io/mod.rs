@@ -15,11 +15,18 @@
 
 pub(crate) use block_appender::*;
 pub use block_reader::*;
+pub use meta_reader::MetaReader;
+pub use table_cache::TableCache;
 
 mod block_appender;
 mod block_reader;
+mod meta_reader;
+mod metrics;
+mod table_cache;
 
 #[cfg(test)]
 mod block_appender_test;
 #[cfg(test)]
 mod block_reader_test;
+#[cfg(test)]
+mod table_cache_test;
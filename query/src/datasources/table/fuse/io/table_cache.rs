@@ -0,0 +1,182 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::any::Any;
+use std::borrow::Borrow;
+use std::io::Read;
+use std::sync::Arc;
+
+use common_cache::Cache;
+use common_cache::DefaultHashBuilder;
+use common_cache::LruCache;
+use common_cache::LruDiskCache;
+use common_cache::Meter;
+use common_dal::DataAccessor;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_infallible::Mutex;
+use common_mem_allocator::malloc_size;
+use common_mem_allocator::MallocSizeOf;
+use common_tracing::tracing;
+use metrics::counter;
+use serde::de::DeserializeOwned;
+
+use super::metrics::METRIC_TABLE_CACHE_BLOCK_HITS;
+use super::metrics::METRIC_TABLE_CACHE_BLOCK_MISSES;
+use super::metrics::METRIC_TABLE_CACHE_META_HITS;
+use super::metrics::METRIC_TABLE_CACHE_META_MISSES;
+use crate::configs::Config;
+use crate::datasources::table::fuse::decode_meta;
+
+/// A deserialized metadata object, with the memory size of it.
+#[derive(Clone)]
+struct CachedObject {
+    object: Arc<dyn Any + Send + Sync>,
+    size: usize,
+}
+
+/// Measures the cached objects by their memory sizes, which are walked once when they are
+/// cached. The encoded sizes do not tell, e.g. the compact binary encoding of the values
+/// of the statistics is much smaller than the decoded ones.
+struct ObjectSize;
+
+impl<K> Meter<K, CachedObject> for ObjectSize {
+    type Measure = usize;
+
+    fn measure<Q: ?Sized>(&self, _: &Q, item: &CachedObject) -> usize
+    where K: Borrow<Q> {
+        item.size
+    }
+}
+
+type MetaCache = LruCache<String, CachedObject, DefaultHashBuilder, ObjectSize>;
+
+/// Caches of the fuse tables, shared by all the queries of the process.
+///
+/// The snapshots, segments, bloom filters and blocks are never modified once they are
+/// written, so that they are cached by their locations, and never invalidated.
+pub struct TableCache {
+    /// Deserialized snapshots, segments and bloom filters, None if disabled
+    meta_cache: Option<Mutex<MetaCache>>,
+    /// Files of the blocks on the local disk, None if disabled
+    block_cache: Option<Mutex<LruDiskCache>>,
+}
+
+impl TableCache {
+    pub fn create_global(conf: &Config) -> Result<Arc<TableCache>> {
+        let meta_cache = match conf.query.table_meta_cache_mb_size {
+            0 => None,
+            mb_size => Some(Mutex::new(MetaCache::with_meter(
+                mb_size * 1024 * 1024,
+                ObjectSize,
+            ))),
+        };
+        let block_cache = match conf.query.table_disk_cache_mb_size {
+            0 => None,
+            mb_size => {
+                let root = &conf.query.table_disk_cache_root;
+                let cache = LruDiskCache::new(root, mb_size * 1024 * 1024).map_err(|e| {
+                    ErrorCode::BadOption(format!(
+                        "Failed to create the table disk cache at {}: {}",
+                        root, e
+                    ))
+                })?;
+                Some(Mutex::new(cache))
+            }
+        };
+        Ok(Arc::new(TableCache {
+            meta_cache,
+            block_cache,
+        }))
+    }
+
+    /// Reads the metadata object at `location`, it is deserialized only if not cached.
+    pub async fn read_meta<T>(&self, da: &Arc<dyn DataAccessor>, location: &str) -> Result<Arc<T>>
+    where T: DeserializeOwned + MallocSizeOf + Send + Sync + 'static {
+        let meta_cache = match &self.meta_cache {
+            None => return Ok(Arc::new(Self::load_meta(da, location).await?)),
+            Some(meta_cache) => meta_cache,
+        };
+
+        let cached = meta_cache
+            .lock()
+            .get(location)
+            .and_then(|cached| cached.object.clone().downcast::<T>().ok());
+        if let Some(object) = cached {
+            counter!(METRIC_TABLE_CACHE_META_HITS, 1);
+            return Ok(object);
+        }
+
+        counter!(METRIC_TABLE_CACHE_META_MISSES, 1);
+        let object = Self::load_meta::<T>(da, location).await?;
+        let size = std::mem::size_of::<T>() + malloc_size(&object);
+        let object = Arc::new(object);
+        meta_cache.lock().put(location.to_string(), CachedObject {
+            object: object.clone(),
+            size,
+        });
+        Ok(object)
+    }
+
+    /// Reads the whole block file at `location` through the disk cache, None if the disk
+    /// cache is disabled, in which case the block should be read from the storage directly.
+    pub async fn read_block(
+        &self,
+        da: &Arc<dyn DataAccessor>,
+        location: &str,
+    ) -> Result<Option<Arc<[u8]>>> {
+        let block_cache = match &self.block_cache {
+            None => return Ok(None),
+            Some(block_cache) => block_cache,
+        };
+
+        let cached = block_cache.lock().get_file(location).ok();
+        if let Some(mut file) = cached {
+            let mut bytes = vec![];
+            if file.read_to_end(&mut bytes).is_ok() {
+                counter!(METRIC_TABLE_CACHE_BLOCK_HITS, 1);
+                return Ok(Some(bytes.into()));
+            }
+        }
+
+        counter!(METRIC_TABLE_CACHE_BLOCK_MISSES, 1);
+        let bytes = da.read(location).await?;
+        // a block that can not be cached is still readable
+        if let Err(e) = block_cache.lock().insert_bytes(location, &bytes) {
+            tracing::warn!("Failed to cache block {}: {}", location, e);
+        }
+        Ok(Some(bytes.into()))
+    }
+
+    /// Removes the cached object at `location`, e.g. after it is rewritten or deleted.
+    pub fn evict(&self, location: &str) {
+        if let Some(meta_cache) = &self.meta_cache {
+            meta_cache.lock().pop(location);
+        }
+        if let Some(block_cache) = &self.block_cache {
+            if let Err(e) = block_cache.lock().remove(location) {
+                tracing::warn!("Failed to evict block {} from the cache: {}", location, e);
+            }
+        }
+    }
+
+    async fn load_meta<T: DeserializeOwned>(
+        da: &Arc<dyn DataAccessor>,
+        location: &str,
+    ) -> Result<T> {
+        let bytes = da.read(location).await?;
+        decode_meta::<T>(&bytes)
+    }
+}
@@ -0,0 +1,112 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::sync::Arc;
+
+use common_base::tokio;
+use common_dal::DataAccessor;
+use common_exception::Result;
+use tempfile::TempDir;
+
+use super::TableCache;
+use crate::configs::Config;
+
+fn config(meta_cache_mb_size: u64, disk_cache: Option<&TempDir>) -> Config {
+    let mut conf = Config::default();
+    conf.query.table_meta_cache_mb_size = meta_cache_mb_size;
+    if let Some(dir) = disk_cache {
+        conf.query.table_disk_cache_root = dir.path().to_string_lossy().to_string();
+        conf.query.table_disk_cache_mb_size = 1;
+    }
+    conf
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_table_cache_meta() -> Result<()> {
+    let tmp_dir = TempDir::new().unwrap();
+    let da: Arc<dyn DataAccessor> =
+        Arc::new(common_dal::Local::with_path(tmp_dir.path().to_owned()));
+    let location = "_prefix/_sg/segment";
+    da.put(location, serde_json::to_vec(&vec![1u64, 2, 3])?)
+        .await?;
+
+    let cache = TableCache::create_global(&config(1, None))?;
+    let first = cache.read_meta::<Vec<u64>>(&da, location).await?;
+    let second = cache.read_meta::<Vec<u64>>(&da, location).await?;
+    assert_eq!(*first, vec![1, 2, 3]);
+    assert!(Arc::ptr_eq(&first, &second));
+
+    // served by the cache, even if the object is gone
+    da.delete(location).await?;
+    assert!(cache.read_meta::<Vec<u64>>(&da, location).await.is_ok());
+
+    cache.evict(location);
+    assert!(cache.read_meta::<Vec<u64>>(&da, location).await.is_err());
+
+    // disabled
+    da.put(location, serde_json::to_vec(&vec![1u64, 2, 3])?)
+        .await?;
+    let cache = TableCache::create_global(&config(0, None))?;
+    let first = cache.read_meta::<Vec<u64>>(&da, location).await?;
+    let second = cache.read_meta::<Vec<u64>>(&da, location).await?;
+    assert!(!Arc::ptr_eq(&first, &second));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_table_cache_meta_size() -> Result<()> {
+    let tmp_dir = TempDir::new().unwrap();
+    let da: Arc<dyn DataAccessor> =
+        Arc::new(common_dal::Local::with_path(tmp_dir.path().to_owned()));
+    let location = "_prefix/_sg/segment";
+
+    // the encoding (about 400KB) fits in the cache, while the decoded one (1.6MB) does not
+    da.put(location, serde_json::to_vec(&vec![0u64; 200_000])?)
+        .await?;
+    let cache = TableCache::create_global(&config(1, None))?;
+    let first = cache.read_meta::<Vec<u64>>(&da, location).await?;
+    let second = cache.read_meta::<Vec<u64>>(&da, location).await?;
+    assert!(!Arc::ptr_eq(&first, &second));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_table_cache_block() -> Result<()> {
+    let tmp_dir = TempDir::new().unwrap();
+    let cache_dir = TempDir::new().unwrap();
+    let da: Arc<dyn DataAccessor> =
+        Arc::new(common_dal::Local::with_path(tmp_dir.path().to_owned()));
+    let location = "_prefix/_b/block";
+    da.put(location, vec![1u8, 2, 3]).await?;
+
+    // disabled by default
+    let cache = TableCache::create_global(&config(1, None))?;
+    assert!(cache.read_block(&da, location).await?.is_none());
+
+    let cache = TableCache::create_global(&config(1, Some(&cache_dir)))?;
+    let block = cache.read_block(&da, location).await?;
+    assert_eq!(block.as_deref(), Some(&[1u8, 2, 3][..]));
+    assert!(cache_dir.path().join(location).exists());
+
+    // served by the cache, even if the block is gone
+    da.delete(location).await?;
+    let block = cache.read_block(&da, location).await?;
+    assert_eq!(block.as_deref(), Some(&[1u8, 2, 3][..]));
+
+    cache.evict(location);
+    assert!(!cache_dir.path().join(location).exists());
+    assert!(cache.read_block(&da, location).await.is_err());
+    Ok(())
+}
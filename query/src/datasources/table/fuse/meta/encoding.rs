@@ -0,0 +1,72 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Leading bytes of the binary encoded metadata files, which JSON files never start with.
+pub const META_FORMAT_MAGIC: &[u8; 4] = b"FUSE";
+
+/// Format version of the metadata files (snapshots and segments) written by this version.
+///
+/// - version 0: the JSON encoding of the early versions, which has no header
+/// - version 1: `META_FORMAT_MAGIC`, the version (u32, little endian), and the bincode
+///   encoding of the object
+///
+/// Adding, removing or reordering fields of the encoded structs changes the bincode
+/// encoding, and thus needs a new version.
+pub const META_FORMAT_VERSION: u32 = 1;
+
+const HEADER_LEN: usize = META_FORMAT_MAGIC.len() + std::mem::size_of::<u32>();
+
+/// Encodes a metadata object in the current format version.
+pub fn encode_meta<T: Serialize>(object: &T) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN);
+    bytes.extend_from_slice(META_FORMAT_MAGIC);
+    bytes.extend_from_slice(&META_FORMAT_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut bytes, object).map_err(|e| {
+        ErrorCode::InvalidMetaBinaryFormat(format!("Failed to encode fuse metadata: {}", e))
+    })?;
+    Ok(bytes)
+}
+
+/// Decodes a metadata object of any known format version.
+pub fn decode_meta<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    match meta_format_version(bytes)? {
+        0 => Ok(serde_json::from_slice(bytes)?),
+        1 => bincode::deserialize(&bytes[HEADER_LEN..]).map_err(|e| {
+            ErrorCode::InvalidMetaBinaryFormat(format!("Failed to decode fuse metadata: {}", e))
+        }),
+        version => Err(ErrorCode::InvalidMetaBinaryFormat(format!(
+            "Unsupported format version {} of fuse metadata, the latest known is {}",
+            version, META_FORMAT_VERSION
+        ))),
+    }
+}
+
+/// Format version of the encoded metadata object, 0 if it has no header.
+pub fn meta_format_version(bytes: &[u8]) -> Result<u32> {
+    if !bytes.starts_with(META_FORMAT_MAGIC) {
+        return Ok(0);
+    }
+    match bytes.get(META_FORMAT_MAGIC.len()..HEADER_LEN) {
+        Some(version) => Ok(u32::from_le_bytes(version.try_into().unwrap())),
+        None => Err(ErrorCode::InvalidMetaBinaryFormat(
+            "Truncated header of fuse metadata",
+        )),
+    }
+}
@@ -0,0 +1,145 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::collections::HashMap;
+
+use common_datavalues::DataValue;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::datasources::table::fuse::decode_meta;
+use crate::datasources::table::fuse::encode_meta;
+use crate::datasources::table::fuse::meta_format_version;
+use crate::datasources::table::fuse::BlockLocation;
+use crate::datasources::table::fuse::BlockMeta;
+use crate::datasources::table::fuse::ColStats;
+use crate::datasources::table::fuse::SegmentInfo;
+use crate::datasources::table::fuse::Stats;
+use crate::datasources::table::fuse::META_FORMAT_VERSION;
+
+fn col_stats(min: DataValue, max: DataValue) -> ColStats {
+    ColStats {
+        min,
+        max,
+        null_count: 0,
+        in_memory_size: 0,
+    }
+}
+
+fn segment(col_stats: HashMap<u32, ColStats>) -> SegmentInfo {
+    let block_meta = BlockMeta {
+        row_count: 3,
+        block_size: 10,
+        file_size: 20,
+        col_stats: col_stats.clone(),
+        location: BlockLocation {
+            location: "_b/block".to_string(),
+            meta_size: 0,
+        },
+        bloom_filter_location: None,
+        cluster_stats: None,
+    };
+    SegmentInfo {
+        blocks: vec![block_meta],
+        summary: Stats {
+            row_count: 3,
+            block_count: 1,
+            uncompressed_byte_size: 10,
+            compressed_byte_size: 20,
+            col_stats,
+        },
+    }
+}
+
+#[test]
+fn test_meta_encoding_round_trip() -> Result<()> {
+    // values that do not survive JSON
+    let mut stats = HashMap::new();
+    stats.insert(
+        0,
+        col_stats(
+            DataValue::Float64(Some(f64::NEG_INFINITY)),
+            DataValue::Float64(Some(f64::NAN)),
+        ),
+    );
+    stats.insert(
+        1,
+        col_stats(
+            DataValue::Int64(Some(i64::MIN)),
+            DataValue::UInt64(Some(u64::MAX)),
+        ),
+    );
+    stats.insert(
+        2,
+        col_stats(
+            DataValue::String(Some(vec![0xff, 0x00])),
+            DataValue::String(None),
+        ),
+    );
+
+    let bytes = encode_meta(&segment(stats))?;
+    assert_eq!(meta_format_version(&bytes)?, META_FORMAT_VERSION);
+
+    let decoded: SegmentInfo = decode_meta(&bytes)?;
+    assert_eq!(decoded.blocks.len(), 1);
+    assert_eq!(decoded.blocks[0].file_size, 20);
+    for col_stats in [&decoded.summary.col_stats, &decoded.blocks[0].col_stats] {
+        assert!(matches!(col_stats[&0].min, DataValue::Float64(Some(v)) if v == f64::NEG_INFINITY));
+        assert!(matches!(col_stats[&0].max, DataValue::Float64(Some(v)) if v.is_nan()));
+        assert_eq!(col_stats[&1].min, DataValue::Int64(Some(i64::MIN)));
+        assert_eq!(col_stats[&1].max, DataValue::UInt64(Some(u64::MAX)));
+        assert_eq!(col_stats[&2].min, DataValue::String(Some(vec![0xff, 0x00])));
+        assert_eq!(col_stats[&2].max, DataValue::String(None));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_meta_encoding_legacy_json() -> Result<()> {
+    let mut stats = HashMap::new();
+    stats.insert(
+        0,
+        col_stats(DataValue::Int32(Some(1)), DataValue::Int32(Some(3))),
+    );
+    let bytes = serde_json::to_vec(&segment(stats))?;
+    assert_eq!(meta_format_version(&bytes)?, 0);
+
+    let decoded: SegmentInfo = decode_meta(&bytes)?;
+    assert_eq!(decoded.summary.row_count, 3);
+    assert_eq!(
+        decoded.blocks[0].col_stats[&0].max,
+        DataValue::Int32(Some(3))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_meta_encoding_unknown_version() -> Result<()> {
+    let mut bytes = encode_meta(&segment(HashMap::new()))?;
+    bytes[4..8].copy_from_slice(&(META_FORMAT_VERSION + 1).to_le_bytes());
+    let result = decode_meta::<SegmentInfo>(&bytes);
+    assert_eq!(
+        result.unwrap_err().code(),
+        ErrorCode::InvalidMetaBinaryFormat("").code()
+    );
+
+    // truncated header
+    let result = decode_meta::<SegmentInfo>(&bytes[..6]);
+    assert_eq!(
+        result.unwrap_err().code(),
+        ErrorCode::InvalidMetaBinaryFormat("").code()
+    );
+    Ok(())
+}
@@ -13,6 +13,13 @@
 //  limitations under the License.
 //
 
+pub use encoding::*;
+pub use table_columns::*;
 pub use table_snapshot::*;
 
+mod encoding;
+mod table_columns;
 mod table_snapshot;
+
+#[cfg(test)]
+mod encoding_test;
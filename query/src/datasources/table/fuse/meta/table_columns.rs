@@ -0,0 +1,145 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_datavalues::DataField;
+use common_datavalues::DataSchema;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataValue;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::datasources::table::fuse::ColumnId;
+use crate::datasources::table::fuse::TableSnapshot;
+
+/// The columns of a table, along with ids of them that are kept across schema changes.
+///
+/// Blocks are not rewritten if columns are added, dropped or renamed. Instead, the columns
+/// of a block are resolved by their ids, and the ones that an older block does not have
+/// are read as their default values.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TableColumns {
+    pub schema: DataSchemaRef,
+    /// Ids of the columns, in the order of the fields of `schema`
+    pub column_ids: Vec<ColumnId>,
+    /// Default values of the added columns, NULL if not specified
+    pub default_values: HashMap<ColumnId, DataValue>,
+    /// Id of the next added column, the ids of the dropped columns are never reused
+    pub next_column_id: ColumnId,
+}
+
+impl TableColumns {
+    /// Columns of a table that has never been altered, which are identified by positions
+    pub fn from_schema(schema: DataSchemaRef) -> Self {
+        let num_columns = schema.fields().len() as ColumnId;
+        Self {
+            schema,
+            column_ids: (0..num_columns).collect(),
+            default_values: HashMap::new(),
+            next_column_id: num_columns,
+        }
+    }
+
+    /// Columns of the table, as of the given snapshot, e.g. the one navigated to by time travel
+    pub fn of_snapshot(&self, snapshot: &TableSnapshot) -> Self {
+        let schema = Arc::new(snapshot.schema.clone());
+        // snapshots of the early versions have no column ids, i.e. the ids are positions
+        let column_ids = if snapshot.column_ids.is_empty() {
+            (0..schema.fields().len() as ColumnId).collect()
+        } else {
+            snapshot.column_ids.clone()
+        };
+        Self {
+            schema,
+            column_ids,
+            default_values: self.default_values.clone(),
+            next_column_id: self.next_column_id,
+        }
+    }
+
+    pub fn column_id(&self, idx: usize) -> ColumnId {
+        self.column_ids[idx]
+    }
+
+    pub fn position_of(&self, column_id: ColumnId) -> Option<usize> {
+        self.column_ids.iter().position(|id| *id == column_id)
+    }
+
+    /// The value of the column, for the rows of the blocks written before the column is added
+    pub fn default_value(&self, column_id: ColumnId) -> DataValue {
+        self.default_values
+            .get(&column_id)
+            .cloned()
+            .unwrap_or(DataValue::Null)
+    }
+
+    pub fn add_column(&self, field: DataField, default_value: Option<DataValue>) -> Result<Self> {
+        if self.schema.column_with_name(field.name()).is_some() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Column {} already exists",
+                field.name()
+            )));
+        }
+
+        let mut altered = self.clone();
+        let mut fields = self.schema.fields().clone();
+        fields.push(field);
+        altered.schema = Arc::new(DataSchema::new_from(fields, self.schema.meta().clone()));
+        altered.column_ids.push(self.next_column_id);
+        if let Some(value) = default_value {
+            altered.default_values.insert(self.next_column_id, value);
+        }
+        altered.next_column_id += 1;
+        Ok(altered)
+    }
+
+    pub fn drop_column(&self, name: &str) -> Result<Self> {
+        let idx = self.schema.index_of(name)?;
+        if self.column_ids.len() == 1 {
+            return Err(ErrorCode::BadArguments(format!(
+                "Can not drop column {}, the only column of the table",
+                name
+            )));
+        }
+
+        let mut altered = self.clone();
+        let mut fields = self.schema.fields().clone();
+        fields.remove(idx);
+        altered.schema = Arc::new(DataSchema::new_from(fields, self.schema.meta().clone()));
+        let column_id = altered.column_ids.remove(idx);
+        altered.default_values.remove(&column_id);
+        Ok(altered)
+    }
+
+    pub fn rename_column(&self, old_name: &str, new_name: &str) -> Result<Self> {
+        let idx = self.schema.index_of(old_name)?;
+        if self.schema.column_with_name(new_name).is_some() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Column {} already exists",
+                new_name
+            )));
+        }
+
+        let mut altered = self.clone();
+        let mut fields = self.schema.fields().clone();
+        let field = &fields[idx];
+        fields[idx] = DataField::new(new_name, field.data_type().clone(), field.is_nullable());
+        altered.schema = Arc::new(DataSchema::new_from(fields, self.schema.meta().clone()));
+        Ok(altered)
+    }
+}
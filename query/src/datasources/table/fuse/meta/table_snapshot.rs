@@ -14,29 +14,44 @@
 
 use std::collections::HashMap;
 
+use chrono::DateTime;
+use chrono::Utc;
 use common_base::uuid;
 use common_datavalues::DataSchema;
 use common_datavalues::DataValue;
+use common_macros::MallocSizeOf;
 use serde::Deserialize;
 use serde::Serialize;
 use uuid::Uuid;
 
+use crate::datasources::table::fuse::TableColumns;
+
 pub type SnapshotId = Uuid; // TODO String might be better
 pub type ColumnId = u32;
 pub type Location = String;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// Encoded with `encode_meta`, the format version is kept in the header of the file
+#[derive(Serialize, Deserialize, Clone, Debug, MallocSizeOf)]
 pub struct TableSnapshot {
-    // TODO format_version
-    // pub format_version: u32,
     /// id of snapshot
+    #[ignore_malloc_size_of = "no heap"]
     pub snapshot_id: SnapshotId,
 
+    #[ignore_malloc_size_of = "no heap"]
     pub prev_snapshot_id: Option<SnapshotId>,
 
+    /// When the snapshot is generated, None for the snapshots of the early versions
+    #[serde(default)]
+    #[ignore_malloc_size_of = "no heap"]
+    pub timestamp: Option<DateTime<Utc>>,
+
     /// For each snapshot, we keep a schema for it (in case of schema evolution)
     pub schema: DataSchema,
 
+    /// Ids of the columns of `schema`, empty for the snapshots of the early versions
+    #[serde(default)]
+    pub column_ids: Vec<ColumnId>,
+
     /// Summary Statistics
     pub summary: Stats,
 
@@ -45,6 +60,10 @@ pub struct TableSnapshot {
     /// We rely on background merge tasks to keep merging segments, so that
     /// this the size of this vector could be kept reasonable
     pub segments: Vec<Location>,
+
+    /// How well the blocks are clustered by the cluster keys, None if the table has no cluster keys
+    #[serde(default)]
+    pub clustering: Option<ClusteringStatistics>,
 }
 
 impl TableSnapshot {
@@ -52,13 +71,23 @@ impl TableSnapshot {
         self.segments.push(location);
         self
     }
+
+    /// Turns the snapshot into a new one of the given columns, which is chained to the current one.
+    pub fn into_next(mut self, columns: &TableColumns) -> TableSnapshot {
+        self.prev_snapshot_id = Some(self.snapshot_id);
+        self.snapshot_id = Uuid::new_v4();
+        self.timestamp = Some(Utc::now());
+        self.schema = columns.schema.as_ref().clone();
+        self.column_ids = columns.column_ids.clone();
+        self
+    }
 }
 
 /// A segment comprised of one or more blocks
-#[derive(serde::Serialize, serde::Deserialize, Debug)]
+///
+/// Encoded with `encode_meta`, the format version is kept in the header of the file
+#[derive(serde::Serialize, serde::Deserialize, Debug, MallocSizeOf)]
 pub struct SegmentInfo {
-    // TODO format_version
-    // pub format_version: u32,
     /// blocks belong to this segment
     pub blocks: Vec<BlockMeta>,
 
@@ -66,7 +95,7 @@ pub struct SegmentInfo {
     pub summary: Stats,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, MallocSizeOf)]
 pub struct Stats {
     pub row_count: u64,
     pub block_count: u64,
@@ -79,23 +108,61 @@ pub struct Stats {
 }
 
 /// Meta information of a block (currently, the parquet file)
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, MallocSizeOf)]
 pub struct BlockMeta {
     /// Pointer of the data Block
     pub row_count: u64,
     pub block_size: u64,
+    /// Size of the block file, 0 if the block is written by an older version
+    #[serde(default)]
+    pub file_size: u64,
     pub col_stats: HashMap<ColumnId, ColStats>,
     pub location: BlockLocation,
+    /// Location of the bloom filters of the block (see `BloomFilterIndex`), if any
+    #[serde(default)]
+    pub bloom_filter_location: Option<Location>,
+    /// Range of the cluster keys of the block, None if the table has no cluster keys
+    #[serde(default)]
+    pub cluster_stats: Option<ClusterStats>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+impl BlockMeta {
+    /// Locations of the objects of the block, i.e. the block file and its bloom filters
+    pub fn object_locations(&self) -> Vec<Location> {
+        let mut locations = vec![self.location.location.clone()];
+        locations.extend(self.bloom_filter_location.clone());
+        locations
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, MallocSizeOf)]
 pub struct BlockLocation {
     pub location: Location,
     // for parquet, this filed can be used to fetch the meta data without seeking around
     pub meta_size: u64,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+/// The smallest and the largest values of the cluster keys of a block, i.e. of its first and last rows
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, MallocSizeOf)]
+pub struct ClusterStats {
+    pub min: Vec<DataValue>,
+    pub max: Vec<DataValue>,
+}
+
+/// Clustering depth of a snapshot, the ranges of the cluster keys of the blocks are compared
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Default, MallocSizeOf)]
+pub struct ClusteringStatistics {
+    /// Blocks of which the cluster keys are known
+    pub total_block_count: u64,
+    /// Blocks overlapping with any other blocks
+    pub overlapping_block_count: u64,
+    /// Average number of the other blocks that a block overlaps with, 0 if perfectly clustered
+    pub average_overlaps: f64,
+    /// Average number of the blocks that a value of the cluster keys may be in, 1 if perfectly clustered
+    pub average_depth: f64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, MallocSizeOf)]
 pub struct ColStats {
     pub min: DataValue,
     pub max: DataValue,
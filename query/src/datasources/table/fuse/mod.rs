@@ -13,14 +13,24 @@
 //  limitations under the License.
 //
 
+mod alter;
 mod append;
+mod cluster;
+mod commit;
+mod compact;
+mod delete;
 pub(crate) mod index;
 pub(crate) mod io;
 mod meta;
+mod mutation;
+mod purge;
 mod read;
 mod read_plan;
 mod table;
+mod table_options;
+mod time_travel;
 mod truncate;
+mod update;
 pub(crate) mod util;
 
 #[cfg(test)]
@@ -28,8 +38,10 @@ mod read_plan_test;
 #[cfg(test)]
 mod table_test;
 #[cfg(test)]
-mod table_test_fixture;
+pub(crate) mod table_test_fixture;
 
 pub(crate) use io::*;
 pub(crate) use meta::*;
+pub(crate) use mutation::*;
 pub(crate) use table::FuseTable;
+pub(crate) use table_options::FuseTableOptions;
@@ -0,0 +1,239 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use common_context::IOContext;
+use common_context::TableIOContext;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_planners::Expression;
+use common_planners::Extras;
+use common_planners::Part;
+
+use crate::datasources::table::fuse::encode_meta;
+use crate::datasources::table::fuse::index;
+use crate::datasources::table::fuse::io;
+use crate::datasources::table::fuse::util;
+use crate::datasources::table::fuse::BlockAppender;
+use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table::fuse::SegmentInfo;
+use crate::datasources::table::fuse::Stats;
+use crate::pipelines::transforms::ExpressionExecutor;
+
+pub enum BlockMutation {
+    /// The block is left as it is
+    Untouched,
+    /// The block is removed from the table
+    Removed,
+    /// The block is replaced by a new one, which has the same schema as the table
+    Replaced(DataBlock),
+}
+
+/// Decides what happens to a block that may be affected by a DELETE or an UPDATE.
+pub trait BlockMutator: Send + Sync {
+    /// If all the candidate blocks are removed, the blocks need not be read at all.
+    fn removes_all(&self) -> bool {
+        false
+    }
+
+    fn mutate(&self, block: DataBlock) -> Result<BlockMutation>;
+}
+
+impl FuseTable {
+    /// Copy on write mutation of the blocks which may match the `selection`.
+    ///
+    /// Only the candidate blocks (according to the min/max index and the bloom filters) are
+    /// read and handed over to the `mutator`, segments without any mutated block are shared
+    /// with the previous snapshot. Returns the number of mutated blocks, no new snapshot is
+    /// committed if it is zero.
+    pub async fn mutate_blocks(
+        &self,
+        io_ctx: Arc<TableIOContext>,
+        selection: Option<&Expression>,
+        mutator: &dyn BlockMutator,
+    ) -> Result<usize> {
+        let prev_snapshot = match self.table_snapshot(&io_ctx).await? {
+            None => return Ok(0),
+            Some(snapshot) => snapshot,
+        };
+
+        let da = io_ctx.get_data_accessor()?;
+        let reader = io::MetaReader::create(&io_ctx)?;
+        let schema = self.table_info.schema();
+        let columns = &self.table_columns;
+
+        // 1. blocks which may contain the rows to be mutated, according to the min/max index
+        // and the bloom filters
+        let push_downs = selection.map(|selection| Extras {
+            filters: vec![selection.clone()],
+            ..Extras::default()
+        });
+        let block_metas = index::range_filter(
+            &prev_snapshot,
+            columns.clone(),
+            push_downs.clone(),
+            reader.clone(),
+        )
+        .await?;
+        let candidates = index::bloom_filter_prune(block_metas, columns, &push_downs, &reader)
+            .await?
+            .into_iter()
+            .map(|block_meta| block_meta.location.location)
+            .collect::<HashSet<_>>();
+        if candidates.is_empty() {
+            return Ok(0);
+        }
+
+        // 2. rewrite the candidate blocks, the untouched segments are shared
+        let projection = (0..schema.fields().len()).collect::<Vec<_>>();
+        let mut segments = Vec::with_capacity(prev_snapshot.segments.len());
+        let mut summaries = Vec::with_capacity(prev_snapshot.segments.len());
+        let mut mutated_blocks = 0;
+        // the objects written, which are removed if the commit is rejected
+        let mut written = vec![];
+        for seg_loc in prev_snapshot.segments.iter() {
+            let segment_info = reader.read_segment(seg_loc).await?;
+            let affected = segment_info
+                .blocks
+                .iter()
+                .any(|block_meta| candidates.contains(&block_meta.location.location));
+            if !affected {
+                segments.push(seg_loc.clone());
+                summaries.push(segment_info.summary.clone());
+                continue;
+            }
+
+            let mut block_metas = Vec::with_capacity(segment_info.blocks.len());
+            let mut segment_changed = false;
+            for block_meta in segment_info.blocks.iter().cloned() {
+                if !candidates.contains(&block_meta.location.location) {
+                    block_metas.push(block_meta);
+                    continue;
+                }
+
+                let mutation = if mutator.removes_all() {
+                    BlockMutation::Removed
+                } else {
+                    let part = Part {
+                        name: block_meta.location.location.clone(),
+                        version: 0,
+                    };
+                    let block =
+                        io::do_read(part, da.clone(), projection.clone(), columns.clone()).await?;
+                    mutator.mutate(block)?
+                };
+
+                match mutation {
+                    BlockMutation::Untouched => block_metas.push(block_meta),
+                    BlockMutation::Removed => {
+                        segment_changed = true;
+                        mutated_blocks += 1;
+                    }
+                    BlockMutation::Replaced(new_block) => {
+                        segment_changed = true;
+                        mutated_blocks += 1;
+                        let new_block =
+                            DataBlock::create(schema.clone(), new_block.columns().to_vec());
+                        let new_block_meta = BlockAppender::append_block(
+                            da.clone(),
+                            &self.meta_location_generator,
+                            &self.table_options,
+                            columns,
+                            new_block,
+                        )
+                        .await?;
+                        written.extend(new_block_meta.object_locations());
+                        block_metas.push(new_block_meta);
+                    }
+                }
+            }
+
+            if !segment_changed {
+                segments.push(seg_loc.clone());
+                summaries.push(segment_info.summary.clone());
+            } else if !block_metas.is_empty() {
+                let new_segment_info = SegmentInfo {
+                    summary: util::reduce_block_metas(&block_metas, columns)?,
+                    blocks: block_metas,
+                };
+                let new_seg_loc = self.meta_location_generator.gen_segment_info_location();
+                let bytes = encode_meta(&new_segment_info)?;
+                da.put(&new_seg_loc, bytes).await?;
+                written.push(new_seg_loc.clone());
+                segments.push(new_seg_loc);
+                summaries.push(new_segment_info.summary);
+            }
+        }
+
+        if mutated_blocks == 0 {
+            return Ok(0);
+        }
+
+        // 3. new snapshot, chained to the previous one
+        let mut new_snapshot = prev_snapshot.into_next(columns);
+        new_snapshot.segments = segments;
+        new_snapshot.summary = summaries
+            .iter()
+            .try_fold(Stats::default(), |acc, summary| {
+                util::merge_stats(columns, &acc, summary)
+            })?;
+        new_snapshot.clustering = self
+            .clustering_of_segments(&reader, &new_snapshot.segments)
+            .await?;
+
+        let new_snapshot_loc = self
+            .meta_location_generator
+            .snapshot_location_from_uuid(&new_snapshot.snapshot_id);
+        let bytes = encode_meta(&new_snapshot)?;
+        da.put(&new_snapshot_loc, bytes).await?;
+
+        // 4. commit
+        self.commit_snapshot(&io_ctx, new_snapshot_loc, written)
+            .await?;
+
+        Ok(mutated_blocks)
+    }
+}
+
+/// Evaluates the selection of a DELETE or an UPDATE against a block.
+pub struct SelectionEvaluator {
+    executor: ExpressionExecutor,
+}
+
+impl SelectionEvaluator {
+    pub fn try_create(schema: DataSchemaRef, selection: Expression) -> Result<Self> {
+        let selection_field = selection.to_data_field(&schema)?;
+        let executor = ExpressionExecutor::try_create(
+            "selection expression executor",
+            schema,
+            DataSchemaRefExt::create(vec![selection_field]),
+            vec![selection],
+            false,
+        )?;
+        executor.validate()?;
+        Ok(SelectionEvaluator { executor })
+    }
+
+    /// A row is matched only if the selection is true, NULL means not matched.
+    pub fn eval(&self, block: &DataBlock) -> Result<DFBooleanArray> {
+        let selection = self.executor.execute(block)?.column(0).to_array()?;
+        let selection = selection.cast_with_type(&DataType::Boolean)?;
+        Ok(selection
+            .bool()?
+            .apply_with_idx_on_opt(|(_, matched)| Some(matched == Some(true))))
+    }
+}
@@ -0,0 +1,175 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::Duration;
+use chrono::Utc;
+use common_context::IOContext;
+use common_context::TableIOContext;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_tracing::tracing;
+
+use crate::catalogs::Catalog;
+use crate::catalogs::Table;
+use crate::datasources::table::fuse::commit::is_version_mismatch;
+use crate::datasources::table::fuse::commit::try_commit;
+use crate::datasources::table::fuse::encode_meta;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_SNAPSHOT_LOC;
+use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table::fuse::MetaReader;
+use crate::datasources::table::fuse::TableSnapshot;
+use crate::sessions::DatabendQueryContext;
+
+impl FuseTable {
+    /// Removes the objects of the table that are not referenced by the retained snapshots.
+    ///
+    /// The latest `retention_snapshots` snapshots, and those generated within the last
+    /// `retention_period_seconds`, are retained; the history ends at the oldest retained one.
+    ///
+    /// Objects written by the ongoing operations are not referenced yet, only the objects
+    /// that are not modified within the last `purge_grace_period_seconds` are removed. The
+    /// version of the table is bumped before anything is removed, so that the transactions
+    /// which have staged objects of the table can not commit them afterwards.
+    #[inline]
+    pub async fn do_purge(&self, io_ctx: Arc<TableIOContext>) -> Result<()> {
+        let ctx: Arc<DatabendQueryContext> = io_ctx
+            .get_user_data()?
+            .expect("DatabendQueryContext should not be None");
+
+        // the objects staged by the transaction are not referenced by any committed snapshot
+        if ctx.get_transaction().is_some() {
+            return Err(ErrorCode::UnImplement(
+                "OPTIMIZE TABLE ... PURGE is not allowed inside a transaction",
+            ));
+        }
+
+        let settings = ctx.get_settings();
+        let retention_snapshots = settings.get_retention_snapshots()?.max(1) as usize;
+        let retention_period = Duration::seconds(settings.get_retention_period_seconds()? as i64);
+        let grace_period = Duration::seconds(settings.get_purge_grace_period_seconds()? as i64);
+        // anything written after the version is bumped is newer than this
+        let modified_before = Utc::now() - grace_period;
+
+        // 1. the latest snapshot, this table might have been changed (e.g. compacted) since
+        // it was got from the catalog
+        let (ident, table_meta) = ctx
+            .get_catalog()
+            .get_table_meta_by_id(self.get_id())
+            .await?;
+        let latest = match table_meta.options.get(TBL_OPT_KEY_SNAPSHOT_LOC) {
+            None => return Ok(()),
+            Some(latest) => latest.clone(),
+        };
+
+        // 2. walk back the snapshots to be retained
+        let da = io_ctx.get_data_accessor()?;
+        let reader = MetaReader::create(&io_ctx)?;
+        let earliest = Utc::now() - retention_period;
+        let mut retained: Vec<(String, Arc<TableSnapshot>)> = vec![];
+        let mut next = Some(latest.clone());
+        while let Some(location) = next {
+            let snapshot = reader.read_snapshot(&location).await?;
+            // snapshots of the early versions have no timestamp, they are not in any period
+            let within_period = matches!(snapshot.timestamp, Some(ts) if ts > earliest);
+            if retained.len() >= retention_snapshots && !within_period {
+                break;
+            }
+            next = snapshot.prev_snapshot_id.map(|id| {
+                self.meta_location_generator
+                    .snapshot_location_from_uuid(&id)
+            });
+            retained.push((location, snapshot));
+        }
+
+        // 3. everything referenced by the retained snapshots
+        let mut referenced = HashSet::new();
+        for (location, snapshot) in retained.iter() {
+            referenced.insert(location.clone());
+            for seg_loc in snapshot.segments.iter() {
+                let segment_info = reader.read_segment(seg_loc).await?;
+                referenced.insert(seg_loc.clone());
+                for block_meta in segment_info.blocks.iter() {
+                    referenced.insert(block_meta.location.location.clone());
+                    referenced.extend(block_meta.bloom_filter_location.clone());
+                }
+            }
+        }
+
+        // 4. bump the version of the table, the transactions staged against the previous
+        // versions are rejected on COMMIT, their objects might be removed below
+        try_commit(
+            &io_ctx,
+            self.get_id(),
+            ident.version,
+            TBL_OPT_KEY_SNAPSHOT_LOC,
+            latest,
+        )
+        .await
+        .map_err(|cause| match is_version_mismatch(&cause) {
+            true => self.commit_conflict(cause.message()),
+            false => cause,
+        })?;
+
+        // 5. the oldest retained snapshot becomes the first one of the history, before
+        // its previous one is gone
+        if let Some((location, oldest)) = retained.last() {
+            if oldest.prev_snapshot_id.is_some() {
+                self.unchain_snapshot(&io_ctx, location, oldest).await?;
+            }
+        }
+
+        // 6. remove the rest, which are not modified within the grace period
+        let prefix = format!("{}/", self.meta_location_generator.prefix());
+        let mut num_removed = 0;
+        for object in da.list_objects(&prefix).await? {
+            // an object with no modification time might be just written
+            let expired = matches!(object.last_modified, Some(time) if time < modified_before);
+            if expired && !referenced.contains(&object.location) {
+                da.delete(&object.location).await?;
+                reader.evict(&object.location);
+                num_removed += 1;
+            }
+        }
+        tracing::debug!(
+            "purged {} objects of table {}",
+            num_removed,
+            self.get_table_info().desc
+        );
+        Ok(())
+    }
+
+    /// Rewrites the snapshot in place without its previous snapshot, the id (and location)
+    /// of the snapshot is kept, so are the time travel queries and `fuse_snapshot` referring
+    /// to it. Nothing else of the snapshot is changed, the readers of the cached copy see the
+    /// same data, only the history ends earlier.
+    async fn unchain_snapshot(
+        &self,
+        io_ctx: &TableIOContext,
+        location: &str,
+        snapshot: &TableSnapshot,
+    ) -> Result<()> {
+        let snapshot = TableSnapshot {
+            prev_snapshot_id: None,
+            ..snapshot.clone()
+        };
+        let da = io_ctx.get_data_accessor()?;
+        da.put(location, encode_meta(&snapshot)?).await?;
+        MetaReader::create(io_ctx)?.evict(location);
+        Ok(())
+    }
+}
@@ -53,6 +53,8 @@ impl FuseTable {
             default_proj()
         };
 
+        let table_cache = ctx.get_table_cache();
+
         // TODO we need a configuration to specify the unit of dequeue operation
         let bite_size = 1;
         let iter = {
@@ -64,11 +66,17 @@ impl FuseTable {
             .flatten()
         };
         let da = io_ctx.get_data_accessor()?;
-        let arrow_schema = self.table_info.schema().to_arrow();
+        let columns = self.table_columns.clone();
 
         let stream = futures::stream::iter(iter);
         let stream = stream.then(move |part| {
-            io::do_read(part, da.clone(), projection.clone(), arrow_schema.clone())
+            io::do_read_cached(
+                part,
+                da.clone(),
+                table_cache.clone(),
+                projection.clone(),
+                columns.clone(),
+            )
         });
         Ok(Box::pin(stream))
     }
@@ -18,16 +18,19 @@ use std::collections::HashSet;
 use common_base::BlockingWait;
 use common_context::IOContext;
 use common_context::TableIOContext;
-use common_dal::read_obj;
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_planners::Extras;
 use common_planners::Part;
 use common_planners::Partitions;
+use common_planners::PruningStatistics;
 use common_planners::Statistics;
 
 use super::index;
+use super::io;
 use crate::datasources::table::fuse::BlockMeta;
 use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table::fuse::TableColumns;
 
 impl FuseTable {
     #[inline]
@@ -38,16 +41,36 @@ impl FuseTable {
     ) -> Result<(Statistics, Partitions)> {
         let location = self.snapshot_loc();
         if let Some(loc) = location {
-            let da = io_ctx.get_data_accessor()?;
-            let schema = self.table_info.schema();
+            let reader = io::MetaReader::create(io_ctx)?;
+            let columns = self.table_columns.clone();
             let push_downs_c = push_downs.clone();
-            let block_metas = async {
-                let snapshot = read_obj(da.clone(), loc).await?;
-                index::range_filter(&snapshot, schema, push_downs_c, da).await
+            let (block_metas, pruning) = async move {
+                let snapshot = reader.read_snapshot(&loc).await?;
+                let (block_metas, pruned_segments) =
+                    index::MinMaxIndex::new(&snapshot, reader.clone())
+                        .apply(columns.clone(), push_downs_c.clone())
+                        .await?;
+                let num_range_filtered = block_metas.len();
+                let block_metas =
+                    index::bloom_filter_prune(block_metas, &columns, &push_downs_c, &reader)
+                        .await?;
+                let pruning = PruningStatistics {
+                    segments_total: snapshot.segments.len(),
+                    pruned_segments,
+                    partitions_total: snapshot.summary.block_count as usize,
+                    pruned_by_range: snapshot.summary.block_count as usize - num_range_filtered,
+                    pruned_by_bloom_filter: num_range_filtered - block_metas.len(),
+                };
+                Ok::<_, ErrorCode>((block_metas, pruning))
             }
             .wait_in(&io_ctx.get_runtime(), None)??;
 
-            let (statistics, parts) = to_partitions(&block_metas, push_downs);
+            let (mut statistics, parts) =
+                to_partitions(&block_metas, &self.table_columns, push_downs.clone());
+            // the pruning is of interest only if there are filters
+            if matches!(&push_downs, Some(extras) if !extras.filters.is_empty()) {
+                statistics.pruning = Some(pruning);
+            }
             Ok((statistics, parts))
         } else {
             Ok((Statistics::default(), vec![]))
@@ -57,10 +80,17 @@ impl FuseTable {
 
 pub(crate) fn to_partitions(
     blocks_metas: &[BlockMeta],
+    columns: &TableColumns,
     push_downs: Option<Extras>,
 ) -> (Statistics, Partitions) {
-    let proj_cols =
-        push_downs.and_then(|extras| extras.projection.map(HashSet::<usize>::from_iter));
+    // the projection refers to the positions of the columns, while the stats are kept by ids
+    let proj_cols = push_downs.and_then(|extras| {
+        extras.projection.map(|proj| {
+            proj.iter()
+                .map(|idx| columns.column_id(*idx))
+                .collect::<HashSet<_>>()
+        })
+    });
     blocks_metas.iter().fold(
         (Statistics::default(), Partitions::default()),
         |(mut stats, mut parts), block_meta| {
@@ -76,7 +106,7 @@ pub(crate) fn to_partitions(
                     stats.read_bytes += block_meta
                         .col_stats
                         .iter()
-                        .filter(|(cid, _)| proj.contains(&(**cid as usize)))
+                        .filter(|(cid, _)| proj.contains(*cid))
                         .map(|(_, col_stats)| col_stats.in_memory_size)
                         .sum::<u64>() as usize
                 }
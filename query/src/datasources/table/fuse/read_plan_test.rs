@@ -15,6 +15,9 @@
 
 use std::collections::HashMap;
 
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
 use common_datavalues::DataValue;
 use common_exception::Result;
 use common_planners::Extras;
@@ -23,6 +26,7 @@ use crate::datasources::table::fuse::read_plan::to_partitions;
 use crate::datasources::table::fuse::BlockLocation;
 use crate::datasources::table::fuse::BlockMeta;
 use crate::datasources::table::fuse::ColStats;
+use crate::datasources::table::fuse::TableColumns;
 
 #[test]
 fn test_to_partitions() -> Result<()> {
@@ -48,11 +52,14 @@ fn test_to_partitions() -> Result<()> {
             .iter()
             .map(|(_, col_stats)| col_stats.in_memory_size)
             .sum(),
+        file_size: 0,
         col_stats: cols_stats.clone(),
         location: BlockLocation {
             location: "".to_string(),
             meta_size: 0,
         },
+        bloom_filter_location: None,
+        cluster_stats: None,
     };
 
     let blocks_metas = (0..num_of_block)
@@ -60,8 +67,15 @@ fn test_to_partitions() -> Result<()> {
         .map(|_| block_meta.clone())
         .collect::<Vec<_>>();
 
+    let schema = DataSchemaRefExt::create(
+        (0..num_of_col)
+            .map(|col_id| DataField::new(&format!("c{}", col_id), DataType::Int8, false))
+            .collect(),
+    );
+    let columns = TableColumns::from_schema(schema);
+
     // CASE I:  no projection
-    let (s, _) = to_partitions(&blocks_metas, None);
+    let (s, _) = to_partitions(&blocks_metas, &columns, None);
     let expected_block_size: u64 = cols_stats
         .iter()
         .map(|(_, col_stats)| col_stats.in_memory_size)
@@ -89,7 +103,23 @@ fn test_to_partitions() -> Result<()> {
         limit: None,
         order_by: vec![],
     });
-    let (stats, _) = to_partitions(&blocks_metas, push_down);
+    let (stats, _) = to_partitions(&blocks_metas, &columns, push_down);
+    assert_eq!(expected_block_size * num_of_block, stats.read_bytes as u64);
+
+    // CASE III: col pruning, after the first column is dropped
+    // the projection refers to the positions, the ids of the projected columns are 1, 3 and 5
+    let columns = columns.drop_column("c0")?;
+    let expected_block_size: u64 = [1, 3, 5]
+        .iter()
+        .map(|cid| cols_stats[cid].in_memory_size)
+        .sum();
+    let push_down = Some(Extras {
+        projection: Some(vec![0, 2, 4]),
+        filters: vec![],
+        limit: None,
+        order_by: vec![],
+    });
+    let (stats, _) = to_partitions(&blocks_metas, &columns, push_down);
     assert_eq!(expected_block_size * num_of_block, stats.read_bytes as u64);
     Ok(())
 }
@@ -17,33 +17,59 @@ use std::any::Any;
 use std::sync::Arc;
 
 use common_context::DataContext;
-use common_context::IOContext;
 use common_context::TableIOContext;
-use common_dal::read_obj;
 use common_exception::Result;
 use common_meta_types::TableInfo;
+use common_planners::AlterTablePlan;
+use common_planners::DeletePlan;
 use common_planners::Extras;
 use common_planners::InsertIntoPlan;
+use common_planners::Optimization;
+use common_planners::OptimizeTablePlan;
 use common_planners::Partitions;
 use common_planners::ReadDataSourcePlan;
 use common_planners::Statistics;
 use common_planners::TruncateTablePlan;
+use common_planners::UpdatePlan;
 use common_streams::SendableDataBlockStream;
 
+use super::io;
 use super::util;
+use super::util::TableMetaLocationGenerator;
+use crate::catalogs::NavigationPoint;
 use crate::catalogs::Table;
+use crate::datasources::table::fuse::FuseTableOptions;
+use crate::datasources::table::fuse::TableColumns;
 use crate::datasources::table::fuse::TableSnapshot;
 
 pub struct FuseTable {
     pub(crate) table_info: TableInfo,
+    pub(crate) meta_location_generator: TableMetaLocationGenerator,
+    pub(crate) table_options: FuseTableOptions,
+    pub(crate) table_columns: TableColumns,
 }
 
 impl FuseTable {
     pub fn try_create(
-        table_info: TableInfo,
+        mut table_info: TableInfo,
         _data_ctx: Arc<dyn DataContext<u64>>,
     ) -> Result<Box<dyn Table>> {
-        Ok(Box::new(FuseTable { table_info }))
+        let meta_location_generator =
+            TableMetaLocationGenerator::of_table(table_info.ident.table_id);
+        let table_columns = match table_info.options().get(util::TBL_OPT_KEY_COLUMNS) {
+            Some(columns) => serde_json::from_str(columns)?,
+            None => TableColumns::from_schema(table_info.schema()),
+        };
+        // the schema of an altered table is the one of its columns
+        table_info.meta.schema = table_columns.schema.clone();
+        let table_options =
+            FuseTableOptions::try_create(table_info.options(), table_info.schema().as_ref())?;
+        Ok(Box::new(FuseTable {
+            table_info,
+            meta_location_generator,
+            table_options,
+            table_columns,
+        }))
     }
 }
 
@@ -97,6 +123,42 @@ impl Table for FuseTable {
     ) -> Result<()> {
         self.do_truncate(io_ctx, truncate_plan).await
     }
+
+    async fn delete(&self, io_ctx: Arc<TableIOContext>, delete_plan: DeletePlan) -> Result<()> {
+        self.do_delete(io_ctx, delete_plan).await
+    }
+
+    async fn update(&self, io_ctx: Arc<TableIOContext>, update_plan: UpdatePlan) -> Result<()> {
+        self.do_update(io_ctx, update_plan).await
+    }
+
+    async fn optimize(
+        &self,
+        io_ctx: Arc<TableIOContext>,
+        optimize_plan: OptimizeTablePlan,
+    ) -> Result<()> {
+        match optimize_plan.operation {
+            Optimization::Compact => self.do_compact(io_ctx).await,
+            Optimization::Purge => self.do_purge(io_ctx).await,
+            Optimization::Recluster => self.do_recluster(io_ctx).await,
+            Optimization::All => {
+                self.do_compact(io_ctx.clone()).await?;
+                self.do_purge(io_ctx).await
+            }
+        }
+    }
+
+    async fn alter(&self, io_ctx: Arc<TableIOContext>, alter_plan: AlterTablePlan) -> Result<()> {
+        self.do_alter(io_ctx, alter_plan).await
+    }
+
+    fn navigate_to(
+        &self,
+        io_ctx: Arc<TableIOContext>,
+        point: &NavigationPoint,
+    ) -> Result<Arc<dyn Table>> {
+        self.do_navigate_to(io_ctx, point)
+    }
 }
 
 impl FuseTable {
@@ -112,8 +174,9 @@ impl FuseTable {
         io_ctx: &TableIOContext,
     ) -> Result<Option<TableSnapshot>> {
         if let Some(loc) = self.snapshot_loc() {
-            let da = io_ctx.get_data_accessor()?;
-            Ok(Some(read_obj(da, loc.to_string()).await?))
+            let reader = io::MetaReader::create(io_ctx)?;
+            let snapshot = reader.read_snapshot(&loc).await?;
+            Ok(Some(snapshot.as_ref().clone()))
         } else {
             Ok(None)
         }
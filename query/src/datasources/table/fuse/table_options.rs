@@ -0,0 +1,184 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use common_arrow::arrow::io::parquet::write::Compression;
+use common_datavalues::DataSchema;
+use common_datavalues::DataType;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_types::TableMeta;
+use common_planners::Expression;
+
+use crate::datasources::table::fuse::index;
+use crate::datasources::table::fuse::util;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_BLOCK_SIZE_THRESHOLD;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_BLOOM_FILTER_COLUMNS;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_CLUSTER_KEYS;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_COMPRESSION;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_DICTIONARY_COLUMNS;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_ROW_PER_BLOCK;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_SNAPSHOT_LOC;
+
+/// How the blocks of a fuse table are written, parsed from the options of the table.
+#[derive(Clone, Debug)]
+pub struct FuseTableOptions {
+    pub compression: Compression,
+    /// String columns of low cardinality, which are dictionary encoded
+    pub dictionary_columns: HashSet<String>,
+    /// Columns of high cardinality, whose values are kept in the bloom filters of the blocks
+    pub bloom_filter_columns: HashSet<String>,
+    /// If specified, the inserted data are re-chunked into blocks of this many rows
+    pub row_per_block: Option<usize>,
+    /// If specified, the inserted data are re-chunked into blocks of this many bytes (roughly)
+    pub block_size_threshold: Option<usize>,
+    /// Expressions of `CLUSTER BY (...)`, the rows of each block are sorted by them
+    pub cluster_keys: Vec<Expression>,
+}
+
+impl Default for FuseTableOptions {
+    fn default() -> Self {
+        Self {
+            compression: Compression::Lz4,
+            dictionary_columns: HashSet::new(),
+            bloom_filter_columns: HashSet::new(),
+            row_per_block: None,
+            block_size_threshold: None,
+            cluster_keys: vec![],
+        }
+    }
+}
+
+impl FuseTableOptions {
+    pub fn try_create(options: &HashMap<String, String>, schema: &DataSchema) -> Result<Self> {
+        let mut table_options = FuseTableOptions::default();
+
+        if let Some(compression) = options.get(TBL_OPT_KEY_COMPRESSION) {
+            table_options.compression = match compression.to_lowercase().as_str() {
+                "none" => Compression::Uncompressed,
+                "lz4" => Compression::Lz4,
+                "zstd" => Compression::Zstd,
+                "snappy" => Compression::Snappy,
+                other => {
+                    return Err(ErrorCode::BadOption(format!(
+                        "Unknown compression {}, expecting one of none, lz4, zstd and snappy",
+                        other
+                    )))
+                }
+            };
+        }
+
+        if let Some(columns) = options.get(TBL_OPT_KEY_DICTIONARY_COLUMNS) {
+            for name in columns.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let field = schema.field_with_name(name).map_err(|_| {
+                    ErrorCode::BadOption(format!("Unknown dictionary column {}", name))
+                })?;
+                if field.data_type() != &DataType::String {
+                    return Err(ErrorCode::BadOption(format!(
+                        "Dictionary column {} must be of type String, but got {}",
+                        name,
+                        field.data_type()
+                    )));
+                }
+                table_options.dictionary_columns.insert(name.to_string());
+            }
+        }
+
+        if let Some(columns) = options.get(TBL_OPT_KEY_BLOOM_FILTER_COLUMNS) {
+            for name in columns.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let field = schema.field_with_name(name).map_err(|_| {
+                    ErrorCode::BadOption(format!("Unknown bloom filter column {}", name))
+                })?;
+                if !index::is_bloom_filter_supported(field.data_type()) {
+                    return Err(ErrorCode::BadOption(format!(
+                        "Bloom filter is not supported for column {} of type {}",
+                        name,
+                        field.data_type()
+                    )));
+                }
+                table_options.bloom_filter_columns.insert(name.to_string());
+            }
+        }
+
+        if let Some(cluster_keys) = options.get(TBL_OPT_KEY_CLUSTER_KEYS) {
+            let cluster_keys: Vec<Expression> = serde_json::from_str(cluster_keys)
+                .map_err(|e| ErrorCode::BadOption(format!("Invalid cluster keys: {}", e)))?;
+            // e.g. the columns of the keys are dropped
+            let schema = Arc::new(schema.clone());
+            for key in &cluster_keys {
+                key.to_data_field(&schema).map_err(|e| {
+                    ErrorCode::BadOption(format!("Invalid cluster key {:?}: {}", key, e.message()))
+                })?;
+            }
+            table_options.cluster_keys = cluster_keys;
+        }
+
+        table_options.row_per_block = parse_positive(options, TBL_OPT_KEY_ROW_PER_BLOCK)?;
+        table_options.block_size_threshold =
+            parse_positive(options, TBL_OPT_KEY_BLOCK_SIZE_THRESHOLD)?;
+        Ok(table_options)
+    }
+
+    /// Validates the options of a table to be created, unknown options are rejected.
+    pub fn validate(table_meta: &TableMeta) -> Result<()> {
+        let known = [
+            TBL_OPT_KEY_COMPRESSION,
+            TBL_OPT_KEY_DICTIONARY_COLUMNS,
+            TBL_OPT_KEY_BLOOM_FILTER_COLUMNS,
+            TBL_OPT_KEY_ROW_PER_BLOCK,
+            TBL_OPT_KEY_BLOCK_SIZE_THRESHOLD,
+            TBL_OPT_KEY_CLUSTER_KEYS,
+            TBL_OPT_KEY_SNAPSHOT_LOC,
+        ];
+        if let Some(key) = table_meta
+            .options
+            .keys()
+            .find(|key| !known.contains(&key.as_str()))
+        {
+            return Err(ErrorCode::BadOption(format!(
+                "Unknown option {} for fuse table",
+                key
+            )));
+        }
+        Self::try_create(&table_meta.options, table_meta.schema.as_ref()).map(|_| ())
+    }
+
+    /// The target rows of a block, blocks with fewer rows are re-packed by compaction
+    pub fn target_block_rows(&self) -> usize {
+        self.row_per_block.unwrap_or(util::DEFAULT_BLOCK_ROWS)
+    }
+
+    /// The target bytes of a block, blocks with fewer bytes are re-packed by compaction
+    pub fn target_block_bytes(&self) -> usize {
+        self.block_size_threshold
+            .unwrap_or(util::DEFAULT_BLOCK_BYTES)
+    }
+}
+
+fn parse_positive(options: &HashMap<String, String>, key: &str) -> Result<Option<usize>> {
+    match options.get(key) {
+        None => Ok(None),
+        Some(value) => match value.parse::<usize>() {
+            Ok(v) if v > 0 => Ok(Some(v)),
+            _ => Err(ErrorCode::BadOption(format!(
+                "Option {} must be a positive integer, but got {}",
+                key, value
+            ))),
+        },
+    }
+}
@@ -15,15 +15,41 @@
 
 use std::sync::Arc;
 
+use chrono::TimeZone;
+use chrono::Utc;
 use common_base::tokio;
+use common_context::IOContext;
+use common_datablocks::assert_blocks_sorted_eq;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::SeriesFrom;
+use common_datavalues::series::Series;
+use common_datavalues::DataField;
+use common_datavalues::DataType;
+use common_datavalues::DataValue;
+use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::col;
+use common_planners::lit;
+use common_planners::AlterTableOperation;
+use common_planners::AlterTablePlan;
+use common_planners::DeletePlan;
+use common_planners::Expression;
+use common_planners::Extras;
+use common_planners::Optimization;
+use common_planners::OptimizeTablePlan;
 use common_planners::ReadDataSourcePlan;
 use common_planners::TruncateTablePlan;
+use common_planners::UpdatePlan;
 use futures::TryStreamExt;
 
 use crate::catalogs::Catalog;
+use crate::catalogs::NavigationPoint;
+use crate::catalogs::Table;
 use crate::catalogs::ToReadDataSourcePlan;
 use crate::datasources::table::fuse::table_test_fixture::TestFixture;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_CLUSTER_KEYS;
+use crate::datasources::table::fuse::FuseTable;
+use crate::sessions::DatabendQueryContextRef;
 
 #[tokio::test]
 async fn test_fuse_table_simple_case() -> Result<()> {
@@ -195,3 +221,1235 @@ async fn test_fuse_table_truncate() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_fuse_table_delete() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+
+    let crate_table_plan = fixture.default_crate_table_plan();
+    let catalog = ctx.get_catalog();
+    catalog.create_table(crate_table_plan).await?;
+
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+    let delete_plan = |selection| DeletePlan {
+        db_name: fixture.default_db(),
+        tbl_name: fixture.default_table(),
+        tbl_id: table.get_id(),
+        table_schema: TestFixture::default_schema(),
+        selection,
+    };
+
+    // 1. delete from empty table
+    let prev_version = table.get_table_info().ident.version;
+    table
+        .delete(io_ctx.clone(), delete_plan(Some(col("id").eq(lit(2)))))
+        .await?;
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    // no side effects
+    assert_eq!(prev_version, table.get_table_info().ident.version);
+
+    // 2. insert 5 blocks of [1, 2, 3]
+    let num_blocks = 5;
+    let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+    let stream = Box::pin(futures::stream::iter(TestFixture::gen_block_stream(
+        num_blocks,
+    )));
+    table
+        .append_data(io_ctx.clone(), insert_into_plan, stream)
+        .await?;
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+
+    // 3. nothing matched, all the blocks are pruned by the min/max index
+    let prev_version = table.get_table_info().ident.version;
+    table
+        .delete(io_ctx.clone(), delete_plan(Some(col("id").gt(lit(5)))))
+        .await?;
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    assert_eq!(prev_version, table.get_table_info().ident.version);
+
+    // 4. delete some of the rows, every block is rewritten
+    let prev_snapshot = table
+        .as_any()
+        .downcast_ref::<FuseTable>()
+        .unwrap()
+        .table_snapshot(io_ctx.as_ref())
+        .await?
+        .unwrap();
+    table
+        .delete(io_ctx.clone(), delete_plan(Some(col("id").eq(lit(2)))))
+        .await?;
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let snapshot = table
+        .as_any()
+        .downcast_ref::<FuseTable>()
+        .unwrap()
+        .table_snapshot(io_ctx.as_ref())
+        .await?
+        .unwrap();
+    assert_eq!(snapshot.prev_snapshot_id, Some(prev_snapshot.snapshot_id));
+    assert_eq!(snapshot.summary.row_count, num_blocks as u64 * 2);
+
+    let (stats, parts) = table.read_partitions(io_ctx.clone(), None)?;
+    assert_eq!(parts.len(), num_blocks as usize);
+    assert_eq!(stats.read_rows, num_blocks as usize * 2);
+
+    ctx.try_set_partitions(parts)?;
+    let stream = table
+        .read(io_ctx.clone(), &ReadDataSourcePlan {
+            table_info: Default::default(),
+            scan_fields: None,
+            parts: Default::default(),
+            statistics: Default::default(),
+            description: "".to_string(),
+            tbl_args: None,
+            push_downs: None,
+        })
+        .await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+----+", //
+        "| id |", //
+        "+----+", //
+        "| 1  |", //
+        "| 1  |", //
+        "| 1  |", //
+        "| 1  |", //
+        "| 1  |", //
+        "| 3  |", //
+        "| 3  |", //
+        "| 3  |", //
+        "| 3  |", //
+        "| 3  |", //
+        "+----+", //
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, blocks.as_slice());
+
+    // 5. delete all the rows
+    table.delete(io_ctx.clone(), delete_plan(None)).await?;
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let (stats, parts) = table.read_partitions(io_ctx.clone(), None)?;
+    assert_eq!(parts.len(), 0);
+    assert_eq!(stats.read_rows, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_table_update() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+
+    let crate_table_plan = fixture.default_crate_table_plan();
+    let catalog = ctx.get_catalog();
+    catalog.create_table(crate_table_plan).await?;
+
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+    let update_plan = |value: i32, selection| UpdatePlan {
+        db_name: fixture.default_db(),
+        tbl_name: fixture.default_table(),
+        tbl_id: table.get_id(),
+        table_schema: TestFixture::default_schema(),
+        assignments: vec![("id".to_string(), Expression::Cast {
+            expr: Box::new(lit(value)),
+            data_type: DataType::Int32,
+        })],
+        selection,
+    };
+
+    // 1. insert 5 blocks of [1, 2, 3]
+    let num_blocks = 5;
+    let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+    let stream = Box::pin(futures::stream::iter(TestFixture::gen_block_stream(
+        num_blocks,
+    )));
+    table
+        .append_data(io_ctx.clone(), insert_into_plan, stream)
+        .await?;
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+
+    // 2. nothing matched, all the blocks are pruned by the min/max index
+    let prev_version = table.get_table_info().ident.version;
+    table
+        .update(io_ctx.clone(), update_plan(0, Some(col("id").gt(lit(5)))))
+        .await?;
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    assert_eq!(prev_version, table.get_table_info().ident.version);
+
+    // 3. update some of the rows, the others keep their values
+    let prev_snapshot = table
+        .as_any()
+        .downcast_ref::<FuseTable>()
+        .unwrap()
+        .table_snapshot(io_ctx.as_ref())
+        .await?
+        .unwrap();
+    table
+        .update(io_ctx.clone(), update_plan(20, Some(col("id").eq(lit(2)))))
+        .await?;
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let snapshot = table
+        .as_any()
+        .downcast_ref::<FuseTable>()
+        .unwrap()
+        .table_snapshot(io_ctx.as_ref())
+        .await?
+        .unwrap();
+    assert_eq!(snapshot.prev_snapshot_id, Some(prev_snapshot.snapshot_id));
+    assert_eq!(snapshot.summary.row_count, num_blocks as u64 * 3);
+
+    let (stats, parts) = table.read_partitions(io_ctx.clone(), None)?;
+    assert_eq!(parts.len(), num_blocks as usize);
+    assert_eq!(stats.read_rows, num_blocks as usize * 3);
+
+    ctx.try_set_partitions(parts)?;
+    let stream = table
+        .read(io_ctx.clone(), &ReadDataSourcePlan {
+            table_info: Default::default(),
+            scan_fields: None,
+            parts: Default::default(),
+            statistics: Default::default(),
+            description: "".to_string(),
+            tbl_args: None,
+            push_downs: None,
+        })
+        .await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+----+", //
+        "| id |", //
+        "+----+", //
+        "| 1  |", //
+        "| 1  |", //
+        "| 1  |", //
+        "| 1  |", //
+        "| 1  |", //
+        "| 20 |", //
+        "| 20 |", //
+        "| 20 |", //
+        "| 20 |", //
+        "| 20 |", //
+        "| 3  |", //
+        "| 3  |", //
+        "| 3  |", //
+        "| 3  |", //
+        "| 3  |", //
+        "+----+", //
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, blocks.as_slice());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_table_time_travel() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+
+    let crate_table_plan = fixture.default_crate_table_plan();
+    let catalog = ctx.get_catalog();
+    catalog.create_table(crate_table_plan).await?;
+
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+
+    // 1. empty table has no history
+    let result = table.navigate_to(io_ctx.clone(), &NavigationPoint::TimePoint(Utc::now()));
+    assert_eq!(
+        result.err().unwrap().code(),
+        ErrorCode::TableHistoricalDataNotFound("").code()
+    );
+
+    // 2. two insertions, each of them generates a new snapshot
+    let mut snapshots = vec![];
+    for num_blocks in [1, 2] {
+        let table = catalog
+            .get_table(
+                fixture.default_db().as_str(),
+                fixture.default_table().as_str(),
+            )
+            .await?;
+        let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+        let stream = Box::pin(futures::stream::iter(TestFixture::gen_block_stream(
+            num_blocks,
+        )));
+        table
+            .append_data(io_ctx.clone(), insert_into_plan, stream)
+            .await?;
+        let table = catalog
+            .get_table(
+                fixture.default_db().as_str(),
+                fixture.default_table().as_str(),
+            )
+            .await?;
+        let snapshot = table
+            .as_any()
+            .downcast_ref::<FuseTable>()
+            .unwrap()
+            .table_snapshot(io_ctx.as_ref())
+            .await?
+            .unwrap();
+        assert!(snapshot.timestamp.is_some());
+        snapshots.push(snapshot);
+    }
+    assert_eq!(
+        snapshots[1].prev_snapshot_id,
+        Some(snapshots[0].snapshot_id)
+    );
+
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+
+    // 3. travel to the first snapshot, by id and by timestamp
+    let points = vec![
+        NavigationPoint::SnapshotID(snapshots[0].snapshot_id.to_string()),
+        NavigationPoint::TimePoint(snapshots[0].timestamp.unwrap()),
+    ];
+    for point in points {
+        let history = table.navigate_to(io_ctx.clone(), &point)?;
+        let (stats, parts) = history.read_partitions(io_ctx.clone(), None)?;
+        assert_eq!(parts.len(), 1);
+        assert_eq!(stats.read_rows, 3);
+    }
+
+    // 4. the latest snapshot
+    let history = table.navigate_to(io_ctx.clone(), &NavigationPoint::TimePoint(Utc::now()))?;
+    let (stats, parts) = history.read_partitions(io_ctx.clone(), None)?;
+    assert_eq!(parts.len(), 3);
+    assert_eq!(stats.read_rows, 9);
+
+    // 5. no such snapshot
+    let points = vec![
+        NavigationPoint::SnapshotID(uuid::Uuid::new_v4().to_string()),
+        NavigationPoint::TimePoint(Utc.ymd(2000, 1, 1).and_hms(0, 0, 0)),
+    ];
+    for point in points {
+        let result = table.navigate_to(io_ctx.clone(), &point);
+        assert_eq!(
+            result.err().unwrap().code(),
+            ErrorCode::TableHistoricalDataNotFound("").code()
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_table_compact() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+
+    let crate_table_plan = fixture.default_crate_table_plan();
+    let catalog = ctx.get_catalog();
+    catalog.create_table(crate_table_plan).await?;
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+
+    // 1. five insertions, each of them generates a segment of one small block
+    let num_inserts = 5;
+    for _ in 0..num_inserts {
+        let table = catalog
+            .get_table(
+                fixture.default_db().as_str(),
+                fixture.default_table().as_str(),
+            )
+            .await?;
+        let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+        let stream = Box::pin(futures::stream::iter(TestFixture::gen_block_stream(1)));
+        table
+            .append_data(io_ctx.clone(), insert_into_plan, stream)
+            .await?;
+    }
+
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let prev_snapshot = table
+        .as_any()
+        .downcast_ref::<FuseTable>()
+        .unwrap()
+        .table_snapshot(io_ctx.as_ref())
+        .await?
+        .unwrap();
+    assert_eq!(prev_snapshot.segments.len(), num_inserts);
+    assert_eq!(prev_snapshot.summary.block_count, num_inserts as u64);
+
+    // 2. compact
+    let optimize_plan = OptimizeTablePlan {
+        db: fixture.default_db(),
+        table: fixture.default_table(),
+        operation: Optimization::Compact,
+    };
+    table
+        .optimize(io_ctx.clone(), optimize_plan.clone())
+        .await?;
+
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let snapshot = table
+        .as_any()
+        .downcast_ref::<FuseTable>()
+        .unwrap()
+        .table_snapshot(io_ctx.as_ref())
+        .await?
+        .unwrap();
+    assert_eq!(snapshot.segments.len(), 1);
+    assert_eq!(snapshot.summary.block_count, 1);
+    assert_eq!(snapshot.summary.row_count, 15);
+    assert_eq!(snapshot.prev_snapshot_id, Some(prev_snapshot.snapshot_id));
+
+    // 3. data are kept intact
+    let (stats, parts) = table.read_partitions(io_ctx.clone(), None)?;
+    assert_eq!(parts.len(), 1);
+    assert_eq!(stats.read_rows, 15);
+    ctx.try_set_partitions(parts)?;
+    let stream = table
+        .read(io_ctx.clone(), &ReadDataSourcePlan {
+            table_info: Default::default(),
+            scan_fields: None,
+            parts: Default::default(),
+            statistics: Default::default(),
+            description: "".to_string(),
+            tbl_args: None,
+            push_downs: None,
+        })
+        .await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+    assert_eq!(rows, 15);
+
+    // 4. compacting a compact table is a no-op
+    let version = table.get_table_info().ident.version;
+    table.optimize(io_ctx.clone(), optimize_plan).await?;
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    assert_eq!(table.get_table_info().ident.version, version);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_table_purge() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+
+    let crate_table_plan = fixture.default_crate_table_plan();
+    let catalog = ctx.get_catalog();
+    catalog.create_table(crate_table_plan).await?;
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+    let da = io_ctx.get_data_accessor()?;
+
+    // 1. three insertions, each of them writes a block, a segment and a snapshot
+    for _ in 0..3 {
+        let table = catalog
+            .get_table(
+                fixture.default_db().as_str(),
+                fixture.default_table().as_str(),
+            )
+            .await?;
+        let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+        let stream = Box::pin(futures::stream::iter(TestFixture::gen_block_stream(1)));
+        table
+            .append_data(io_ctx.clone(), insert_into_plan, stream)
+            .await?;
+    }
+
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let fuse_table = table.as_any().downcast_ref::<FuseTable>().unwrap();
+    let prefix = format!("{}/", fuse_table.meta_location_generator.prefix());
+    assert_eq!(da.list(&prefix).await?.len(), 9);
+    let history = fuse_table.snapshot_history(io_ctx.as_ref()).await?;
+    let snapshot_ids = history
+        .iter()
+        .map(|snapshot| snapshot.snapshot_id)
+        .collect::<Vec<_>>();
+
+    // 2. keep the latest two snapshots, the objects written are all out of the grace period
+    ctx.get_settings().set_purge_grace_period_seconds(0)?;
+    ctx.get_settings().set_retention_snapshots(2)?;
+    let optimize_plan = OptimizeTablePlan {
+        db: fixture.default_db(),
+        table: fixture.default_table(),
+        operation: Optimization::Purge,
+    };
+    table
+        .optimize(io_ctx.clone(), optimize_plan.clone())
+        .await?;
+    assert_eq!(da.list(&prefix).await?.len(), 6);
+
+    // the retained snapshots keep their ids, the history ends at the oldest one of them
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let fuse_table = table.as_any().downcast_ref::<FuseTable>().unwrap();
+    let history = fuse_table.snapshot_history(io_ctx.as_ref()).await?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].snapshot_id, snapshot_ids[0]);
+    assert_eq!(history[1].snapshot_id, snapshot_ids[1]);
+    assert_eq!(history[1].prev_snapshot_id, None);
+
+    // 3. compact and keep the current snapshot only
+    ctx.get_settings().set_retention_snapshots(1)?;
+    let optimize_plan = OptimizeTablePlan {
+        operation: Optimization::All,
+        ..optimize_plan
+    };
+    table.optimize(io_ctx.clone(), optimize_plan).await?;
+    assert_eq!(da.list(&prefix).await?.len(), 3);
+
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let fuse_table = table.as_any().downcast_ref::<FuseTable>().unwrap();
+    let history = fuse_table.snapshot_history(io_ctx.as_ref()).await?;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].summary.block_count, 1);
+
+    // 4. data are kept intact
+    let (stats, parts) = table.read_partitions(io_ctx.clone(), None)?;
+    ctx.try_set_partitions(parts)?;
+    assert_eq!(stats.read_rows, 9);
+    let stream = table
+        .read(io_ctx.clone(), &ReadDataSourcePlan {
+            table_info: Default::default(),
+            scan_fields: None,
+            parts: Default::default(),
+            statistics: Default::default(),
+            description: "".to_string(),
+            tbl_args: None,
+            push_downs: None,
+        })
+        .await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+    assert_eq!(rows, 9);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_table_options() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+    let catalog = ctx.get_catalog();
+
+    // 1. invalid options are rejected at creation
+    let invalid_options = vec![
+        ("compression", "gzip"),
+        ("row_per_block", "0"),
+        ("block_size_threshold", "abc"),
+        ("dictionary_columns", "id"),
+        ("dictionary_columns", "not_exist"),
+        ("bloom_filter_columns", "not_exist"),
+        ("not_exist", "1"),
+        (TBL_OPT_KEY_CLUSTER_KEYS, "id"),
+        (TBL_OPT_KEY_CLUSTER_KEYS, r#"[{"Column":"not_exist"}]"#),
+    ];
+    for (key, value) in invalid_options {
+        let mut plan = fixture.default_crate_table_plan();
+        plan.table_meta
+            .options
+            .insert(key.to_string(), value.to_string());
+        let result = catalog.create_table(plan).await;
+        assert_eq!(
+            result.err().unwrap().code(),
+            ErrorCode::BadOption("").code(),
+            "option {} = {}",
+            key,
+            value
+        );
+    }
+
+    // 2. blocks are compressed with zstd and re-chunked into blocks of 2 rows
+    let mut plan = fixture.default_crate_table_plan();
+    plan.table_meta
+        .options
+        .insert("compression".to_string(), "zstd".to_string());
+    plan.table_meta
+        .options
+        .insert("row_per_block".to_string(), "2".to_string());
+    catalog.create_table(plan).await?;
+
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+    let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+    let stream = Box::pin(futures::stream::iter(TestFixture::gen_block_stream(3)));
+    table
+        .append_data(io_ctx.clone(), insert_into_plan, stream)
+        .await?;
+
+    let table = catalog
+        .get_table(
+            fixture.default_db().as_str(),
+            fixture.default_table().as_str(),
+        )
+        .await?;
+    let (stats, parts) = table.read_partitions(io_ctx.clone(), None)?;
+    assert_eq!(parts.len(), 5);
+    assert_eq!(stats.read_rows, 9);
+
+    ctx.try_set_partitions(parts)?;
+    let stream = table
+        .read(io_ctx.clone(), &ReadDataSourcePlan {
+            table_info: Default::default(),
+            scan_fields: None,
+            parts: Default::default(),
+            statistics: Default::default(),
+            description: "".to_string(),
+            tbl_args: None,
+            push_downs: None,
+        })
+        .await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+    assert_eq!(rows, 9);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_table_alter() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+    let catalog = ctx.get_catalog();
+    catalog
+        .create_table(fixture.default_crate_table_plan())
+        .await?;
+
+    let db = fixture.default_db();
+    let tbl = fixture.default_table();
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+    let alter_plan = |operation| AlterTablePlan {
+        db: db.clone(),
+        table: tbl.clone(),
+        operation,
+    };
+
+    // 1. insert 2 blocks of [1, 2, 3]
+    let table = catalog.get_table(&db, &tbl).await?;
+    let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+    let stream = Box::pin(futures::stream::iter(TestFixture::gen_block_stream(2)));
+    table
+        .append_data(io_ctx.clone(), insert_into_plan, stream)
+        .await?;
+
+    // 2. add column c, the rows inserted before are read as the default value
+    table
+        .alter(
+            io_ctx.clone(),
+            alter_plan(AlterTableOperation::AddColumn {
+                field: DataField::new("c", DataType::Int32, false),
+                default_value: Some(DataValue::Int32(Some(10))),
+            }),
+        )
+        .await?;
+    let table = catalog.get_table(&db, &tbl).await?;
+    let block = DataBlock::create_by_array(table.schema(), vec![
+        Series::new(vec![4, 5]),
+        Series::new(vec![20, 30]),
+    ]);
+    let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+    let stream = Box::pin(futures::stream::iter(vec![Ok(block)]));
+    table
+        .append_data(io_ctx.clone(), insert_into_plan, stream)
+        .await?;
+
+    let table = catalog.get_table(&db, &tbl).await?;
+    assert_blocks_sorted_eq(
+        vec![
+            "+----+----+",
+            "| id | c  |",
+            "+----+----+",
+            "| 1  | 10 |",
+            "| 1  | 10 |",
+            "| 2  | 10 |",
+            "| 2  | 10 |",
+            "| 3  | 10 |",
+            "| 3  | 10 |",
+            "| 4  | 20 |",
+            "| 5  | 30 |",
+            "+----+----+",
+        ],
+        &read_all(&ctx, table.as_ref()).await?,
+    );
+
+    // the blocks without the stats of column c are never pruned
+    let push_downs = Extras {
+        filters: vec![col("c").lt(lit(15))],
+        ..Extras::default()
+    };
+    let (_, parts) = table.read_partitions(io_ctx.clone(), Some(push_downs))?;
+    assert_eq!(parts.len(), 2);
+
+    // 3. rename column id, and drop column c
+    table
+        .alter(
+            io_ctx.clone(),
+            alter_plan(AlterTableOperation::RenameColumn {
+                old_name: "id".to_string(),
+                new_name: "key".to_string(),
+            }),
+        )
+        .await?;
+    let table = catalog.get_table(&db, &tbl).await?;
+    table
+        .alter(
+            io_ctx.clone(),
+            alter_plan(AlterTableOperation::DropColumn {
+                name: "c".to_string(),
+            }),
+        )
+        .await?;
+
+    // 4. add column c again, which is not the dropped one
+    let table = catalog.get_table(&db, &tbl).await?;
+    table
+        .alter(
+            io_ctx.clone(),
+            alter_plan(AlterTableOperation::AddColumn {
+                field: DataField::new("c", DataType::Int32, false),
+                default_value: None,
+            }),
+        )
+        .await?;
+    let table = catalog.get_table(&db, &tbl).await?;
+    assert_blocks_sorted_eq(
+        vec![
+            "+-----+------+",
+            "| key | c    |",
+            "+-----+------+",
+            "| 1   | NULL |",
+            "| 1   | NULL |",
+            "| 2   | NULL |",
+            "| 2   | NULL |",
+            "| 3   | NULL |",
+            "| 3   | NULL |",
+            "| 4   | NULL |",
+            "| 5   | NULL |",
+            "+-----+------+",
+        ],
+        &read_all(&ctx, table.as_ref()).await?,
+    );
+
+    // 5. invalid alterations
+    let invalid_operations = vec![
+        AlterTableOperation::AddColumn {
+            field: DataField::new("key", DataType::Int32, false),
+            default_value: None,
+        },
+        AlterTableOperation::DropColumn {
+            name: "not_exist".to_string(),
+        },
+        AlterTableOperation::RenameColumn {
+            old_name: "key".to_string(),
+            new_name: "c".to_string(),
+        },
+    ];
+    for operation in invalid_operations {
+        let result = table.alter(io_ctx.clone(), alter_plan(operation)).await;
+        assert_eq!(
+            result.err().unwrap().code(),
+            ErrorCode::BadArguments("").code()
+        );
+    }
+
+    Ok(())
+}
+
+async fn read_all(ctx: &DatabendQueryContextRef, table: &dyn Table) -> Result<Vec<DataBlock>> {
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+    let (_, parts) = table.read_partitions(io_ctx.clone(), None)?;
+    ctx.try_set_partitions(parts)?;
+    let stream = table
+        .read(io_ctx, &ReadDataSourcePlan {
+            table_info: Default::default(),
+            scan_fields: None,
+            parts: Default::default(),
+            statistics: Default::default(),
+            description: "".to_string(),
+            tbl_args: None,
+            push_downs: None,
+        })
+        .await?;
+    stream.try_collect::<Vec<_>>().await
+}
+
+#[tokio::test]
+async fn test_fuse_table_bloom_filter() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+    let catalog = ctx.get_catalog();
+
+    let mut plan = fixture.default_crate_table_plan();
+    plan.table_meta
+        .options
+        .insert("bloom_filter_columns".to_string(), "id".to_string());
+    catalog.create_table(plan).await?;
+
+    let db = fixture.default_db();
+    let tbl = fixture.default_table();
+    let table = catalog.get_table(&db, &tbl).await?;
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+
+    // the ranges of the blocks overlap, the min/max index prunes nothing by equalities
+    let blocks = vec![vec![1, 100], vec![2, 99], vec![3, 98]]
+        .into_iter()
+        .map(|ids| {
+            Ok(DataBlock::create_by_array(
+                TestFixture::default_schema(),
+                vec![Series::new(ids)],
+            ))
+        })
+        .collect::<Vec<_>>();
+    let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+    table
+        .append_data(
+            io_ctx.clone(),
+            insert_into_plan,
+            Box::pin(futures::stream::iter(blocks)),
+        )
+        .await?;
+    let table = catalog.get_table(&db, &tbl).await?;
+
+    let cases = vec![
+        // (filter, expected parts, pruned by range, pruned by bloom filter)
+        (col("id").eq(lit(98)), 1, 0, 2),
+        (col("id").eq(lit(50)), 0, 0, 3),
+        (col("id").eq(lit(200)), 0, 3, 0),
+        (col("id").eq(lit(2)).or(col("id").eq(lit(3))), 2, 0, 1),
+        (col("id").eq(lit(99)).and(col("id").gt(lit(0))), 1, 1, 1),
+        // not an equality, nothing pruned
+        (col("id").not_eq(lit(50)), 3, 0, 0),
+    ];
+    for (filter, expected_parts, pruned_by_range, pruned_by_bloom_filter) in cases {
+        let push_downs = Extras {
+            filters: vec![filter.clone()],
+            ..Extras::default()
+        };
+        let (stats, parts) = table.read_partitions(io_ctx.clone(), Some(push_downs))?;
+        assert_eq!(parts.len(), expected_parts, "filter {:?}", filter);
+        let pruning = stats.pruning.unwrap();
+        assert_eq!(pruning.partitions_total, 3);
+        assert_eq!(
+            pruning.pruned_by_range, pruned_by_range,
+            "filter {:?}",
+            filter
+        );
+        assert_eq!(
+            pruning.pruned_by_bloom_filter, pruned_by_bloom_filter,
+            "filter {:?}",
+            filter
+        );
+    }
+
+    // no pruning without filters
+    let (stats, parts) = table.read_partitions(io_ctx.clone(), None)?;
+    assert_eq!(parts.len(), 3);
+    assert!(stats.pruning.is_none());
+
+    // the bloom filters are referenced, thus retained by purge
+    table
+        .optimize(io_ctx.clone(), OptimizeTablePlan {
+            db: db.clone(),
+            table: tbl.clone(),
+            operation: Optimization::Purge,
+        })
+        .await?;
+    let table = catalog.get_table(&db, &tbl).await?;
+    let push_downs = Extras {
+        filters: vec![col("id").eq(lit(99))],
+        ..Extras::default()
+    };
+    let (_, parts) = table.read_partitions(io_ctx.clone(), Some(push_downs))?;
+    assert_eq!(parts.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_table_pruning() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+    let catalog = ctx.get_catalog();
+    catalog
+        .create_table(fixture.default_crate_table_plan())
+        .await?;
+
+    let db = fixture.default_db();
+    let tbl = fixture.default_table();
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+
+    // one segment of one block per insertion, ids of [1, 10], [11, 20] and [21, 30]
+    for start in [1, 11, 21] {
+        let table = catalog.get_table(&db, &tbl).await?;
+        let block = DataBlock::create_by_array(TestFixture::default_schema(), vec![Series::new(
+            (start..start + 10).collect::<Vec<i32>>(),
+        )]);
+        let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+        table
+            .append_data(
+                io_ctx.clone(),
+                insert_into_plan,
+                Box::pin(futures::stream::iter(vec![Ok(block)])),
+            )
+            .await?;
+    }
+    let table = catalog.get_table(&db, &tbl).await?;
+
+    let cases = vec![
+        // (filters, expected parts, pruned segments)
+        (
+            vec![col("id").gt_eq(lit(11)), col("id").lt_eq(lit(20))],
+            1,
+            2,
+        ),
+        (vec![col("id").gt(lit(5)), col("id").lt(lit(0))], 0, 3),
+        // e.g. id BETWEEN 5 AND 15
+        (
+            vec![col("id").gt_eq(lit(5)).and(col("id").lt_eq(lit(15)))],
+            2,
+            1,
+        ),
+        // e.g. id IN (5, 25)
+        (vec![col("id").eq(lit(5)).or(col("id").eq(lit(25)))], 2, 1),
+        // there are no NULLs
+        (
+            vec![Expression::create_scalar_function("isNull", vec![col(
+                "id",
+            )])],
+            0,
+            3,
+        ),
+    ];
+    for (filters, expected_parts, pruned_segments) in cases {
+        let push_downs = Extras {
+            filters: filters.clone(),
+            ..Extras::default()
+        };
+        let (stats, parts) = table.read_partitions(io_ctx.clone(), Some(push_downs))?;
+        assert_eq!(parts.len(), expected_parts, "filters {:?}", filters);
+        let pruning = stats.pruning.unwrap();
+        assert_eq!(pruning.segments_total, 3);
+        assert_eq!(
+            pruning.pruned_segments, pruned_segments,
+            "filters {:?}",
+            filters
+        );
+        assert_eq!(pruning.partitions_total, 3);
+        assert_eq!(pruning.pruned_partitions(), 3 - expected_parts);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_table_cluster_keys() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+    let catalog = ctx.get_catalog();
+    let db = fixture.default_db();
+    let tbl = fixture.default_table();
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+
+    // 1. a table without cluster keys can not be re-clustered
+    catalog
+        .create_table(fixture.default_crate_table_plan())
+        .await?;
+    let recluster_plan = OptimizeTablePlan {
+        db: db.clone(),
+        table: tbl.clone(),
+        operation: Optimization::Recluster,
+    };
+    let table = catalog.get_table(&db, &tbl).await?;
+    let result = table.optimize(io_ctx.clone(), recluster_plan.clone()).await;
+    assert_eq!(
+        result.err().unwrap().code(),
+        ErrorCode::BadArguments("").code()
+    );
+
+    // 2. clustered by id, one block per insertion, of ids [1, 10], [3, 12] and [20, 25]
+    let mut plan = fixture.default_crate_table_plan();
+    plan.table = "clustered".to_string();
+    plan.table_meta.options.insert(
+        TBL_OPT_KEY_CLUSTER_KEYS.to_string(),
+        serde_json::to_string(&vec![col("id")])?,
+    );
+    catalog.create_table(plan).await?;
+    let tbl = "clustered".to_string();
+    for ids in [vec![10, 1, 5], vec![3, 12, 7], vec![20, 25, 21]] {
+        let table = catalog.get_table(&db, &tbl).await?;
+        let block =
+            DataBlock::create_by_array(TestFixture::default_schema(), vec![Series::new(ids)]);
+        let mut insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+        insert_into_plan.tbl_name = tbl.clone();
+        table
+            .append_data(
+                io_ctx.clone(),
+                insert_into_plan,
+                Box::pin(futures::stream::iter(vec![Ok(block)])),
+            )
+            .await?;
+    }
+
+    let table = catalog.get_table(&db, &tbl).await?;
+    let snapshot = table
+        .as_any()
+        .downcast_ref::<FuseTable>()
+        .unwrap()
+        .table_snapshot(io_ctx.as_ref())
+        .await?
+        .unwrap();
+    let clustering = snapshot.clustering.unwrap();
+    assert_eq!(clustering.total_block_count, 3);
+    assert_eq!(clustering.overlapping_block_count, 2);
+
+    // 3. re-cluster, the two overlapping blocks are merged into one
+    let recluster_plan = OptimizeTablePlan {
+        table: tbl.clone(),
+        ..recluster_plan
+    };
+    table
+        .optimize(io_ctx.clone(), recluster_plan.clone())
+        .await?;
+
+    let table = catalog.get_table(&db, &tbl).await?;
+    let new_snapshot = table
+        .as_any()
+        .downcast_ref::<FuseTable>()
+        .unwrap()
+        .table_snapshot(io_ctx.as_ref())
+        .await?
+        .unwrap();
+    assert_eq!(new_snapshot.prev_snapshot_id, Some(snapshot.snapshot_id));
+    assert_eq!(new_snapshot.summary.block_count, 2);
+    assert_eq!(new_snapshot.summary.row_count, 9);
+    let clustering = new_snapshot.clustering.unwrap();
+    assert_eq!(clustering.total_block_count, 2);
+    assert_eq!(clustering.overlapping_block_count, 0);
+    assert!(clustering.average_overlaps.abs() < f64::EPSILON);
+
+    // 4. rows are kept, sorted by the cluster keys within the blocks
+    let (_, parts) = table.read_partitions(io_ctx.clone(), None)?;
+    ctx.try_set_partitions(parts)?;
+    let stream = table
+        .read(io_ctx.clone(), &ReadDataSourcePlan {
+            table_info: Default::default(),
+            scan_fields: None,
+            parts: Default::default(),
+            statistics: Default::default(),
+            description: "".to_string(),
+            tbl_args: None,
+            push_downs: None,
+        })
+        .await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+    assert_eq!(rows, 9);
+    for block in blocks {
+        let ids = (0..block.num_rows())
+            .map(|row| block.column(0).try_get(row)?.as_i64())
+            .collect::<Result<Vec<_>>>()?;
+        let mut sorted = ids.clone();
+        sorted.sort_unstable();
+        assert_eq!(ids, sorted);
+    }
+
+    // 5. re-clustering a well clustered table is a no-op
+    let version = table.get_table_info().ident.version;
+    table.optimize(io_ctx.clone(), recluster_plan).await?;
+    let table = catalog.get_table(&db, &tbl).await?;
+    assert_eq!(table.get_table_info().ident.version, version);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_table_concurrent_commits() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+    let catalog = ctx.get_catalog();
+    catalog
+        .create_table(fixture.default_crate_table_plan())
+        .await?;
+
+    let db = fixture.default_db();
+    let tbl = fixture.default_table();
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+    let append = |table: Arc<dyn Table>| {
+        let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+        let io_ctx = io_ctx.clone();
+        async move {
+            let stream = Box::pin(futures::stream::iter(TestFixture::gen_block_stream(1)));
+            table.append_data(io_ctx, insert_into_plan, stream).await
+        }
+    };
+
+    // 1. both of the appends are committed, against the same version of the table, the
+    // latter one is rebased onto the former one
+    let stale = catalog.get_table(&db, &tbl).await?;
+    append(catalog.get_table(&db, &tbl).await?).await?;
+    append(stale.clone()).await?;
+
+    let table = catalog.get_table(&db, &tbl).await?;
+    let snapshot = table
+        .as_any()
+        .downcast_ref::<FuseTable>()
+        .unwrap()
+        .table_snapshot(io_ctx.as_ref())
+        .await?
+        .unwrap();
+    assert_eq!(snapshot.segments.len(), 2);
+    assert_eq!(snapshot.summary.row_count, 6);
+    assert!(snapshot.prev_snapshot_id.is_some());
+    assert_eq!(read_all(&ctx, table.as_ref()).await?.len(), 2);
+
+    // 2. the operations which can not be rebased, fail against a stale version
+    let stale = table;
+    append(catalog.get_table(&db, &tbl).await?).await?;
+    let truncate_plan = TruncateTablePlan {
+        db: db.clone(),
+        table: tbl.clone(),
+    };
+    let result = stale.truncate(io_ctx.clone(), truncate_plan.clone()).await;
+    assert_eq!(
+        result.err().unwrap().code(),
+        ErrorCode::TableCommitConflict("").code()
+    );
+    let optimize_plan = OptimizeTablePlan {
+        db: db.clone(),
+        table: tbl.clone(),
+        operation: Optimization::Compact,
+    };
+    let result = stale.optimize(io_ctx.clone(), optimize_plan).await;
+    assert_eq!(
+        result.err().unwrap().code(),
+        ErrorCode::TableCommitConflict("").code()
+    );
+
+    // 3. nor could the appends be rebased onto the altered columns
+    let stale = catalog.get_table(&db, &tbl).await?;
+    stale
+        .alter(io_ctx.clone(), AlterTablePlan {
+            db: db.clone(),
+            table: tbl.clone(),
+            operation: AlterTableOperation::AddColumn {
+                field: DataField::new("c", DataType::Int32, true),
+                default_value: None,
+            },
+        })
+        .await?;
+    let da = io_ctx.get_data_accessor()?;
+    let prefix = format!(
+        "{}/",
+        stale
+            .as_any()
+            .downcast_ref::<FuseTable>()
+            .unwrap()
+            .meta_location_generator
+            .prefix()
+    );
+    let num_objects = da.list(&prefix).await?.len();
+    let result = append(stale).await;
+    assert_eq!(
+        result.err().unwrap().code(),
+        ErrorCode::TableCommitConflict("").code()
+    );
+    // the append is given up, nothing written by it is left behind
+    assert_eq!(da.list(&prefix).await?.len(), num_objects);
+
+    // the latest table is intact
+    let table = catalog.get_table(&db, &tbl).await?;
+    table.truncate(io_ctx.clone(), truncate_plan).await?;
+    assert!(read_all(&ctx, table.as_ref()).await?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::sync::Arc;
+
+use common_base::BlockingWait;
+use common_context::IOContext;
+use common_context::TableIOContext;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use uuid::Uuid;
+
+use crate::catalogs::NavigationPoint;
+use crate::catalogs::Table;
+use crate::datasources::table::fuse::util::TableMetaLocationGenerator;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_COLUMNS;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_SNAPSHOT_LOC;
+use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table::fuse::MetaReader;
+use crate::datasources::table::fuse::TableSnapshot;
+
+impl FuseTable {
+    #[inline]
+    pub fn do_navigate_to(
+        &self,
+        io_ctx: Arc<TableIOContext>,
+        point: &NavigationPoint,
+    ) -> Result<Arc<dyn Table>> {
+        let location = self.snapshot_loc().ok_or_else(|| {
+            ErrorCode::TableHistoricalDataNotFound(format!(
+                "Table {} has no history yet",
+                self.name()
+            ))
+        })?;
+
+        let reader = MetaReader::create(&io_ctx)?;
+        let target = point.clone();
+        let locs = self.meta_location_generator.clone();
+        let (location, snapshot) =
+            async move { find_snapshot(&reader, &locs, location, &target).await }
+                .wait_in(&io_ctx.get_runtime(), None)??;
+
+        // The schema might have evolved since then, the one of the snapshot is used.
+        let table_columns = self.table_columns.of_snapshot(&snapshot);
+        let mut table_info = self.table_info.clone();
+        table_info.meta.schema = table_columns.schema.clone();
+        table_info
+            .meta
+            .options
+            .insert(TBL_OPT_KEY_SNAPSHOT_LOC.to_string(), location);
+        table_info.meta.options.insert(
+            TBL_OPT_KEY_COLUMNS.to_string(),
+            serde_json::to_string(&table_columns)?,
+        );
+        Ok(Arc::new(FuseTable {
+            table_info,
+            meta_location_generator: self.meta_location_generator.clone(),
+            table_options: self.table_options.clone(),
+            table_columns,
+        }))
+    }
+
+    /// All the snapshots of the table, from the current one back to the first one.
+    pub async fn snapshot_history(&self, io_ctx: &TableIOContext) -> Result<Vec<TableSnapshot>> {
+        let mut snapshots = vec![];
+        let mut location = self.snapshot_loc();
+        let reader = MetaReader::create(io_ctx)?;
+        while let Some(loc) = location {
+            let snapshot = reader.read_snapshot(&loc).await?;
+            location = snapshot.prev_snapshot_id.map(|id| {
+                self.meta_location_generator
+                    .snapshot_location_from_uuid(&id)
+            });
+            snapshots.push(snapshot.as_ref().clone());
+        }
+        Ok(snapshots)
+    }
+}
+
+/// Walks back the snapshot chain, starting from the one at `location`.
+///
+/// The chain, rather than the snapshot location, is followed to find a snapshot by id,
+/// so that only the snapshots of this table could be reached.
+async fn find_snapshot(
+    reader: &MetaReader,
+    locs: &TableMetaLocationGenerator,
+    mut location: String,
+    point: &NavigationPoint,
+) -> Result<(String, TableSnapshot)> {
+    let snapshot_id = match point {
+        NavigationPoint::SnapshotID(id) => Some(
+            Uuid::parse_str(id)
+                .map_err(|_| ErrorCode::BadArguments(format!("Invalid snapshot id: {}", id)))?,
+        ),
+        NavigationPoint::TimePoint(_) => None,
+    };
+
+    loop {
+        let snapshot = reader.read_snapshot(&location).await?;
+        let found = match point {
+            NavigationPoint::SnapshotID(_) => Some(snapshot.snapshot_id) == snapshot_id,
+            NavigationPoint::TimePoint(time_point) => match snapshot.timestamp {
+                Some(timestamp) => timestamp <= *time_point,
+                // Snapshots of the early versions have no timestamp, we can not tell.
+                None => break,
+            },
+        };
+        if found {
+            return Ok((location, snapshot.as_ref().clone()));
+        }
+
+        match snapshot.prev_snapshot_id {
+            Some(prev_snapshot_id) => {
+                location = locs.snapshot_location_from_uuid(&prev_snapshot_id);
+            }
+            None => break,
+        }
+    }
+
+    Err(ErrorCode::TableHistoricalDataNotFound(match point {
+        NavigationPoint::SnapshotID(id) => format!("No snapshot found with id {}", id),
+        NavigationPoint::TimePoint(time_point) => {
+            format!("No snapshot found at or before {}", time_point)
+        }
+    }))
+}
@@ -19,14 +19,9 @@ use common_context::IOContext;
 use common_context::TableIOContext;
 use common_exception::Result;
 use common_planners::TruncateTablePlan;
-use uuid::Uuid;
 
-use crate::catalogs::Catalog;
-use crate::catalogs::Table;
-use crate::datasources::table::fuse::util;
-use crate::datasources::table::fuse::util::TBL_OPT_KEY_SNAPSHOT_LOC;
+use crate::datasources::table::fuse::encode_meta;
 use crate::datasources::table::fuse::FuseTable;
-use crate::sessions::DatabendQueryContext;
 
 impl FuseTable {
     #[inline]
@@ -36,32 +31,19 @@ impl FuseTable {
         _truncate_plan: TruncateTablePlan,
     ) -> Result<()> {
         if let Some(prev_snapshot) = self.table_snapshot(&io_ctx).await? {
-            let prev_id = prev_snapshot.snapshot_id;
-            let mut new_snapshot = prev_snapshot;
+            let mut new_snapshot = prev_snapshot.into_next(&self.table_columns);
             new_snapshot.segments = vec![];
-            new_snapshot.prev_snapshot_id = Some(prev_id);
             new_snapshot.summary = Default::default();
-            let ctx: Arc<DatabendQueryContext> = io_ctx
-                .get_user_data()?
-                .expect("DatabendQueryContext should not be None");
-            new_snapshot.snapshot_id = Uuid::new_v4();
-            let new_snapshot_loc =
-                util::snapshot_location(new_snapshot.snapshot_id.to_simple().to_string().as_str()); // TODO refine this
+            new_snapshot.clustering = self.clustering_of_blocks(&[])?;
+            let new_snapshot_loc = self
+                .meta_location_generator
+                .snapshot_location_from_uuid(&new_snapshot.snapshot_id);
             let da = io_ctx.get_data_accessor()?;
-            let bytes = serde_json::to_vec(&new_snapshot)?;
+            let bytes = encode_meta(&new_snapshot)?;
             da.put(&new_snapshot_loc, bytes).await?;
 
-            let catalog = ctx.get_catalog();
-            let table_id = self.get_id();
-            // TODO backoff retry
-            catalog
-                .upsert_table_option(
-                    table_id,
-                    self.table_info.ident.version,
-                    TBL_OPT_KEY_SNAPSHOT_LOC.to_string(),
-                    new_snapshot_loc,
-                )
-                .await?
+            self.commit_snapshot(&io_ctx, new_snapshot_loc, vec![])
+                .await?;
         }
 
         Ok(())
@@ -0,0 +1,132 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_context::TableIOContext;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_planners::UpdatePlan;
+
+use crate::datasources::table::fuse::BlockMutation;
+use crate::datasources::table::fuse::BlockMutator;
+use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table::fuse::SelectionEvaluator;
+use crate::pipelines::transforms::ExpressionExecutor;
+
+impl FuseTable {
+    #[inline]
+    pub async fn do_update(
+        &self,
+        io_ctx: Arc<TableIOContext>,
+        update_plan: UpdatePlan,
+    ) -> Result<()> {
+        let updater = BlockUpdater::try_create(self.table_info.schema(), &update_plan)?;
+        self.mutate_blocks(io_ctx, update_plan.selection.as_ref(), &updater)
+            .await?;
+        Ok(())
+    }
+}
+
+struct BlockUpdater {
+    schema: DataSchemaRef,
+    /// None if all the rows are updated
+    evaluator: Option<SelectionEvaluator>,
+    /// Evaluates the new values of the updated columns
+    executor: ExpressionExecutor,
+    /// Column name => index of the new value in the output of the executor
+    updated_columns: HashMap<String, usize>,
+}
+
+impl BlockUpdater {
+    fn try_create(schema: DataSchemaRef, update_plan: &UpdatePlan) -> Result<Self> {
+        let evaluator = update_plan
+            .selection
+            .clone()
+            .map(|selection| SelectionEvaluator::try_create(schema.clone(), selection))
+            .transpose()?;
+
+        let mut updated_columns = HashMap::with_capacity(update_plan.assignments.len());
+        let mut values = Vec::with_capacity(update_plan.assignments.len());
+        let mut value_fields = Vec::with_capacity(update_plan.assignments.len());
+        for (idx, (column, value)) in update_plan.assignments.iter().enumerate() {
+            updated_columns.insert(column.clone(), idx);
+            value_fields.push(value.to_data_field(&schema)?);
+            values.push(value.clone());
+        }
+
+        let executor = ExpressionExecutor::try_create(
+            "update expression executor",
+            schema.clone(),
+            DataSchemaRefExt::create(value_fields),
+            values,
+            false,
+        )?;
+        executor.validate()?;
+
+        Ok(BlockUpdater {
+            schema,
+            evaluator,
+            executor,
+            updated_columns,
+        })
+    }
+}
+
+impl BlockMutator for BlockUpdater {
+    fn mutate(&self, block: DataBlock) -> Result<BlockMutation> {
+        let matched = match &self.evaluator {
+            None => None,
+            Some(evaluator) => {
+                let matched = evaluator.eval(&block)?;
+                let matched_rows = matched.into_no_null_iter().filter(|v| *v).count();
+                if matched_rows == 0 {
+                    return Ok(BlockMutation::Untouched);
+                }
+                Some(DataColumn::Array(matched.into_series()))
+            }
+        };
+
+        let values = self.executor.execute(&block)?;
+        let mut columns = Vec::with_capacity(self.schema.fields().len());
+        for (idx, field) in self.schema.fields().iter().enumerate() {
+            let column = match self.updated_columns.get(field.name()) {
+                None => block.column(idx).clone(),
+                Some(value_idx) => {
+                    let value = values.column(*value_idx);
+                    // The rows not matching the selection keep their old values.
+                    let column = match &matched {
+                        None => value.clone(),
+                        Some(matched) => matched.if_then_else(value, block.column(idx))?,
+                    };
+                    let array = column.to_array()?;
+                    let array = if array.data_type() == field.data_type() {
+                        array
+                    } else {
+                        array.cast_with_type(field.data_type())?
+                    };
+                    DataColumn::Array(array)
+                }
+            };
+            columns.push(column);
+        }
+
+        Ok(BlockMutation::Replaced(DataBlock::create(
+            self.schema.clone(),
+            columns,
+        )))
+    }
+}
@@ -0,0 +1,251 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use common_datablocks::DataBlock;
+use common_datablocks::SortColumnDescription;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_planners::Expression;
+
+use crate::datasources::table::fuse::BlockMeta;
+use crate::datasources::table::fuse::ClusterStats;
+use crate::datasources::table::fuse::ClusteringStatistics;
+use crate::pipelines::transforms::ExpressionExecutor;
+
+/// Types of the cluster keys, evaluated against the schema of the table
+pub fn cluster_key_types(
+    cluster_keys: &[Expression],
+    schema: &DataSchemaRef,
+) -> Result<Vec<DataType>> {
+    cluster_keys
+        .iter()
+        .map(|key| key.to_data_type(schema))
+        .collect()
+}
+
+/// Sorts the rows of the block by the cluster keys, gives back the sorted block along with
+/// the range of the keys. The block is untouched if there are no cluster keys.
+pub fn sort_by_cluster_keys(
+    block: DataBlock,
+    cluster_keys: &[Expression],
+) -> Result<(DataBlock, Option<ClusterStats>)> {
+    if cluster_keys.is_empty() || block.num_rows() == 0 {
+        return Ok((block, None));
+    }
+
+    let schema = block.schema().clone();
+    let key_fields = cluster_keys
+        .iter()
+        .map(|key| key.to_data_field(&schema))
+        .collect::<Result<Vec<_>>>()?;
+    let executor = ExpressionExecutor::try_create(
+        "cluster keys executor",
+        schema.clone(),
+        DataSchemaRefExt::create(key_fields.clone()),
+        cluster_keys.to_vec(),
+        false,
+    )?;
+    let keys = executor.execute(&block)?;
+
+    // the keys are appended to the block as the sort columns, with names of no conflicts
+    let mut fields = schema.fields().clone();
+    let mut columns = block.columns().to_vec();
+    let mut sort_columns = Vec::with_capacity(key_fields.len());
+    for (idx, field) in key_fields.iter().enumerate() {
+        let name = format!("_cluster_key_{}", idx);
+        fields.push(DataField::new(
+            &name,
+            field.data_type().clone(),
+            field.is_nullable(),
+        ));
+        columns.push(keys.column(idx).clone());
+        sort_columns.push(SortColumnDescription {
+            column_name: name,
+            asc: true,
+            nulls_first: true,
+        });
+    }
+    let with_keys = DataBlock::create(DataSchemaRefExt::create(fields), columns);
+    let sorted = DataBlock::sort_block(&with_keys, &sort_columns, None)?;
+
+    let num_columns = schema.fields().len();
+    let last = sorted.num_rows() - 1;
+    let key_columns = &sorted.columns()[num_columns..];
+    let cluster_stats = ClusterStats {
+        min: key_columns
+            .iter()
+            .map(|column| column.try_get(0))
+            .collect::<Result<Vec<_>>>()?,
+        max: key_columns
+            .iter()
+            .map(|column| column.try_get(last))
+            .collect::<Result<Vec<_>>>()?,
+    };
+    let sorted = DataBlock::create(schema, sorted.columns()[..num_columns].to_vec());
+    Ok((sorted, Some(cluster_stats)))
+}
+
+/// For each of the blocks, the number of the other blocks whose ranges of the cluster keys
+/// overlap with its range. None for the blocks of unknown ranges.
+pub fn block_overlaps(
+    block_metas: &[BlockMeta],
+    key_types: &[DataType],
+) -> Result<Vec<Option<usize>>> {
+    let ranges = ranks_of_ranges(block_metas, key_types)?;
+    Ok(RankedRanges::new(&ranges).overlaps(&ranges))
+}
+
+/// How well the blocks are clustered, by the ranges of their cluster keys.
+pub fn clustering_statistics(
+    block_metas: &[BlockMeta],
+    key_types: &[DataType],
+) -> Result<ClusteringStatistics> {
+    let ranges = ranks_of_ranges(block_metas, key_types)?;
+    let ranked = RankedRanges::new(&ranges);
+    let overlaps = ranked
+        .overlaps(&ranges)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    if overlaps.is_empty() {
+        return Ok(ClusteringStatistics::default());
+    }
+
+    // the depth of a value is the number of the ranges containing it, the distinct bounds
+    // of the ranges are taken as the values
+    let mut points = ranked
+        .los
+        .iter()
+        .chain(ranked.his.iter())
+        .cloned()
+        .collect::<Vec<_>>();
+    points.sort_unstable();
+    points.dedup();
+    let total_depth = points.iter().map(|p| ranked.depth(*p)).sum::<usize>();
+
+    let num_blocks = overlaps.len();
+    Ok(ClusteringStatistics {
+        total_block_count: num_blocks as u64,
+        overlapping_block_count: overlaps.iter().filter(|v| **v > 0).count() as u64,
+        average_overlaps: overlaps.iter().sum::<usize>() as f64 / num_blocks as f64,
+        average_depth: total_depth as f64 / points.len() as f64,
+    })
+}
+
+/// The sorted lower and upper bounds of the (known) ranges.
+struct RankedRanges {
+    los: Vec<usize>,
+    his: Vec<usize>,
+}
+
+impl RankedRanges {
+    fn new(ranges: &[Option<(usize, usize)>]) -> Self {
+        let mut los = ranges.iter().flatten().map(|r| r.0).collect::<Vec<_>>();
+        let mut his = ranges.iter().flatten().map(|r| r.1).collect::<Vec<_>>();
+        los.sort_unstable();
+        his.sort_unstable();
+        Self { los, his }
+    }
+
+    fn overlaps(&self, ranges: &[Option<(usize, usize)>]) -> Vec<Option<usize>> {
+        let num_ranges = self.los.len();
+        ranges
+            .iter()
+            .map(|range| {
+                range.map(|(lo, hi)| {
+                    // all but itself, the ones starting after it, and the ones ending before it
+                    let after = num_ranges - self.los.partition_point(|v| *v <= hi);
+                    let before = self.his.partition_point(|v| *v < lo);
+                    num_ranges - after - before - 1
+                })
+            })
+            .collect()
+    }
+
+    /// Number of the ranges containing the point
+    fn depth(&self, point: usize) -> usize {
+        self.los.partition_point(|v| *v <= point) - self.his.partition_point(|v| *v < point)
+    }
+}
+
+/// The ranges of the cluster keys of the blocks, as the ranks of their bounds among the
+/// bounds of all the ranges, so that they can be compared as integers.
+fn ranks_of_ranges(
+    block_metas: &[BlockMeta],
+    key_types: &[DataType],
+) -> Result<Vec<Option<(usize, usize)>>> {
+    let known = block_metas
+        .iter()
+        .filter_map(|meta| meta.cluster_stats.as_ref())
+        .filter(|stats| stats.min.len() == key_types.len() && stats.max.len() == key_types.len())
+        .collect::<Vec<_>>();
+    if known.is_empty() {
+        return Ok(vec![None; block_metas.len()]);
+    }
+
+    // the bounds of the i-th range are the (2 * i)-th and the (2 * i + 1)-th rows
+    let mut fields = Vec::with_capacity(key_types.len() + 1);
+    let mut columns = Vec::with_capacity(key_types.len() + 1);
+    let mut sort_columns = Vec::with_capacity(key_types.len());
+    for (idx, data_type) in key_types.iter().enumerate() {
+        let name = format!("_cluster_key_{}", idx);
+        let values = known
+            .iter()
+            .flat_map(|stats| [stats.min[idx].clone(), stats.max[idx].clone()])
+            .collect::<Vec<_>>();
+        fields.push(DataField::new(&name, data_type.clone(), true));
+        columns.push(DataValue::try_into_data_array(&values, data_type)?);
+        sort_columns.push(SortColumnDescription {
+            column_name: name,
+            asc: true,
+            nulls_first: true,
+        });
+    }
+    fields.push(DataField::new("_bound", DataType::UInt64, false));
+    columns.push(Series::new((0..known.len() as u64 * 2).collect::<Vec<_>>()));
+    let bounds = DataBlock::create_by_array(DataSchemaRefExt::create(fields), columns);
+    let sorted = DataBlock::sort_block(&bounds, &sort_columns, None)?;
+
+    // equal bounds are of the same rank
+    let mut ranks = vec![0; known.len() * 2];
+    let mut rank = 0;
+    let mut prev: Option<Vec<DataValue>> = None;
+    for row in 0..sorted.num_rows() {
+        let bound = (0..key_types.len())
+            .map(|idx| sorted.column(idx).try_get(row))
+            .collect::<Result<Vec<_>>>()?;
+        if matches!(&prev, Some(prev) if prev != &bound) {
+            rank += 1;
+        }
+        let bound_idx = sorted.column(key_types.len()).try_get(row)?.as_u64()? as usize;
+        ranks[bound_idx] = rank;
+        prev = Some(bound);
+    }
+
+    let mut known_idx = 0;
+    Ok(block_metas
+        .iter()
+        .map(|meta| match &meta.cluster_stats {
+            Some(stats)
+                if stats.min.len() == key_types.len() && stats.max.len() == key_types.len() =>
+            {
+                let range = (ranks[known_idx * 2], ranks[known_idx * 2 + 1]);
+                known_idx += 1;
+                Some(range)
+            }
+            _ => None,
+        })
+        .collect())
+}
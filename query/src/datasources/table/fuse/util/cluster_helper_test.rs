@@ -0,0 +1,152 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::collections::HashMap;
+
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_planners::col;
+
+use super::cluster_helper;
+use crate::datasources::table::fuse::BlockLocation;
+use crate::datasources::table::fuse::BlockMeta;
+use crate::datasources::table::fuse::ClusterStats;
+use crate::datasources::table::fuse::ClusteringStatistics;
+
+#[test]
+fn test_ft_cluster_sort_by_cluster_keys() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int32, false),
+        DataField::new("b", DataType::Int32, false),
+        DataField::new("c", DataType::String, false),
+    ]);
+    let block = DataBlock::create_by_array(schema.clone(), vec![
+        Series::new(vec![1, 1, 0]),
+        Series::new(vec![2, 1, 5]),
+        Series::new(vec!["x", "y", "z"]),
+    ]);
+
+    // no cluster keys, untouched
+    let (unsorted, stats) = cluster_helper::sort_by_cluster_keys(block.clone(), &[])?;
+    assert!(stats.is_none());
+    assert_eq!(
+        unsorted.column(2).try_get(0)?,
+        DataValue::String(Some(b"x".to_vec()))
+    );
+
+    let (sorted, stats) = cluster_helper::sort_by_cluster_keys(block, &[col("a"), col("b")])?;
+    assert_eq!(sorted.schema(), &schema);
+    let column = |idx: usize| -> Result<Vec<DataValue>> {
+        (0..sorted.num_rows())
+            .map(|row| sorted.column(idx).try_get(row))
+            .collect()
+    };
+    assert_eq!(column(2)?, vec![
+        DataValue::String(Some(b"z".to_vec())),
+        DataValue::String(Some(b"y".to_vec())),
+        DataValue::String(Some(b"x".to_vec())),
+    ]);
+    assert_eq!(
+        stats,
+        Some(ClusterStats {
+            min: vec![DataValue::Int32(Some(0)), DataValue::Int32(Some(5))],
+            max: vec![DataValue::Int32(Some(1)), DataValue::Int32(Some(2))],
+        })
+    );
+    Ok(())
+}
+
+fn block_meta_of_range(range: Option<(i32, i32)>) -> BlockMeta {
+    BlockMeta {
+        row_count: 0,
+        block_size: 0,
+        file_size: 0,
+        col_stats: HashMap::new(),
+        location: BlockLocation {
+            location: "".to_string(),
+            meta_size: 0,
+        },
+        bloom_filter_location: None,
+        cluster_stats: range.map(|(min, max)| ClusterStats {
+            min: vec![DataValue::Int32(Some(min))],
+            max: vec![DataValue::Int32(Some(max))],
+        }),
+    }
+}
+
+#[test]
+fn test_ft_cluster_block_overlaps() -> Result<()> {
+    let key_types = vec![DataType::Int32];
+    let block_metas = vec![
+        block_meta_of_range(Some((1, 3))),
+        block_meta_of_range(Some((2, 5))),
+        block_meta_of_range(None),
+        block_meta_of_range(Some((6, 8))),
+    ];
+    let overlaps = cluster_helper::block_overlaps(&block_metas, &key_types)?;
+    assert_eq!(overlaps, vec![Some(1), Some(1), None, Some(0)]);
+
+    // the ranges sharing a bound overlap
+    let block_metas = vec![
+        block_meta_of_range(Some((1, 3))),
+        block_meta_of_range(Some((3, 5))),
+    ];
+    let overlaps = cluster_helper::block_overlaps(&block_metas, &key_types)?;
+    assert_eq!(overlaps, vec![Some(1), Some(1)]);
+
+    // all the ranges are unknown
+    let block_metas = vec![block_meta_of_range(None)];
+    let overlaps = cluster_helper::block_overlaps(&block_metas, &key_types)?;
+    assert_eq!(overlaps, vec![None]);
+    Ok(())
+}
+
+#[test]
+fn test_ft_cluster_clustering_statistics() -> Result<()> {
+    let key_types = vec![DataType::Int32];
+
+    // the blocks of unknown ranges are not counted
+    let block_metas = vec![
+        block_meta_of_range(Some((1, 3))),
+        block_meta_of_range(Some((2, 5))),
+        block_meta_of_range(None),
+        block_meta_of_range(Some((6, 8))),
+    ];
+    let stats = cluster_helper::clustering_statistics(&block_metas, &key_types)?;
+    assert_eq!(stats.total_block_count, 3);
+    assert_eq!(stats.overlapping_block_count, 2);
+    assert!((stats.average_overlaps - 2.0 / 3.0).abs() < f64::EPSILON);
+    // depths of the bounds 1, 2, 3, 5, 6, 8 are 1, 2, 2, 1, 1, 1
+    assert!((stats.average_depth - 8.0 / 6.0).abs() < f64::EPSILON);
+
+    // perfectly clustered
+    let block_metas = vec![
+        block_meta_of_range(Some((1, 3))),
+        block_meta_of_range(Some((4, 5))),
+    ];
+    let stats = cluster_helper::clustering_statistics(&block_metas, &key_types)?;
+    assert_eq!(stats, ClusteringStatistics {
+        total_block_count: 2,
+        overlapping_block_count: 0,
+        average_overlaps: 0.0,
+        average_depth: 1.0,
+    });
+
+    // no blocks at all
+    let stats = cluster_helper::clustering_statistics(&[], &key_types)?;
+    assert_eq!(stats, ClusteringStatistics::default());
+    Ok(())
+}
@@ -13,8 +13,22 @@
 //  limitations under the License.
 //
 
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use common_arrow::arrow::array::Array;
+use common_arrow::arrow::array::BinaryArray;
+use common_arrow::arrow::array::DictionaryArray;
+use common_arrow::arrow::array::MutableBinaryArray;
+use common_arrow::arrow::array::MutableDictionaryArray;
+use common_arrow::arrow::array::TryExtend;
 use common_arrow::arrow::datatypes::DataType as ArrowDataType;
+use common_arrow::arrow::datatypes::Field as ArrowField;
+use common_arrow::arrow::datatypes::Schema as ArrowSchema;
+use common_arrow::arrow::record_batch::RecordBatch;
 use common_arrow::parquet::encoding::Encoding;
+use common_exception::ErrorCode;
+use common_exception::Result;
 
 /// mapping from arrow DataType to Encoding
 ///
@@ -37,7 +51,11 @@ use common_arrow::parquet::encoding::Encoding;
 ///  ~~~
 ///
 ///
-pub fn col_encoding(_data_type: &ArrowDataType) -> Encoding {
+pub fn col_encoding(data_type: &ArrowDataType) -> Encoding {
+    if let ArrowDataType::Dictionary(_, _) = data_type {
+        return Encoding::RleDictionary;
+    }
+
     // Although encoding does work, parquet2 has not implemented decoding of DeltaLengthByteArray yet, we fallback to Plain
     // From parquet2: Decoding "DeltaLengthByteArray"-encoded required V2 pages is not yet implemented for Binary.
     //
@@ -50,3 +68,53 @@ pub fn col_encoding(_data_type: &ArrowDataType) -> Encoding {
     //}
     Encoding::Plain
 }
+
+/// Converts the given (String) columns into dictionary arrays, which are written
+/// with the dictionary encoding.
+///
+/// Reading them back needs nothing special, the dictionary encoded pages are decoded
+/// into the plain (LargeBinary) arrays of the table schema.
+pub fn dictionary_encode_columns(
+    arrow_schema: &ArrowSchema,
+    batch: RecordBatch,
+    columns: &HashSet<String>,
+) -> Result<(ArrowSchema, RecordBatch)> {
+    if columns.is_empty() {
+        return Ok((arrow_schema.clone(), batch));
+    }
+
+    let mut fields = Vec::with_capacity(arrow_schema.fields().len());
+    let mut arrays = Vec::with_capacity(arrow_schema.fields().len());
+    for (field, array) in arrow_schema.fields().iter().zip(batch.columns()) {
+        if !columns.contains(field.name()) {
+            fields.push(field.clone());
+            arrays.push(array.clone());
+            continue;
+        }
+
+        let values = array
+            .as_any()
+            .downcast_ref::<BinaryArray<i64>>()
+            .ok_or_else(|| {
+                ErrorCode::BadDataValueType(format!(
+                    "Dictionary encoding of column {} of type {:?} is not supported",
+                    field.name(),
+                    field.data_type()
+                ))
+            })?;
+        let mut dict = MutableDictionaryArray::<i32, MutableBinaryArray<i64>>::new();
+        dict.try_extend(values.iter())?;
+        let dict: DictionaryArray<i32> = dict.into();
+
+        fields.push(ArrowField::new(
+            field.name(),
+            dict.data_type().clone(),
+            field.is_nullable(),
+        ));
+        arrays.push(Arc::new(dict) as Arc<dyn Array>);
+    }
+
+    let schema = ArrowSchema::new(fields);
+    let batch = RecordBatch::try_new(Arc::new(schema.clone()), arrays)?;
+    Ok((schema, batch))
+}
@@ -14,3 +14,32 @@
 //
 
 pub const TBL_OPT_KEY_SNAPSHOT_LOC: &str = "SNAPSHOT_LOC";
+/// Columns of an altered table, along with their ids (see `TableColumns`)
+pub const TBL_OPT_KEY_COLUMNS: &str = "COLUMNS";
+/// Expressions of `CLUSTER BY (...)` (in JSON), by which the blocks are sorted
+pub const TBL_OPT_KEY_CLUSTER_KEYS: &str = "CLUSTER_KEYS";
+
+/// Key of the parquet metadata of a block, which keeps the (comma separated) ids of its columns
+pub const BLOCK_META_KEY_COLUMN_IDS: &str = "fuse.column_ids";
+
+/// Target size of blocks, unless specified by the table options; blocks with fewer rows
+/// and bytes are re-packed by compaction
+pub const DEFAULT_BLOCK_ROWS: usize = 1000 * 1000;
+pub const DEFAULT_BLOCK_BYTES: usize = 100 * 1024 * 1024;
+/// Max number of blocks of a segment, after compaction
+pub const DEFAULT_SEGMENT_BLOCKS: usize = 1000;
+/// Max number of blocks rewritten by a single re-clustering
+pub const DEFAULT_RECLUSTER_BLOCKS: usize = 100;
+
+/// Max number of times that an append is rebased onto the concurrent commits and retried,
+/// the backoff between the retries grows exponentially from the base up to the max
+pub const MAX_COMMIT_RETRIES: usize = 10;
+pub const COMMIT_BACKOFF_BASE_MILLIS: u64 = 10;
+pub const COMMIT_BACKOFF_MAX_MILLIS: u64 = 1000;
+
+/// Storage options of fuse tables, given at creation, e.g. `ENGINE = FUSE COMPRESSION = 'zstd'`
+pub const TBL_OPT_KEY_COMPRESSION: &str = "compression";
+pub const TBL_OPT_KEY_DICTIONARY_COLUMNS: &str = "dictionary_columns";
+pub const TBL_OPT_KEY_BLOOM_FILTER_COLUMNS: &str = "bloom_filter_columns";
+pub const TBL_OPT_KEY_ROW_PER_BLOCK: &str = "row_per_block";
+pub const TBL_OPT_KEY_BLOCK_SIZE_THRESHOLD: &str = "block_size_threshold";
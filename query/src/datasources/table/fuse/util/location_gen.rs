@@ -13,22 +13,60 @@
 //  limitations under the License.
 //
 
+use common_meta_types::MetaId;
 use uuid::Uuid;
 
 const FUSE_TBL_BLOCK_PREFIX: &str = "_b";
 const FUSE_TBL_SEGMENT_PREFIX: &str = "_sg";
 const FUSE_TBL_SNAPSHOT_PREFIX: &str = "_ss";
+const FUSE_TBL_INDEX_PREFIX: &str = "_i";
 
-pub fn gen_unique_block_location() -> String {
-    let part_uuid = Uuid::new_v4().to_simple().to_string() + ".parquet";
-    format!("{}/{}", FUSE_TBL_BLOCK_PREFIX, part_uuid)
+/// Generates the locations of the blocks, segments, snapshots and indexes of a table.
+///
+/// All of them are kept under the prefix of the table, so that the objects of
+/// a table can be listed, e.g. to purge the unreferenced ones.
+#[derive(Clone, Debug)]
+pub struct TableMetaLocationGenerator {
+    prefix: String,
 }
 
-pub fn gen_segment_info_location() -> String {
-    let segment_uuid = Uuid::new_v4().to_simple().to_string();
-    format!("{}/{}", FUSE_TBL_SEGMENT_PREFIX, segment_uuid)
-}
+impl TableMetaLocationGenerator {
+    pub fn with_prefix(prefix: String) -> Self {
+        Self { prefix }
+    }
+
+    pub fn of_table(table_id: MetaId) -> Self {
+        Self::with_prefix(table_id.to_string())
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn gen_block_location(&self) -> String {
+        let part_uuid = Uuid::new_v4().to_simple().to_string() + ".parquet";
+        format!("{}/{}/{}", &self.prefix, FUSE_TBL_BLOCK_PREFIX, part_uuid)
+    }
+
+    pub fn gen_bloom_filter_location(&self) -> String {
+        let index_uuid = Uuid::new_v4().to_simple().to_string() + ".bloom";
+        format!("{}/{}/{}", &self.prefix, FUSE_TBL_INDEX_PREFIX, index_uuid)
+    }
+
+    pub fn gen_segment_info_location(&self) -> String {
+        let segment_uuid = Uuid::new_v4().to_simple().to_string();
+        format!(
+            "{}/{}/{}",
+            &self.prefix, FUSE_TBL_SEGMENT_PREFIX, segment_uuid
+        )
+    }
 
-pub fn snapshot_location(name: impl AsRef<str>) -> String {
-    format!("{}/{}", FUSE_TBL_SNAPSHOT_PREFIX, name.as_ref())
+    pub fn snapshot_location_from_uuid(&self, id: &Uuid) -> String {
+        format!(
+            "{}/{}/{}",
+            &self.prefix,
+            FUSE_TBL_SNAPSHOT_PREFIX,
+            id.to_simple()
+        )
+    }
 }
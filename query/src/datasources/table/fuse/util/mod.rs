@@ -13,16 +13,20 @@
 //  limitations under the License.
 //
 
+pub use cluster_helper::*;
 pub use col_encoding::*;
-pub use constants::TBL_OPT_KEY_SNAPSHOT_LOC;
+pub use constants::*;
 pub use location_gen::*;
 pub use statistic_helper::*;
 
+mod cluster_helper;
 mod col_encoding;
 mod location_gen;
 mod statistic_helper;
 
 mod constants;
 
+#[cfg(test)]
+mod cluster_helper_test;
 #[cfg(test)]
 mod statistic_helper_test;
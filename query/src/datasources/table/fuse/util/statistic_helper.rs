@@ -19,15 +19,16 @@ use std::collections::HashMap;
 
 use common_datablocks::DataBlock;
 use common_datavalues::columns::DataColumn;
-use common_datavalues::DataSchema;
 use common_exception::Result;
 
 use crate::datasources::table::fuse::util;
 use crate::datasources::table::fuse::BlockLocation;
 use crate::datasources::table::fuse::BlockMeta;
+use crate::datasources::table::fuse::ClusterStats;
 use crate::datasources::table::fuse::ColStats;
 use crate::datasources::table::fuse::ColumnId;
 use crate::datasources::table::fuse::Stats;
+use crate::datasources::table::fuse::TableColumns;
 
 // TODO move this to other crate
 pub type BlockStats = HashMap<ColumnId, ColStats>;
@@ -52,7 +53,7 @@ impl StatisticsAccumulator {
 }
 
 impl StatisticsAccumulator {
-    pub fn acc(&mut self, block: &DataBlock) -> Result<()> {
+    pub fn acc(&mut self, block: &DataBlock, column_ids: &[ColumnId]) -> Result<()> {
         let row_count = block.num_rows() as u64;
         let block_in_memory_size = block.memory_size() as u64;
 
@@ -61,7 +62,7 @@ impl StatisticsAccumulator {
         self.in_memory_size += block_in_memory_size;
         self.last_block_rows = block.num_rows() as u64;
         self.last_block_size = block.memory_size() as u64;
-        let block_stats = block_stats(block)?;
+        let block_stats = block_stats(block, column_ids)?;
         self.last_block_col_stats = Some(block_stats.clone());
         self.blocks_stats.push(block_stats);
         Ok(())
@@ -80,7 +81,14 @@ impl BlockMetaAccumulator {
 }
 
 impl BlockMetaAccumulator {
-    pub fn acc(&mut self, file_size: u64, location: String, stats: &mut StatisticsAccumulator) {
+    pub fn acc(
+        &mut self,
+        file_size: u64,
+        location: String,
+        bloom_filter_location: Option<String>,
+        cluster_stats: Option<ClusterStats>,
+        stats: &mut StatisticsAccumulator,
+    ) {
         stats.file_size += file_size;
         let block_meta = BlockMeta {
             location: BlockLocation {
@@ -89,18 +97,20 @@ impl BlockMetaAccumulator {
             },
             row_count: stats.last_block_rows,
             block_size: stats.last_block_size,
+            file_size,
             col_stats: stats.last_block_col_stats.take().unwrap_or_default(),
+            bloom_filter_location,
+            cluster_stats,
         };
         self.blocks_metas.push(block_meta);
     }
 }
 
-pub(super) fn block_stats(data_block: &DataBlock) -> Result<BlockStats> {
-    // NOTE:
-    // column id is FAKED, this is OK as long as table schema is NOT changed (which is not realistic)
-    // we should extend DataField with column_id ...
-    (0..)
-        .into_iter()
+/// Statistics of the columns of the block, by the ids of the columns
+pub(super) fn block_stats(data_block: &DataBlock, column_ids: &[ColumnId]) -> Result<BlockStats> {
+    column_ids
+        .iter()
+        .cloned()
         .zip(data_block.columns().iter())
         .map(|(idx, col)| {
             let min = match col {
@@ -140,7 +150,7 @@ pub(super) fn block_stats(data_block: &DataBlock) -> Result<BlockStats> {
 
 pub fn column_stats_reduce_with_schema<T: Borrow<HashMap<ColumnId, ColStats>>>(
     stats: &[T],
-    schema: &DataSchema,
+    columns: &TableColumns,
 ) -> Result<HashMap<ColumnId, ColStats>> {
     let len = stats.len();
 
@@ -181,8 +191,11 @@ pub fn column_stats_reduce_with_schema<T: Borrow<HashMap<ColumnId, ColStats>>>(
                 in_memory_size += col_stats.in_memory_size;
             }
 
-            // TODO panic
-            let data_type = schema.field((*id) as usize).data_type();
+            // the stats of the dropped columns are discarded
+            let data_type = match columns.position_of(*id) {
+                Some(idx) => columns.schema.field(idx).data_type(),
+                None => return Ok(acc),
+            };
 
             // TODO
             // for some data types, we shall balance the accuracy and the length
@@ -205,13 +218,28 @@ pub fn column_stats_reduce_with_schema<T: Borrow<HashMap<ColumnId, ColStats>>>(
         })
 }
 
-pub fn merge_stats(schema: &DataSchema, l: &Stats, r: &Stats) -> Result<Stats> {
+/// Summary statistics of the given blocks, e.g. of a segment after some of its blocks are rewritten.
+pub fn reduce_block_metas(block_metas: &[BlockMeta], columns: &TableColumns) -> Result<Stats> {
+    let col_stats = block_metas
+        .iter()
+        .map(|meta| &meta.col_stats)
+        .collect::<Vec<_>>();
+    Ok(Stats {
+        row_count: block_metas.iter().map(|meta| meta.row_count).sum(),
+        block_count: block_metas.len() as u64,
+        uncompressed_byte_size: block_metas.iter().map(|meta| meta.block_size).sum(),
+        compressed_byte_size: block_metas.iter().map(|meta| meta.file_size).sum(),
+        col_stats: column_stats_reduce_with_schema(&col_stats, columns)?,
+    })
+}
+
+pub fn merge_stats(columns: &TableColumns, l: &Stats, r: &Stats) -> Result<Stats> {
     let s = Stats {
         row_count: l.row_count + r.row_count,
         block_count: l.block_count + r.block_count,
         uncompressed_byte_size: l.uncompressed_byte_size + r.uncompressed_byte_size,
         compressed_byte_size: l.compressed_byte_size + r.compressed_byte_size,
-        col_stats: util::column_stats_reduce_with_schema(&[&l.col_stats, &r.col_stats], schema)?,
+        col_stats: util::column_stats_reduce_with_schema(&[&l.col_stats, &r.col_stats], columns)?,
     };
     Ok(s)
 }
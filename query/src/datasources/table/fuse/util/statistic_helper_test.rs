@@ -24,12 +24,13 @@ use common_exception::ErrorCode;
 
 use super::statistic_helper;
 use crate::datasources::table::fuse::table_test_fixture::TestFixture;
+use crate::datasources::table::fuse::TableColumns;
 
 #[test]
 fn test_ft_stats_block_stats() -> common_exception::Result<()> {
     let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int32, false)]);
     let block = DataBlock::create_by_array(schema, vec![Series::new(vec![1, 2, 3])]);
-    let r = statistic_helper::block_stats(&block)?;
+    let r = statistic_helper::block_stats(&block, &[0])?;
     assert_eq!(1, r.len());
     let col_stats = r.get(&0).unwrap();
     assert_eq!(col_stats.min, DataValue::Int32(Some(1)));
@@ -43,9 +44,10 @@ fn test_ft_stats_col_stats_reduce() -> common_exception::Result<()> {
     let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int32, false)]);
     let col_stats = blocks
         .iter()
-        .map(|b| statistic_helper::block_stats(&b.clone().unwrap()))
+        .map(|b| statistic_helper::block_stats(&b.clone().unwrap(), &[0]))
         .collect::<common_exception::Result<Vec<_>>>()?;
-    let r = statistic_helper::column_stats_reduce_with_schema(&col_stats, &schema);
+    let columns = TableColumns::from_schema(schema);
+    let r = statistic_helper::column_stats_reduce_with_schema(&col_stats, &columns);
     assert!(r.is_ok());
     let r = r.unwrap();
     assert_eq!(1, r.len());
@@ -55,6 +57,32 @@ fn test_ft_stats_col_stats_reduce() -> common_exception::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_ft_stats_col_stats_reduce_by_column_ids() -> common_exception::Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int32, false),
+        DataField::new("b", DataType::Int32, false),
+    ]);
+    let block = DataBlock::create_by_array(schema.clone(), vec![
+        Series::new(vec![1, 2, 3]),
+        Series::new(vec![4, 5, 6]),
+    ]);
+    let col_stats = vec![statistic_helper::block_stats(&block, &[0, 1])?];
+
+    // column "a" is dropped, and column "c" is added
+    let columns = TableColumns::from_schema(schema)
+        .drop_column("a")?
+        .add_column(DataField::new("c", DataType::String, false), None)?;
+    assert_eq!(columns.column_ids, vec![1, 2]);
+
+    let r = statistic_helper::column_stats_reduce_with_schema(&col_stats, &columns)?;
+    assert_eq!(1, r.len());
+    let col_stats = r.get(&1).unwrap();
+    assert_eq!(col_stats.min, DataValue::Int32(Some(4)));
+    assert_eq!(col_stats.max, DataValue::Int32(Some(6)));
+    Ok(())
+}
+
 #[test]
 fn test_ft_stats_accumulator() -> common_exception::Result<()> {
     let blocks = TestFixture::gen_block_stream(10);
@@ -62,8 +90,8 @@ fn test_ft_stats_accumulator() -> common_exception::Result<()> {
     let mut meta_acc = statistic_helper::BlockMetaAccumulator::new();
     blocks.iter().try_for_each(|item| {
         let item = item.clone().unwrap();
-        stats_acc.acc(&item)?;
-        meta_acc.acc(1, "".to_owned(), &mut stats_acc);
+        stats_acc.acc(&item, &[0])?;
+        meta_acc.acc(1, "".to_owned(), None, None, &mut stats_acc);
         Ok::<_, ErrorCode>(())
     })?;
     assert_eq!(10, stats_acc.blocks_stats.len());
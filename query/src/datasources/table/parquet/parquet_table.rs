@@ -16,10 +16,10 @@ use std::any::Any;
 use std::sync::Arc;
 
 use async_stream::stream;
+use common_base::BlockingWait;
 use common_context::DataContext;
 use common_context::IOContext;
 use common_context::TableIOContext;
-use common_dal::Local;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_meta_types::TableInfo;
@@ -33,25 +33,32 @@ use common_streams::SendableDataBlockStream;
 use common_streams::Source;
 
 use crate::catalogs::Table;
+use crate::datasources::common::external_data_accessor;
+use crate::datasources::common::list_external_files;
 use crate::sessions::DatabendQueryContext;
 
 pub struct ParquetTable {
     table_info: TableInfo,
-    file: String,
+    // a file, a prefix or a glob pattern, see `list_external_files`
+    location: String,
+    // the files are on the disk of each node, rather than shared by the cluster
+    local_storage: bool,
 }
 
 impl ParquetTable {
     pub fn try_create(
         table_info: TableInfo,
         _data_ctx: Arc<dyn DataContext<u64>>,
+        local_storage: bool,
     ) -> Result<Box<dyn Table>> {
         let options = table_info.options();
-        let file = options.get("location").cloned();
-        return match file {
-            Some(file) => {
+        let location = options.get("location").cloned();
+        return match location {
+            Some(location) => {
                 let table = ParquetTable {
                     table_info,
-                    file: file.trim_matches(|s| s == '\'' || s == '"').to_string(),
+                    location: location.trim_matches(|s| s == '\'' || s == '"').to_string(),
+                    local_storage,
                 };
                 Ok(Box::new(table))
             }
@@ -64,6 +71,10 @@ impl ParquetTable {
 
 #[async_trait::async_trait]
 impl Table for ParquetTable {
+    fn is_local(&self) -> bool {
+        self.local_storage
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -78,14 +89,28 @@ impl Table for ParquetTable {
 
     fn read_partitions(
         &self,
-        _io_ctx: Arc<TableIOContext>,
+        io_ctx: Arc<TableIOContext>,
         _push_downs: Option<Extras>,
     ) -> Result<(Statistics, Partitions)> {
-        let parts = vec![Part {
-            name: self.file.clone(),
-            version: 0,
-        }];
-        Ok((Statistics::default(), parts))
+        let ctx: Arc<DatabendQueryContext> = io_ctx
+            .get_user_data()?
+            .expect("DatabendQueryContext should not be None");
+        let da = external_data_accessor(&ctx.get_config().storage)?;
+        let location = self.location.clone();
+        let files = async move { list_external_files(da.as_ref(), &location).await }
+            .wait_in(&io_ctx.get_runtime(), None)??;
+
+        // the rows are not known before reading the footers
+        let bytes = files.iter().map(|file| file.size as usize).sum();
+        // one partition per file
+        let parts = files
+            .into_iter()
+            .map(|file| Part {
+                name: file.location,
+                version: 0,
+            })
+            .collect();
+        Ok((Statistics::new_estimated(0, bytes), parts))
     }
 
     async fn read(
@@ -99,8 +124,7 @@ impl Table for ParquetTable {
         let ctx_clone = ctx.clone();
         let table_schema = self.get_table_info().schema();
         let projection = plan.projections();
-        let conf = ctx.get_config().storage;
-        let dal = Arc::new(Local::new(conf.disk.temp_data_path.as_str()));
+        let dal = external_data_accessor(&ctx.get_config().storage)?;
 
         let s = stream! {
             loop {
@@ -53,7 +53,7 @@ async fn test_parquet_table() -> Result<()> {
             options,
         },
     };
-    let table = ParquetTable::try_create(table_info, Arc::new(TableDataContext::default()))?;
+    let table = ParquetTable::try_create(table_info, Arc::new(TableDataContext::default()), true)?;
 
     let io_ctx = ctx.get_cluster_table_io_context()?;
     let io_ctx = Arc::new(io_ctx);
@@ -13,8 +13,15 @@
 //  limitations under the License.
 //
 
+use std::str::FromStr;
+use std::sync::Arc;
+
+use common_context::DataContext;
+use common_dal::StorageScheme;
 use common_exception::Result;
+use common_meta_types::TableInfo;
 
+use crate::configs::StorageConfig;
 use crate::datasources::table::csv::csv_table::CsvTable;
 use crate::datasources::table::fuse::FuseTable;
 use crate::datasources::table::memory::memory_table::MemoryTable;
@@ -22,11 +29,32 @@ use crate::datasources::table::null::null_table::NullTable;
 use crate::datasources::table::parquet::parquet_table::ParquetTable;
 use crate::datasources::table_engine_registry::TableEngineRegistry;
 
-pub fn register_prelude_tbl_engines(registry: &TableEngineRegistry) -> Result<()> {
-    registry.register("CSV", std::sync::Arc::new(CsvTable::try_create))?;
-    registry.register("PARQUET", std::sync::Arc::new(ParquetTable::try_create))?;
-    registry.register("NULL", std::sync::Arc::new(NullTable::try_create))?;
-    registry.register("MEMORY", std::sync::Arc::new(MemoryTable::try_create))?;
-    registry.register("FUSE", std::sync::Arc::new(FuseTable::try_create))?;
+pub fn register_prelude_tbl_engines(
+    registry: &TableEngineRegistry,
+    storage_conf: &StorageConfig,
+) -> Result<()> {
+    // the files of the external tables are on the disk of each node with the disk storage,
+    // or shared by the cluster on the object storages
+    let local_storage =
+        StorageScheme::from_str(&storage_conf.storage_type)? == StorageScheme::LocalFs;
+    registry.register(
+        "CSV",
+        Arc::new(
+            move |table_info: TableInfo, data_ctx: Arc<dyn DataContext<u64>>| {
+                CsvTable::try_create(table_info, data_ctx, local_storage)
+            },
+        ),
+    )?;
+    registry.register(
+        "PARQUET",
+        Arc::new(
+            move |table_info: TableInfo, data_ctx: Arc<dyn DataContext<u64>>| {
+                ParquetTable::try_create(table_info, data_ctx, local_storage)
+            },
+        ),
+    )?;
+    registry.register("NULL", Arc::new(NullTable::try_create))?;
+    registry.register("MEMORY", Arc::new(MemoryTable::try_create))?;
+    registry.register("FUSE", Arc::new(FuseTable::try_create))?;
     Ok(())
 }
@@ -0,0 +1,231 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_context::IOContext;
+use common_context::TableIOContext;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_types::TableIdent;
+use common_meta_types::TableInfo;
+use common_meta_types::TableMeta;
+use common_planners::Expression;
+use common_planners::ReadDataSourcePlan;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+
+use crate::catalogs::Table;
+use crate::catalogs::TableFunction;
+use crate::datasources::table::fuse::FuseTable;
+use crate::datasources::table_func_engine::TableArgs;
+use crate::sessions::DatabendQueryContext;
+
+/// `fuse_snapshot('db', 'table')`, the snapshot history of a fuse table, the latest first.
+pub struct FuseSnapshotTable {
+    table_info: TableInfo,
+    arg_database_name: String,
+    arg_table_name: String,
+}
+
+impl FuseSnapshotTable {
+    pub fn create(
+        database_name: &str,
+        table_func_name: &str,
+        table_id: u64,
+        table_args: TableArgs,
+    ) -> Result<Arc<dyn TableFunction>> {
+        let mut names = vec![];
+        if let Some(args) = &table_args {
+            for arg in args {
+                if let Expression::Literal {
+                    value: DataValue::String(Some(name)),
+                    ..
+                } = arg
+                {
+                    names.push(String::from_utf8_lossy(name).to_string());
+                }
+            }
+        }
+
+        if names.len() != 2 {
+            return Err(ErrorCode::BadArguments(format!(
+                "Must have exactly two string arguments (database and table name) for table function.{}",
+                &table_func_name
+            )));
+        }
+        let arg_table_name = names.pop().unwrap();
+        let arg_database_name = names.pop().unwrap();
+
+        let schema = DataSchemaRefExt::create(vec![
+            DataField::new("snapshot_id", DataType::String, false),
+            DataField::new("prev_snapshot_id", DataType::String, true),
+            DataField::new("timestamp", DataType::String, true),
+            DataField::new("segment_count", DataType::UInt64, false),
+            DataField::new("block_count", DataType::UInt64, false),
+            DataField::new("row_count", DataType::UInt64, false),
+            DataField::new("bytes_uncompressed", DataType::UInt64, false),
+            DataField::new("bytes_compressed", DataType::UInt64, false),
+            DataField::new("average_overlaps", DataType::Float64, true),
+            DataField::new("average_depth", DataType::Float64, true),
+        ]);
+
+        let table_info = TableInfo {
+            ident: TableIdent::new(table_id, 0),
+            desc: format!("'{}'.'{}'", database_name, table_func_name),
+            name: table_func_name.to_string(),
+            meta: TableMeta {
+                schema,
+                engine: "FuseSnapshot".to_string(),
+                options: Default::default(),
+            },
+        };
+
+        Ok(Arc::new(FuseSnapshotTable {
+            table_info,
+            arg_database_name,
+            arg_table_name,
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for FuseSnapshotTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_table_info(&self) -> &TableInfo {
+        &self.table_info
+    }
+
+    fn table_args(&self) -> Option<Vec<Expression>> {
+        Some(vec![
+            Expression::create_literal(DataValue::String(Some(
+                self.arg_database_name.as_bytes().to_vec(),
+            ))),
+            Expression::create_literal(DataValue::String(Some(
+                self.arg_table_name.as_bytes().to_vec(),
+            ))),
+        ])
+    }
+
+    async fn read(
+        &self,
+        io_ctx: Arc<TableIOContext>,
+        _plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream> {
+        let ctx: Arc<DatabendQueryContext> = io_ctx
+            .get_user_data()?
+            .expect("DatabendQueryContext should not be None");
+
+        let table = ctx.get_table(&self.arg_database_name, &self.arg_table_name)?;
+        let fuse_table = table.as_any().downcast_ref::<FuseTable>().ok_or_else(|| {
+            ErrorCode::BadArguments(format!(
+                "Expecting fuse table, but got table of engine type: {}",
+                table.engine()
+            ))
+        })?;
+
+        let snapshots = fuse_table.snapshot_history(io_ctx.as_ref()).await?;
+        let snapshot_ids = snapshots
+            .iter()
+            .map(|s| s.snapshot_id.to_simple().to_string())
+            .collect::<Vec<_>>();
+        let prev_snapshot_ids = snapshots
+            .iter()
+            .map(|s| s.prev_snapshot_id.map(|id| id.to_simple().to_string()))
+            .collect::<Vec<_>>();
+        let timestamps = snapshots
+            .iter()
+            .map(|s| {
+                s.timestamp
+                    .map(|t| t.format("%Y-%m-%d %H:%M:%S%.f").to_string())
+            })
+            .collect::<Vec<_>>();
+
+        let block = DataBlock::create_by_array(self.table_info.schema(), vec![
+            Series::new(snapshot_ids.iter().map(|v| v.as_str()).collect::<Vec<_>>()),
+            Series::new(
+                prev_snapshot_ids
+                    .iter()
+                    .map(|v| v.as_deref())
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(timestamps.iter().map(|v| v.as_deref()).collect::<Vec<_>>()),
+            Series::new(
+                snapshots
+                    .iter()
+                    .map(|s| s.segments.len() as u64)
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(
+                snapshots
+                    .iter()
+                    .map(|s| s.summary.block_count)
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(
+                snapshots
+                    .iter()
+                    .map(|s| s.summary.row_count)
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(
+                snapshots
+                    .iter()
+                    .map(|s| s.summary.uncompressed_byte_size)
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(
+                snapshots
+                    .iter()
+                    .map(|s| s.summary.compressed_byte_size)
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(
+                snapshots
+                    .iter()
+                    .map(|s| s.clustering.as_ref().map(|c| c.average_overlaps))
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(
+                snapshots
+                    .iter()
+                    .map(|s| s.clustering.as_ref().map(|c| c.average_depth))
+                    .collect::<Vec<_>>(),
+            ),
+        ]);
+
+        Ok(Box::pin(DataBlockStream::create(
+            self.table_info.schema(),
+            None,
+            vec![block],
+        )))
+    }
+}
+
+impl TableFunction for FuseSnapshotTable {
+    fn function_name(&self) -> &str {
+        self.name()
+    }
+
+    fn as_table<'a>(self: Arc<Self>) -> Arc<dyn Table + 'a>
+    where Self: 'a {
+        self
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::tokio;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_planners::*;
+use futures::TryStreamExt;
+
+use super::FuseSnapshotTable;
+use crate::catalogs::Catalog;
+use crate::catalogs::ToReadDataSourcePlan;
+use crate::datasources::table::fuse::table_test_fixture::TestFixture;
+
+fn snapshot_table_args(db: &str, table: &str) -> Option<Vec<Expression>> {
+    Some(vec![
+        Expression::create_literal(DataValue::String(Some(db.as_bytes().to_vec()))),
+        Expression::create_literal(DataValue::String(Some(table.as_bytes().to_vec()))),
+    ])
+}
+
+#[tokio::test]
+async fn test_fuse_snapshot_table() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let ctx = fixture.ctx();
+    let catalog = ctx.get_catalog();
+    catalog
+        .create_table(fixture.default_crate_table_plan())
+        .await?;
+
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+    let tbl_args = snapshot_table_args(&fixture.default_db(), &fixture.default_table());
+
+    // 1. two insertions, of 1 block and 2 blocks
+    for num_blocks in [1, 2] {
+        let table = catalog
+            .get_table(
+                fixture.default_db().as_str(),
+                fixture.default_table().as_str(),
+            )
+            .await?;
+        let insert_into_plan = fixture.insert_plan_of_table(table.as_ref());
+        let stream = Box::pin(futures::stream::iter(TestFixture::gen_block_stream(
+            num_blocks,
+        )));
+        table
+            .append_data(io_ctx.clone(), insert_into_plan, stream)
+            .await?;
+    }
+
+    // 2. the latest snapshot comes first
+    let table = FuseSnapshotTable::create("system", "fuse_snapshot", 1, tbl_args)?;
+    let source_plan = table
+        .clone()
+        .as_table()
+        .read_plan(io_ctx.clone(), Some(Extras::default()))?;
+    ctx.try_set_partitions(source_plan.parts.clone())?;
+
+    let stream = table.read(io_ctx.clone(), &source_plan).await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let block = &result[0];
+    assert_eq!(block.num_columns(), 10);
+    assert_eq!(block.num_rows(), 2);
+
+    let column = |name: &str| -> Result<Vec<DataValue>> {
+        let column = block.try_column_by_name(name)?;
+        (0..block.num_rows()).map(|i| column.try_get(i)).collect()
+    };
+    assert_eq!(column("segment_count")?, vec![
+        DataValue::UInt64(Some(2)),
+        DataValue::UInt64(Some(1))
+    ]);
+    assert_eq!(column("block_count")?, vec![
+        DataValue::UInt64(Some(3)),
+        DataValue::UInt64(Some(1))
+    ]);
+    assert_eq!(column("row_count")?, vec![
+        DataValue::UInt64(Some(9)),
+        DataValue::UInt64(Some(3))
+    ]);
+    assert_eq!(column("prev_snapshot_id")?[0], column("snapshot_id")?[1]);
+    assert_eq!(column("prev_snapshot_id")?[1], DataValue::String(None));
+    // no cluster keys, no clustering statistics
+    assert_eq!(column("average_depth")?, vec![
+        DataValue::Float64(None),
+        DataValue::Float64(None)
+    ]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_snapshot_table_args() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let io_ctx = Arc::new(ctx.get_cluster_table_io_context()?);
+
+    // wrong number of arguments
+    let tbl_args = Some(vec![Expression::create_literal(DataValue::String(Some(
+        b"system".to_vec(),
+    )))]);
+    let result = FuseSnapshotTable::create("system", "fuse_snapshot", 1, tbl_args);
+    assert_eq!(
+        result.err().unwrap().message(),
+        "Must have exactly two string arguments (database and table name) for table function.fuse_snapshot"
+    );
+
+    // not a fuse table
+    let tbl_args = snapshot_table_args("system", "one");
+    let table = FuseSnapshotTable::create("system", "fuse_snapshot", 1, tbl_args)?;
+    let source_plan = table
+        .clone()
+        .as_table()
+        .read_plan(io_ctx.clone(), Some(Extras::default()))?;
+    let result = table.read(io_ctx, &source_plan).await;
+    assert_eq!(
+        result.err().unwrap().message(),
+        "Expecting fuse table, but got table of engine type: SystemOne"
+    );
+
+    Ok(())
+}
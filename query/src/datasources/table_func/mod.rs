@@ -13,8 +13,12 @@
 //  limitations under the License.
 //
 
+pub use fuse_snapshot_table::FuseSnapshotTable;
 pub use numbers_table::NumbersTable;
 
+mod fuse_snapshot_table;
+#[cfg(test)]
+mod fuse_snapshot_table_test;
 mod numbers_stream;
 mod numbers_table;
 #[cfg(test)]
@@ -52,6 +52,7 @@ impl Interpreter for SettingInterpreter {
             match var.variable.to_lowercase().as_str() {
                 // To be compatible with some drivers
                 "sql_mode" | "autocommit" => {}
+                "extra_float_digits" | "application_name" | "client_encoding" | "datestyle" => {}
                 "max_threads" => {
                     let threads: u64 = var.value.parse()?;
                     self.ctx.get_settings().set_max_threads(threads)?;
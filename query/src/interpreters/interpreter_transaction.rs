@@ -0,0 +1,108 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_context::IOContext;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_planners::TransactionOperation;
+use common_planners::TransactionPlan;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+
+use crate::catalogs::Catalog;
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterPtr;
+use crate::sessions::DatabendQueryContextRef;
+use crate::sessions::Transaction;
+
+pub struct TransactionInterpreter {
+    ctx: DatabendQueryContextRef,
+    plan: TransactionPlan,
+}
+
+impl TransactionInterpreter {
+    pub fn try_create(
+        ctx: DatabendQueryContextRef,
+        plan: TransactionPlan,
+    ) -> Result<InterpreterPtr> {
+        Ok(Arc::new(TransactionInterpreter { ctx, plan }))
+    }
+
+    /// Commits the changes staged for all the tables in one shot. If any of the tables has
+    /// been changed by others since the transaction touched it, none of them is committed.
+    async fn commit(&self) -> Result<()> {
+        let txn = match self.ctx.take_transaction() {
+            None => return Ok(()),
+            Some(txn) => txn,
+        };
+
+        let upserts = txn.get_upserts();
+        if upserts.is_empty() {
+            return Ok(());
+        }
+
+        let catalog = self.ctx.get_catalog();
+        match catalog.upsert_multi_table_options(upserts).await {
+            Ok(_) => Ok(()),
+            Err(cause) if cause.code() == ErrorCode::TableVersionMissMatch("").code() => {
+                self.discard(&txn).await?;
+                Err(ErrorCode::TableCommitConflict(format!(
+                    "Transaction is rolled back, since the tables have been changed by a concurrent commit: {}",
+                    cause.message()
+                )))
+            }
+            // the transaction might have been committed, its objects are kept
+            Err(cause) => Err(cause),
+        }
+    }
+
+    async fn rollback(&self) -> Result<()> {
+        match self.ctx.take_transaction() {
+            None => Ok(()),
+            Some(txn) => self.discard(&txn).await,
+        }
+    }
+
+    async fn discard(&self, txn: &Transaction) -> Result<()> {
+        let io_ctx = self.ctx.get_cluster_table_io_context()?;
+        txn.discard(io_ctx.get_data_accessor()?, self.ctx.get_table_cache())
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for TransactionInterpreter {
+    fn name(&self) -> &str {
+        "TransactionInterpreter"
+    }
+
+    async fn execute(
+        &self,
+        _input_stream: Option<SendableDataBlockStream>,
+    ) -> Result<SendableDataBlockStream> {
+        match self.plan.operation {
+            TransactionOperation::Begin => self.ctx.begin_transaction()?,
+            TransactionOperation::Commit => self.commit().await?,
+            TransactionOperation::Rollback => self.rollback().await?,
+        }
+
+        Ok(Box::pin(DataBlockStream::create(
+            self.plan.schema(),
+            None,
+            vec![],
+        )))
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+use std::time::Duration;
+
+use common_base::tokio;
+use common_datablocks::DataBlock;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use futures::TryStreamExt;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+use crate::interpreters::*;
+use crate::sessions::SessionRef;
+use crate::sql::*;
+use crate::tests::SessionManagerBuilder;
+
+async fn execute(session: &SessionRef, query: &str) -> Result<Vec<DataBlock>> {
+    // a new context for each statement, as the servers do
+    let ctx = session.create_context().await?;
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(query)?;
+    let executor = InterpreterFactory::get(ctx, plan)?;
+    let stream = executor.execute(None).await?;
+    stream.try_collect::<Vec<_>>().await
+}
+
+fn num_files(dir: &Path) -> Result<usize> {
+    let mut num = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        num += match path.is_dir() {
+            true => num_files(&path)?,
+            false => 1,
+        };
+    }
+    Ok(num)
+}
+
+async fn num_rows(session: &SessionRef, table: &str) -> Result<usize> {
+    let blocks = execute(session, &format!("SELECT * FROM {}", table)).await?;
+    Ok(blocks.iter().map(|block| block.num_rows()).sum())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transaction_interpreter_commit() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let sessions = SessionManagerBuilder::create()
+        .disk_storage_path(tmp_dir.path().to_str().unwrap().to_string())
+        .build()?;
+    let session = sessions.create_session("TestSession")?;
+    let other = sessions.create_session("TestSession")?;
+
+    execute(&session, "CREATE DATABASE db_txn_commit").await?;
+    execute(
+        &session,
+        "CREATE TABLE db_txn_commit.t1(a Int32) Engine = Fuse",
+    )
+    .await?;
+    execute(
+        &session,
+        "CREATE TABLE db_txn_commit.t2(a Int32) Engine = Fuse",
+    )
+    .await?;
+
+    // begin twice
+    execute(&session, "BEGIN").await?;
+    let result = execute(&session, "START TRANSACTION").await;
+    assert_eq!(
+        result.err().unwrap().code(),
+        ErrorCode::BadArguments("").code()
+    );
+
+    execute(&session, "INSERT INTO db_txn_commit.t1 VALUES(1), (2)").await?;
+    execute(&session, "INSERT INTO db_txn_commit.t1 VALUES(3)").await?;
+    execute(&session, "INSERT INTO db_txn_commit.t2 VALUES(4)").await?;
+
+    // the transaction sees its own changes, which are invisible to the others until committed
+    assert_eq!(num_rows(&session, "db_txn_commit.t1").await?, 3);
+    assert_eq!(num_rows(&session, "db_txn_commit.t2").await?, 1);
+    assert_eq!(num_rows(&other, "db_txn_commit.t1").await?, 0);
+    assert_eq!(num_rows(&other, "db_txn_commit.t2").await?, 0);
+
+    execute(&session, "COMMIT").await?;
+    assert_eq!(num_rows(&other, "db_txn_commit.t1").await?, 3);
+    assert_eq!(num_rows(&other, "db_txn_commit.t2").await?, 1);
+
+    // commit without a transaction in progress is a no-op
+    execute(&session, "COMMIT").await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transaction_interpreter_rollback() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let sessions = SessionManagerBuilder::create()
+        .disk_storage_path(tmp_dir.path().to_str().unwrap().to_string())
+        .build()?;
+    let session = sessions.create_session("TestSession")?;
+    let other = sessions.create_session("TestSession")?;
+
+    execute(&session, "CREATE DATABASE db_txn_rollback").await?;
+    execute(
+        &session,
+        "CREATE TABLE db_txn_rollback.t1(a Int32) Engine = Fuse",
+    )
+    .await?;
+    execute(
+        &session,
+        "CREATE TABLE db_txn_rollback.t2(a Int32) Engine = Fuse",
+    )
+    .await?;
+
+    // rolled back explicitly, everything written by the statements is removed
+    execute(&session, "INSERT INTO db_txn_rollback.t1 VALUES(1), (2)").await?;
+    let files = num_files(tmp_dir.path())?;
+    execute(&session, "BEGIN").await?;
+    execute(&session, "INSERT INTO db_txn_rollback.t1 VALUES(3)").await?;
+    execute(&session, "DELETE FROM db_txn_rollback.t1 WHERE a = 1").await?;
+    execute(&session, "ROLLBACK").await?;
+    assert_eq!(num_rows(&session, "db_txn_rollback.t1").await?, 2);
+    assert_eq!(num_files(tmp_dir.path())?, files);
+
+    // rolled back when the session is closed
+    let closed = sessions.create_session("TestSession")?;
+    execute(&closed, "BEGIN").await?;
+    execute(&closed, "INSERT INTO db_txn_rollback.t1 VALUES(3)").await?;
+    drop(closed);
+    for _ in 0..50 {
+        if num_files(tmp_dir.path())? == files {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(num_files(tmp_dir.path())?, files);
+    assert_eq!(num_rows(&session, "db_txn_rollback.t1").await?, 2);
+    execute(&session, "TRUNCATE TABLE db_txn_rollback.t1").await?;
+
+    // rolled back by a concurrent commit to one of the tables
+    execute(&session, "BEGIN").await?;
+    execute(&session, "INSERT INTO db_txn_rollback.t1 VALUES(1), (2)").await?;
+    execute(&session, "INSERT INTO db_txn_rollback.t2 VALUES(3)").await?;
+    execute(&other, "INSERT INTO db_txn_rollback.t2 VALUES(4)").await?;
+
+    let result = execute(&session, "COMMIT").await;
+    assert_eq!(
+        result.err().unwrap().code(),
+        ErrorCode::TableCommitConflict("").code()
+    );
+    assert_eq!(num_rows(&session, "db_txn_rollback.t1").await?, 0);
+    assert_eq!(num_rows(&session, "db_txn_rollback.t2").await?, 1);
+
+    // rolled back by a concurrent purge of one of the tables, which might have removed the
+    // objects staged by the transaction
+    execute(&session, "BEGIN").await?;
+    execute(&session, "INSERT INTO db_txn_rollback.t2 VALUES(5)").await?;
+    execute(&other, "SET purge_grace_period_seconds = 0").await?;
+    execute(&other, "OPTIMIZE TABLE db_txn_rollback.t2 PURGE").await?;
+
+    let result = execute(&session, "COMMIT").await;
+    assert_eq!(
+        result.err().unwrap().code(),
+        ErrorCode::TableCommitConflict("").code()
+    );
+    assert_eq!(num_rows(&session, "db_txn_rollback.t2").await?, 1);
+
+    // purge is not allowed inside a transaction
+    execute(&session, "BEGIN").await?;
+    let result = execute(&session, "OPTIMIZE TABLE db_txn_rollback.t1 PURGE").await;
+    assert_eq!(
+        result.err().unwrap().code(),
+        ErrorCode::UnImplement("").code()
+    );
+    execute(&session, "ROLLBACK").await?;
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use common_planners::*;
+use pretty_assertions::assert_eq;
+
+use crate::interpreters::*;
+use crate::sql::*;
+
+#[tokio::test]
+async fn test_update_interpreter() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    // Create table.
+    {
+        if let PlanNode::CreateTable(plan) = PlanParser::create(ctx.clone())
+            .build_from_sql("create table default.a(a bigint, b String) Engine = Memory")?
+        {
+            let executor = CreateTableInterpreter::try_create(ctx.clone(), plan.clone())?;
+            let _ = executor.execute(None).await?;
+        }
+    }
+
+    // Update is not supported by the memory table.
+    {
+        if let PlanNode::Update(plan) = PlanParser::create(ctx.clone())
+            .build_from_sql("update default.a set a = a + 1 where a > 1")?
+        {
+            assert_eq!(plan.assignments.len(), 1);
+            assert_eq!(plan.assignments[0].0, "a");
+            assert_eq!(
+                format!("{:?}", plan.assignments[0].1),
+                "cast((a + 1) as Int64)"
+            );
+            assert_eq!(format!("{:?}", plan.selection), "Some((a > 1))");
+
+            let executor = UpdateInterpreter::try_create(ctx.clone(), plan.clone())?;
+            assert_eq!(executor.name(), "UpdateInterpreter");
+
+            let result = executor.execute(None).await;
+            assert_eq!(
+                result.err().unwrap().to_string(),
+                "Code: 2, displayText = update for local table a is not implemented."
+            );
+        } else {
+            panic!()
+        }
+    }
+
+    Ok(())
+}
@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use common_exception::Result;
+use common_meta_types::AuthType;
 use common_planners::AlterUserPlan;
 use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
@@ -22,6 +23,7 @@ use common_tracing::tracing;
 
 use crate::interpreters::Interpreter;
 use crate::interpreters::InterpreterPtr;
+use crate::servers::scram_sha256_verifier;
 use crate::sessions::DatabendQueryContextRef;
 
 #[derive(Debug)]
@@ -49,6 +51,10 @@ impl Interpreter for AlterUserInterpreter {
     ) -> Result<SendableDataBlockStream> {
         let plan = self.plan.clone();
         let user_mgr = self.ctx.get_sessions_manager().get_user_manager();
+        let scram_verifier = match plan.new_auth_type {
+            AuthType::None => None,
+            _ => Some(scram_sha256_verifier(&plan.new_password)),
+        };
         //TODO:alter current user
         user_mgr
             .update_user(
@@ -56,6 +62,7 @@ impl Interpreter for AlterUserInterpreter {
                 plan.hostname.as_str(),
                 Some(plan.new_auth_type),
                 Some(plan.new_password),
+                scram_verifier,
             )
             .await?;
 
@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use common_exception::Result;
 use common_management::UserInfo;
+use common_meta_types::AuthType;
 use common_meta_types::UserPrivilege;
 use common_meta_types::UserQuota;
 use common_planners::CreateUserPlan;
@@ -25,6 +26,7 @@ use common_tracing::tracing;
 
 use crate::interpreters::Interpreter;
 use crate::interpreters::InterpreterPtr;
+use crate::servers::scram_sha256_verifier;
 use crate::sessions::DatabendQueryContextRef;
 
 #[derive(Debug)]
@@ -55,6 +57,11 @@ impl Interpreter for CreatUserInterpreter {
     ) -> Result<SendableDataBlockStream> {
         let plan = self.plan.clone();
         let user_mgr = self.ctx.get_sessions_manager().get_user_manager();
+        // Kept for the PostgreSQL protocol, which can't verify the hashed passwords.
+        let scram_verifier = match plan.auth_type {
+            AuthType::None => None,
+            _ => Some(scram_sha256_verifier(&plan.password)),
+        };
         let user_info = UserInfo {
             name: plan.name,
             hostname: plan.hostname,
@@ -62,6 +69,7 @@ impl Interpreter for CreatUserInterpreter {
             auth_type: plan.auth_type,
             privileges: UserPrivilege::empty(),
             quota: UserQuota::no_limit(),
+            scram_verifier,
         };
         user_mgr.add_user(user_info).await?;
 
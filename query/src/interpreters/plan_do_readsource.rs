@@ -47,6 +47,9 @@ impl PlanRewriter for PlanDoReadSource {
         let io_ctx = self.context.get_cluster_table_io_context()?;
         let io_ctx = Arc::new(io_ctx);
         let plan = table.read_plan(io_ctx, plan.push_downs.clone())?;
+        if let Some(pruning) = &plan.statistics.pruning {
+            self.context.add_pruning_statistics(pruning);
+        }
 
         Ok(PlanNode::ReadSource(plan))
     }
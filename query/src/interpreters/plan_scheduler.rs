@@ -19,16 +19,19 @@ use std::sync::Arc;
 
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_infallible::Mutex;
 use common_meta_types::NodeInfo;
 use common_planners::AggregatorFinalPlan;
 use common_planners::AggregatorPartialPlan;
 use common_planners::BroadcastPlan;
+use common_planners::CteScanPlan;
 use common_planners::EmptyPlan;
 use common_planners::Expression;
 use common_planners::ExpressionPlan;
 use common_planners::Expressions;
 use common_planners::FilterPlan;
 use common_planners::HavingPlan;
+use common_planners::JoinPlan;
 use common_planners::LimitByPlan;
 use common_planners::LimitPlan;
 use common_planners::Partitions;
@@ -37,10 +40,12 @@ use common_planners::ProjectionPlan;
 use common_planners::ReadDataSourcePlan;
 use common_planners::RemotePlan;
 use common_planners::SelectPlan;
+use common_planners::SetOperationPlan;
 use common_planners::SortPlan;
 use common_planners::StageKind;
 use common_planners::StagePlan;
 use common_planners::SubQueriesSetPlan;
+use common_planners::WindowPlan;
 use common_tracing::tracing;
 
 use crate::api::BroadcastAction;
@@ -69,6 +74,8 @@ pub struct PlanScheduler {
     running_mode: RunningMode,
     query_context: DatabendQueryContextRef,
     subqueries_expressions: Vec<Expressions>,
+    // The local plans of the scheduled CTEs by cte_id, shared with the subquery schedulers.
+    cte_plans: Arc<Mutex<HashMap<String, PlanNode>>>,
 }
 
 impl PlanScheduler {
@@ -96,6 +103,7 @@ impl PlanScheduler {
             subqueries_expressions: vec![],
             cluster_nodes: cluster_nodes_name,
             running_mode: RunningMode::Standalone,
+            cte_plans: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
@@ -301,7 +309,11 @@ impl PlanScheduler {
             PlanNode::Sort(plan) => self.visit_sort(plan, tasks),
             PlanNode::Limit(plan) => self.visit_limit(plan, tasks),
             PlanNode::LimitBy(plan) => self.visit_limit_by(plan, tasks),
+            PlanNode::Window(plan) => self.visit_window(plan, tasks),
             PlanNode::ReadSource(plan) => self.visit_data_source(plan, tasks),
+            PlanNode::Join(plan) => self.visit_join(plan, tasks),
+            PlanNode::SetOperation(plan) => self.visit_set_operation(plan, tasks),
+            PlanNode::CteScan(plan) => self.visit_cte_scan(plan, tasks),
             PlanNode::Select(plan) => self.visit_select(plan, tasks),
             PlanNode::Stage(plan) => self.visit_stage(plan, tasks),
             PlanNode::Broadcast(plan) => self.visit_broadcast(plan, tasks),
@@ -639,10 +651,68 @@ impl PlanScheduler {
     fn visit_subquery(&mut self, plan: &PlanNode, tasks: &mut Tasks) -> Result<Vec<PlanNode>> {
         let subquery_context = DatabendQueryContext::new(self.query_context.clone());
         let mut subquery_scheduler = PlanScheduler::try_create(subquery_context)?;
+        subquery_scheduler.cte_plans = self.cte_plans.clone();
         subquery_scheduler.visit_plan_node(plan, tasks)?;
         Ok(subquery_scheduler.nodes_plan)
     }
 
+    fn visit_join(&mut self, plan: &JoinPlan, tasks: &mut Tasks) -> Result<()> {
+        // The inputs of join are converged by the optimizer, the join runs on the local node.
+        let left_nodes_plan = self.visit_subquery(plan.left.as_ref(), tasks)?;
+        let right_nodes_plan = self.visit_subquery(plan.right.as_ref(), tasks)?;
+
+        self.running_mode = RunningMode::Standalone;
+        self.nodes_plan[self.local_pos] = PlanNode::Join(JoinPlan {
+            join_type: plan.join_type,
+            left_keys: plan.left_keys.clone(),
+            right_keys: plan.right_keys.clone(),
+            filter: plan.filter.clone(),
+            left: Arc::new(left_nodes_plan[self.local_pos].clone()),
+            right: Arc::new(right_nodes_plan[self.local_pos].clone()),
+            schema: plan.schema.clone(),
+        });
+        Ok(())
+    }
+
+    fn visit_set_operation(&mut self, plan: &SetOperationPlan, tasks: &mut Tasks) -> Result<()> {
+        // The inputs of set operation are converged by the optimizer, it runs on the local node.
+        let left_nodes_plan = self.visit_subquery(plan.left.as_ref(), tasks)?;
+        let right_nodes_plan = self.visit_subquery(plan.right.as_ref(), tasks)?;
+
+        self.running_mode = RunningMode::Standalone;
+        self.nodes_plan[self.local_pos] = PlanNode::SetOperation(SetOperationPlan {
+            op: plan.op,
+            left: Arc::new(left_nodes_plan[self.local_pos].clone()),
+            right: Arc::new(right_nodes_plan[self.local_pos].clone()),
+            schema: plan.schema.clone(),
+        });
+        Ok(())
+    }
+
+    fn visit_cte_scan(&mut self, plan: &CteScanPlan, tasks: &mut Tasks) -> Result<()> {
+        // The input of CTE is converged by the optimizer, it is materialized on the local node.
+        // It is scheduled for the first reference only, the others read the same materialized
+        // result, which is shared by cte_id.
+        let scheduled = self.cte_plans.lock().get(&plan.cte_id).cloned();
+        let input = match scheduled {
+            Some(input) => input,
+            None => {
+                let input_nodes_plan = self.visit_subquery(plan.input.as_ref(), tasks)?;
+                let input = input_nodes_plan[self.local_pos].clone();
+                self.cte_plans
+                    .lock()
+                    .insert(plan.cte_id.clone(), input.clone());
+                input
+            }
+        };
+
+        self.running_mode = RunningMode::Standalone;
+        let mut new_plan = plan.clone();
+        new_plan.set_input(&input);
+        self.nodes_plan[self.local_pos] = PlanNode::CteScan(new_plan);
+        Ok(())
+    }
+
     fn visit_filter(&mut self, plan: &FilterPlan, tasks: &mut Tasks) -> Result<()> {
         self.visit_plan_node(plan.input.as_ref(), tasks)?;
         match self.running_mode {
@@ -724,6 +794,33 @@ impl PlanScheduler {
         }
     }
 
+    fn visit_window(&mut self, plan: &WindowPlan, tasks: &mut Tasks) -> Result<()> {
+        self.visit_plan_node(plan.input.as_ref(), tasks)?;
+        match self.running_mode {
+            RunningMode::Cluster => self.visit_cluster_window(plan),
+            RunningMode::Standalone => self.visit_local_window(plan),
+        };
+        Ok(())
+    }
+
+    fn visit_local_window(&mut self, plan: &WindowPlan) {
+        self.nodes_plan[self.local_pos] = PlanNode::Window(WindowPlan {
+            window_funcs: plan.window_funcs.clone(),
+            schema: plan.schema.clone(),
+            input: Arc::new(self.nodes_plan[self.local_pos].clone()),
+        });
+    }
+
+    fn visit_cluster_window(&mut self, plan: &WindowPlan) {
+        for index in 0..self.nodes_plan.len() {
+            self.nodes_plan[index] = PlanNode::Window(WindowPlan {
+                window_funcs: plan.window_funcs.clone(),
+                schema: plan.schema.clone(),
+                input: Arc::new(self.nodes_plan[index].clone()),
+            });
+        }
+    }
+
     fn visit_limit(&mut self, plan: &LimitPlan, tasks: &mut Tasks) -> Result<()> {
         self.visit_plan_node(plan.input.as_ref(), tasks)?;
         match self.running_mode {
@@ -324,6 +324,49 @@ async fn test_scheduler_plan_with_convergent_and_normal_stage() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_scheduler_plan_with_cte_referenced_twice() -> Result<()> {
+    // WITH t AS (...) SELECT * FROM t UNION ALL SELECT * FROM t, the convergent stage of the
+    // CTE is scheduled once, both references read the same materialized result
+    let context = create_env().await?;
+    let scheduler = PlanScheduler::try_create(context)?;
+    let cte_scan = CteScanPlan {
+        cte_id: String::from("cte_1"),
+        name: String::from("t"),
+        input: Arc::new(PlanNode::Stage(StagePlan {
+            kind: StageKind::Convergent,
+            scatters_expr: Expression::create_literal(DataValue::UInt64(Some(0))),
+            input: Arc::new(PlanNode::Empty(EmptyPlan::cluster())),
+        })),
+    };
+    let scheduled_tasks = scheduler.reschedule(&PlanNode::SetOperation(SetOperationPlan {
+        op: SetOperator::UnionAll,
+        schema: cte_scan.schema(),
+        left: Arc::new(PlanNode::CteScan(cte_scan.clone())),
+        right: Arc::new(PlanNode::CteScan(cte_scan)),
+    }))?;
+
+    // one for each node
+    assert_eq!(scheduled_tasks.get_tasks()?.len(), 2);
+
+    match scheduled_tasks.get_local_task() {
+        PlanNode::SetOperation(plan) => match (plan.left.as_ref(), plan.right.as_ref()) {
+            (PlanNode::CteScan(left), PlanNode::CteScan(right)) => {
+                assert!(matches!(left.input.as_ref(), PlanNode::Remote(_)));
+                assert_eq!(left.input, right.input);
+            }
+            _ => {
+                panic!("test_scheduler_plan_with_cte_referenced_twice must be have CteScan plans!")
+            }
+        },
+        _ => {
+            panic!("test_scheduler_plan_with_cte_referenced_twice must be have SetOperation plan!")
+        }
+    }
+
+    Ok(())
+}
+
 async fn create_env() -> Result<DatabendQueryContextRef> {
     try_create_cluster_context(
         ClusterDescriptor::new()
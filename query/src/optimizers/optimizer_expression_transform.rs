@@ -246,11 +246,7 @@ impl PlanRewriter for ExprTransformImpl {
                 table_info: plan.table_info.clone(),
                 scan_fields: plan.scan_fields.clone(),
                 parts: vec![], // set parts to empty vector, read_table should return None immediately
-                statistics: Statistics {
-                    read_rows: 0,
-                    read_bytes: 0,
-                    is_exact: true,
-                },
+                statistics: Statistics::new_exact(0, 0),
                 description: format!("(Read from {} table)", plan.table_info.desc),
                 tbl_args: plan.tbl_args.clone(),
                 push_downs: plan.push_downs.clone(),
@@ -0,0 +1,72 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::Result;
+use common_planners::Extras;
+use common_planners::FilterPlan;
+use common_planners::PlanBuilder;
+use common_planners::PlanNode;
+use common_planners::PlanRewriter;
+use common_planners::RewriteHelper;
+
+use crate::optimizers::Optimizer;
+use crate::sessions::DatabendQueryContextRef;
+
+/// Pushes the predicate of the filter right above the scan down to the read_source_plan,
+/// so that the table may prune its partitions by it, e.g. by the indexes of fuse tables.
+///
+/// The filter is kept, since the partitions may still contain the rows not matching it.
+pub struct FilterPushDownOptimizer {}
+
+struct FilterPushDownImpl {}
+
+impl PlanRewriter for FilterPushDownImpl {
+    fn rewrite_filter(&mut self, plan: &FilterPlan) -> Result<PlanNode> {
+        let new_input = match plan.input.as_ref() {
+            PlanNode::ReadSource(read_source_plan)
+                if RewriteHelper::collect_exprs_sub_queries(&[plan.predicate.clone()])?
+                    .is_empty() =>
+            {
+                let mut new_plan = read_source_plan.clone();
+                let mut extras = read_source_plan
+                    .push_downs
+                    .clone()
+                    .unwrap_or_else(Extras::default);
+                extras.filters = vec![plan.predicate.clone()];
+                new_plan.push_downs = Some(extras);
+                PlanNode::ReadSource(new_plan)
+            }
+            other => self.rewrite_plan_node(other)?,
+        };
+        let new_predicate = self.rewrite_expr(&new_input.schema(), &plan.predicate)?;
+        PlanBuilder::from(&new_input).filter(new_predicate)?.build()
+    }
+}
+
+impl Optimizer for FilterPushDownOptimizer {
+    fn name(&self) -> &str {
+        "FilterPushDown"
+    }
+
+    fn optimize(&mut self, plan: &PlanNode) -> Result<PlanNode> {
+        let mut visitor = FilterPushDownImpl {};
+        visitor.rewrite_plan_node(plan)
+    }
+}
+
+impl FilterPushDownOptimizer {
+    pub fn create(_ctx: DatabendQueryContextRef) -> FilterPushDownOptimizer {
+        FilterPushDownOptimizer {}
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::Result;
+use common_planners::*;
+use pretty_assertions::assert_eq;
+
+use crate::optimizers::*;
+
+#[test]
+fn test_filter_push_down_optimizer() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let plan = crate::tests::parse_query("select number from numbers_mt(10) where number > 1")?;
+
+    let mut optimizer = FilterPushDownOptimizer::create(ctx);
+    let optimized = optimizer.optimize(&plan)?;
+
+    // the filter is kept
+    let expect = "\
+    Projection: number:UInt64\
+    \n  Filter: (number > 1)\
+    \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]";
+    let actual = format!("{:?}", optimized);
+    assert_eq!(expect, actual);
+
+    match optimized.input(0).input(0).as_ref() {
+        PlanNode::ReadSource(plan) => {
+            let filters = plan.push_downs.as_ref().map(|extras| &extras.filters);
+            assert_eq!("Some([(number > 1)])", format!("{:?}", filters));
+        }
+        other => panic!("expecting ReadSource, but got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_filter_push_down_optimizer_not_above_scan() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let plan = crate::tests::parse_query(
+        "select * from (select number % 3 as c from numbers_mt(10)) where c > 1",
+    )?;
+
+    let mut optimizer = FilterPushDownOptimizer::create(ctx);
+    let optimized = optimizer.optimize(&plan)?;
+
+    // the filter on the derived column can not be pushed down to the scan
+    let expect = format!("{:?}", plan);
+    let actual = format!("{:?}", optimized);
+    assert_eq!(expect, actual);
+    Ok(())
+}
@@ -20,17 +20,20 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 use common_planners::AggregatorFinalPlan;
 use common_planners::AggregatorPartialPlan;
+use common_planners::CteScanPlan;
 use common_planners::EmptyPlan;
 use common_planners::Expression;
 use common_planners::ExpressionPlan;
 use common_planners::Extras;
 use common_planners::FilterPlan;
+use common_planners::JoinPlan;
 use common_planners::PlanBuilder;
 use common_planners::PlanNode;
 use common_planners::PlanRewriter;
 use common_planners::ProjectionPlan;
 use common_planners::ReadDataSourcePlan;
 use common_planners::SortPlan;
+use common_planners::WindowPlan;
 
 use crate::optimizers::Optimizer;
 use crate::optimizers::RequireColumnsVisitor;
@@ -115,6 +118,41 @@ impl PlanRewriter for ProjectionPushDownImpl {
             .build()
     }
 
+    fn rewrite_window(&mut self, plan: &WindowPlan) -> Result<PlanNode> {
+        self.collect_column_names_from_expr_vec(&plan.window_funcs)?;
+        let new_input = self.rewrite_plan_node(&plan.input)?;
+        PlanBuilder::from(&new_input)
+            .window(&plan.window_funcs)?
+            .build()
+    }
+
+    fn rewrite_join(&mut self, plan: &JoinPlan) -> Result<PlanNode> {
+        self.collect_column_names_from_expr_vec(&plan.left_keys)?;
+        self.collect_column_names_from_expr_vec(&plan.right_keys)?;
+        if let Some(filter) = &plan.filter {
+            self.collect_column_names_from_expr(filter)?;
+        }
+
+        let new_left = self.rewrite_plan_node(&plan.left)?;
+        let new_right = self.rewrite_plan_node(&plan.right)?;
+        PlanBuilder::from(&new_left)
+            .join(
+                &new_right,
+                plan.join_type,
+                &plan.left_keys,
+                &plan.right_keys,
+                plan.filter.clone(),
+            )?
+            .build()
+    }
+
+    fn rewrite_cte_scan(&mut self, plan: &CteScanPlan) -> Result<PlanNode> {
+        // All the references of a CTE share the same input, which can't be pruned by any of them.
+        let mut new_plan = plan.clone();
+        new_plan.set_input(&ProjectionPushDownImpl::new().rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::CteScan(new_plan))
+    }
+
     fn rewrite_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<PlanNode> {
         self.get_projection(plan.table_info.schema().as_ref())
             .map(|projection| {
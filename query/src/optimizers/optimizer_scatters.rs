@@ -12,25 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use common_datavalues::DataSchemaRef;
 use common_datavalues::DataValue;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_infallible::Mutex;
 use common_planners::AggregatorFinalPlan;
 use common_planners::AggregatorPartialPlan;
 use common_planners::BroadcastPlan;
+use common_planners::CteScanPlan;
 use common_planners::Expression;
+use common_planners::JoinPlan;
 use common_planners::LimitByPlan;
 use common_planners::LimitPlan;
 use common_planners::PlanBuilder;
 use common_planners::PlanNode;
 use common_planners::PlanRewriter;
 use common_planners::ReadDataSourcePlan;
+use common_planners::SetOperationPlan;
 use common_planners::SortPlan;
 use common_planners::StageKind;
 use common_planners::StagePlan;
+use common_planners::WindowPlan;
 
 use crate::optimizers::Optimizer;
 use crate::sessions::DatabendQueryContext;
@@ -53,6 +59,9 @@ struct ScattersOptimizerImpl {
 
     // temporary node
     input: Option<Arc<PlanNode>>,
+
+    // The rewritten inputs of the CTEs by cte_id, shared with the input optimizers.
+    cte_inputs: Arc<Mutex<HashMap<String, PlanNode>>>,
 }
 
 impl ScattersOptimizerImpl {
@@ -62,6 +71,7 @@ impl ScattersOptimizerImpl {
             running_mode: RunningMode::Standalone,
             before_group_by_schema: None,
             input: None,
+            cte_inputs: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -175,6 +185,39 @@ impl ScattersOptimizerImpl {
         }
     }
 
+    fn cluster_window(&mut self, plan: &WindowPlan) -> Result<PlanNode> {
+        // Window functions need all the rows of the partition, we convergent it in local node
+        self.running_mode = RunningMode::Standalone;
+
+        match self.input.take() {
+            None => Err(ErrorCode::LogicalError("Cluster window input is None")),
+            Some(input) => Self::convergent_shuffle_stage_builder(input)
+                .window(&plan.window_funcs)?
+                .build(),
+        }
+    }
+
+    fn standalone_window(&mut self, plan: &WindowPlan) -> Result<PlanNode> {
+        match self.input.take() {
+            None => Err(ErrorCode::LogicalError("Standalone window input is None")),
+            Some(input) => PlanBuilder::from(input.as_ref())
+                .window(&plan.window_funcs)?
+                .build(),
+        }
+    }
+
+    fn rewrite_convergent_input(&mut self, input: &PlanNode) -> Result<PlanNode> {
+        let input_ctx = DatabendQueryContext::new(self.ctx.clone());
+        let mut input_optimizer = ScattersOptimizerImpl::create(input_ctx);
+        input_optimizer.cte_inputs = self.cte_inputs.clone();
+        let rewritten_input = input_optimizer.rewrite_plan_node(input)?;
+
+        match input_optimizer.running_mode {
+            RunningMode::Standalone => Ok(rewritten_input),
+            RunningMode::Cluster => Self::convergent_shuffle_stage(rewritten_input),
+        }
+    }
+
     fn convergent_shuffle_stage_builder(input: Arc<PlanNode>) -> PlanBuilder {
         PlanBuilder::from(&PlanNode::Stage(StagePlan {
             kind: StageKind::Convergent,
@@ -209,6 +252,7 @@ impl PlanRewriter for ScattersOptimizerImpl {
     fn rewrite_subquery_plan(&mut self, subquery_plan: &PlanNode) -> Result<PlanNode> {
         let subquery_ctx = DatabendQueryContext::new(self.ctx.clone());
         let mut subquery_optimizer = ScattersOptimizerImpl::create(subquery_ctx);
+        subquery_optimizer.cte_inputs = self.cte_inputs.clone();
         let rewritten_subquery = subquery_optimizer.rewrite_plan_node(subquery_plan)?;
 
         match (&self.running_mode, &subquery_optimizer.running_mode) {
@@ -229,6 +273,55 @@ impl PlanRewriter for ScattersOptimizerImpl {
         }
     }
 
+    fn rewrite_join(&mut self, plan: &JoinPlan) -> Result<PlanNode> {
+        // Join we convergent both sides in local node
+        let new_left = self.rewrite_convergent_input(&plan.left)?;
+        let new_right = self.rewrite_convergent_input(&plan.right)?;
+        self.running_mode = RunningMode::Standalone;
+
+        PlanBuilder::from(&new_left)
+            .join(
+                &new_right,
+                plan.join_type,
+                &plan.left_keys,
+                &plan.right_keys,
+                plan.filter.clone(),
+            )?
+            .build()
+    }
+
+    fn rewrite_set_operation(&mut self, plan: &SetOperationPlan) -> Result<PlanNode> {
+        // Set operation we convergent both sides in local node
+        let new_left = self.rewrite_convergent_input(&plan.left)?;
+        let new_right = self.rewrite_convergent_input(&plan.right)?;
+        self.running_mode = RunningMode::Standalone;
+
+        PlanBuilder::from(&new_left)
+            .set_operation(&new_right, plan.op)?
+            .build()
+    }
+
+    fn rewrite_cte_scan(&mut self, plan: &CteScanPlan) -> Result<PlanNode> {
+        // CTE is materialized once in local node, all the references of it share one
+        // convergent stage
+        let rewritten = self.cte_inputs.lock().get(&plan.cte_id).cloned();
+        let new_input = match rewritten {
+            Some(new_input) => new_input,
+            None => {
+                let new_input = self.rewrite_convergent_input(&plan.input)?;
+                self.cte_inputs
+                    .lock()
+                    .insert(plan.cte_id.clone(), new_input.clone());
+                new_input
+            }
+        };
+        self.running_mode = RunningMode::Standalone;
+
+        let mut new_plan = plan.clone();
+        new_plan.set_input(&new_input);
+        Ok(PlanNode::CteScan(new_plan))
+    }
+
     fn rewrite_aggregate_partial(&mut self, plan: &AggregatorPartialPlan) -> Result<PlanNode> {
         let new_input = Arc::new(self.rewrite_plan_node(&plan.input)?);
 
@@ -261,6 +354,15 @@ impl PlanRewriter for ScattersOptimizerImpl {
         }
     }
 
+    fn rewrite_window(&mut self, plan: &WindowPlan) -> Result<PlanNode> {
+        self.input = Some(Arc::new(self.rewrite_plan_node(plan.input.as_ref())?));
+
+        match self.running_mode {
+            RunningMode::Cluster => self.cluster_window(plan),
+            RunningMode::Standalone => self.standalone_window(plan),
+        }
+    }
+
     fn rewrite_limit(&mut self, plan: &LimitPlan) -> Result<PlanNode> {
         self.input = Some(Arc::new(self.rewrite_plan_node(plan.input.as_ref())?));
 
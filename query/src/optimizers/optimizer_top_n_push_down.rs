@@ -132,6 +132,30 @@ impl PlanRewriter for TopNPushDownImpl {
         optimizer.optimize(subquery_plan)
     }
 
+    fn rewrite_join(&mut self, plan: &JoinPlan) -> Result<PlanNode> {
+        // The limit can't be pushed through the join, optimize both sides independently.
+        let new_left = TopNPushDownOptimizer {}.optimize(&plan.left)?;
+        let new_right = TopNPushDownOptimizer {}.optimize(&plan.right)?;
+        PlanBuilder::from(&new_left)
+            .join(
+                &new_right,
+                plan.join_type,
+                &plan.left_keys,
+                &plan.right_keys,
+                plan.filter.clone(),
+            )?
+            .build()
+    }
+
+    fn rewrite_set_operation(&mut self, plan: &SetOperationPlan) -> Result<PlanNode> {
+        // The limit can't be pushed through the set operation, optimize both sides independently.
+        let new_left = TopNPushDownOptimizer {}.optimize(&plan.left)?;
+        let new_right = TopNPushDownOptimizer {}.optimize(&plan.right)?;
+        PlanBuilder::from(&new_left)
+            .set_operation(&new_right, plan.op)?
+            .build()
+    }
+
     fn rewrite_sort(&mut self, plan: &SortPlan) -> Result<PlanNode> {
         if self.limit.is_some() {
             self.order_by = plan.order_by.clone();
@@ -18,9 +18,11 @@ use common_exception::Result;
 use common_planners::AggregatorFinalPlan;
 use common_planners::AggregatorPartialPlan;
 use common_planners::BroadcastPlan;
+use common_planners::CteScanPlan;
 use common_planners::ExpressionPlan;
 use common_planners::FilterPlan;
 use common_planners::HavingPlan;
+use common_planners::JoinPlan;
 use common_planners::LimitByPlan;
 use common_planners::LimitPlan;
 use common_planners::PlanNode;
@@ -28,29 +30,40 @@ use common_planners::ProjectionPlan;
 use common_planners::ReadDataSourcePlan;
 use common_planners::RemotePlan;
 use common_planners::SelectPlan;
+use common_planners::SetOperationPlan;
+use common_planners::SetOperator;
 use common_planners::SortPlan;
 use common_planners::StagePlan;
 use common_planners::SubQueriesSetPlan;
+use common_planners::WindowPlan;
 use common_tracing::tracing;
 
 use crate::api::FlightTicket;
 use crate::pipelines::processors::Pipeline;
+use crate::pipelines::processors::Processor;
 use crate::pipelines::transforms::AggregatorFinalTransform;
 use crate::pipelines::transforms::AggregatorPartialTransform;
 use crate::pipelines::transforms::CreateSetsTransform;
+use crate::pipelines::transforms::CteMaterializer;
+use crate::pipelines::transforms::CteScanTransform;
 use crate::pipelines::transforms::ExpressionTransform;
 use crate::pipelines::transforms::GroupByFinalTransform;
 use crate::pipelines::transforms::GroupByPartialTransform;
+use crate::pipelines::transforms::HashJoinBuilder;
+use crate::pipelines::transforms::HashJoinProbeTransform;
 use crate::pipelines::transforms::HavingTransform;
 use crate::pipelines::transforms::LimitByTransform;
 use crate::pipelines::transforms::LimitTransform;
 use crate::pipelines::transforms::ProjectionTransform;
 use crate::pipelines::transforms::RemoteTransform;
+use crate::pipelines::transforms::SetOperationTransform;
 use crate::pipelines::transforms::SortMergeTransform;
 use crate::pipelines::transforms::SortPartialTransform;
 use crate::pipelines::transforms::SourceTransform;
 use crate::pipelines::transforms::SubQueriesPuller;
 use crate::pipelines::transforms::WhereTransform;
+use crate::pipelines::transforms::WindowTransform;
+use crate::sessions::DatabendQueryContext;
 use crate::sessions::DatabendQueryContextRef;
 
 pub struct PipelineBuilder {
@@ -92,7 +105,11 @@ impl PipelineBuilder {
             PlanNode::Sort(node) => self.visit_sort(node),
             PlanNode::Limit(node) => self.visit_limit(node),
             PlanNode::LimitBy(node) => self.visit_limit_by(node),
+            PlanNode::Window(node) => self.visit_window(node),
             PlanNode::ReadSource(node) => self.visit_read_data_source(node),
+            PlanNode::Join(node) => self.visit_join(node),
+            PlanNode::SetOperation(node) => self.visit_set_operation(node),
+            PlanNode::CteScan(node) => self.visit_cte_scan(node),
             PlanNode::SubQueryExpression(node) => self.visit_create_sets(node),
             other => Result::Err(ErrorCode::UnknownPlan(format!(
                 "Build pipeline from the plan node unsupported:{:?}",
@@ -308,6 +325,54 @@ impl PipelineBuilder {
         Ok(pipeline)
     }
 
+    fn visit_window(&mut self, plan: &WindowPlan) -> Result<Pipeline> {
+        let mut pipeline = self.visit(&*plan.input)?;
+
+        // Sort by the partition keys and the order keys, the limit does not apply
+        // because the window functions need all the rows of the partition.
+        let sort_exprs = plan.sort_exprs();
+        if !sort_exprs.is_empty() {
+            let input_schema = plan.input.schema();
+            pipeline.add_simple_transform(|| {
+                Ok(Box::new(SortPartialTransform::try_create(
+                    input_schema.clone(),
+                    sort_exprs.clone(),
+                    None,
+                )?))
+            })?;
+            pipeline.add_simple_transform(|| {
+                Ok(Box::new(SortMergeTransform::try_create(
+                    input_schema.clone(),
+                    sort_exprs.clone(),
+                    None,
+                )?))
+            })?;
+
+            if pipeline.last_pipe()?.nums() > 1 {
+                pipeline.merge_processor()?;
+                pipeline.add_simple_transform(|| {
+                    Ok(Box::new(SortMergeTransform::try_create(
+                        input_schema.clone(),
+                        sort_exprs.clone(),
+                        None,
+                    )?))
+                })?;
+            }
+        } else {
+            pipeline.merge_processor()?;
+        }
+
+        // processor: sorted blocks ---> window functions evaluated over the partitions
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(WindowTransform::try_create(
+                plan.schema(),
+                plan.input.schema(),
+                plan.window_funcs.clone(),
+            )?))
+        })?;
+        Ok(pipeline)
+    }
+
     fn visit_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<Pipeline> {
         // Bind plan partitions to context.
         self.ctx.try_set_partitions(plan.parts.clone())?;
@@ -340,4 +405,69 @@ impl PipelineBuilder {
 
         Ok(pipeline)
     }
+
+    fn visit_join(&mut self, plan: &JoinPlan) -> Result<Pipeline> {
+        // The left input is the probe side, the right input is built into hash table.
+        let mut pipeline = PipelineBuilder::create(self.ctx.clone()).visit(&*plan.left)?;
+
+        // The unmatched rows of the build side are returned by the only probe transform.
+        if plan.join_type.keep_right_unmatched() && pipeline.last_pipe()?.nums() > 1 {
+            pipeline.merge_processor()?;
+        }
+
+        let context = self.ctx.clone();
+        let join_builder = HashJoinBuilder::create(context.clone(), plan.clone());
+        pipeline.add_simple_transform(move || {
+            Ok(Box::new(HashJoinProbeTransform::try_create(
+                context.clone(),
+                plan,
+                join_builder.clone(),
+            )?))
+        })?;
+
+        Ok(pipeline)
+    }
+
+    fn visit_set_operation(&mut self, plan: &SetOperationPlan) -> Result<Pipeline> {
+        // Each side reads its own partitions, so the right side runs in a new context.
+        let right_ctx = DatabendQueryContext::new(self.ctx.clone());
+        let mut left = PipelineBuilder::create(self.ctx.clone()).visit(&*plan.left)?;
+        let mut right = PipelineBuilder::create(right_ctx).visit(&*plan.right)?;
+
+        let mut pipeline = Pipeline::create(self.ctx.clone());
+        match plan.op {
+            SetOperator::UnionAll => {
+                for processor in left.last_pipe()?.processors() {
+                    pipeline.add_source(processor)?;
+                }
+                for processor in right.last_pipe()?.processors() {
+                    pipeline.add_source(processor)?;
+                }
+            }
+            _ => {
+                left.merge_processor()?;
+                right.merge_processor()?;
+
+                let mut transform = SetOperationTransform::try_create(plan.op, plan.schema())?;
+                transform.connect_to(left.last_pipe()?.first())?;
+                transform.connect_to(right.last_pipe()?.first())?;
+                pipeline.add_source(Arc::new(transform))?;
+            }
+        }
+
+        Ok(pipeline)
+    }
+
+    fn visit_cte_scan(&mut self, plan: &CteScanPlan) -> Result<Pipeline> {
+        // All the scans of the same CTE share one materializer, the CTE query runs only once.
+        let context = self.ctx.clone();
+        let materializer = self.ctx.get_cte_materializer(&plan.cte_id, || {
+            CteMaterializer::create(context.clone(), plan.input.clone())
+        });
+
+        let mut pipeline = Pipeline::create(self.ctx.clone());
+        let source = CteScanTransform::try_create(self.ctx.clone(), materializer)?;
+        pipeline.add_source(Arc::new(source))?;
+        Ok(pipeline)
+    }
 }
@@ -16,26 +16,36 @@ pub use transform_aggregator_final::AggregatorFinalTransform;
 pub use transform_aggregator_partial::AggregatorPartialTransform;
 pub use transform_create_sets::CreateSetsTransform;
 pub use transform_create_sets::SubQueriesPuller;
+pub use transform_cte_scan::CteMaterializer;
+pub use transform_cte_scan::CteMaterializerRef;
+pub use transform_cte_scan::CteScanTransform;
 pub use transform_expression::ExpressionTransform;
 pub use transform_expression_executor::ExpressionExecutor;
 pub use transform_filter::HavingTransform;
 pub use transform_filter::WhereTransform;
 pub use transform_group_by_final::GroupByFinalTransform;
 pub use transform_group_by_partial::GroupByPartialTransform;
+pub use transform_hash_join_build::HashJoinBuilder;
+pub use transform_hash_join_build::JoinHashTable;
+pub use transform_hash_join_probe::HashJoinProbeTransform;
 pub use transform_limit::LimitTransform;
 pub use transform_limit_by::LimitByTransform;
 pub use transform_projection::ProjectionTransform;
 pub use transform_remote::RemoteTransform;
+pub use transform_set_operation::SetOperationTransform;
 pub use transform_sort_merge::SortMergeTransform;
 pub use transform_sort_partial::get_sort_descriptions;
 pub use transform_sort_partial::SortPartialTransform;
 pub use transform_source::SourceTransform;
+pub use transform_window::WindowTransform;
 
 #[cfg(test)]
 mod transform_aggregator_final_test;
 #[cfg(test)]
 mod transform_aggregator_partial_test;
 #[cfg(test)]
+mod transform_cte_scan_test;
+#[cfg(test)]
 mod transform_expression_test;
 #[cfg(test)]
 mod transform_filter_test;
@@ -44,30 +54,41 @@ mod transform_group_by_final_test;
 #[cfg(test)]
 mod transform_group_by_partial_test;
 #[cfg(test)]
+mod transform_hash_join_test;
+#[cfg(test)]
 mod transform_limit_by_test;
 #[cfg(test)]
 mod transform_limit_test;
 #[cfg(test)]
 mod transform_projection_test;
 #[cfg(test)]
+mod transform_set_operation_test;
+#[cfg(test)]
 mod transform_sort_test;
 #[cfg(test)]
 mod transform_source_test;
+#[cfg(test)]
+mod transform_window_test;
 
 mod transform_aggregator_final;
 mod transform_aggregator_partial;
 mod transform_create_sets;
+mod transform_cte_scan;
 mod transform_expression;
 mod transform_expression_executor;
 mod transform_filter;
 mod transform_group_by_final;
 mod transform_group_by_partial;
+mod transform_hash_join_build;
+mod transform_hash_join_probe;
 mod transform_limit;
 mod transform_limit_by;
 mod transform_projection;
 mod transform_remote;
+mod transform_set_operation;
 mod transform_sort_merge;
 mod transform_sort_partial;
 mod transform_source;
+mod transform_window;
 
 mod group_by;
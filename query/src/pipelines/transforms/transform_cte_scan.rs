@@ -0,0 +1,147 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_base::TrySpawn;
+use common_datablocks::DataBlock;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_infallible::Mutex;
+use common_planners::PlanNode;
+use common_streams::SendableDataBlockStream;
+use futures::future::BoxFuture;
+use futures::future::Shared;
+use futures::stream;
+use futures::Future;
+use futures::FutureExt;
+use futures::StreamExt;
+
+use crate::pipelines::processors::EmptyProcessor;
+use crate::pipelines::processors::PipelineBuilder;
+use crate::pipelines::processors::Processor;
+use crate::sessions::DatabendQueryContext;
+use crate::sessions::DatabendQueryContextRef;
+
+type CteData = Result<Arc<Vec<DataBlock>>>;
+type SharedFuture<'a> = Shared<BoxFuture<'a, CteData>>;
+
+pub type CteMaterializerRef = Arc<Mutex<CteMaterializer<'static>>>;
+
+/// Executes the query of a CTE once, and shares the result to all the scans of the CTE.
+pub struct CteMaterializer<'a> {
+    ctx: DatabendQueryContextRef,
+    plan: Arc<PlanNode>,
+    result: Option<SharedFuture<'a>>,
+}
+
+impl<'a> CteMaterializer<'a> {
+    pub fn create(
+        ctx: DatabendQueryContextRef,
+        plan: Arc<PlanNode>,
+    ) -> Arc<Mutex<CteMaterializer<'a>>> {
+        Arc::new(Mutex::new(CteMaterializer {
+            ctx,
+            plan,
+            result: None,
+        }))
+    }
+
+    pub fn take_result(&mut self) -> Result<impl Future<Output = CteData> + 'a> {
+        if self.result.is_none() {
+            self.init()?;
+        }
+
+        match &self.result {
+            Some(result) => Ok(result.clone()),
+            None => unreachable!(),
+        }
+    }
+
+    fn init(&mut self) -> Result<()> {
+        let cte_ctx = DatabendQueryContext::new(self.ctx.clone());
+        let mut pipeline = PipelineBuilder::create(cte_ctx).build(&self.plan)?;
+
+        let cte_future = async move {
+            let mut stream = pipeline.execute().await?;
+
+            let mut blocks = vec![];
+            while let Some(data_block) = stream.next().await {
+                blocks.push(data_block?);
+            }
+            Ok(Arc::new(blocks))
+        };
+
+        self.result = Some(cte_future.boxed().shared());
+        Ok(())
+    }
+}
+
+/// The source of a CTE scan, reads the materialized result of the CTE.
+pub struct CteScanTransform {
+    ctx: DatabendQueryContextRef,
+    input: Arc<dyn Processor>,
+    materializer: CteMaterializerRef,
+}
+
+impl CteScanTransform {
+    pub fn try_create(
+        ctx: DatabendQueryContextRef,
+        materializer: CteMaterializerRef,
+    ) -> Result<CteScanTransform> {
+        Ok(CteScanTransform {
+            ctx,
+            materializer,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for CteScanTransform {
+    fn name(&self) -> &str {
+        "CteScanTransform"
+    }
+
+    fn connect_to(&mut self, _: Arc<dyn Processor>) -> Result<()> {
+        Result::Err(ErrorCode::LogicalError(
+            "Cannot call CteScanTransform connect_to",
+        ))
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![self.input.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        let cte_future = self.materializer.lock().take_result()?;
+        let blocks = match self.ctx.try_spawn(cte_future)?.await {
+            Ok(blocks) => blocks?,
+            Err(error) => {
+                return Err(ErrorCode::TokioError(format!(
+                    "Cannot materialize the CTE. cause: {}",
+                    error
+                )));
+            }
+        };
+
+        let blocks = blocks.iter().cloned().map(Ok).collect::<Vec<_>>();
+        Ok(Box::pin(stream::iter(blocks)))
+    }
+}
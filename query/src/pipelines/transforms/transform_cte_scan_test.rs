@@ -0,0 +1,79 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use futures::TryStreamExt;
+
+use crate::pipelines::processors::*;
+use crate::sql::PlanParser;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_cte_scan() -> Result<()> {
+    struct Test {
+        name: &'static str,
+        query: &'static str,
+        expect: Vec<&'static str>,
+    }
+
+    let tests = vec![
+        Test {
+            name: "cte-inlined-passed",
+            query: "with t as (select number from numbers(3)) select number + 1 as n from t",
+            expect: vec!["+---+", "| n |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+"],
+        },
+        Test {
+            name: "cte-union-all-passed",
+            query: "with t as (select number from numbers(2)) select number from t union all select number from t",
+            expect: vec![
+                "+--------+",
+                "| number |",
+                "+--------+",
+                "| 0      |",
+                "| 0      |",
+                "| 1      |",
+                "| 1      |",
+                "+--------+",
+            ],
+        },
+        Test {
+            name: "cte-join-passed",
+            query: "with t(n) as (select number from numbers(3)), s as (select n from t where n > 0) \
+                    select a.n, b.n from s as a join s as b on a.n = b.n",
+            expect: vec![
+                "+-----+-----+",
+                "| a.n | b.n |",
+                "+-----+-----+",
+                "| 1   | 1   |",
+                "| 2   | 2   |",
+                "+-----+-----+",
+            ],
+        },
+    ];
+
+    for test in tests {
+        let ctx = crate::tests::try_create_context()?;
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(test.query)?;
+        let mut pipeline = PipelineBuilder::create(ctx).build(&plan)?;
+        let stream = pipeline.execute().await?;
+        let result = stream.try_collect::<Vec<_>>().await?;
+        common_datablocks::assert_blocks_sorted_eq_with_name(
+            test.name,
+            test.expect,
+            result.as_slice(),
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,399 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use bumpalo::Bump;
+use common_datablocks::DataBlock;
+use common_datablocks::HashMethod;
+use common_datablocks::HashMethodKind;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_infallible::Mutex;
+use common_planners::Expression;
+use common_planners::JoinPlan;
+use common_planners::JoinType;
+use futures::future::BoxFuture;
+use futures::future::Shared;
+use futures::Future;
+use futures::FutureExt;
+use futures::StreamExt;
+
+use crate::common::HashMap;
+use crate::common::HashTableEntity;
+use crate::common::HashTableKeyable;
+use crate::common::KeyValueEntity;
+use crate::pipelines::processors::Pipeline;
+use crate::pipelines::processors::PipelineBuilder;
+use crate::pipelines::transforms::group_by::KeysRef;
+use crate::pipelines::transforms::ExpressionExecutor;
+use crate::sessions::DatabendQueryContext;
+use crate::sessions::DatabendQueryContextRef;
+
+/// The join keys are hashed like the group by keys: the fixed-width keys are packed into
+/// an integer, the others are serialized. The value is the index of the rows of the key.
+enum JoinHashMap {
+    Serializer(HashMap<KeysRef, usize>),
+    KeysU8(HashMap<u8, usize>),
+    KeysU16(HashMap<u16, usize>),
+    KeysU32(HashMap<u32, usize>),
+    KeysU64(HashMap<u64, usize>),
+}
+
+/// The hash table of the build(right) side of a hash join.
+/// All the rows of the build side are kept in one block, the table maps
+/// the join keys to the row indices of the block.
+pub struct JoinHashTable {
+    join_type: JoinType,
+    block: DataBlock,
+    method: HashMethodKind,
+    hash_map: JoinHashMap,
+    // The serialized keys referenced by the hash map.
+    keys_area: Bump,
+    key_rows: Vec<Vec<u32>>,
+    // Whether the build row is matched by any probe row, used by RIGHT/FULL join.
+    matched: Mutex<Vec<bool>>,
+}
+
+// The hash map holds raw pointers, it is only read after being built, and the keys it
+// references live as long as the table.
+unsafe impl Send for JoinHashTable {}
+
+unsafe impl Sync for JoinHashTable {}
+
+impl JoinHashTable {
+    pub fn try_create(
+        join_type: JoinType,
+        schema: DataSchemaRef,
+        keys: &[Expression],
+        blocks: Vec<DataBlock>,
+    ) -> Result<JoinHashTable> {
+        let block = match blocks.is_empty() {
+            true => DataBlock::empty_with_schema(schema),
+            false => DataBlock::concat_blocks(&blocks)?,
+        };
+
+        let (keys_block, valid) = Self::join_keys_block(keys, &block)?;
+        let method = Self::choose_hash_method(&keys_block)?;
+        let hash_map = match &method {
+            HashMethodKind::Serializer(_) => JoinHashMap::Serializer(HashMap::create()),
+            HashMethodKind::KeysU8(_) => JoinHashMap::KeysU8(HashMap::create()),
+            HashMethodKind::KeysU16(_) => JoinHashMap::KeysU16(HashMap::create()),
+            HashMethodKind::KeysU32(_) => JoinHashMap::KeysU32(HashMap::create()),
+            HashMethodKind::KeysU64(_) => JoinHashMap::KeysU64(HashMap::create()),
+        };
+
+        let matched = Mutex::new(vec![false; block.num_rows()]);
+        let mut hash_table = JoinHashTable {
+            join_type,
+            block,
+            method,
+            hash_map,
+            keys_area: Bump::new(),
+            key_rows: vec![],
+            matched,
+        };
+        hash_table.insert_rows(&keys_block, &valid)?;
+        Ok(hash_table)
+    }
+
+    pub fn join_type(&self) -> JoinType {
+        self.join_type
+    }
+
+    pub fn block(&self) -> &DataBlock {
+        &self.block
+    }
+
+    fn insert_rows(&mut self, keys_block: &DataBlock, valid: &[bool]) -> Result<()> {
+        let columns = keys_block.columns().iter().collect::<Vec<_>>();
+        let rows = valid.len();
+        let key_rows = &mut self.key_rows;
+        match (&self.method, &mut self.hash_map) {
+            (HashMethodKind::Serializer(method), JoinHashMap::Serializer(hash_map)) => {
+                let keys = method.build_keys(&columns, rows)?;
+                for (row, key) in keys.iter().enumerate() {
+                    if !valid[row] {
+                        continue;
+                    }
+
+                    let mut inserted = false;
+                    let mut keys_ref = KeysRef::create(key.as_ptr() as usize, key.len());
+                    let entity = hash_map.insert_key(&keys_ref, &mut inserted);
+                    if inserted {
+                        // The keys are dropped after building, copy them to the table.
+                        let global_keys = self.keys_area.alloc_slice_copy(key);
+                        keys_ref.address = global_keys.as_ptr() as usize;
+                        unsafe {
+                            let hash = entity.get_hash();
+                            entity.set_key_and_hash(&keys_ref, hash);
+                        }
+                    }
+                    Self::push_row(key_rows, entity, inserted, row);
+                }
+            }
+            (HashMethodKind::KeysU8(method), JoinHashMap::KeysU8(hash_map)) => {
+                Self::insert_fixed_keys(
+                    hash_map,
+                    method.build_keys(&columns, rows)?,
+                    valid,
+                    key_rows,
+                )
+            }
+            (HashMethodKind::KeysU16(method), JoinHashMap::KeysU16(hash_map)) => {
+                Self::insert_fixed_keys(
+                    hash_map,
+                    method.build_keys(&columns, rows)?,
+                    valid,
+                    key_rows,
+                )
+            }
+            (HashMethodKind::KeysU32(method), JoinHashMap::KeysU32(hash_map)) => {
+                Self::insert_fixed_keys(
+                    hash_map,
+                    method.build_keys(&columns, rows)?,
+                    valid,
+                    key_rows,
+                )
+            }
+            (HashMethodKind::KeysU64(method), JoinHashMap::KeysU64(hash_map)) => {
+                Self::insert_fixed_keys(
+                    hash_map,
+                    method.build_keys(&columns, rows)?,
+                    valid,
+                    key_rows,
+                )
+            }
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    fn insert_fixed_keys<Key: HashTableKeyable>(
+        hash_map: &mut HashMap<Key, usize>,
+        keys: Vec<Key>,
+        valid: &[bool],
+        key_rows: &mut Vec<Vec<u32>>,
+    ) {
+        for (row, key) in keys.iter().enumerate() {
+            // NULL never equals to anything, skip the rows with NULL keys.
+            if valid[row] {
+                let mut inserted = false;
+                let entity = hash_map.insert_key(key, &mut inserted);
+                Self::push_row(key_rows, entity, inserted, row);
+            }
+        }
+    }
+
+    fn push_row<Key: HashTableKeyable>(
+        key_rows: &mut Vec<Vec<u32>>,
+        entity: *mut KeyValueEntity<Key, usize>,
+        inserted: bool,
+        row: usize,
+    ) {
+        if inserted {
+            entity.set_value(key_rows.len());
+            key_rows.push(vec![]);
+        }
+        key_rows[*entity.get_value()].push(row as u32);
+    }
+
+    /// For every row of the probe block, the build rows whose join keys equal to the keys of
+    /// the row, None if there is no such row or the keys of the row contain NULL.
+    pub fn probe_rows(
+        &self,
+        keys: &[Expression],
+        block: &DataBlock,
+    ) -> Result<Vec<Option<&[u32]>>> {
+        let (keys_block, valid) = Self::join_keys_block(keys, block)?;
+        let columns = keys_block.columns().iter().collect::<Vec<_>>();
+        let rows = valid.len();
+
+        let indices = match (&self.method, &self.hash_map) {
+            (HashMethodKind::Serializer(method), JoinHashMap::Serializer(hash_map)) => method
+                .build_keys(&columns, rows)?
+                .iter()
+                .map(|key| {
+                    let keys_ref = KeysRef::create(key.as_ptr() as usize, key.len());
+                    hash_map
+                        .find_key(&keys_ref)
+                        .map(|entity| *entity.get_value())
+                })
+                .collect::<Vec<_>>(),
+            (HashMethodKind::KeysU8(method), JoinHashMap::KeysU8(hash_map)) => {
+                Self::find_fixed_keys(hash_map, method.build_keys(&columns, rows)?)
+            }
+            (HashMethodKind::KeysU16(method), JoinHashMap::KeysU16(hash_map)) => {
+                Self::find_fixed_keys(hash_map, method.build_keys(&columns, rows)?)
+            }
+            (HashMethodKind::KeysU32(method), JoinHashMap::KeysU32(hash_map)) => {
+                Self::find_fixed_keys(hash_map, method.build_keys(&columns, rows)?)
+            }
+            (HashMethodKind::KeysU64(method), JoinHashMap::KeysU64(hash_map)) => {
+                Self::find_fixed_keys(hash_map, method.build_keys(&columns, rows)?)
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(indices
+            .into_iter()
+            .enumerate()
+            .map(|(row, index)| match valid[row] {
+                true => index.map(|index| self.key_rows[index].as_slice()),
+                false => None,
+            })
+            .collect())
+    }
+
+    fn find_fixed_keys<Key: HashTableKeyable>(
+        hash_map: &HashMap<Key, usize>,
+        keys: Vec<Key>,
+    ) -> Vec<Option<usize>> {
+        keys.iter()
+            .map(|key| hash_map.find_key(key).map(|entity| *entity.get_value()))
+            .collect()
+    }
+
+    pub fn mark_matched(&self, rows: &[u32]) {
+        let mut matched = self.matched.lock();
+        for row in rows {
+            matched[*row as usize] = true;
+        }
+    }
+
+    /// The build rows which are not matched by any probe row.
+    pub fn unmatched_rows(&self) -> Vec<u32> {
+        let matched = self.matched.lock();
+        matched
+            .iter()
+            .enumerate()
+            .filter(|(_, matched)| !**matched)
+            .map(|(row, _)| row as u32)
+            .collect()
+    }
+
+    /// The keys are coerced to the same types on both sides by the planner, so the build
+    /// and the probe side choose the same hash method.
+    fn choose_hash_method(keys_block: &DataBlock) -> Result<HashMethodKind> {
+        let names = keys_block
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect::<Vec<_>>();
+        DataBlock::choose_hash_method(keys_block, &names)
+    }
+
+    /// Evaluate the join keys of every row, and whether the keys of the row are all not NULL.
+    fn join_keys_block(keys: &[Expression], block: &DataBlock) -> Result<(DataBlock, Vec<bool>)> {
+        let rows = block.num_rows();
+        if keys.is_empty() {
+            let keys_block = DataBlock::create(DataSchemaRefExt::create(vec![]), vec![]);
+            return Ok((keys_block, vec![true; rows]));
+        }
+
+        let schema = block.schema();
+        let fields = keys
+            .iter()
+            .map(|key| key.to_data_field(schema))
+            .collect::<Result<Vec<_>>>()?;
+
+        let executor = ExpressionExecutor::try_create(
+            "join keys executor",
+            schema.clone(),
+            DataSchemaRefExt::create(fields.clone()),
+            keys.to_vec(),
+            false,
+        )?;
+
+        let keys_block = executor.execute(block)?;
+        let mut columns = Vec::with_capacity(fields.len());
+        for field in &fields {
+            columns.push(keys_block.try_column_by_name(field.name())?.clone());
+        }
+
+        let mut valid = vec![true; rows];
+        for column in &columns {
+            let series = column.to_array()?;
+            for (row, valid) in valid.iter_mut().enumerate() {
+                *valid = *valid && !series.is_null(row);
+            }
+        }
+
+        let keys_block = DataBlock::create(DataSchemaRefExt::create(fields), columns);
+        Ok((keys_block, valid))
+    }
+}
+
+type HashTableData = Result<Arc<JoinHashTable>>;
+type SharedFuture<'a> = Shared<BoxFuture<'a, HashTableData>>;
+
+/// Builds the hash table from the build side once, and shares it to all the probe transforms.
+pub struct HashJoinBuilder<'a> {
+    ctx: DatabendQueryContextRef,
+    plan: JoinPlan,
+    hash_table: Option<SharedFuture<'a>>,
+}
+
+impl<'a> HashJoinBuilder<'a> {
+    pub fn create(ctx: DatabendQueryContextRef, plan: JoinPlan) -> Arc<Mutex<HashJoinBuilder<'a>>> {
+        Arc::new(Mutex::new(HashJoinBuilder {
+            ctx,
+            plan,
+            hash_table: None,
+        }))
+    }
+
+    pub fn take_hash_table(&mut self) -> Result<impl Future<Output = HashTableData> + 'a> {
+        if self.hash_table.is_none() {
+            self.init()?;
+        }
+
+        match &self.hash_table {
+            Some(hash_table) => Ok(hash_table.clone()),
+            None => unreachable!(),
+        }
+    }
+
+    fn init(&mut self) -> Result<()> {
+        let build_ctx = DatabendQueryContext::new(self.ctx.clone());
+        let builder = PipelineBuilder::create(build_ctx);
+        let pipeline = builder.build(&self.plan.right)?;
+        self.hash_table = Some(Self::receive_hash_table(&self.plan, pipeline));
+        Ok(())
+    }
+
+    fn receive_hash_table(plan: &JoinPlan, mut pipeline: Pipeline) -> SharedFuture<'a> {
+        let join_type = plan.join_type;
+        let schema = plan.right.schema();
+        let keys = plan.right_keys.clone();
+
+        let build_future = async move {
+            let mut stream = pipeline.execute().await?;
+
+            let mut blocks = vec![];
+            while let Some(data_block) = stream.next().await {
+                let data_block = data_block?;
+                if !data_block.is_empty() {
+                    blocks.push(data_block);
+                }
+            }
+
+            let hash_table = JoinHashTable::try_create(join_type, schema, &keys, blocks)?;
+            Ok(Arc::new(hash_table))
+        };
+
+        build_future.boxed().shared()
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_base::TrySpawn;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_infallible::Mutex;
+use common_planners::Expression;
+use common_planners::JoinPlan;
+use common_streams::SendableDataBlockStream;
+use futures::stream;
+use futures::StreamExt;
+
+use crate::pipelines::processors::EmptyProcessor;
+use crate::pipelines::processors::Processor;
+use crate::pipelines::transforms::transform_hash_join_build::HashJoinBuilder;
+use crate::pipelines::transforms::transform_hash_join_build::JoinHashTable;
+use crate::pipelines::transforms::ExpressionExecutor;
+use crate::sessions::DatabendQueryContextRef;
+
+/// Probes the blocks of the left input with the hash table of the right input.
+/// For RIGHT/FULL join, the unmatched build rows are returned after all the input
+/// is consumed, so there must be only one probe transform in the pipeline.
+pub struct HashJoinProbeTransform {
+    ctx: DatabendQueryContextRef,
+    input: Arc<dyn Processor>,
+    probe: Arc<JoinProbe>,
+    join_builder: Arc<Mutex<HashJoinBuilder<'static>>>,
+}
+
+impl HashJoinProbeTransform {
+    pub fn try_create(
+        ctx: DatabendQueryContextRef,
+        plan: &JoinPlan,
+        join_builder: Arc<Mutex<HashJoinBuilder<'static>>>,
+    ) -> Result<HashJoinProbeTransform> {
+        let filter = match &plan.filter {
+            None => None,
+            Some(filter) => {
+                let filter_field = filter.to_data_field(&plan.schema)?;
+                Some(ExpressionExecutor::try_create(
+                    "join filter executor",
+                    plan.schema.clone(),
+                    DataSchemaRefExt::create(vec![filter_field]),
+                    vec![filter.clone()],
+                    false,
+                )?)
+            }
+        };
+
+        Ok(HashJoinProbeTransform {
+            ctx,
+            join_builder,
+            input: Arc::new(EmptyProcessor::create()),
+            probe: Arc::new(JoinProbe {
+                schema: plan.schema.clone(),
+                left_schema: plan.left.schema(),
+                right_schema: plan.right.schema(),
+                keys: plan.left_keys.clone(),
+                filter,
+            }),
+        })
+    }
+}
+
+struct JoinProbe {
+    schema: DataSchemaRef,
+    left_schema: DataSchemaRef,
+    right_schema: DataSchemaRef,
+    keys: Vec<Expression>,
+    filter: Option<ExpressionExecutor>,
+}
+
+impl JoinProbe {
+    fn probe(&self, hash_table: &JoinHashTable, block: &DataBlock) -> Result<Vec<DataBlock>> {
+        let matched_rows = hash_table.probe_rows(&self.keys, block)?;
+
+        let mut probe_indices = vec![];
+        let mut build_indices = vec![];
+        for (row, rows) in matched_rows.into_iter().enumerate() {
+            if let Some(rows) = rows {
+                probe_indices.extend(std::iter::repeat(row as u32).take(rows.len()));
+                build_indices.extend_from_slice(rows);
+            }
+        }
+
+        // Apply the residual filter to the candidate pairs.
+        if let Some(filter) = &self.filter {
+            let candidates = self.join_block(block, &probe_indices, hash_table, &build_indices)?;
+            let predicate = filter.execute(&candidates)?.column(0).to_array()?;
+            let predicate = predicate.cast_with_type(&DataType::Boolean)?;
+
+            let mut selected = predicate.bool()?.into_iter();
+            probe_indices.retain(|_| selected.next() == Some(Some(true)));
+            let mut selected = predicate.bool()?.into_iter();
+            build_indices.retain(|_| selected.next() == Some(Some(true)));
+        }
+
+        if hash_table.join_type().keep_right_unmatched() {
+            hash_table.mark_matched(&build_indices);
+        }
+
+        let mut blocks =
+            vec![self.join_block(block, &probe_indices, hash_table, &build_indices)?];
+
+        if hash_table.join_type().keep_left_unmatched() {
+            let mut matched = vec![false; block.num_rows()];
+            for row in &probe_indices {
+                matched[*row as usize] = true;
+            }
+
+            let unmatched = (0..block.num_rows() as u32)
+                .filter(|row| !matched[*row as usize])
+                .collect::<Vec<_>>();
+
+            if !unmatched.is_empty() {
+                let mut columns = DataBlock::block_take_by_indices(block, &[], &unmatched)?
+                    .columns()
+                    .to_vec();
+                columns.extend(Self::null_columns(&self.right_schema, unmatched.len())?);
+                blocks.push(DataBlock::create(self.schema.clone(), columns));
+            }
+        }
+
+        Ok(blocks)
+    }
+
+    /// The build rows which are not matched by any probe row, padded with NULLs on the left.
+    fn unmatched_build_block(&self, hash_table: &JoinHashTable) -> Result<DataBlock> {
+        let unmatched = hash_table.unmatched_rows();
+        if unmatched.is_empty() {
+            return Ok(DataBlock::empty_with_schema(self.schema.clone()));
+        }
+
+        let mut columns = Self::null_columns(&self.left_schema, unmatched.len())?;
+        columns.extend(
+            DataBlock::block_take_by_indices(hash_table.block(), &[], &unmatched)?
+                .columns()
+                .to_vec(),
+        );
+        Ok(DataBlock::create(self.schema.clone(), columns))
+    }
+
+    fn join_block(
+        &self,
+        block: &DataBlock,
+        probe_indices: &[u32],
+        hash_table: &JoinHashTable,
+        build_indices: &[u32],
+    ) -> Result<DataBlock> {
+        if probe_indices.is_empty() {
+            return Ok(DataBlock::empty_with_schema(self.schema.clone()));
+        }
+
+        let mut columns = DataBlock::block_take_by_indices(block, &[], probe_indices)?
+            .columns()
+            .to_vec();
+        columns.extend(
+            DataBlock::block_take_by_indices(hash_table.block(), &[], build_indices)?
+                .columns()
+                .to_vec(),
+        );
+        Ok(DataBlock::create(self.schema.clone(), columns))
+    }
+
+    fn null_columns(schema: &DataSchemaRef, rows: usize) -> Result<Vec<DataColumn>> {
+        schema
+            .fields()
+            .iter()
+            .map(|f| {
+                let nulls = DataValue::from(f.data_type()).to_series_with_size(rows)?;
+                Ok(DataColumn::Array(nulls.cast_with_type(f.data_type())?))
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for HashJoinProbeTransform {
+    fn name(&self) -> &str {
+        "HashJoinProbeTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![self.input.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        let build_future = self.join_builder.lock().take_hash_table()?;
+        let hash_table = match self.ctx.try_spawn(build_future)?.await {
+            Ok(hash_table) => hash_table?,
+            Err(error) => {
+                return Err(ErrorCode::TokioError(format!(
+                    "Cannot build the hash table of join. cause: {}",
+                    error
+                )));
+            }
+        };
+
+        let probe = self.probe.clone();
+        let probe_hash_table = hash_table.clone();
+        let input_stream = self.input.execute().await?;
+        let probe_stream = input_stream.flat_map(move |data_block| {
+            let blocks = match data_block {
+                Err(error) => vec![Err(error)],
+                Ok(data_block) => match probe.probe(&probe_hash_table, &data_block) {
+                    Ok(blocks) => blocks.into_iter().map(Ok).collect(),
+                    Err(error) => vec![Err(error)],
+                },
+            };
+            stream::iter(blocks)
+        });
+
+        let probe = self.probe.clone();
+        let unmatched_stream = stream::once(async move {
+            match hash_table.join_type().keep_right_unmatched() {
+                true => probe.unmatched_build_block(&hash_table),
+                false => Ok(DataBlock::empty_with_schema(probe.schema.clone())),
+            }
+        });
+
+        let stream = probe_stream.chain(unmatched_stream).filter(|data_block| {
+            futures::future::ready(!matches!(data_block, Ok(block) if block.is_empty()))
+        });
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use futures::TryStreamExt;
+
+use crate::pipelines::processors::*;
+use crate::sql::PlanParser;
+
+async fn execute_join_query(query: &str) -> Result<Vec<common_datablocks::DataBlock>> {
+    let ctx = crate::tests::try_create_context()?;
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(query)?;
+    let mut pipeline = PipelineBuilder::create(ctx).build(&plan)?;
+    let stream = pipeline.execute().await?;
+    stream.try_collect::<Vec<_>>().await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_hash_join() -> Result<()> {
+    struct Test {
+        name: &'static str,
+        query: &'static str,
+        expect: Vec<&'static str>,
+    }
+
+    let tests = vec![
+        Test {
+            name: "inner-join-passed",
+            query: "select * from numbers(4) as a join numbers(3) as b on a.number = b.number + 1",
+            expect: vec![
+                "+----------+----------+",
+                "| a.number | b.number |",
+                "+----------+----------+",
+                "| 1        | 0        |",
+                "| 2        | 1        |",
+                "| 3        | 2        |",
+                "+----------+----------+",
+            ],
+        },
+        Test {
+            name: "inner-join-with-filter-passed",
+            query: "select * from numbers(4) as a join numbers(3) as b on a.number = b.number + 1 and a.number > 1",
+            expect: vec![
+                "+----------+----------+",
+                "| a.number | b.number |",
+                "+----------+----------+",
+                "| 2        | 1        |",
+                "| 3        | 2        |",
+                "+----------+----------+",
+            ],
+        },
+        Test {
+            name: "left-join-passed",
+            query: "select * from numbers(3) as a left join numbers(2) as b on a.number = b.number",
+            expect: vec![
+                "+----------+----------+",
+                "| a.number | b.number |",
+                "+----------+----------+",
+                "| 0        | 0        |",
+                "| 1        | 1        |",
+                "| 2        | NULL     |",
+                "+----------+----------+",
+            ],
+        },
+        Test {
+            name: "right-join-passed",
+            query: "select * from numbers(2) as a right join numbers(3) as b on a.number = b.number",
+            expect: vec![
+                "+----------+----------+",
+                "| a.number | b.number |",
+                "+----------+----------+",
+                "| 0        | 0        |",
+                "| 1        | 1        |",
+                "| NULL     | 2        |",
+                "+----------+----------+",
+            ],
+        },
+        Test {
+            name: "full-join-passed",
+            query: "select * from numbers(3) as a full join numbers(3) as b on a.number = b.number + 1",
+            expect: vec![
+                "+----------+----------+",
+                "| a.number | b.number |",
+                "+----------+----------+",
+                "| 0        | NULL     |",
+                "| 1        | 0        |",
+                "| 2        | 1        |",
+                "| NULL     | 2        |",
+                "+----------+----------+",
+            ],
+        },
+        Test {
+            name: "cross-join-passed",
+            query: "select * from numbers(2) as a, numbers(2) as b",
+            expect: vec![
+                "+----------+----------+",
+                "| a.number | b.number |",
+                "+----------+----------+",
+                "| 0        | 0        |",
+                "| 0        | 1        |",
+                "| 1        | 0        |",
+                "| 1        | 1        |",
+                "+----------+----------+",
+            ],
+        },
+    ];
+
+    for test in tests {
+        let result = execute_join_query(test.query).await?;
+        common_datablocks::assert_blocks_sorted_eq_with_name(
+            test.name,
+            test.expect,
+            result.as_slice(),
+        );
+    }
+
+    Ok(())
+}
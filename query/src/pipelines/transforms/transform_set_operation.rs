@@ -0,0 +1,162 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datablocks::HashMethod;
+use common_datablocks::HashMethodSerializer;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_planners::SetOperator;
+use common_streams::SendableDataBlockStream;
+use futures::stream;
+use futures::StreamExt;
+
+use crate::pipelines::processors::Processor;
+
+/// Deduplicates(UNION), intersects(INTERSECT) or subtracts(EXCEPT) the rows of
+/// the two inputs by hashing. UNION ALL is a plain concatenation, not handled here.
+pub struct SetOperationTransform {
+    op: SetOperator,
+    schema: DataSchemaRef,
+    inputs: Vec<Arc<dyn Processor>>,
+}
+
+impl SetOperationTransform {
+    pub fn try_create(op: SetOperator, schema: DataSchemaRef) -> Result<SetOperationTransform> {
+        if op == SetOperator::UnionAll {
+            return Result::Err(ErrorCode::LogicalError(
+                "UNION ALL must not be executed by SetOperationTransform",
+            ));
+        }
+
+        Ok(SetOperationTransform {
+            op,
+            schema,
+            inputs: vec![],
+        })
+    }
+
+    /// Serialize every row of the block, NULL is distinguished from the default value.
+    fn build_row_keys(block: &DataBlock) -> Result<Vec<Vec<u8>>> {
+        let rows = block.num_rows();
+        let columns = block.columns().iter().collect::<Vec<_>>();
+        let method = HashMethodSerializer::default();
+        let mut keys = method.build_keys(&columns, rows)?;
+
+        for column in block.columns() {
+            let series = column.to_array()?;
+            for (row, key) in keys.iter_mut().enumerate() {
+                key.push(series.is_null(row) as u8);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Take the rows whose keys are selected, and remember them to avoid duplicates.
+    fn take_rows(
+        &self,
+        block: &DataBlock,
+        seen: &mut HashSet<Vec<u8>>,
+        select: impl Fn(&[u8]) -> bool,
+    ) -> Result<DataBlock> {
+        let mut indices = vec![];
+        for (row, key) in Self::build_row_keys(block)?.into_iter().enumerate() {
+            if select(&key) && seen.insert(key) {
+                indices.push(row as u32);
+            }
+        }
+
+        let taken = DataBlock::block_take_by_indices(block, &[], &indices)?;
+        Ok(DataBlock::create(
+            self.schema.clone(),
+            taken.columns().to_vec(),
+        ))
+    }
+
+    async fn collect_right_keys(&self) -> Result<HashSet<Vec<u8>>> {
+        let mut keys = HashSet::new();
+        let mut stream = self.inputs[1].execute().await?;
+        while let Some(block) = stream.next().await {
+            keys.extend(Self::build_row_keys(&block?)?);
+        }
+        Ok(keys)
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for SetOperationTransform {
+    fn name(&self) -> &str {
+        "SetOperationTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        if self.inputs.len() == 2 {
+            return Result::Err(ErrorCode::IllegalTransformConnectionState(
+                "SetOperationTransform can only connect to two inputs",
+            ));
+        }
+
+        self.inputs.push(input);
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        self.inputs.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        if self.inputs.len() != 2 {
+            return Result::Err(ErrorCode::IllegalTransformConnectionState(
+                "SetOperationTransform must connect to two inputs",
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        let mut blocks = vec![];
+        match self.op {
+            SetOperator::Union => {
+                for input in &self.inputs {
+                    let mut stream = input.execute().await?;
+                    while let Some(block) = stream.next().await {
+                        blocks.push(self.take_rows(&block?, &mut seen, |_| true)?);
+                    }
+                }
+            }
+            SetOperator::Intersect | SetOperator::Except => {
+                let right_keys = self.collect_right_keys().await?;
+                let intersect = self.op == SetOperator::Intersect;
+
+                let mut stream = self.inputs[0].execute().await?;
+                while let Some(block) = stream.next().await {
+                    blocks.push(self.take_rows(&block?, &mut seen, |key| {
+                        right_keys.contains(key) == intersect
+                    })?);
+                }
+            }
+            SetOperator::UnionAll => unreachable!(),
+        }
+
+        let blocks = blocks.into_iter().filter(|block| !block.is_empty()).map(Ok);
+        Ok(Box::pin(stream::iter(blocks.collect::<Vec<_>>())))
+    }
+}
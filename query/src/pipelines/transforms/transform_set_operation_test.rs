@@ -0,0 +1,92 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use futures::TryStreamExt;
+
+use crate::pipelines::processors::*;
+use crate::sql::PlanParser;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_set_operation() -> Result<()> {
+    struct Test {
+        name: &'static str,
+        query: &'static str,
+        expect: Vec<&'static str>,
+    }
+
+    let tests = vec![
+        Test {
+            name: "union-all-passed",
+            query: "select number from numbers(3) union all select number from numbers(2)",
+            expect: vec![
+                "+--------+",
+                "| number |",
+                "+--------+",
+                "| 0      |",
+                "| 0      |",
+                "| 1      |",
+                "| 1      |",
+                "| 2      |",
+                "+--------+",
+            ],
+        },
+        Test {
+            name: "union-passed",
+            query: "select number from numbers(3) union select number from numbers(2)",
+            expect: vec![
+                "+--------+",
+                "| number |",
+                "+--------+",
+                "| 0      |",
+                "| 1      |",
+                "| 2      |",
+                "+--------+",
+            ],
+        },
+        Test {
+            name: "intersect-passed",
+            query: "select number from numbers(5) intersect select number + 3 from numbers(5)",
+            expect: vec![
+                "+--------+",
+                "| number |",
+                "+--------+",
+                "| 3      |",
+                "| 4      |",
+                "+--------+",
+            ],
+        },
+        Test {
+            name: "except-passed",
+            query: "select number % 3 as n from numbers(10) except select 1",
+            expect: vec!["+---+", "| n |", "+---+", "| 0 |", "| 2 |", "+---+"],
+        },
+    ];
+
+    for test in tests {
+        let ctx = crate::tests::try_create_context()?;
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(test.query)?;
+        let mut pipeline = PipelineBuilder::create(ctx).build(&plan)?;
+        let stream = pipeline.execute().await?;
+        let result = stream.try_collect::<Vec<_>>().await?;
+        common_datablocks::assert_blocks_sorted_eq_with_name(
+            test.name,
+            test.expect,
+            result.as_slice(),
+        );
+    }
+
+    Ok(())
+}
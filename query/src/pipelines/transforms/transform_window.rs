@@ -0,0 +1,540 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datablocks::HashMethod;
+use common_datablocks::HashMethodSerializer;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_functions::aggregates::AggregateFunctionRef;
+use common_functions::aggregates::StateAddr;
+use common_planners::Expression;
+use common_planners::WindowFrame;
+use common_planners::WindowFrameBound;
+use common_planners::WindowFrameUnits;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+use futures::StreamExt;
+
+use crate::pipelines::processors::EmptyProcessor;
+use crate::pipelines::processors::Processor;
+
+enum WindowFuncKind {
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag { offset: usize, default: DataValue },
+    Lead { offset: usize, default: DataValue },
+    FirstValue,
+    LastValue,
+    Aggregate(AggregateFunctionRef),
+}
+
+struct WindowFunc {
+    column_name: String,
+    return_type: DataType,
+    kind: WindowFuncKind,
+    args: Vec<String>,
+    frame: WindowFrame,
+}
+
+/// Evaluates the window functions over the input, which must be sorted by the
+/// partition keys and then the order keys.
+/// All the window functions share the same PARTITION BY and ORDER BY.
+pub struct WindowTransform {
+    schema: DataSchemaRef,
+    funcs: Vec<WindowFunc>,
+    partition_by: Vec<String>,
+    /// The order keys and whether they are ascending
+    order_by: Vec<(String, bool)>,
+    input: Arc<dyn Processor>,
+}
+
+impl WindowTransform {
+    pub fn try_create(
+        schema: DataSchemaRef,
+        input_schema: DataSchemaRef,
+        window_funcs: Vec<Expression>,
+    ) -> Result<Self> {
+        let mut funcs = Vec::with_capacity(window_funcs.len());
+        let mut partition_by = vec![];
+        let mut order_by = vec![];
+
+        for expr in window_funcs.iter() {
+            let (op, params, args, partition_exprs, order_exprs, window_frame) = match expr {
+                Expression::WindowFunction {
+                    op,
+                    params,
+                    args,
+                    partition_by,
+                    order_by,
+                    window_frame,
+                } => (op, params, args, partition_by, order_by, window_frame),
+                _ => {
+                    return Result::Err(ErrorCode::BadTransformType(format!(
+                        "Window expression must be Expression::WindowFunction, but got: {:?}",
+                        expr
+                    )));
+                }
+            };
+
+            partition_by = partition_exprs.iter().map(|e| e.column_name()).collect();
+            order_by = order_exprs
+                .iter()
+                .map(|e| match e {
+                    Expression::Sort { expr, asc, .. } => Ok((expr.column_name(), *asc)),
+                    _ => Result::Err(ErrorCode::BadTransformType(format!(
+                        "Sort expression must be ExpressionPlan::Sort, but got: {:?}",
+                        e
+                    ))),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let return_type = expr.to_data_type(&input_schema)?;
+            let kind = match op.to_lowercase().as_str() {
+                "row_number" => WindowFuncKind::RowNumber,
+                "rank" => WindowFuncKind::Rank,
+                "dense_rank" => WindowFuncKind::DenseRank,
+                "lag" | "lead" => {
+                    let offset = match params.get(0) {
+                        Some(offset) => offset.as_u64()? as usize,
+                        None => 1,
+                    };
+                    let default = match params.get(1) {
+                        Some(value) if !value.is_null() => value
+                            .to_series_with_size(1)?
+                            .cast_with_type(&return_type)?
+                            .try_get(0)?,
+                        _ => DataValue::from(&return_type),
+                    };
+                    match op.to_lowercase().as_str() {
+                        "lag" => WindowFuncKind::Lag { offset, default },
+                        _ => WindowFuncKind::Lead { offset, default },
+                    }
+                }
+                "first_value" => WindowFuncKind::FirstValue,
+                "last_value" => WindowFuncKind::LastValue,
+                _ => WindowFuncKind::Aggregate(expr.to_aggregate_function(&input_schema)?),
+            };
+
+            funcs.push(WindowFunc {
+                column_name: expr.column_name(),
+                return_type,
+                kind,
+                args: args.iter().map(|e| e.column_name()).collect(),
+                frame: window_frame
+                    .unwrap_or_else(|| WindowFrame::default_frame(!order_exprs.is_empty())),
+            });
+        }
+
+        Ok(WindowTransform {
+            schema,
+            funcs,
+            partition_by,
+            order_by,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+
+    /// Serialize the given columns of every row, NULL is distinguished from the default value.
+    fn build_row_keys(block: &DataBlock, names: &[String]) -> Result<Vec<Vec<u8>>> {
+        let rows = block.num_rows();
+        if names.is_empty() {
+            return Ok(vec![vec![]; rows]);
+        }
+
+        let columns = names
+            .iter()
+            .map(|name| block.try_column_by_name(name))
+            .collect::<Result<Vec<_>>>()?;
+        let method = HashMethodSerializer::default();
+        let mut keys = method.build_keys(&columns, rows)?;
+
+        for column in columns {
+            let series = column.to_array()?;
+            for (row, key) in keys.iter_mut().enumerate() {
+                key.push(series.is_null(row) as u8);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// The [start, end) of every partition.
+    fn partition_ranges(&self, block: &DataBlock) -> Result<Vec<(usize, usize)>> {
+        let keys = Self::build_row_keys(block, &self.partition_by)?;
+        let mut ranges = vec![];
+        let mut start = 0;
+        for row in 1..=keys.len() {
+            if row == keys.len() || keys[row] != keys[start] {
+                ranges.push((start, row));
+                start = row;
+            }
+        }
+        Ok(ranges)
+    }
+
+    /// The [start, end) of the peers of every row, peers are the rows of the same
+    /// partition with the same order keys.
+    fn peer_ranges(
+        &self,
+        block: &DataBlock,
+        partitions: &[(usize, usize)],
+    ) -> Result<Vec<(usize, usize)>> {
+        let names = self
+            .order_by
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        let keys = Self::build_row_keys(block, &names)?;
+
+        let mut peers = vec![(0, 0); keys.len()];
+        for &(partition_start, partition_end) in partitions {
+            let mut start = partition_start;
+            for row in partition_start + 1..=partition_end {
+                if row == partition_end || keys[row] != keys[start] {
+                    for peer in peers.iter_mut().take(row).skip(start) {
+                        *peer = (start, row);
+                    }
+                    start = row;
+                }
+            }
+        }
+        Ok(peers)
+    }
+
+    /// The order key as Float64 for the RANGE frames with offset, it is negated
+    /// if the order is descending so that the keys are always ascending in a partition.
+    fn range_keys(&self, block: &DataBlock) -> Result<Vec<Option<f64>>> {
+        let (name, asc) = match self.order_by.as_slice() {
+            [order_by] => order_by,
+            _ => {
+                return Result::Err(ErrorCode::BadArguments(
+                    "RANGE frame with offset requires exactly one ORDER BY expression",
+                ));
+            }
+        };
+
+        let series = block
+            .try_column_by_name(name)?
+            .to_array()?
+            .cast_with_type(&DataType::Float64)?;
+        (0..series.len())
+            .map(|row| match series.try_get(row)? {
+                DataValue::Float64(Some(v)) if *asc => Ok(Some(v)),
+                DataValue::Float64(Some(v)) => Ok(Some(-v)),
+                _ => Ok(None),
+            })
+            .collect()
+    }
+
+    /// The frame [start, end) of the row.
+    fn frame_range(
+        frame: &WindowFrame,
+        row: usize,
+        partition: (usize, usize),
+        peers: &[(usize, usize)],
+        range_keys: &[Option<f64>],
+    ) -> (usize, usize) {
+        let (partition_start, partition_end) = partition;
+        let (peer_start, peer_end) = peers[row];
+
+        let start = match (frame.units, frame.start_bound) {
+            (_, WindowFrameBound::Preceding(None)) => partition_start,
+            (_, WindowFrameBound::Following(None)) => partition_end,
+            (WindowFrameUnits::Rows, WindowFrameBound::Preceding(Some(n))) => {
+                row.saturating_sub(n as usize).max(partition_start)
+            }
+            (WindowFrameUnits::Rows, WindowFrameBound::CurrentRow) => row,
+            (WindowFrameUnits::Rows, WindowFrameBound::Following(Some(n))) => {
+                (row + n as usize).min(partition_end)
+            }
+            (WindowFrameUnits::Range, WindowFrameBound::CurrentRow) => peer_start,
+            (WindowFrameUnits::Range, WindowFrameBound::Preceding(Some(n))) => {
+                Self::range_offset(partition, peers, range_keys, row, -(n as f64), false)
+            }
+            (WindowFrameUnits::Range, WindowFrameBound::Following(Some(n))) => {
+                Self::range_offset(partition, peers, range_keys, row, n as f64, false)
+            }
+        };
+
+        let end = match (frame.units, frame.end_bound) {
+            (_, WindowFrameBound::Preceding(None)) => partition_start,
+            (_, WindowFrameBound::Following(None)) => partition_end,
+            (WindowFrameUnits::Rows, WindowFrameBound::Preceding(Some(n))) => {
+                (row + 1).saturating_sub(n as usize).max(partition_start)
+            }
+            (WindowFrameUnits::Rows, WindowFrameBound::CurrentRow) => row + 1,
+            (WindowFrameUnits::Rows, WindowFrameBound::Following(Some(n))) => {
+                (row + 1 + n as usize).min(partition_end)
+            }
+            (WindowFrameUnits::Range, WindowFrameBound::CurrentRow) => peer_end,
+            (WindowFrameUnits::Range, WindowFrameBound::Preceding(Some(n))) => {
+                Self::range_offset(partition, peers, range_keys, row, -(n as f64), true)
+            }
+            (WindowFrameUnits::Range, WindowFrameBound::Following(Some(n))) => {
+                Self::range_offset(partition, peers, range_keys, row, n as f64, true)
+            }
+        };
+
+        (start, end.max(start))
+    }
+
+    /// The first row of the partition whose key is not less than (or greater than, if
+    /// `after` is true) the key of the row plus the delta.
+    /// The NULL keys are peers of each other and only match themselves.
+    fn range_offset(
+        partition: (usize, usize),
+        peers: &[(usize, usize)],
+        range_keys: &[Option<f64>],
+        row: usize,
+        delta: f64,
+        after: bool,
+    ) -> usize {
+        let target = match range_keys[row] {
+            Some(key) => key + delta,
+            None => {
+                let (peer_start, peer_end) = peers[row];
+                return if after { peer_end } else { peer_start };
+            }
+        };
+
+        // NULLs are either at the head or the tail of the partition.
+        let (partition_start, partition_end) = partition;
+        let keys = &range_keys[partition_start..partition_end];
+        let non_null_start = keys.iter().position(|k| k.is_some()).unwrap_or(0);
+        let non_null_end = keys.iter().rposition(|k| k.is_some()).map_or(0, |p| p + 1);
+        let non_null_keys = &keys[non_null_start..non_null_end];
+
+        let offset = non_null_keys.partition_point(|key| {
+            let key = key.unwrap_or_default();
+            if after {
+                key <= target
+            } else {
+                key < target
+            }
+        });
+        partition_start + non_null_start + offset
+    }
+
+    fn evaluate(
+        func: &WindowFunc,
+        block: &DataBlock,
+        partitions: &[(usize, usize)],
+        peers: &[(usize, usize)],
+        range_keys: &[Option<f64>],
+    ) -> Result<Vec<DataValue>> {
+        let arrays = func
+            .args
+            .iter()
+            .map(|name| block.try_column_by_name(name)?.to_array())
+            .collect::<Result<Vec<_>>>()?;
+        let null = DataValue::from(&func.return_type);
+
+        let mut values = Vec::with_capacity(block.num_rows());
+        for &partition in partitions {
+            let (partition_start, partition_end) = partition;
+            match &func.kind {
+                WindowFuncKind::RowNumber => {
+                    for row in partition_start..partition_end {
+                        values.push(DataValue::UInt64(Some((row - partition_start + 1) as u64)));
+                    }
+                }
+                WindowFuncKind::Rank => {
+                    for &(peer_start, _) in &peers[partition_start..partition_end] {
+                        values.push(DataValue::UInt64(Some(
+                            (peer_start - partition_start + 1) as u64,
+                        )));
+                    }
+                }
+                WindowFuncKind::DenseRank => {
+                    let mut rank = 0;
+                    for row in partition_start..partition_end {
+                        if peers[row].0 == row {
+                            rank += 1;
+                        }
+                        values.push(DataValue::UInt64(Some(rank)));
+                    }
+                }
+                WindowFuncKind::Lag { offset, default } => {
+                    for row in partition_start..partition_end {
+                        match row >= partition_start + offset {
+                            true => values.push(arrays[0].try_get(row - offset)?),
+                            false => values.push(default.clone()),
+                        }
+                    }
+                }
+                WindowFuncKind::Lead { offset, default } => {
+                    for row in partition_start..partition_end {
+                        match row + offset < partition_end {
+                            true => values.push(arrays[0].try_get(row + offset)?),
+                            false => values.push(default.clone()),
+                        }
+                    }
+                }
+                WindowFuncKind::FirstValue | WindowFuncKind::LastValue => {
+                    for row in partition_start..partition_end {
+                        let (start, end) =
+                            Self::frame_range(&func.frame, row, partition, peers, range_keys);
+                        let value = match (&func.kind, start < end) {
+                            (_, false) => null.clone(),
+                            (WindowFuncKind::FirstValue, true) => arrays[0].try_get(start)?,
+                            (_, true) => arrays[0].try_get(end - 1)?,
+                        };
+                        values.push(value);
+                    }
+                }
+                WindowFuncKind::Aggregate(agg) => {
+                    let arena = bumpalo::Bump::new();
+                    let accumulate = |place: StateAddr, start: usize, end: usize| {
+                        let arrays = arrays
+                            .iter()
+                            .map(|array| array.slice(start, end - start))
+                            .collect::<Vec<_>>();
+                        agg.accumulate(place, &arrays, end - start)
+                    };
+
+                    // The frames only grow if they start from the partition start,
+                    // so that one state is accumulated incrementally.
+                    let incremental = func.frame.start_bound == WindowFrameBound::Preceding(None);
+                    let mut place: StateAddr = arena.alloc_layout(agg.state_layout()).into();
+                    agg.init_state(place);
+                    let mut accumulated_end = partition_start;
+
+                    for row in partition_start..partition_end {
+                        let (start, end) =
+                            Self::frame_range(&func.frame, row, partition, peers, range_keys);
+                        if incremental {
+                            if end > accumulated_end {
+                                accumulate(place, accumulated_end, end)?;
+                                accumulated_end = end;
+                            }
+                        } else {
+                            place = arena.alloc_layout(agg.state_layout()).into();
+                            agg.init_state(place);
+                            if end > start {
+                                accumulate(place, start, end)?;
+                            }
+                        }
+                        values.push(agg.merge_result(place)?);
+                    }
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    fn build_series(values: &[DataValue], data_type: &DataType) -> Result<Series> {
+        let values = values
+            .iter()
+            .map(|value| match value {
+                v if v.is_null() => Ok(DataValue::from(data_type)),
+                v if &v.data_type() == data_type => Ok(v.clone()),
+                v => v
+                    .to_series_with_size(1)?
+                    .cast_with_type(data_type)?
+                    .try_get(0),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        DataValue::try_into_data_array(&values, data_type)
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for WindowTransform {
+    fn name(&self) -> &str {
+        "WindowTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![self.input.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        tracing::debug!("execute...");
+
+        let mut stream = self.input.execute().await?;
+        let mut blocks = vec![];
+        while let Some(block) = stream.next().await {
+            let block = block?;
+            if !block.is_empty() {
+                blocks.push(block);
+            }
+        }
+
+        if blocks.is_empty() {
+            return Ok(Box::pin(DataBlockStream::create(
+                self.schema.clone(),
+                None,
+                vec![],
+            )));
+        }
+
+        let block = DataBlock::concat_blocks(&blocks)?;
+        let partitions = self.partition_ranges(&block)?;
+        let peers = self.peer_ranges(&block, &partitions)?;
+        let range_keys = match self.funcs.iter().any(|func| {
+            func.frame.units == WindowFrameUnits::Range
+                && [func.frame.start_bound, func.frame.end_bound]
+                    .iter()
+                    .any(|bound| {
+                        matches!(
+                            bound,
+                            WindowFrameBound::Preceding(Some(_))
+                                | WindowFrameBound::Following(Some(_))
+                        )
+                    })
+        }) {
+            true => self.range_keys(&block)?,
+            false => vec![],
+        };
+
+        let mut results = HashMap::with_capacity(self.funcs.len());
+        for func in self.funcs.iter() {
+            let values = Self::evaluate(func, &block, &partitions, &peers, &range_keys)?;
+            let series = Self::build_series(&values, &func.return_type)?;
+            results.insert(func.column_name.clone(), series);
+        }
+
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| match results.get(field.name()) {
+                Some(series) => Ok(series.clone()),
+                None => block.try_column_by_name(field.name())?.to_array(),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Box::pin(DataBlockStream::create(
+            self.schema.clone(),
+            None,
+            vec![DataBlock::create_by_array(self.schema.clone(), columns)],
+        )))
+    }
+}
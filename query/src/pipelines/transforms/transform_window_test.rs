@@ -0,0 +1,140 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use futures::TryStreamExt;
+
+use crate::pipelines::processors::*;
+use crate::sql::PlanParser;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_window() -> Result<()> {
+    struct Test {
+        name: &'static str,
+        query: &'static str,
+        expect: Vec<&'static str>,
+    }
+
+    let tests = vec![
+        Test {
+            name: "row-number-passed",
+            query: "select number % 2 as p, number, row_number() over (partition by number % 2 order by number) as rn from numbers(6)",
+            expect: vec![
+                "+---+--------+----+",
+                "| p | number | rn |",
+                "+---+--------+----+",
+                "| 0 | 0      | 1  |",
+                "| 0 | 2      | 2  |",
+                "| 0 | 4      | 3  |",
+                "| 1 | 1      | 1  |",
+                "| 1 | 3      | 2  |",
+                "| 1 | 5      | 3  |",
+                "+---+--------+----+",
+            ],
+        },
+        Test {
+            name: "rank-passed",
+            query: "select number % 3 as n, rank() over (order by number % 3) as r, dense_rank() over (order by number % 3) as d from numbers(6)",
+            expect: vec![
+                "+---+---+---+",
+                "| n | r | d |",
+                "+---+---+---+",
+                "| 0 | 1 | 1 |",
+                "| 0 | 1 | 1 |",
+                "| 1 | 3 | 2 |",
+                "| 1 | 3 | 2 |",
+                "| 2 | 5 | 3 |",
+                "| 2 | 5 | 3 |",
+                "+---+---+---+",
+            ],
+        },
+        Test {
+            name: "lag-lead-passed",
+            query: "select number, lag(number) over (order by number) as lg, lead(number, 2, 0) over (order by number) as ld from numbers(5)",
+            expect: vec![
+                "+--------+------+----+",
+                "| number | lg   | ld |",
+                "+--------+------+----+",
+                "| 0      | NULL | 2  |",
+                "| 1      | 0    | 3  |",
+                "| 2      | 1    | 4  |",
+                "| 3      | 2    | 0  |",
+                "| 4      | 3    | 0  |",
+                "+--------+------+----+",
+            ],
+        },
+        Test {
+            name: "first-last-value-passed",
+            query: "select number, first_value(number) over (order by number rows between 1 preceding and 1 following) as f, last_value(number) over (order by number rows between 1 preceding and 1 following) as l from numbers(5)",
+            expect: vec![
+                "+--------+---+---+",
+                "| number | f | l |",
+                "+--------+---+---+",
+                "| 0      | 0 | 1 |",
+                "| 1      | 0 | 2 |",
+                "| 2      | 1 | 3 |",
+                "| 3      | 2 | 4 |",
+                "| 4      | 3 | 4 |",
+                "+--------+---+---+",
+            ],
+        },
+        Test {
+            name: "aggregate-rows-passed",
+            query: "select number, sum(number) over (order by number) as s1, sum(number) over (order by number rows between 1 preceding and current row) as s2, sum(number) over () as s3 from numbers(5)",
+            expect: vec![
+                "+--------+----+----+----+",
+                "| number | s1 | s2 | s3 |",
+                "+--------+----+----+----+",
+                "| 0      | 0  | 0  | 10 |",
+                "| 1      | 1  | 1  | 10 |",
+                "| 2      | 3  | 3  | 10 |",
+                "| 3      | 6  | 5  | 10 |",
+                "| 4      | 10 | 7  | 10 |",
+                "+--------+----+----+----+",
+            ],
+        },
+        Test {
+            name: "aggregate-range-passed",
+            query: "select number % 3 as n, sum(number % 3) over (order by number % 3 range between 1 preceding and current row) as s from numbers(6)",
+            expect: vec![
+                "+---+---+",
+                "| n | s |",
+                "+---+---+",
+                "| 0 | 0 |",
+                "| 0 | 0 |",
+                "| 1 | 2 |",
+                "| 1 | 2 |",
+                "| 2 | 6 |",
+                "| 2 | 6 |",
+                "+---+---+",
+            ],
+        },
+    ];
+
+    for test in tests {
+        let ctx = crate::tests::try_create_context()?;
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(test.query)?;
+        let mut pipeline = PipelineBuilder::create(ctx).build(&plan)?;
+        let stream = pipeline.execute().await?;
+        let result = stream.try_collect::<Vec<_>>().await?;
+        common_datablocks::assert_blocks_sorted_eq_with_name(
+            test.name,
+            test.expect,
+            result.as_slice(),
+        );
+    }
+
+    Ok(())
+}
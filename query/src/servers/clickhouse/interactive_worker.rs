@@ -105,7 +105,13 @@ impl ClickHouseSession for InteractiveWorker {
         futures::executor::block_on(async move {
             // TODO: use get_users and check client address
             let res = match user_manager.get_user(user, "%").await {
-                Ok(user_info) => user_manager.auth_user(user_info, info).await,
+                Ok(user_info) => {
+                    let authed = user_manager.auth_user(user_info.clone(), info).await;
+                    if let Ok(true) = authed {
+                        self.session.set_current_user(user_info);
+                    }
+                    authed
+                }
                 Err(err) => Err(err),
             };
             match res {
@@ -0,0 +1,339 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_management::UserInfo;
+use common_planners::PlanNode;
+use futures::StreamExt;
+use headers::authorization::Basic;
+use headers::Authorization;
+use headers::HeaderMapExt;
+use poem::http::header;
+use poem::http::HeaderMap;
+use poem::http::Method;
+use poem::http::StatusCode;
+use poem::web::Data;
+use poem::web::Query;
+use poem::Body;
+use poem::Endpoint;
+use poem::EndpointExt;
+use poem::Request;
+use poem::Response;
+use poem::Route;
+
+use super::output_format::OutputFormat;
+use super::output_format::OutputWriter;
+use crate::common::service::HttpShutdownHandler;
+use crate::interpreters::InterpreterFactory;
+use crate::servers::http::v1::insert_from_body;
+use crate::servers::Server;
+use crate::sessions::SessionManagerRef;
+use crate::sql::PlanParser;
+use crate::users::CertifiedInfo;
+
+const DEFAULT_FORMAT: OutputFormat = OutputFormat::TabSeparated;
+
+const USER_HEADER: &str = "X-ClickHouse-User";
+const KEY_HEADER: &str = "X-ClickHouse-Key";
+const DATABASE_HEADER: &str = "X-ClickHouse-Database";
+const FORMAT_HEADER: &str = "X-ClickHouse-Format";
+const EXCEPTION_CODE_HEADER: &str = "X-ClickHouse-Exception-Code";
+
+/// Serves the ClickHouse HTTP protocol, for the tools which do not speak the native protocol.
+pub struct ClickHouseHttpHandler {
+    session_manager: SessionManagerRef,
+    shutdown_handler: HttpShutdownHandler,
+}
+
+impl ClickHouseHttpHandler {
+    pub fn create(session_manager: SessionManagerRef) -> Box<dyn Server> {
+        Box::new(ClickHouseHttpHandler {
+            session_manager,
+            shutdown_handler: HttpShutdownHandler::create("clickhouse http handler".to_string()),
+        })
+    }
+
+    fn build_router(&self) -> impl Endpoint {
+        clickhouse_router()
+            .data(self.session_manager.clone())
+            .boxed()
+    }
+}
+
+#[async_trait::async_trait]
+impl Server for ClickHouseHttpHandler {
+    async fn shutdown(&mut self, graceful: bool) {
+        self.shutdown_handler.shutdown(graceful).await;
+    }
+
+    async fn start(&mut self, listening: SocketAddr) -> Result<SocketAddr> {
+        let addr = self
+            .shutdown_handler
+            .start_service(listening, None, self.build_router())
+            .await?;
+        Ok(addr)
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+/// The credentials are taken from the `user` and `password` params, the `X-ClickHouse-User`
+/// and `X-ClickHouse-Key` headers or the HTTP Basic authorization, in this order, the requests
+/// without credentials are rejected.
+async fn authenticate(
+    session_manager: &SessionManagerRef,
+    params: &mut HashMap<String, String>,
+    req: &Request,
+) -> Result<UserInfo> {
+    let headers = req.headers();
+    let basic = headers.typed_get::<Authorization<Basic>>();
+
+    let (user_name, password) = match params.remove("user") {
+        Some(user_name) => (user_name, params.remove("password").unwrap_or_default()),
+        None => match header_value(headers, USER_HEADER) {
+            Some(user_name) => (
+                user_name,
+                header_value(headers, KEY_HEADER).unwrap_or_default(),
+            ),
+            None => match basic {
+                Some(Authorization(basic)) => {
+                    (basic.username().to_string(), basic.password().to_string())
+                }
+                None => {
+                    return Err(ErrorCode::AuthenticateFailure(
+                        "Missing credentials, expect the user param, the X-ClickHouse-User header or the Basic authorization",
+                    ));
+                }
+            },
+        },
+    };
+
+    // the users are looked up by the IP of the client, empty if not connected by TCP
+    let client_ip = match req.remote_addr().as_socket_addr() {
+        Some(addr) => addr.ip().to_string(),
+        None => String::new(),
+    };
+    let info = CertifiedInfo::create(&user_name, password, &client_ip);
+
+    let user_manager = session_manager.get_user_manager();
+    let user = user_manager
+        .get_user_by_address(&user_name, &client_ip)
+        .await?;
+    match user_manager.auth_user(user.clone(), info).await? {
+        true => Ok(user),
+        false => Err(ErrorCode::AuthenticateFailure(format!(
+            "Authenticate failed for user {}",
+            user_name
+        ))),
+    }
+}
+
+/// Splits the trailing `FORMAT <name>` clause off the query.
+fn split_format(query: &str) -> (&str, Option<&str>) {
+    let query = query.trim().trim_end_matches(';').trim_end();
+    if let Some((rest, name)) = query.rsplit_once(char::is_whitespace) {
+        if let Some((rest, keyword)) = rest.trim_end().rsplit_once(char::is_whitespace) {
+            if keyword.eq_ignore_ascii_case("FORMAT")
+                && name.chars().all(|c| c.is_ascii_alphanumeric())
+            {
+                return (rest.trim_end(), Some(name));
+            }
+        }
+    }
+    (query, None)
+}
+
+/// Whether the query is an `INSERT ... FORMAT <format>`, whose rows are the request body.
+fn is_insert_with_format(query: &str) -> bool {
+    let (query, format) = split_format(query);
+    let insert = match query.split_whitespace().next() {
+        Some(keyword) => keyword.eq_ignore_ascii_case("INSERT"),
+        None => false,
+    };
+    insert && format.is_some()
+}
+
+/// The statements allowed in the GET requests, which are read-only like in ClickHouse.
+fn is_read_only(plan: &PlanNode) -> bool {
+    matches!(
+        plan,
+        PlanNode::Select(_)
+            | PlanNode::Explain(_)
+            | PlanNode::DescribeTable(_)
+            | PlanNode::ShowCreateTable(_)
+    )
+}
+
+async fn body_string(body: Body) -> Result<String> {
+    body.into_string()
+        .await
+        .map_err(|cause| ErrorCode::BadArguments(format!("Invalid request body: {}", cause)))
+}
+
+async fn execute(
+    session_manager: &SessionManagerRef,
+    user: UserInfo,
+    req: &Request,
+    mut params: HashMap<String, String>,
+    body: Body,
+) -> Result<(OutputFormat, Vec<u8>)> {
+    // The body follows the query param, like the data of an INSERT, the rows of an
+    // `INSERT ... FORMAT <format>` are streamed into the table instead, they follow the
+    // first line of the body without the query param.
+    let (query, rows) = match params.remove("query") {
+        Some(query) if is_insert_with_format(&query) => (query, Some(body)),
+        Some(query) => match body_string(body).await? {
+            body if body.trim().is_empty() => (query, None),
+            body => (format!("{}\n{}", query, body), None),
+        },
+        None => {
+            let body = body_string(body).await?;
+            match body.split_once('\n') {
+                Some((query, rows)) if is_insert_with_format(query) => {
+                    (query.to_string(), Some(Body::from_string(rows.to_string())))
+                }
+                _ => (body, None),
+            }
+        }
+    };
+    if query.trim().is_empty() {
+        return Err(ErrorCode::SyntaxException(
+            "Empty query, expect the query param or the request body",
+        ));
+    }
+
+    // The GET requests are read-only, like the `readonly` setting of ClickHouse.
+    let read_only = req.method() == Method::GET;
+    if read_only && rows.is_some() {
+        return Err(ErrorCode::ReadOnlyMode(
+            "Cannot execute INSERT in a GET request, which is read-only",
+        ));
+    }
+
+    let session = session_manager.create_session("ClickHouseHttp")?;
+    session.set_current_user(user);
+    let ctx = session.create_context().await?;
+
+    let database = params
+        .remove("database")
+        .or_else(|| header_value(req.headers(), DATABASE_HEADER));
+    if let Some(database) = database {
+        ctx.set_current_database(database)?;
+    }
+
+    let default_format = params
+        .remove("default_format")
+        .or_else(|| header_value(req.headers(), FORMAT_HEADER));
+
+    // The remaining params are settings, the ones unknown here are of ClickHouse only.
+    for (name, value) in params {
+        if let Err(cause) = ctx.get_settings().update_settings(&name, value) {
+            if cause.code() != ErrorCode::UnknownVariable("").code() {
+                return Err(cause);
+            }
+        }
+    }
+
+    // The FORMAT of an insert is of its rows, nothing is written in the output.
+    if let Some(rows) = rows {
+        let format = match default_format {
+            Some(name) => OutputFormat::try_from_name(&name)?,
+            None => DEFAULT_FORMAT,
+        };
+        insert_from_body(ctx, &query, rows).await?;
+        return Ok((format, vec![]));
+    }
+
+    let (query, format) = split_format(&query);
+    let format = match format.map(|v| v.to_string()).or(default_format) {
+        Some(name) => OutputFormat::try_from_name(&name)?,
+        None => DEFAULT_FORMAT,
+    };
+
+    let start = Instant::now();
+    ctx.attach_query_str(query);
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(query)?;
+    if read_only && !is_read_only(&plan) {
+        return Err(ErrorCode::ReadOnlyMode(format!(
+            "Cannot execute {} in a GET request, which is read-only",
+            plan.name()
+        )));
+    }
+    let schema = plan.schema();
+    let interpreter = InterpreterFactory::get(ctx.clone(), plan)?;
+    let mut stream = interpreter.execute(None).await?;
+
+    // The output is buffered, the failures in the middle of the stream are reported with
+    // an error status instead of truncated results.
+    let mut writer = None;
+    while let Some(block) = stream.next().await {
+        let block = block?;
+        writer
+            .get_or_insert_with(|| OutputWriter::create(format, block.schema().clone()))
+            .write_block(&block)?;
+    }
+    let writer = writer.unwrap_or_else(|| OutputWriter::create(format, schema));
+    let output = writer.finish(&ctx.get_and_reset_progress_value(), start.elapsed());
+    Ok((format, output))
+}
+
+fn error_response(status: StatusCode, cause: ErrorCode) -> Response {
+    Response::builder()
+        .status(status)
+        .header(EXCEPTION_CODE_HEADER, cause.code().to_string())
+        .body(format!("{}\n", cause))
+}
+
+#[poem::handler]
+pub async fn clickhouse_handler(
+    req: &Request,
+    sessions_extension: Data<&SessionManagerRef>,
+    Query(mut params): Query<HashMap<String, String>>,
+    body: Body,
+) -> Response {
+    let session_manager = sessions_extension.0;
+    let user = match authenticate(session_manager, &mut params, req).await {
+        Ok(user) => user,
+        Err(cause) => {
+            log::warn!(
+                "ClickHouse HTTP handler authenticate failed, cause: {}",
+                cause
+            );
+            return error_response(StatusCode::UNAUTHORIZED, cause);
+        }
+    };
+
+    match execute(session_manager, user, req, params, body).await {
+        Ok((format, output)) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, format.content_type())
+            .header(FORMAT_HEADER, format.name())
+            .body(output),
+        Err(cause) => error_response(StatusCode::INTERNAL_SERVER_ERROR, cause),
+    }
+}
+
+pub fn clickhouse_router() -> Route {
+    Route::new().at("/", poem::get(clickhouse_handler).post(clickhouse_handler))
+}
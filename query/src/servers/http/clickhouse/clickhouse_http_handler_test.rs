@@ -0,0 +1,191 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use common_meta_types::AuthType;
+use poem::http::header;
+use poem::http::Method;
+use poem::http::StatusCode;
+use poem::Endpoint;
+use poem::EndpointExt;
+use poem::Request;
+use poem::Response;
+use pretty_assertions::assert_eq;
+
+use super::clickhouse_http_handler::clickhouse_router;
+use crate::tests::SessionManagerBuilder;
+use crate::users::User;
+
+fn encode(v: &str) -> String {
+    v.bytes()
+        .map(|b| match b.is_ascii_alphanumeric() {
+            true => (b as char).to_string(),
+            false => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+async fn body_string(response: Response) -> String {
+    response.into_body().into_string().await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_clickhouse_http_handler() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let user = User::new("ch-user", "%", "ch-pwd", AuthType::PlainText);
+    sessions.get_user_manager().add_user(user.into()).await?;
+
+    let route = clickhouse_router().data(sessions);
+    let request = |method: Method, params: &[(&str, &str)]| {
+        let query = params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, encode(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+        Request::builder()
+            .uri(format!("/?{}", query).parse().unwrap())
+            .method(method)
+            // root without password, unless the user param is given
+            .header(header::AUTHORIZATION, "Basic cm9vdDo=")
+    };
+    let get = |params: &[(&str, &str)]| route.call(request(Method::GET, params).finish());
+    let post = |params: &[(&str, &str)], body: &'static str| {
+        route.call(request(Method::POST, params).body(body))
+    };
+
+    // query param, TabSeparated by default
+    let response = get(&[("query", "SELECT number FROM numbers(3)")]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("X-ClickHouse-Format").unwrap(),
+        "TabSeparated"
+    );
+    assert_eq!(body_string(response).await, "0\n1\n2\n");
+
+    // body with the FORMAT clause
+    let response = post(
+        &[],
+        "SELECT number, number + 1 AS n FROM numbers(2) FORMAT JSONEachRow",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/json; charset=UTF-8"
+    );
+    assert_eq!(
+        body_string(response).await,
+        "{\"number\": 0, \"n\": 1}\n{\"number\": 1, \"n\": 2}\n"
+    );
+
+    // default_format
+    let response = get(&[
+        ("query", "SELECT number AS a FROM numbers(2)"),
+        ("default_format", "TabSeparatedWithNames"),
+    ])
+    .await;
+    assert_eq!(body_string(response).await, "a\n0\n1\n");
+
+    // database and settings
+    let response = get(&[("query", "SELECT database()"), ("database", "system")]).await;
+    assert_eq!(body_string(response).await, "system\n");
+
+    let response = get(&[
+        (
+            "query",
+            "SELECT value FROM system.settings WHERE name = 'max_threads'",
+        ),
+        ("max_threads", "3"),
+        ("output_format_json_quote_64bit_integers", "0"),
+    ])
+    .await;
+    assert_eq!(body_string(response).await, "3\n");
+
+    // user and password
+    let response = get(&[
+        ("query", "SELECT 1"),
+        ("user", "ch-user"),
+        ("password", "ch-pwd"),
+    ])
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = get(&[
+        ("query", "SELECT 1"),
+        ("user", "ch-user"),
+        ("password", "bad-pwd"),
+    ])
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // errors
+    let response = get(&[("query", "SELECT 1 FORMAT Pretty")]).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response
+        .headers()
+        .contains_key("X-ClickHouse-Exception-Code"));
+
+    let response = get(&[("query", "SELECT * FROM system.no_such_table")]).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(body_string(response).await.starts_with("Code: "));
+
+    let response = post(&[], "").await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let request = Request::builder()
+        .uri(format!("/?query={}", encode("SELECT 1")).parse().unwrap())
+        .finish();
+    let response = route.call(request).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // inserts, the rows in the formats of the streaming load
+    let response = post(
+        &[],
+        "CREATE TABLE default.ch_t(a Int32, b String) Engine = Memory",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = post(
+        &[("query", "INSERT INTO default.ch_t FORMAT CSV")],
+        "1,a\n2,b\n",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = post(&[], "INSERT INTO default.ch_t FORMAT TabSeparated\n3\tc\n").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = post(&[("query", "INSERT INTO default.ch_t VALUES")], "(4, 'd')").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = get(&[("query", "SELECT a, b FROM default.ch_t ORDER BY a")]).await;
+    assert_eq!(body_string(response).await, "1\ta\n2\tb\n3\tc\n4\td\n");
+
+    // the GET requests are read-only
+    let response = get(&[("query", "INSERT INTO default.ch_t VALUES (5, 'e')")]).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let response = get(&[("query", "INSERT INTO default.ch_t FORMAT CSV")]).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let response = get(&[("query", "DROP TABLE default.ch_t")]).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let response = get(&[("query", "SELECT count() FROM default.ch_t")]).await;
+    assert_eq!(body_string(response).await, "4\n");
+
+    Ok(())
+}
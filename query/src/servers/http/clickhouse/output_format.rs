@@ -0,0 +1,233 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_base::ProgressValues;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use serde_json::Value as JsonValue;
+
+use crate::servers::http::v1::block_to_json;
+
+/// The ClickHouse formats of the query results.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    TabSeparated,
+    TabSeparatedWithNames,
+    Csv,
+    CsvWithNames,
+    JsonEachRow,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn try_from_name(name: &str) -> Result<OutputFormat> {
+        match name.to_ascii_uppercase().as_str() {
+            "TABSEPARATED" | "TSV" => Ok(OutputFormat::TabSeparated),
+            "TABSEPARATEDWITHNAMES" | "TSVWITHNAMES" => Ok(OutputFormat::TabSeparatedWithNames),
+            "CSV" => Ok(OutputFormat::Csv),
+            "CSVWITHNAMES" => Ok(OutputFormat::CsvWithNames),
+            "JSONEACHROW" => Ok(OutputFormat::JsonEachRow),
+            "JSON" => Ok(OutputFormat::Json),
+            _ => Err(ErrorCode::BadOption(format!("Unknown format {}", name))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::TabSeparated => "TabSeparated",
+            OutputFormat::TabSeparatedWithNames => "TabSeparatedWithNames",
+            OutputFormat::Csv => "CSV",
+            OutputFormat::CsvWithNames => "CSVWithNames",
+            OutputFormat::JsonEachRow => "JSONEachRow",
+            OutputFormat::Json => "JSON",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            OutputFormat::TabSeparated | OutputFormat::TabSeparatedWithNames => {
+                "text/tab-separated-values; charset=UTF-8"
+            }
+            OutputFormat::Csv | OutputFormat::CsvWithNames => "text/csv; charset=UTF-8",
+            OutputFormat::JsonEachRow | OutputFormat::Json => "application/json; charset=UTF-8",
+        }
+    }
+}
+
+/// Serializes the result blocks of a query in one of the `OutputFormat`.
+pub struct OutputWriter {
+    format: OutputFormat,
+    schema: DataSchemaRef,
+    buf: Vec<u8>,
+    rows: usize,
+}
+
+impl OutputWriter {
+    pub fn create(format: OutputFormat, schema: DataSchemaRef) -> OutputWriter {
+        let mut writer = OutputWriter {
+            format,
+            schema,
+            buf: vec![],
+            rows: 0,
+        };
+        writer.write_prefix();
+        writer
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.schema
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect()
+    }
+
+    fn write_prefix(&mut self) {
+        match self.format {
+            OutputFormat::TabSeparatedWithNames => {
+                let names = self.names().iter().map(|name| tsv_escape(name)).collect();
+                self.write_line(names, "\t");
+            }
+            OutputFormat::CsvWithNames => {
+                let names = self.names().iter().map(|name| csv_quote(name)).collect();
+                self.write_line(names, ",");
+            }
+            OutputFormat::Json => {
+                let meta = self
+                    .schema
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        let data_type = match field.is_nullable() {
+                            true => format!("Nullable({})", field.data_type()),
+                            false => field.data_type().to_string(),
+                        };
+                        format!(
+                            "\t\t{{\"name\": {}, \"type\": {}}}",
+                            JsonValue::from(field.name().as_str()),
+                            JsonValue::from(data_type)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                self.buf.extend_from_slice(b"{\n\t\"meta\":\n\t[\n");
+                self.buf.extend_from_slice(meta.join(",\n").as_bytes());
+                self.buf.extend_from_slice(b"\n\t],\n\n\t\"data\":\n\t[");
+            }
+            _ => {}
+        }
+    }
+
+    fn write_line(&mut self, values: Vec<String>, delimiter: &str) {
+        self.buf
+            .extend_from_slice(values.join(delimiter).as_bytes());
+        self.buf.push(b'\n');
+    }
+
+    fn json_object(names: &[String], row: &[JsonValue]) -> String {
+        let members = names
+            .iter()
+            .zip(row.iter())
+            .map(|(name, value)| format!("{}: {}", JsonValue::from(name.as_str()), value))
+            .collect::<Vec<_>>();
+        format!("{{{}}}", members.join(", "))
+    }
+
+    pub fn write_block(&mut self, block: &DataBlock) -> Result<()> {
+        if block.num_rows() == 0 {
+            return Ok(());
+        }
+
+        let names = self.names();
+        for row in block_to_json(block)? {
+            match self.format {
+                OutputFormat::TabSeparated | OutputFormat::TabSeparatedWithNames => {
+                    let values = row.iter().map(tsv_value).collect();
+                    self.write_line(values, "\t");
+                }
+                OutputFormat::Csv | OutputFormat::CsvWithNames => {
+                    let values = row.iter().map(csv_value).collect();
+                    self.write_line(values, ",");
+                }
+                OutputFormat::JsonEachRow => {
+                    let object = Self::json_object(&names, &row);
+                    self.write_line(vec![object], "");
+                }
+                OutputFormat::Json => {
+                    if self.rows > 0 {
+                        self.buf.push(b',');
+                    }
+                    self.buf.extend_from_slice(b"\n\t\t");
+                    self.buf
+                        .extend_from_slice(Self::json_object(&names, &row).as_bytes());
+                }
+            }
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    /// The statistics are only written by the JSON format.
+    pub fn finish(mut self, progress: &ProgressValues, elapsed: Duration) -> Vec<u8> {
+        if self.format == OutputFormat::Json {
+            let suffix = format!(
+                "\n\t],\n\n\t\"rows\": {},\n\n\t\"statistics\":\n\t{{\n\t\t\"elapsed\": {},\n\t\t\"rows_read\": {},\n\t\t\"bytes_read\": {}\n\t}}\n}}\n",
+                self.rows,
+                elapsed.as_secs_f64(),
+                progress.read_rows,
+                progress.read_bytes
+            );
+            self.buf.extend_from_slice(suffix.as_bytes());
+        }
+        self.buf
+    }
+}
+
+fn tsv_escape(v: &str) -> String {
+    let mut escaped = String::with_capacity(v.len());
+    for c in v.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn csv_quote(v: &str) -> String {
+    format!("\"{}\"", v.replace('"', "\"\""))
+}
+
+fn tsv_value(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "\\N".to_string(),
+        JsonValue::String(v) => tsv_escape(v),
+        v => v.to_string(),
+    }
+}
+
+fn csv_value(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "\\N".to_string(),
+        JsonValue::String(v) => csv_quote(v),
+        v => v.to_string(),
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_base::ProgressValues;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+
+use super::output_format::OutputFormat;
+use super::output_format::OutputWriter;
+
+fn test_block() -> DataBlock {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("c1", DataType::Int32, false),
+        DataField::new("c2", DataType::String, true),
+        DataField::new("c3", DataType::Date16, false),
+    ]);
+
+    DataBlock::create_by_array(schema, vec![
+        Series::new(vec![1, 2]),
+        Series::new(vec!["a\tb", "say \"hi\""]),
+        Series::new(vec![1_u16, 2_u16])
+            .cast_with_type(&DataType::Date16)
+            .unwrap(),
+    ])
+}
+
+fn write(format: OutputFormat) -> Result<String> {
+    let block = test_block();
+    let mut writer = OutputWriter::create(format, block.schema().clone());
+    writer.write_block(&block)?;
+    let progress = ProgressValues {
+        read_rows: 2,
+        read_bytes: 16,
+        total_rows_to_read: 0,
+    };
+    let output = writer.finish(&progress, Duration::from_millis(10));
+    Ok(String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn test_output_format_name() -> Result<()> {
+    assert_eq!(
+        OutputFormat::try_from_name("TabSeparated")?,
+        OutputFormat::TabSeparated
+    );
+    assert_eq!(
+        OutputFormat::try_from_name("tsvwithnames")?,
+        OutputFormat::TabSeparatedWithNames
+    );
+    assert_eq!(
+        OutputFormat::try_from_name("JSONEachRow")?,
+        OutputFormat::JsonEachRow
+    );
+
+    let e = OutputFormat::try_from_name("Pretty").unwrap_err();
+    assert_eq!(e.code(), ErrorCode::BadOption("").code());
+    Ok(())
+}
+
+#[test]
+fn test_output_format_tsv() -> Result<()> {
+    assert_eq!(
+        write(OutputFormat::TabSeparated)?,
+        "1\ta\\tb\t1970-01-02\n2\tsay \"hi\"\t1970-01-03\n"
+    );
+    assert_eq!(
+        write(OutputFormat::TabSeparatedWithNames)?,
+        "c1\tc2\tc3\n1\ta\\tb\t1970-01-02\n2\tsay \"hi\"\t1970-01-03\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_output_format_csv() -> Result<()> {
+    assert_eq!(
+        write(OutputFormat::Csv)?,
+        "1,\"a\tb\",\"1970-01-02\"\n2,\"say \"\"hi\"\"\",\"1970-01-03\"\n"
+    );
+    assert_eq!(
+        write(OutputFormat::CsvWithNames)?,
+        "\"c1\",\"c2\",\"c3\"\n1,\"a\tb\",\"1970-01-02\"\n2,\"say \"\"hi\"\"\",\"1970-01-03\"\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_output_format_json_each_row() -> Result<()> {
+    assert_eq!(
+        write(OutputFormat::JsonEachRow)?,
+        "{\"c1\": 1, \"c2\": \"a\\tb\", \"c3\": \"1970-01-02\"}\n{\"c1\": 2, \"c2\": \"say \\\"hi\\\"\", \"c3\": \"1970-01-03\"}\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_output_format_json() -> Result<()> {
+    let output = write(OutputFormat::Json)?;
+    let json = serde_json::from_str::<Value>(&output)?;
+
+    assert_eq!(
+        json["meta"],
+        serde_json::json!([
+            {"name": "c1", "type": "Int32"},
+            {"name": "c2", "type": "Nullable(String)"},
+            {"name": "c3", "type": "Date16"},
+        ])
+    );
+    assert_eq!(
+        json["data"],
+        serde_json::json!([
+            {"c1": 1, "c2": "a\tb", "c3": "1970-01-02"},
+            {"c1": 2, "c2": "say \"hi\"", "c3": "1970-01-03"},
+        ])
+    );
+    assert_eq!(json["rows"], 2);
+    assert_eq!(json["statistics"]["rows_read"], 2);
+    assert_eq!(json["statistics"]["bytes_read"], 16);
+    Ok(())
+}
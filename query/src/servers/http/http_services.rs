@@ -21,8 +21,11 @@ use poem::EndpointExt;
 use poem::Route;
 
 use crate::common::service::HttpShutdownHandler;
+use crate::servers::http::v1::auth_route;
 use crate::servers::http::v1::query_route;
 use crate::servers::http::v1::statement_router;
+use crate::servers::http::v1::streaming_load_router;
+use crate::servers::http::v1::HTTPAuthMiddleware;
 use crate::servers::Server;
 use crate::sessions::SessionManagerRef;
 
@@ -39,13 +42,16 @@ impl HttpHandler {
         })
     }
     fn build_router(&self) -> impl Endpoint {
+        let auth = HTTPAuthMiddleware::create(self.session_manager.clone());
         Route::new()
             .at(
                 "/",
                 get(poem::endpoint::make_sync(|_| "This is http handler.")),
             )
-            .nest("/v1/statement", statement_router())
-            .nest("/v1/query", query_route())
+            .nest("/v1/statement", statement_router().with(auth.clone()))
+            .nest("/v1/query", query_route().with(auth.clone()))
+            .nest("/v1/auth", auth_route().with(auth.clone()))
+            .nest("/v1/streaming_load", streaming_load_router().with(auth))
             .data(self.session_manager.clone())
             .boxed()
     }
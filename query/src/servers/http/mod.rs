@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod clickhouse;
 mod http_services;
 pub mod v1;
+
+pub use clickhouse::ClickHouseHttpHandler;
 pub use http_services::HttpHandler;
@@ -0,0 +1,211 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_infallible::RwLock;
+use common_management::UserInfo;
+use headers::authorization::Basic;
+use headers::authorization::Bearer;
+use headers::Authorization;
+use headers::HeaderMapExt;
+use poem::http::header;
+use poem::http::StatusCode;
+use poem::post;
+use poem::web::Data;
+use poem::web::Json;
+use poem::Endpoint;
+use poem::IntoResponse;
+use poem::Middleware;
+use poem::Request;
+use poem::Response;
+use poem::Route;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::sessions::SessionManagerRef;
+use crate::users::CertifiedInfo;
+
+const TOKEN_TTL: Duration = Duration::from_secs(60 * 60);
+
+pub type HttpAuthTokensRef = Arc<HttpAuthTokens>;
+
+/// Bearer tokens issued by `/v1/auth/token`, they are kept in memory, valid only on the
+/// query node which issued them.
+pub struct HttpAuthTokens {
+    // token -> (user name, user hostname, expire time)
+    tokens: RwLock<HashMap<String, (String, String, Instant)>>,
+}
+
+impl HttpAuthTokens {
+    pub fn create() -> HttpAuthTokensRef {
+        Arc::new(HttpAuthTokens {
+            tokens: RwLock::new(HashMap::new()),
+        })
+    }
+
+    pub fn issue(&self, user: &UserInfo) -> String {
+        let token = uuid::Uuid::new_v4().to_simple().to_string();
+        let expire_at = Instant::now() + TOKEN_TTL;
+        let mut tokens = self.tokens.write();
+        tokens.retain(|_, (_, _, expire_at)| *expire_at > Instant::now());
+        tokens.insert(
+            token.clone(),
+            (user.name.clone(), user.hostname.clone(), expire_at),
+        );
+        token
+    }
+
+    /// The name and hostname of the user of the token, None if unknown or expired.
+    pub fn get(&self, token: &str) -> Option<(String, String)> {
+        match self.tokens.read().get(token) {
+            Some((name, hostname, expire_at)) if *expire_at > Instant::now() => {
+                Some((name.clone(), hostname.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Authenticates the requests with HTTP Basic or bearer-token credentials, the authenticated
+/// `UserInfo` is attached to the request for the sessions created by the handlers.
+#[derive(Clone)]
+pub struct HTTPAuthMiddleware {
+    session_manager: SessionManagerRef,
+    tokens: HttpAuthTokensRef,
+}
+
+impl HTTPAuthMiddleware {
+    pub fn create(session_manager: SessionManagerRef) -> HTTPAuthMiddleware {
+        HTTPAuthMiddleware {
+            session_manager,
+            tokens: HttpAuthTokens::create(),
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for HTTPAuthMiddleware {
+    type Output = HTTPAuthEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        HTTPAuthEndpoint {
+            session_manager: self.session_manager.clone(),
+            tokens: self.tokens.clone(),
+            ep,
+        }
+    }
+}
+
+pub struct HTTPAuthEndpoint<E> {
+    session_manager: SessionManagerRef,
+    tokens: HttpAuthTokensRef,
+    ep: E,
+}
+
+impl<E> HTTPAuthEndpoint<E> {
+    async fn auth(&self, req: &Request) -> Result<UserInfo> {
+        let user_manager = self.session_manager.get_user_manager();
+        // the users are looked up by the IP of the client, empty if not connected by TCP
+        let client_ip = match req.remote_addr().as_socket_addr() {
+            Some(addr) => addr.ip().to_string(),
+            None => String::new(),
+        };
+        let headers = req.headers();
+
+        if let Some(Authorization(bearer)) = headers.typed_get::<Authorization<Bearer>>() {
+            return match self.tokens.get(bearer.token()) {
+                Some((name, hostname)) => user_manager.get_user(&name, &hostname).await,
+                None => Err(ErrorCode::AuthenticateFailure(
+                    "Invalid or expired bearer token",
+                )),
+            };
+        }
+
+        let info = match headers.typed_get::<Authorization<Basic>>() {
+            Some(Authorization(basic)) => {
+                CertifiedInfo::create(basic.username(), basic.password(), &client_ip)
+            }
+            None if headers.contains_key(header::AUTHORIZATION) => {
+                return Err(ErrorCode::AuthenticateFailure(
+                    "Unsupported authorization scheme, expect Basic or Bearer",
+                ));
+            }
+            None => {
+                return Err(ErrorCode::AuthenticateFailure(
+                    "Missing authorization header, expect Basic or Bearer",
+                ));
+            }
+        };
+
+        let user = user_manager
+            .get_user_by_address(&info.user_name, &client_ip)
+            .await?;
+        let user_name = info.user_name.clone();
+        match user_manager.auth_user(user.clone(), info).await? {
+            true => Ok(user),
+            false => Err(ErrorCode::AuthenticateFailure(format!(
+                "Authenticate failed for user {}",
+                user_name
+            ))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for HTTPAuthEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Self::Output {
+        match self.auth(&req).await {
+            Ok(user) => {
+                req.extensions_mut().insert(user);
+                req.extensions_mut().insert(self.tokens.clone());
+                self.ep.call(req).await.into_response()
+            }
+            Err(cause) => {
+                log::warn!("HTTP handler authenticate failed, cause: {}", cause);
+                Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .header(header::WWW_AUTHENTICATE, "Basic realm=\"databend\"")
+                    .body(cause.message())
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuthTokenResponse {
+    pub token: String,
+    pub expires_in_secs: u64,
+}
+
+#[poem::handler]
+pub(crate) async fn auth_token_handler(
+    user: Data<&UserInfo>,
+    tokens: Data<&HttpAuthTokensRef>,
+) -> impl IntoResponse {
+    Json(AuthTokenResponse {
+        token: tokens.0.issue(user.0),
+        expires_in_secs: TOKEN_TTL.as_secs(),
+    })
+}
+
+pub fn auth_route() -> Route {
+    Route::new().at("/token", post(auth_token_handler))
+}
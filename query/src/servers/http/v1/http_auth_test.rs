@@ -0,0 +1,111 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use common_management::UserInfo;
+use common_meta_types::AuthType;
+use headers::authorization::Credentials;
+use headers::Authorization;
+use poem::http::header;
+use poem::http::Method;
+use poem::http::StatusCode;
+use poem::post;
+use poem::web::Data;
+use poem::Endpoint;
+use poem::EndpointExt;
+use poem::Request;
+use poem::Route;
+use pretty_assertions::assert_eq;
+
+use crate::servers::http::v1::http_auth::auth_route;
+use crate::servers::http::v1::http_auth::AuthTokenResponse;
+use crate::servers::http::v1::http_auth::HTTPAuthMiddleware;
+use crate::tests::SessionManagerBuilder;
+use crate::users::User;
+
+#[poem::handler]
+async fn whoami_handler(user: Data<&UserInfo>) -> String {
+    user.0.name.clone()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_http_auth() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let user = User::new("http-user", "%", "http-pwd", AuthType::PlainText);
+    sessions.get_user_manager().add_user(user.into()).await?;
+
+    let route = Route::new()
+        .at("/whoami", post(whoami_handler))
+        .nest("/auth", auth_route())
+        .with(HTTPAuthMiddleware::create(sessions.clone()))
+        .data(sessions);
+
+    let call = |uri: &'static str, authorization: Option<String>| {
+        let mut builder = Request::builder()
+            .uri(uri.parse().unwrap())
+            .method(Method::POST);
+        if let Some(authorization) = authorization {
+            builder = builder.header(header::AUTHORIZATION, authorization);
+        }
+        route.call(builder.finish())
+    };
+    let basic = |user: &str, password: &str| {
+        let credentials = Authorization::basic(user, password);
+        Some(credentials.0.encode().to_str().unwrap().to_string())
+    };
+
+    // without credentials
+    let response = call("/whoami", None).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+
+    // basic
+    let response = call("/whoami", basic("root", "")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.into_body().into_string().await.unwrap(), "root");
+
+    let response = call("/whoami", basic("http-user", "http-pwd")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.into_body().into_string().await.unwrap(),
+        "http-user"
+    );
+
+    let response = call("/whoami", basic("http-user", "bad-pwd")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+
+    let response = call("/whoami", Some("Digest username=\"root\"".to_string())).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // bearer
+    let response = call("/whoami", Some("Bearer not-a-token".to_string())).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = call("/auth/token", basic("http-user", "http-pwd")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().into_vec().await.unwrap();
+    let token = serde_json::from_slice::<AuthTokenResponse>(&body)?;
+    assert!(token.expires_in_secs > 0);
+
+    let response = call("/whoami", Some(format!("Bearer {}", token.token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.into_body().into_string().await.unwrap(),
+        "http-user"
+    );
+
+    Ok(())
+}
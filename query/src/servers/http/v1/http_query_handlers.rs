@@ -21,6 +21,7 @@ use common_base::ProgressValues;
 use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_management::UserInfo;
 use hyper::http::header;
 use poem::get;
 use poem::http::StatusCode;
@@ -56,6 +57,8 @@ pub fn make_delete_uri(query_id: &str) -> String {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct QueryResponse {
     pub id: Option<String>,
+    /// The session kept on the server, to run the following queries in.
+    pub session_id: Option<String>,
     pub columns: Option<DataSchemaRef>,
     pub data: JsonBlockRef,
     pub next_uri: Option<String>,
@@ -91,6 +94,7 @@ impl QueryResponse {
                     data,
                     columns,
                     id: Some(id.clone()),
+                    session_id: r.session_id.clone(),
                     next_uri: next_url,
                     state_uri: Some(make_state_uri(&id)),
                     delete_uri: Some(make_delete_uri(&id)),
@@ -104,13 +108,18 @@ impl QueryResponse {
         }
     }
 
-    fn not_found(query_id: String) -> QueryResponse {
-        QueryResponse::request_error(None, format!("query id not found {}", query_id))
+    /// The query does not exist, or it is started by another user.
+    fn not_found(query_id: String) -> Response {
+        let message = format!("query id not found {}", query_id);
+        let mut response = QueryResponse::request_error(None, message).into_response();
+        response.set_status(StatusCode::NOT_FOUND);
+        response
     }
 
     fn fail_to_start_sql(err: ErrorCode) -> QueryResponse {
         QueryResponse {
             id: None,
+            session_id: None,
             data: Arc::new(vec![]),
             query_state: None,
             columns: None,
@@ -126,6 +135,7 @@ impl QueryResponse {
     fn request_error(id: Option<String>, message: String) -> QueryResponse {
         QueryResponse {
             id,
+            session_id: None,
             data: Arc::new(vec![]),
             query_error: None,
             columns: None,
@@ -160,12 +170,13 @@ pub(crate) struct CancelParams {
 #[poem::handler]
 async fn query_cancel_handler(
     sessions_extension: Data<&SessionManagerRef>,
+    user: Data<&UserInfo>,
     Query(params): Query<CancelParams>,
     Path(query_id): Path<String>,
 ) -> impl IntoResponse {
     let session_manager = sessions_extension.0;
     let http_query_manager = session_manager.get_http_query_manager();
-    match http_query_manager.get_query_by_id(&query_id).await {
+    match http_query_manager.get_query_by_id(&query_id, user.0).await {
         Some(query) => {
             query.kill().await;
             if params.delete.unwrap_or(false) {
@@ -180,14 +191,15 @@ async fn query_cancel_handler(
 #[poem::handler]
 async fn query_state_handler(
     sessions_extension: Data<&SessionManagerRef>,
+    user: Data<&UserInfo>,
     Path(query_id): Path<String>,
 ) -> impl IntoResponse {
     let session_manager = sessions_extension.0;
     let http_query_manager = session_manager.get_http_query_manager();
-    match http_query_manager.get_query_by_id(&query_id).await {
+    match http_query_manager.get_query_by_id(&query_id, user.0).await {
         Some(query) => {
             let response = query.get_response_state_only().await;
-            QueryResponse::from_internal(query_id, &Ok(response))
+            QueryResponse::from_internal(query_id, &Ok(response)).into_response()
         }
         None => QueryResponse::not_found(query_id),
     }
@@ -213,16 +225,17 @@ impl PageParams {
 #[poem::handler]
 async fn query_page_handler(
     sessions_extension: Data<&SessionManagerRef>,
+    user: Data<&UserInfo>,
     Query(params): Query<PageParams>,
     Path((query_id, page_no)): Path<(String, usize)>,
 ) -> impl IntoResponse {
     let session_manager = sessions_extension.0;
     let http_query_manager = session_manager.get_http_query_manager();
-    match http_query_manager.get_query_by_id(&query_id).await {
+    match http_query_manager.get_query_by_id(&query_id, user.0).await {
         Some(query) => {
             let wait_type = params.get_wait_type();
             let result = query.get_response_page(page_no, &wait_type, false).await;
-            QueryResponse::from_internal(query_id, &result)
+            QueryResponse::from_internal(query_id, &result).into_response()
         }
         None => QueryResponse::not_found(query_id),
     }
@@ -231,13 +244,16 @@ async fn query_page_handler(
 #[poem::handler]
 pub(crate) async fn query_handler(
     sessions_extension: Data<&SessionManagerRef>,
+    user: Data<&UserInfo>,
     Query(params): Query<PageParams>,
     Json(req): Json<HttpQueryRequest>,
 ) -> impl IntoResponse {
     log::info!("receive http query: {:?} {:?}", req, params);
     let session_manager = sessions_extension.0;
     let http_query_manager = session_manager.get_http_query_manager();
-    let query = http_query_manager.create_query(req, session_manager).await;
+    let query = http_query_manager
+        .create_query(req, session_manager, user.0.clone())
+        .await;
     match query {
         Ok(query) => {
             let wait_type = params.get_wait_type();
@@ -26,6 +26,8 @@ use poem::Route;
 use pretty_assertions::assert_eq;
 use serde_json;
 
+use crate::servers::http::v1::http_auth::HTTPAuthEndpoint;
+use crate::servers::http::v1::http_auth::HTTPAuthMiddleware;
 use crate::servers::http::v1::http_query_handlers::make_delete_uri;
 use crate::servers::http::v1::http_query_handlers::make_page_uri;
 use crate::servers::http::v1::http_query_handlers::make_state_uri;
@@ -41,7 +43,12 @@ use crate::tests::SessionManagerBuilder;
 // 2. query fail after started
 // 3. get old page other than the last
 
-type RouteWithData = AddDataEndpoint<Route, SessionManagerRef>;
+type RouteWithData = AddDataEndpoint<HTTPAuthEndpoint<Route>, SessionManagerRef>;
+
+// root without password
+const ROOT_AUTHORIZATION: &str = "Basic cm9vdDo=";
+// default without password
+const DEFAULT_AUTHORIZATION: &str = "Basic ZGVmYXVsdDo=";
 
 #[tokio::test]
 async fn test_simple_sql() -> Result<()> {
@@ -73,8 +80,7 @@ async fn test_bad_sql() -> Result<()> {
 
 #[tokio::test]
 async fn test_async() -> Result<()> {
-    let sessions = SessionManagerBuilder::create().build().unwrap();
-    let route = Route::new().nest("/v1/query", query_route()).data(sessions);
+    let route = create_router();
     let sql = "select sum(number+1) from numbers(1000000) where number>0 group by number%3";
     let json = serde_json::json!({"sql": sql.to_string()});
 
@@ -154,6 +160,144 @@ async fn test_async() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_query_of_another_user() -> Result<()> {
+    let route = create_router();
+    let sql = "select sum(number+1) from numbers(100000000) where number>0 group by number%3";
+    let json = serde_json::json!({ "sql": sql });
+    let (_, result) = post_json_to_router(&route, &json, 0).await?;
+    let query_id = result.id.unwrap();
+
+    // the query of root is not visible to the others
+    for uri in [
+        make_state_uri(&query_id),
+        make_page_uri(&query_id, 0),
+        make_delete_uri(&query_id),
+    ] {
+        let response = get_uri_as(&route, uri, DEFAULT_AUTHORIZATION).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    let (status, result) = get_state(&route, query_id.clone()).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(result.request_error.is_none(), "{:?}", result);
+    assert_eq!(delete_query(&route, query_id).await, StatusCode::OK);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_session() -> Result<()> {
+    let route = create_router();
+    let max_block_size = "select value from system.settings where name = 'max_block_size'";
+
+    // per-query database and settings
+    let json = serde_json::json!({"sql": "select value from settings where name = 'max_block_size'",
+        "session": {"database": "system", "settings": {"max_block_size": "1000"}}});
+    let (status, result) = post_json_to_router(&route, &json, 1).await?;
+    assert_eq!(status, StatusCode::OK, "{:?}", result);
+    assert_eq!(result.data[0][0], "1000");
+    assert!(result.session_id.is_none());
+
+    let json = serde_json::json!({"sql": "select 1", "session": {"database": "not_exists"}});
+    let (_, result) = post_json_to_router(&route, &json, 1).await?;
+    assert!(result.query_error.is_some());
+
+    // a session kept on the server keeps USE and SET
+    let json =
+        serde_json::json!({"sql": "use system", "session": {"keep_server_session_secs": 10}});
+    let (status, result) = post_json_to_router(&route, &json, 1).await?;
+    assert_eq!(status, StatusCode::OK, "{:?}", result);
+    let session_id = result.session_id.unwrap();
+
+    let json = serde_json::json!({"sql": "set max_block_size = 2000", "session_id": session_id});
+    let (_, result) = post_json_to_router(&route, &json, 1).await?;
+    assert!(result.query_error.is_none(), "{:?}", result);
+    assert_eq!(result.session_id, Some(session_id.clone()));
+
+    let json = serde_json::json!({"sql": "select value from settings where name = 'max_block_size'",
+        "session_id": session_id});
+    let (_, result) = post_json_to_router(&route, &json, 1).await?;
+    assert!(result.query_error.is_none(), "{:?}", result);
+    assert_eq!(result.data[0][0], "2000");
+
+    // the per-query settings do not change the settings of the session
+    let json = serde_json::json!({"sql": "select value from settings where name = 'max_block_size'",
+        "session_id": session_id, "session": {"settings": {"max_block_size": "3000"}}});
+    let (_, result) = post_json_to_router(&route, &json, 1).await?;
+    assert!(result.query_error.is_none(), "{:?}", result);
+    assert_eq!(result.data[0][0], "3000");
+
+    let json = serde_json::json!({"sql": "select value from settings where name = 'max_block_size'",
+        "session_id": session_id});
+    let (_, result) = post_json_to_router(&route, &json, 1).await?;
+    assert_eq!(result.data[0][0], "2000");
+
+    // not shared with the other sessions
+    let (_, result) =
+        post_json_to_router(&route, &serde_json::json!({ "sql": max_block_size }), 1).await?;
+    assert_eq!(result.data[0][0], "10000");
+
+    let json = serde_json::json!({"sql": "select 1", "session_id": "not_exists"});
+    let (_, result) = post_json_to_router(&route, &json, 1).await?;
+    assert!(result.query_error.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_session_running_one_query() -> Result<()> {
+    let route = create_router();
+    let json = serde_json::json!({"sql": "select 1", "session": {"keep_server_session_secs": 10}});
+    let (_, result) = post_json_to_router(&route, &json, 1).await?;
+    let session_id = result.session_id.unwrap();
+
+    // two queries in the same session at the same time, only one of them is started
+    let sql = "select sum(number+1) from numbers(100000000) where number>0 group by number%3";
+    let json = serde_json::json!({"sql": sql, "session_id": session_id});
+    let (first, second) = futures::join!(
+        post_json_to_router(&route, &json, 0),
+        post_json_to_router(&route, &json, 0)
+    );
+    let results = [first?.1, second?.1];
+    let started = results
+        .iter()
+        .filter(|result| result.query_error.is_none())
+        .collect::<Vec<_>>();
+    assert_eq!(started.len(), 1, "{:?}", results);
+    let rejected = results
+        .iter()
+        .find(|result| result.query_error.is_some())
+        .unwrap();
+    assert!(
+        rejected
+            .query_error
+            .as_ref()
+            .unwrap()
+            .contains("is running another query"),
+        "{:?}",
+        rejected
+    );
+
+    let status = delete_query(&route, started[0].id.clone().unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_session_idle_timeout() -> Result<()> {
+    let route = create_router();
+    let json = serde_json::json!({"sql": "select 1", "session": {"keep_server_session_secs": 1}});
+    let (_, result) = post_json_to_router(&route, &json, 1).await?;
+    let session_id = result.session_id.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    let json = serde_json::json!({"sql": "select 1", "session_id": session_id});
+    let (_, result) = post_json_to_router(&route, &json, 1).await?;
+    assert!(result.query_error.is_some());
+
+    Ok(())
+}
+
 async fn get_state(route: &RouteWithData, query_id: String) -> Result<(StatusCode, QueryResponse)> {
     let uri = make_state_uri(&query_id);
     get_uri_checked(route, uri).await
@@ -179,11 +323,16 @@ async fn check_response(response: Response) -> Result<(StatusCode, QueryResponse
 }
 
 async fn get_uri(route: &RouteWithData, uri: String) -> Response {
+    get_uri_as(route, uri, ROOT_AUTHORIZATION).await
+}
+
+async fn get_uri_as(route: &RouteWithData, uri: String, authorization: &str) -> Response {
     route
         .call(
             Request::builder()
                 .uri(uri.parse().unwrap())
                 .method(Method::GET)
+                .header(header::AUTHORIZATION, authorization)
                 .finish(),
         )
         .await
@@ -217,7 +366,10 @@ async fn post_sql(sql: &'static str, wait_time: i32) -> Result<(StatusCode, Quer
 
 pub fn create_router() -> RouteWithData {
     let sessions = SessionManagerBuilder::create().build().unwrap();
-    Route::new().nest("/v1/query", query_route()).data(sessions)
+    Route::new()
+        .nest("/v1/query", query_route())
+        .with(HTTPAuthMiddleware::create(sessions.clone()))
+        .data(sessions)
 }
 
 async fn post_json(
@@ -244,6 +396,7 @@ async fn post_json_to_router(
                 .uri(uri.parse().unwrap())
                 .method(Method::POST)
                 .header(header::CONTENT_TYPE, content_type)
+                .header(header::AUTHORIZATION, ROOT_AUTHORIZATION)
                 .body(body),
         )
         .await;
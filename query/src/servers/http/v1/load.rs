@@ -0,0 +1,238 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use common_base::tokio;
+use common_dal::Local;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_management::UserInfo;
+use common_planners::InsertIntoPlan;
+use common_planners::PlanNode;
+use common_streams::CsvSource;
+use common_streams::NDJsonSource;
+use common_streams::ParquetSource;
+use common_streams::SendableDataBlockStream;
+use common_streams::Source;
+use common_streams::SourceStream;
+use futures::StreamExt;
+use poem::put;
+use poem::web::Data;
+use poem::web::Json;
+use poem::Body;
+use poem::Endpoint;
+use poem::Request;
+use poem::Route;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::interpreters::InterpreterFactory;
+use crate::sessions::DatabendQueryContextRef;
+use crate::sessions::SessionManagerRef;
+use crate::sql::PlanParser;
+
+/// The header carrying the `INSERT INTO <table> [(<columns>)] FORMAT <format>` statement.
+pub const INSERT_SQL_HEADER: &str = "insert_sql";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LoadResponse {
+    pub id: String,
+    pub state: String,
+    pub rows: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LoadFormat {
+    Csv { header: bool },
+    Tsv { header: bool },
+    NDJson,
+    Parquet,
+}
+
+impl LoadFormat {
+    fn try_from_name(name: &str) -> Result<LoadFormat> {
+        match name.to_ascii_uppercase().as_str() {
+            "CSV" => Ok(LoadFormat::Csv { header: false }),
+            "CSVWITHNAMES" => Ok(LoadFormat::Csv { header: true }),
+            "TSV" | "TABSEPARATED" => Ok(LoadFormat::Tsv { header: false }),
+            "TSVWITHNAMES" | "TABSEPARATEDWITHNAMES" => Ok(LoadFormat::Tsv { header: true }),
+            "NDJSON" | "JSONEACHROW" => Ok(LoadFormat::NDJson),
+            "PARQUET" => Ok(LoadFormat::Parquet),
+            _ => Err(ErrorCode::BadOption(format!(
+                "Unsupported streaming load format: {}",
+                name
+            ))),
+        }
+    }
+}
+
+/// Splits `INSERT INTO t FORMAT CSV` into the insert statement and the format of the body.
+fn split_format(insert_sql: &str) -> Result<(String, LoadFormat)> {
+    let insert_sql = insert_sql.trim().trim_end_matches(';').trim_end();
+    let format_at = insert_sql
+        .to_ascii_uppercase()
+        .rfind(" FORMAT ")
+        .ok_or_else(|| {
+            ErrorCode::SyntaxException(
+                "Streaming load expects: INSERT INTO <table> FORMAT <format>",
+            )
+        })?;
+    let format = LoadFormat::try_from_name(insert_sql[format_at + " FORMAT ".len()..].trim())?;
+    Ok((insert_sql[..format_at].to_string(), format))
+}
+
+/// The body is decoded while it is received, only parquet is spooled to a temporary file since
+/// its metadata is at the end.
+async fn body_source(
+    ctx: &DatabendQueryContextRef,
+    plan: &InsertIntoPlan,
+    format: LoadFormat,
+    body: Body,
+    spool_file: &mut Option<std::path::PathBuf>,
+) -> Result<Box<dyn Source>> {
+    let schema = plan.schema();
+    let block_size = ctx.get_settings().get_max_block_size()? as usize;
+    let reader = body.into_async_read();
+    Ok(match format {
+        LoadFormat::Csv { header } => Box::new(CsvSource::new(reader, schema, header, block_size)),
+        LoadFormat::Tsv { header } => Box::new(CsvSource::with_delimiter(
+            reader, schema, header, block_size, b'\t',
+        )),
+        LoadFormat::NDJson => Box::new(NDJsonSource::new(reader, schema, block_size)),
+        LoadFormat::Parquet => {
+            let dir = std::env::temp_dir();
+            let name = format!("streaming_load_{}.parquet", uuid::Uuid::new_v4());
+            let path = dir.join(&name);
+            *spool_file = Some(path.clone());
+
+            let mut file = tokio::fs::File::create(&path).await?;
+            let mut reader = reader;
+            tokio::io::copy(&mut reader, &mut file).await?;
+
+            let projection = (0..schema.fields().len()).collect::<Vec<_>>();
+            let da = Arc::new(Local::with_path(dir));
+            Box::new(ParquetSource::new(da, name, schema, projection))
+        }
+    })
+}
+
+async fn load(
+    session_manager: SessionManagerRef,
+    user: UserInfo,
+    insert_sql: &str,
+    body: Body,
+) -> Result<usize> {
+    let session = session_manager.create_session("http-streaming-load")?;
+    session.set_current_user(user);
+    let ctx = session.create_context().await?;
+    insert_from_body(ctx, insert_sql, body).await
+}
+
+/// Inserts the rows of the body by `INSERT INTO <table> [(<columns>)] FORMAT <format>`,
+/// returns the number of the inserted rows.
+pub(crate) async fn insert_from_body(
+    ctx: DatabendQueryContextRef,
+    insert_sql: &str,
+    body: Body,
+) -> Result<usize> {
+    let (insert_sql, format) = split_format(insert_sql)?;
+    ctx.attach_query_str(&insert_sql);
+
+    // Parsed as an insert without values, the rows are read from the body instead.
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(&format!("{} VALUES", insert_sql))?;
+    let mut plan = match plan {
+        PlanNode::InsertInto(plan) => plan,
+        _ => {
+            return Err(ErrorCode::SyntaxException(
+                "Streaming load only supports INSERT INTO statements",
+            ))
+        }
+    };
+    plan.values_opt = None;
+
+    let mut spool_file = None;
+    let rows = Arc::new(AtomicUsize::new(0));
+    let counter = rows.clone();
+    let result = async {
+        let source = body_source(&ctx, &plan, format, body, &mut spool_file).await?;
+        let stream = SourceStream::new(source).execute().await?;
+        let stream: SendableDataBlockStream = Box::pin(stream.map(move |block| {
+            if let Ok(block) = &block {
+                counter.fetch_add(block.num_rows(), Ordering::Relaxed);
+            }
+            block
+        }));
+
+        let interpreter = InterpreterFactory::get(ctx.clone(), PlanNode::InsertInto(plan))?;
+        interpreter.execute(Some(stream)).await?;
+        Ok(())
+    }
+    .await;
+
+    if let Some(path) = spool_file {
+        tokio::fs::remove_file(path).await.ok();
+    }
+    // the rows read from the body are loaded only if the insert succeeds
+    result.map(|_| rows.load(Ordering::Relaxed))
+}
+
+#[poem::handler]
+pub async fn streaming_load(
+    req: &Request,
+    body: Body,
+    sessions_extension: Data<&SessionManagerRef>,
+    user: Data<&UserInfo>,
+) -> Json<LoadResponse> {
+    let id = uuid::Uuid::new_v4().to_string();
+
+    let result = match req.headers().get(INSERT_SQL_HEADER) {
+        None => Err(ErrorCode::BadArguments(format!(
+            "Missing the {} header",
+            INSERT_SQL_HEADER
+        ))),
+        Some(value) => match value.to_str() {
+            Err(cause) => Err(ErrorCode::BadArguments(format!(
+                "Invalid {} header: {}",
+                INSERT_SQL_HEADER, cause
+            ))),
+            Ok(insert_sql) => {
+                let session_manager = sessions_extension.0.clone();
+                load(session_manager, user.0.clone(), insert_sql, body).await
+            }
+        },
+    };
+
+    Json(match result {
+        Ok(rows) => LoadResponse {
+            id,
+            state: "SUCCESS".to_string(),
+            rows,
+            error: None,
+        },
+        Err(cause) => LoadResponse {
+            id,
+            state: "FAILED".to_string(),
+            rows: 0,
+            error: Some(cause.message()),
+        },
+    })
+}
+
+pub fn streaming_load_router() -> impl Endpoint {
+    Route::new().at("/", put(streaming_load))
+}
@@ -0,0 +1,132 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_datablocks::assert_blocks_sorted_eq;
+use common_datablocks::DataBlock;
+use common_exception::Result;
+use futures::TryStreamExt;
+use poem::http::header;
+use poem::http::Method;
+use poem::http::StatusCode;
+use poem::Endpoint;
+use poem::EndpointExt;
+use poem::Request;
+use poem::Route;
+use pretty_assertions::assert_eq;
+
+use crate::interpreters::InterpreterFactory;
+use crate::servers::http::v1::http_auth::HTTPAuthMiddleware;
+use crate::servers::http::v1::load::streaming_load_router;
+use crate::servers::http::v1::load::LoadResponse;
+use crate::servers::http::v1::load::INSERT_SQL_HEADER;
+use crate::sessions::SessionManagerRef;
+use crate::sql::PlanParser;
+use crate::tests::SessionManagerBuilder;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_streaming_load() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    execute_sql(
+        &sessions,
+        "create table default.t(a Int32, b String) Engine = Memory",
+    )
+    .await?;
+
+    let route = Route::new()
+        .nest("/v1/streaming_load", streaming_load_router())
+        .with(HTTPAuthMiddleware::create(sessions.clone()))
+        .data(sessions.clone());
+    let load = |insert_sql: Option<&'static str>, body: &'static str| {
+        let mut builder = Request::builder()
+            .uri("/v1/streaming_load".parse().unwrap())
+            .method(Method::PUT)
+            // root without password
+            .header(header::AUTHORIZATION, "Basic cm9vdDo=");
+        if let Some(insert_sql) = insert_sql {
+            builder = builder.header(INSERT_SQL_HEADER, insert_sql);
+        }
+        let request = builder.body(body);
+        let route = &route;
+        async move {
+            let response = route.call(request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = response.into_body().into_vec().await.unwrap();
+            serde_json::from_slice::<LoadResponse>(&body).unwrap()
+        }
+    };
+
+    let result = load(Some("INSERT INTO t FORMAT CSV"), "1,a\n2,b\n").await;
+    assert_eq!(result.error, None);
+    assert_eq!(result.state, "SUCCESS");
+    assert_eq!(result.rows, 2);
+
+    let result = load(
+        Some("insert into default.t format TabSeparatedWithNames"),
+        "a\tb\n3\tc\n",
+    )
+    .await;
+    assert_eq!(result.error, None);
+    assert_eq!(result.rows, 1);
+
+    let result = load(
+        Some("INSERT INTO t FORMAT JSONEachRow"),
+        "{\"a\": 4, \"b\": \"d\"}\n\n{\"b\": \"e\", \"a\": 5}\n",
+    )
+    .await;
+    assert_eq!(result.error, None);
+    assert_eq!(result.rows, 2);
+
+    let blocks = execute_sql(&sessions, "select * from default.t").await?;
+    let expected = vec![
+        "+---+---+",
+        "| a | b |",
+        "+---+---+",
+        "| 1 | a |",
+        "| 2 | b |",
+        "| 3 | c |",
+        "| 4 | d |",
+        "| 5 | e |",
+        "+---+---+",
+    ];
+    assert_blocks_sorted_eq(expected, blocks.as_slice());
+
+    // errors
+    let result = load(None, "1,a\n").await;
+    assert_eq!(result.state, "FAILED");
+    assert!(result.error.is_some());
+
+    let result = load(Some("INSERT INTO t FORMAT Avro"), "").await;
+    assert_eq!(result.state, "FAILED");
+    assert!(result.error.unwrap().contains("Avro"));
+
+    let result = load(Some("INSERT INTO t"), "1,a\n").await;
+    assert_eq!(result.state, "FAILED");
+
+    // no rows are reported for a failed load
+    let result = load(Some("INSERT INTO t FORMAT CSV"), "1,a\nx,a\n").await;
+    assert_eq!(result.state, "FAILED");
+    assert!(result.error.is_some());
+    assert_eq!(result.rows, 0);
+
+    Ok(())
+}
+
+async fn execute_sql(sessions: &SessionManagerRef, sql: &str) -> Result<Vec<DataBlock>> {
+    let session = sessions.create_session("test")?;
+    let ctx = session.create_context().await?;
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(sql)?;
+    let interpreter = InterpreterFactory::get(ctx, plan)?;
+    interpreter.execute(None).await?.try_collect().await
+}
@@ -13,16 +13,27 @@
 // limitations under the License.
 
 mod block_to_json;
+mod http_auth;
 
 #[cfg(test)]
 mod block_to_json_test;
+#[cfg(test)]
+mod http_auth_test;
 mod http_query_handlers;
 #[cfg(test)]
 mod http_query_handlers_test;
+mod load;
+#[cfg(test)]
+mod load_test;
 pub(crate) mod query;
 pub mod statement;
 #[cfg(test)]
 mod statement_test;
 
+pub(crate) use block_to_json::block_to_json;
+pub(super) use http_auth::auth_route;
+pub use http_auth::HTTPAuthMiddleware;
 pub(super) use http_query_handlers::query_route;
+pub(super) use load::insert_from_body;
+pub(super) use load::streaming_load_router;
 pub(super) use statement::statement_router;
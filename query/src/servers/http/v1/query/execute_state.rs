@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use common_base::tokio;
@@ -28,13 +29,33 @@ use serde::Deserialize;
 
 use crate::interpreters::InterpreterFactory;
 use crate::sessions::DatabendQueryContextRef;
-use crate::sessions::SessionManagerRef;
 use crate::sessions::SessionRef;
 use crate::sql::PlanParser;
 
 #[derive(Deserialize, Debug)]
 pub struct HttpQueryRequest {
     pub sql: String,
+    /// The id of a session kept on the server by a previous query, to run in.
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub session: HttpSessionConf,
+}
+
+impl HttpQueryRequest {
+    /// Whether the session of the query is kept on the server for the following queries.
+    pub fn keeps_session(&self) -> bool {
+        self.session_id.is_some() || self.session.keep_server_session_secs.unwrap_or(0) > 0
+    }
+}
+
+/// The database is applied to the session of the query, it stays with a session kept on the
+/// server, while the settings are of the query only.
+#[derive(Deserialize, Debug, Default)]
+pub struct HttpSessionConf {
+    pub database: Option<String>,
+    /// Keeps a new session on the server until it is idle for that long.
+    pub keep_server_session_secs: Option<u64>,
+    pub settings: Option<HashMap<String, String>>,
 }
 
 pub(crate) enum ExecuteState {
@@ -97,12 +118,17 @@ pub(crate) struct ExecuteRunning {
 impl ExecuteState {
     pub(crate) async fn try_create(
         request: &HttpQueryRequest,
-        session_manager: &SessionManagerRef,
+        session: SessionRef,
         block_tx: mpsc::Sender<DataBlock>,
     ) -> Result<(ExecuteStateRef, DataSchemaRef)> {
         let sql = &request.sql;
-        let session = session_manager.create_session("http-statement")?;
         let context = session.create_context().await?;
+        if let Some(database) = &request.session.database {
+            context.set_current_database(database.clone())?;
+        }
+        if let Some(settings) = &request.session.settings {
+            context.apply_query_settings(settings)?;
+        }
         context.attach_query_str(sql);
 
         let plan = PlanParser::create(context.clone()).build_from_sql(sql)?;
@@ -156,9 +182,9 @@ impl ExecuteState {
     pub(crate) async fn stop(this: &ExecuteStateRef, reason: Result<()>) {
         let mut guard = this.write().await;
         if let Running(r) = &guard.state {
-            // release session
+            // release the query of the session, which may be kept for the following queries
             let progress = Some(r.context.get_progress_value());
-            r.session.force_kill_session();
+            r.session.force_kill_query();
             guard.state = Stopped(ExecuteStopped { progress, reason });
         };
     }
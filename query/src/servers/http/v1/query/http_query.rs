@@ -20,6 +20,7 @@ use common_base::ProgressValues;
 use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_management::UserInfo;
 
 use crate::servers::http::v1::query::execute_state::ExecuteState;
 use crate::servers::http::v1::query::execute_state::ExecuteStateRef;
@@ -27,7 +28,7 @@ use crate::servers::http::v1::query::execute_state::HttpQueryRequest;
 use crate::servers::http::v1::query::result_data_manager::ResponseData;
 use crate::servers::http::v1::query::result_data_manager::ResultDataManager;
 use crate::servers::http::v1::query::result_data_manager::Wait;
-use crate::sessions::SessionManagerRef;
+use crate::sessions::SessionRef;
 
 pub struct ResponseInitialState {
     pub schema: Option<DataSchemaRef>,
@@ -40,6 +41,8 @@ pub struct ResponseState {
 }
 
 pub struct HttpQueryResponseInternal {
+    /// The id of the session kept on the server, if any.
+    pub session_id: Option<String>,
     pub data: Option<ResponseData>,
     pub initial_state: Option<ResponseInitialState>,
     pub state: ResponseState,
@@ -47,6 +50,9 @@ pub struct HttpQueryResponseInternal {
 
 pub struct HttpQuery {
     pub(crate) id: String,
+    // the user who started the query, the only one allowed to see or kill it
+    owner: UserInfo,
+    session_id: Option<String>,
     #[allow(dead_code)]
     request: HttpQueryRequest,
     state: ExecuteStateRef,
@@ -59,15 +65,22 @@ impl HttpQuery {
     pub(crate) async fn try_create(
         id: String,
         request: HttpQueryRequest,
-        session_manager: &SessionManagerRef,
+        session: SessionRef,
+        owner: UserInfo,
     ) -> Result<HttpQueryRef> {
         //TODO(youngsofun): support config/set channel size
         let (block_tx, block_rx) = mpsc::channel(10);
+        let session_id = match request.keeps_session() {
+            true => Some(session.get_id()),
+            false => None,
+        };
 
-        let (state, schema) = ExecuteState::try_create(&request, session_manager, block_tx).await?;
+        let (state, schema) = ExecuteState::try_create(&request, session, block_tx).await?;
         let data = Arc::new(TokioMutex::new(ResultDataManager::new(schema, block_rx)));
         let query = HttpQuery {
             id,
+            owner,
+            session_id,
             request,
             state,
             data,
@@ -76,6 +89,10 @@ impl HttpQuery {
         Ok(query)
     }
 
+    pub fn is_owned_by(&self, user: &UserInfo) -> bool {
+        self.owner.name == user.name && self.owner.hostname == user.hostname
+    }
+
     pub async fn get_response_page(
         &self,
         page_no: usize,
@@ -83,6 +100,7 @@ impl HttpQuery {
         init: bool,
     ) -> Result<HttpQueryResponseInternal> {
         Ok(HttpQueryResponseInternal {
+            session_id: self.session_id.clone(),
             data: Some(self.get_page(page_no, wait).await?),
             initial_state: if init {
                 Some(self.get_initial_state().await)
@@ -95,6 +113,7 @@ impl HttpQuery {
 
     pub async fn get_response_state_only(&self) -> HttpQueryResponseInternal {
         HttpQueryResponseInternal {
+            session_id: self.session_id.clone(),
             data: None,
             initial_state: None,
             state: self.get_state().await,
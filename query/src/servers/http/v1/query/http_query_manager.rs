@@ -13,19 +13,49 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
+use common_base::tokio;
 use common_base::tokio::sync::RwLock;
+use common_exception::ErrorCode;
 use common_exception::Result;
+use common_management::UserInfo;
 
 use crate::configs::Config;
 use crate::servers::http::v1::query::execute_state::HttpQueryRequest;
 use crate::servers::http::v1::query::http_query::HttpQuery;
 use crate::servers::http::v1::query::http_query::HttpQueryRef;
 use crate::sessions::SessionManagerRef;
+use crate::sessions::SessionRef;
+
+/// A session kept on the server for the following queries.
+struct HttpSession {
+    session: SessionRef,
+    max_idle_time: Duration,
+    last_access: Instant,
+    // set from checking that the session is idle until its query is started
+    claimed: Arc<AtomicBool>,
+}
+
+/// A kept session claimed for a query, released once the query is started (or failed to),
+/// after that the running query keeps the others out.
+struct SessionClaim {
+    claimed: Arc<AtomicBool>,
+}
+
+impl Drop for SessionClaim {
+    fn drop(&mut self) {
+        self.claimed.store(false, Ordering::Release);
+    }
+}
 
 pub struct HttpQueryManager {
     pub(crate) queries: Arc<RwLock<HashMap<String, HttpQueryRef>>>,
+    sessions: Arc<RwLock<HashMap<String, HttpSession>>>,
 }
 
 pub type HttpQueryManagerRef = Arc<HttpQueryManager>;
@@ -34,6 +64,7 @@ impl HttpQueryManager {
     pub async fn create_global(_cfg: Config) -> Result<HttpQueryManagerRef> {
         Ok(Arc::new(HttpQueryManager {
             queries: Arc::new(RwLock::new(HashMap::new())),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
         }))
     }
 
@@ -45,9 +76,15 @@ impl HttpQueryManager {
         self: &Arc<Self>,
         req: HttpQueryRequest,
         session_manager: &SessionManagerRef,
+        user: UserInfo,
     ) -> Result<HttpQueryRef> {
+        let (session, claim) = self
+            .get_or_create_session(&req, session_manager, user.clone())
+            .await?;
         let query_id = self.next_query_id();
-        let query = HttpQuery::try_create(query_id.clone(), req, session_manager).await?;
+        let query = HttpQuery::try_create(query_id.clone(), req, session, user).await;
+        drop(claim);
+        let query = query?;
         self.queries
             .write()
             .await
@@ -55,9 +92,116 @@ impl HttpQueryManager {
         Ok(query)
     }
 
-    pub(crate) async fn get_query_by_id(self: &Arc<Self>, query_id: &str) -> Option<HttpQueryRef> {
+    async fn get_or_create_session(
+        self: &Arc<Self>,
+        req: &HttpQueryRequest,
+        session_manager: &SessionManagerRef,
+        user: UserInfo,
+    ) -> Result<(SessionRef, Option<SessionClaim>)> {
+        if let Some(session_id) = &req.session_id {
+            let (session, claim) = self.claim_session(session_id, &user).await?;
+            return Ok((session, Some(claim)));
+        }
+
+        let session = session_manager.create_session("http-statement")?;
+        session.set_current_user(user);
+        if let Some(secs) = req.session.keep_server_session_secs {
+            if secs > 0 {
+                self.add_session(session.clone(), Duration::from_secs(secs))
+                    .await;
+            }
+        }
+        Ok((session, None))
+    }
+
+    /// Claims the kept session for a query, only one query runs in a session at a time.
+    async fn claim_session(
+        self: &Arc<Self>,
+        session_id: &str,
+        user: &UserInfo,
+    ) -> Result<(SessionRef, SessionClaim)> {
+        let mut sessions = self.sessions.write().await;
+        let s = match sessions.get_mut(session_id) {
+            // the sessions of the other users are not visible
+            Some(s) if s.session.get_current_user().as_ref() == Some(user) => s,
+            _ => {
+                return Err(ErrorCode::UnknownSession(format!(
+                    "Session {} is not found or expired",
+                    session_id
+                )))
+            }
+        };
+        s.last_access = Instant::now();
+
+        let busy =
+            || ErrorCode::BadArguments(format!("Session {} is running another query", session_id));
+        if s.claimed
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(busy());
+        }
+        // released on return, if the session is still running the previous query
+        let claim = SessionClaim {
+            claimed: s.claimed.clone(),
+        };
+        if s.session.is_running_query() {
+            return Err(busy());
+        }
+        Ok((s.session.clone(), claim))
+    }
+
+    /// Keeps the session until it is idle for `max_idle_time`.
+    async fn add_session(self: &Arc<Self>, session: SessionRef, max_idle_time: Duration) {
+        let session_id = session.get_id();
+        self.sessions
+            .write()
+            .await
+            .insert(session_id.clone(), HttpSession {
+                session,
+                max_idle_time,
+                last_access: Instant::now(),
+                claimed: Arc::new(AtomicBool::new(false)),
+            });
+
+        let sessions = self.sessions.clone();
+        tokio::spawn(async move {
+            let mut deadline = Instant::now() + max_idle_time;
+            loop {
+                tokio::time::sleep_until(deadline.into()).await;
+                let mut sessions = sessions.write().await;
+                match sessions.get_mut(&session_id) {
+                    // the session is not idle until its query is finished
+                    Some(s)
+                        if s.session.is_running_query() || s.claimed.load(Ordering::Acquire) =>
+                    {
+                        s.last_access = Instant::now();
+                        deadline = s.last_access + s.max_idle_time;
+                    }
+                    Some(s) if s.last_access + s.max_idle_time > Instant::now() => {
+                        deadline = s.last_access + s.max_idle_time;
+                    }
+                    _ => {
+                        sessions.remove(&session_id);
+                        log::debug!("Drop the idle http session {}", session_id);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    /// The query of the id started by the user, the queries of the others are not visible.
+    pub(crate) async fn get_query_by_id(
+        self: &Arc<Self>,
+        query_id: &str,
+        user: &UserInfo,
+    ) -> Option<HttpQueryRef> {
         let queries = self.queries.read().await;
-        queries.get(query_id).map(|q| q.to_owned())
+        queries
+            .get(query_id)
+            .filter(|q| q.is_owned_by(user))
+            .map(|q| q.to_owned())
     }
 
     pub(crate) async fn remove_query_by_id(self: &Arc<Self>, query_id: &str) {
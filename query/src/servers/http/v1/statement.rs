@@ -17,6 +17,7 @@ use std::collections::HashMap;
 use common_base::ProgressValues;
 use common_datavalues::DataSchemaRef;
 use common_exception::Result;
+use common_management::UserInfo;
 use common_streams::SendableDataBlockStream;
 use futures::StreamExt;
 use hyper::http::header;
@@ -106,8 +107,13 @@ impl HttpQuery {
         }
     }
 
-    async fn start(&mut self, session_manager: SessionManagerRef) -> Result<HttpQueryState> {
+    async fn start(
+        &mut self,
+        session_manager: SessionManagerRef,
+        user: UserInfo,
+    ) -> Result<HttpQueryState> {
         let session = session_manager.create_session("http-statement")?;
+        session.set_current_user(user);
         let ctx = session.create_context().await?;
         if self.db.is_some() && !self.db.clone().unwrap().is_empty() {
             ctx.set_current_database(self.db.clone().unwrap())?;
@@ -125,8 +131,12 @@ impl HttpQuery {
         Ok(state)
     }
 
-    async fn initial_result(&mut self, session_manager: SessionManagerRef) -> HttpQueryResult {
-        let state = self.start(session_manager).await;
+    async fn initial_result(
+        &mut self,
+        session_manager: SessionManagerRef,
+        user: UserInfo,
+    ) -> HttpQueryResult {
+        let state = self.start(session_manager, user).await;
         let mut result = HttpQueryResult::create(self.id.clone());
         match state {
             Ok(st) => {
@@ -172,6 +182,7 @@ impl HttpQueryState {
 #[poem::handler]
 pub(crate) async fn statement_handler(
     sessions_extension: Data<&SessionManagerRef>,
+    user: Data<&UserInfo>,
     sql: String,
     Query(params): Query<HashMap<String, String>>,
 ) -> impl IntoResponse {
@@ -179,7 +190,7 @@ pub(crate) async fn statement_handler(
     let query_id = uuid::Uuid::new_v4().to_string();
     let db = params.get("db");
     let mut query = HttpQuery::new(query_id, sql, db.cloned());
-    query.initial_result(session_manager).await
+    query.initial_result(session_manager, user.0.clone()).await
 }
 
 pub fn statement_router() -> impl Endpoint {
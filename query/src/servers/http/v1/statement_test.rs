@@ -14,6 +14,7 @@
 
 use common_base::tokio;
 use common_exception::Result;
+use poem::http::header;
 use poem::http::Method;
 use poem::http::StatusCode;
 use poem::post;
@@ -23,6 +24,7 @@ use poem::Request;
 use poem::Route;
 use pretty_assertions::assert_eq;
 
+use crate::servers::http::v1::http_auth::HTTPAuthMiddleware;
 use crate::servers::http::v1::statement::statement_handler;
 use crate::servers::http::v1::statement::HttpQueryResult;
 use crate::tests::SessionManagerBuilder;
@@ -70,6 +72,7 @@ async fn test_sql(
     let sessions = SessionManagerBuilder::create().build()?;
     let cluster_router = Route::new()
         .at(path, post(statement_handler))
+        .with(HTTPAuthMiddleware::create(sessions.clone()))
         .data(sessions);
     let uri = match database {
         Some(db) => format!("{}?db={:}", path, db),
@@ -80,6 +83,8 @@ async fn test_sql(
             Request::builder()
                 .uri(uri.parse().unwrap())
                 .method(Method::POST)
+                // root without password
+                .header(header::AUTHORIZATION, "Basic cm9vdDo=")
                 .body(sql),
         )
         .await;
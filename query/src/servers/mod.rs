@@ -15,7 +15,10 @@
 // The servers module used for external communication with user, such as MySQL wired protocol, etc.
 
 pub use clickhouse::ClickHouseHandler;
+pub use http::ClickHouseHttpHandler;
 pub use http::HttpHandler;
+pub use postgres::scram_sha256_verifier;
+pub use postgres::PostgresHandler;
 pub use server::Server;
 pub use server::ShutdownHandle;
 
@@ -25,4 +28,5 @@ pub use self::mysql::MySQLHandler;
 mod clickhouse;
 pub mod http;
 mod mysql;
+mod postgres;
 pub(crate) mod server;
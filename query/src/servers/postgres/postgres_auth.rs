@@ -0,0 +1,281 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use hmac::Hmac;
+use hmac::Mac;
+use hmac::NewMac;
+use rand::Rng;
+use sha2::Digest;
+use sha2::Sha256;
+
+pub const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+const SCRAM_ITERATIONS: u32 = 4096;
+
+/// How the users with plaintext passwords are authenticated, the users with hashed passwords
+/// are always authenticated by SCRAM, against the verifier kept since they were created.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostgresAuthMethod {
+    Md5,
+    ScramSha256,
+}
+
+impl PostgresAuthMethod {
+    pub fn try_from_name(name: &str) -> Result<PostgresAuthMethod> {
+        match name.to_ascii_lowercase().as_str() {
+            "md5" => Ok(PostgresAuthMethod::Md5),
+            "scram-sha-256" => Ok(PostgresAuthMethod::ScramSha256),
+            _ => Err(ErrorCode::BadOption(format!(
+                "Unknown PostgreSQL auth method {}, expect md5 or scram-sha-256",
+                name
+            ))),
+        }
+    }
+}
+
+/// The response to `AuthenticationMD5Password`: `md5` + md5(md5(password + user) + salt).
+pub fn md5_password(user: &str, password: &[u8], salt: &[u8; 4]) -> String {
+    let mut inner = password.to_vec();
+    inner.extend_from_slice(user.as_bytes());
+    let inner = format!("{:x}", md5::compute(inner));
+
+    let mut outer = inner.into_bytes();
+    outer.extend_from_slice(salt);
+    format!("md5{:x}", md5::compute(outer))
+}
+
+/// Compares the secrets in a time independent of where they differ.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// PBKDF2 with HMAC-SHA-256, of one block as long as the hash.
+fn salted_password(password: &[u8], salt: &[u8], iterations: u32) -> Vec<u8> {
+    let mut first = salt.to_vec();
+    first.extend_from_slice(&1u32.to_be_bytes());
+
+    let mut u = hmac_sha256(password, &first);
+    let mut result = u.clone();
+    for _ in 1..iterations {
+        u = hmac_sha256(password, &u);
+        result.iter_mut().zip(u.iter()).for_each(|(r, v)| *r ^= v);
+    }
+    result
+}
+
+/// The SCRAM-SHA-256 verifier of a password, kept with the user to authenticate it without
+/// the password, in the format of RFC 5803:
+/// `SCRAM-SHA-256$<iterations>:<salt>$<StoredKey>:<ServerKey>`.
+pub fn scram_sha256_verifier(password: &[u8]) -> String {
+    let salt = rand::thread_rng().gen::<[u8; 16]>();
+    ScramVerifier::create(password, &salt, SCRAM_ITERATIONS).encode()
+}
+
+struct ScramVerifier {
+    iterations: u32,
+    salt: Vec<u8>,
+    stored_key: Vec<u8>,
+    server_key: Vec<u8>,
+}
+
+impl ScramVerifier {
+    fn create(password: &[u8], salt: &[u8], iterations: u32) -> ScramVerifier {
+        let salted_password = salted_password(password, salt, iterations);
+        let client_key = hmac_sha256(&salted_password, b"Client Key");
+        ScramVerifier {
+            iterations,
+            salt: salt.to_vec(),
+            stored_key: Sha256::digest(&client_key).to_vec(),
+            server_key: hmac_sha256(&salted_password, b"Server Key"),
+        }
+    }
+
+    fn encode(&self) -> String {
+        format!(
+            "{}${}:{}${}:{}",
+            SCRAM_SHA_256,
+            self.iterations,
+            base64::encode(&self.salt),
+            base64::encode(&self.stored_key),
+            base64::encode(&self.server_key)
+        )
+    }
+
+    fn decode(verifier: &str) -> Result<ScramVerifier> {
+        let bad_verifier = || ErrorCode::AuthenticateFailure("Invalid SCRAM-SHA-256 verifier");
+        let decode = |v: &str| base64::decode(v).map_err(|_| bad_verifier());
+
+        let parts = verifier.split('$').collect::<Vec<_>>();
+        match parts.as_slice() {
+            [SCRAM_SHA_256, salt, keys] => {
+                let (iterations, salt) = salt.split_once(':').ok_or_else(bad_verifier)?;
+                let (stored_key, server_key) = keys.split_once(':').ok_or_else(bad_verifier)?;
+                Ok(ScramVerifier {
+                    iterations: iterations.parse().map_err(|_| bad_verifier())?,
+                    salt: decode(salt)?,
+                    stored_key: decode(stored_key)?,
+                    server_key: decode(server_key)?,
+                })
+            }
+            _ => Err(bad_verifier()),
+        }
+    }
+}
+
+fn scram_error(message: &str) -> ErrorCode {
+    ErrorCode::AuthenticateFailure(format!("Invalid SCRAM-SHA-256 message, {}", message))
+}
+
+fn server_nonce() -> String {
+    base64::encode(rand::thread_rng().gen::<[u8; 18]>())
+}
+
+/// The server side of a SCRAM-SHA-256 exchange (RFC 5802 and RFC 7677), without channel
+/// binding.
+pub struct ScramSha256 {
+    verifier: ScramVerifier,
+    server_nonce: String,
+    gs2_header: String,
+    client_first_bare: String,
+    server_first: String,
+    nonce: String,
+}
+
+impl ScramSha256 {
+    pub fn create(password: &[u8]) -> ScramSha256 {
+        let salt = rand::thread_rng().gen::<[u8; 16]>();
+        Self::with_salt_and_nonce(password, &salt, &server_nonce(), SCRAM_ITERATIONS)
+    }
+
+    /// Authenticates against a verifier of `scram_sha256_verifier`, instead of the password.
+    pub fn from_verifier(verifier: &str) -> Result<ScramSha256> {
+        Ok(Self::with_verifier(
+            ScramVerifier::decode(verifier)?,
+            &server_nonce(),
+        ))
+    }
+
+    pub fn with_salt_and_nonce(
+        password: &[u8],
+        salt: &[u8],
+        server_nonce: &str,
+        iterations: u32,
+    ) -> ScramSha256 {
+        let verifier = ScramVerifier::create(password, salt, iterations);
+        Self::with_verifier(verifier, server_nonce)
+    }
+
+    fn with_verifier(verifier: ScramVerifier, server_nonce: &str) -> ScramSha256 {
+        ScramSha256 {
+            verifier,
+            server_nonce: server_nonce.to_string(),
+            gs2_header: String::new(),
+            client_first_bare: String::new(),
+            server_first: String::new(),
+            nonce: String::new(),
+        }
+    }
+
+    /// Answers the client-first-message with the server-first-message.
+    pub fn server_first(&mut self, client_first: &[u8]) -> Result<Vec<u8>> {
+        let client_first =
+            std::str::from_utf8(client_first).map_err(|_| scram_error("not UTF-8"))?;
+
+        // gs2-header: the channel binding flag and the authzid, then the bare message.
+        let mut parts = client_first.splitn(3, ',');
+        let flag = match parts.next() {
+            Some(flag @ "n") | Some(flag @ "y") => flag,
+            Some(flag) if flag.starts_with("p=") => {
+                return Err(scram_error("channel binding is not supported"))
+            }
+            _ => return Err(scram_error("bad gs2 header")),
+        };
+        let authzid = parts.next().ok_or_else(|| scram_error("bad gs2 header"))?;
+        let bare = parts.next().ok_or_else(|| scram_error("bad gs2 header"))?;
+
+        let client_nonce = bare
+            .split(',')
+            .find_map(|attr| attr.strip_prefix("r="))
+            .ok_or_else(|| scram_error("missing nonce"))?;
+
+        self.nonce = format!("{}{}", client_nonce, self.server_nonce);
+        self.gs2_header = format!("{},{},", flag, authzid);
+        self.client_first_bare = bare.to_string();
+        self.server_first = format!(
+            "r={},s={},i={}",
+            self.nonce,
+            base64::encode(&self.verifier.salt),
+            self.verifier.iterations
+        );
+        Ok(self.server_first.clone().into_bytes())
+    }
+
+    /// Verifies the proof of the client-final-message, answers it with the server-final-message.
+    pub fn server_final(&self, client_final: &[u8]) -> Result<Vec<u8>> {
+        let client_final =
+            std::str::from_utf8(client_final).map_err(|_| scram_error("not UTF-8"))?;
+        let (without_proof, proof) = client_final
+            .rsplit_once(",p=")
+            .ok_or_else(|| scram_error("missing proof"))?;
+
+        // the channel binding is the gs2-header of the client-first-message, without the data
+        let channel_binding = without_proof
+            .split(',')
+            .find_map(|attr| attr.strip_prefix("c="))
+            .ok_or_else(|| scram_error("missing channel binding"))?;
+        if channel_binding != base64::encode(&self.gs2_header) {
+            return Err(scram_error("channel binding mismatch"));
+        }
+
+        let nonce = without_proof
+            .split(',')
+            .find_map(|attr| attr.strip_prefix("r="))
+            .ok_or_else(|| scram_error("missing nonce"))?;
+        if nonce != self.nonce {
+            return Err(scram_error("nonce mismatch"));
+        }
+        let proof = base64::decode(proof).map_err(|_| scram_error("bad proof"))?;
+
+        let auth_message = format!(
+            "{},{},{}",
+            self.client_first_bare, self.server_first, without_proof
+        );
+
+        let stored_key = &self.verifier.stored_key;
+        let client_signature = hmac_sha256(stored_key, auth_message.as_bytes());
+        if proof.len() != client_signature.len() {
+            return Err(scram_error("bad proof"));
+        }
+        let recovered_key = proof
+            .iter()
+            .zip(client_signature.iter())
+            .map(|(p, s)| p ^ s)
+            .collect::<Vec<_>>();
+        if !constant_time_eq(Sha256::digest(&recovered_key).as_slice(), stored_key) {
+            return Err(ErrorCode::AuthenticateFailure(
+                "Password authentication failed",
+            ));
+        }
+
+        let server_signature = hmac_sha256(&self.verifier.server_key, auth_message.as_bytes());
+        Ok(format!("v={}", base64::encode(server_signature)).into_bytes())
+    }
+}
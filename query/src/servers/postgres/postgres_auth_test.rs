@@ -0,0 +1,140 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::servers::postgres::postgres_auth::constant_time_eq;
+use crate::servers::postgres::postgres_auth::md5_password;
+use crate::servers::postgres::postgres_auth::scram_sha256_verifier;
+use crate::servers::postgres::postgres_auth::PostgresAuthMethod;
+use crate::servers::postgres::postgres_auth::ScramSha256;
+
+#[test]
+fn test_auth_method() -> Result<()> {
+    assert_eq!(
+        PostgresAuthMethod::try_from_name("md5")?,
+        PostgresAuthMethod::Md5
+    );
+    assert_eq!(
+        PostgresAuthMethod::try_from_name("SCRAM-SHA-256")?,
+        PostgresAuthMethod::ScramSha256
+    );
+
+    match PostgresAuthMethod::try_from_name("trust") {
+        Ok(_) => panic!("trust must be rejected"),
+        Err(e) => assert_eq!(e.code(), ErrorCode::BadOption("").code()),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_md5_password() {
+    assert_eq!(
+        md5_password("pg-user", b"pg-pwd", &[1, 2, 3, 4]),
+        "md5d3595956bd750d57277c8f8fe6263ef3"
+    );
+}
+
+#[test]
+fn test_constant_time_eq() {
+    assert!(constant_time_eq(b"", b""));
+    assert!(constant_time_eq(b"md5abc", b"md5abc"));
+    assert!(!constant_time_eq(b"md5abc", b"md5abd"));
+    assert!(!constant_time_eq(b"md5abc", b"md5ab"));
+}
+
+// The example of RFC 7677.
+const SALT: &str = "W22ZaJ0SNY7soEsUEjb6gQ==";
+const SERVER_NONCE: &str = "%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0";
+const CLIENT_FIRST: &str = "n,,n=user,r=rOprNGfwEbeRWgbNEkqO";
+const CLIENT_FINAL_WITHOUT_PROOF: &str =
+    "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0";
+
+fn rfc_scram() -> Result<ScramSha256> {
+    let salt = base64::decode(SALT).unwrap();
+    let mut scram = ScramSha256::with_salt_and_nonce(b"pencil", &salt, SERVER_NONCE, 4096);
+    let server_first = scram.server_first(CLIENT_FIRST.as_bytes())?;
+    assert_eq!(
+        String::from_utf8(server_first).unwrap(),
+        format!("r=rOprNGfwEbeRWgbNEkqO{},s={},i=4096", SERVER_NONCE, SALT)
+    );
+    Ok(scram)
+}
+
+#[test]
+fn test_scram_sha_256() -> Result<()> {
+    let scram = rfc_scram()?;
+    let client_final = format!(
+        "{},p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ=",
+        CLIENT_FINAL_WITHOUT_PROOF
+    );
+    let server_final = scram.server_final(client_final.as_bytes())?;
+    assert_eq!(
+        String::from_utf8(server_final).unwrap(),
+        "v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4="
+    );
+    Ok(())
+}
+
+#[test]
+fn test_scram_sha_256_wrong_proof() -> Result<()> {
+    let scram = rfc_scram()?;
+    let client_final = format!(
+        "{},p=AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+        CLIENT_FINAL_WITHOUT_PROOF
+    );
+    match scram.server_final(client_final.as_bytes()) {
+        Ok(_) => panic!("the wrong proof must be rejected"),
+        Err(e) => assert_eq!(e.code(), ErrorCode::AuthenticateFailure("").code()),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_scram_sha_256_channel_binding_mismatch() -> Result<()> {
+    let scram = rfc_scram()?;
+    // base64("y,,") instead of base64("n,,") of the client-first-message
+    let client_final = format!(
+        "{},p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ=",
+        CLIENT_FINAL_WITHOUT_PROOF.replace("c=biws", "c=eSws")
+    );
+    match scram.server_final(client_final.as_bytes()) {
+        Ok(_) => panic!("the mismatched channel binding must be rejected"),
+        Err(e) => assert_eq!(e.code(), ErrorCode::AuthenticateFailure("").code()),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_scram_sha_256_verifier() -> Result<()> {
+    let verifier = scram_sha256_verifier(b"pencil");
+    assert!(verifier.starts_with("SCRAM-SHA-256$4096:"));
+
+    let mut scram = ScramSha256::from_verifier(&verifier)?;
+    let server_first = scram.server_first(CLIENT_FIRST.as_bytes())?;
+    let server_first = String::from_utf8(server_first).unwrap();
+    assert!(server_first.starts_with("r=rOprNGfwEbeRWgbNEkqO"));
+    assert!(server_first.ends_with(",i=4096"));
+
+    assert!(ScramSha256::from_verifier("SCRAM-SHA-256$4096:salt").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_scram_sha_256_channel_binding() {
+    let mut scram = ScramSha256::create(b"pencil");
+    let client_first = "p=tls-server-end-point,,n=user,r=rOprNGfwEbeRWgbNEkqO";
+    assert!(scram.server_first(client_first.as_bytes()).is_err());
+}
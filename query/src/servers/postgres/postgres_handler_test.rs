@@ -0,0 +1,415 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+use common_base::tokio;
+use common_base::tokio::io::AsyncReadExt;
+use common_base::tokio::io::AsyncWriteExt;
+use common_base::tokio::net::TcpStream;
+use common_exception::Result;
+use common_management::UserInfo;
+use common_meta_types::AuthType;
+use hmac::Hmac;
+use hmac::Mac;
+use hmac::NewMac;
+use pretty_assertions::assert_eq;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::servers::postgres::postgres_auth::md5_password;
+use crate::servers::postgres::postgres_auth::scram_sha256_verifier;
+use crate::servers::postgres::postgres_interactive_worker::bind_parameters;
+use crate::servers::postgres::postgres_interactive_worker::param_literal;
+use crate::servers::postgres::postgres_interactive_worker::split_statements;
+use crate::servers::PostgresHandler;
+use crate::servers::Server;
+use crate::sessions::SessionManagerRef;
+use crate::tests::SessionManagerBuilder;
+use crate::users::User;
+
+type Message = (u8, Vec<u8>);
+
+async fn start_handler(sessions: SessionManagerRef) -> Result<(Box<dyn Server>, u16)> {
+    let mut handler = PostgresHandler::create(sessions);
+    let listening = "0.0.0.0:0".parse::<SocketAddr>()?;
+    let listening = handler.start(listening).await?;
+    Ok((handler, listening.port()))
+}
+
+async fn send(stream: &mut TcpStream, tag: u8, body: &[u8]) -> Result<()> {
+    let mut message = vec![tag];
+    message.extend_from_slice(&(body.len() as i32 + 4).to_be_bytes());
+    message.extend_from_slice(body);
+    stream.write_all(&message).await?;
+    Ok(())
+}
+
+async fn receive(stream: &mut TcpStream) -> Result<Message> {
+    let tag = stream.read_u8().await?;
+    let len = stream.read_i32().await? as usize;
+    let mut body = vec![0; len - 4];
+    stream.read_exact(&mut body).await?;
+    Ok((tag, body))
+}
+
+async fn receive_until_ready(stream: &mut TcpStream) -> Result<Vec<Message>> {
+    let mut messages = vec![];
+    loop {
+        let message = receive(stream).await?;
+        let ready = message.0 == b'Z';
+        messages.push(message);
+        if ready {
+            return Ok(messages);
+        }
+    }
+}
+
+async fn connect(port: u16, user: &str) -> Result<TcpStream> {
+    let mut stream = TcpStream::connect(format!("127.0.0.1:{}", port)).await?;
+    let mut body = 196608_i32.to_be_bytes().to_vec();
+    body.extend_from_slice(format!("user\0{}\0database\0default\0\0", user).as_bytes());
+    stream
+        .write_all(&(body.len() as i32 + 4).to_be_bytes())
+        .await?;
+    stream.write_all(&body).await?;
+    Ok(stream)
+}
+
+fn tags(messages: &[Message]) -> String {
+    messages.iter().map(|(tag, _)| *tag as char).collect()
+}
+
+fn cstr(value: &str) -> Vec<u8> {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.push(0);
+    bytes
+}
+
+// The text of the single column of a DataRow.
+fn data_row_value(body: &[u8]) -> String {
+    assert_eq!(&body[..2], &[0, 1]);
+    String::from_utf8(body[6..].to_vec()).unwrap()
+}
+
+// The SQLSTATE of an ErrorResponse.
+fn error_code(body: &[u8]) -> String {
+    let fields = String::from_utf8(body.to_vec()).unwrap();
+    fields
+        .split('\0')
+        .find_map(|field| field.strip_prefix('C'))
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_postgres_simple_query() -> Result<()> {
+    let (_handler, port) = start_handler(SessionManagerBuilder::create().build()?).await?;
+    let mut stream = connect(port, "root").await?;
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(messages[0], (b'R', vec![0, 0, 0, 0]));
+    assert_eq!(messages.last().unwrap(), &(b'Z', vec![b'I']));
+
+    let query = "SELECT number FROM numbers(3); SELECT 'databend' AS name;";
+    send(&mut stream, b'Q', &cstr(query)).await?;
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(tags(&messages), "TDDDCTDCZ");
+    assert_eq!(data_row_value(&messages[3].1), "2");
+    assert_eq!(messages[4].1, cstr("SELECT 3"));
+    assert_eq!(data_row_value(&messages[6].1), "databend");
+
+    send(&mut stream, b'Q', &cstr(" ; ")).await?;
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(tags(&messages), "IZ");
+
+    send(
+        &mut stream,
+        b'Q',
+        &cstr("SELECT * FROM not_exists; SELECT 1"),
+    )
+    .await?;
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(tags(&messages), "EZ");
+    assert_eq!(error_code(&messages[0].1), "42P01");
+
+    send(&mut stream, b'Q', &cstr("SET extra_float_digits = 3")).await?;
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(messages[0], (b'C', cstr("SET")));
+
+    send(
+        &mut stream,
+        b'Q',
+        &cstr("CREATE TABLE t(a Int32) Engine = Memory"),
+    )
+    .await?;
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(messages[0], (b'C', cstr("CREATE TABLE")));
+
+    send(&mut stream, b'Q', &cstr("INSERT INTO t VALUES (1), (2)")).await?;
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(messages[0], (b'C', cstr("INSERT 0 2")));
+
+    send(&mut stream, b'X', &[]).await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_postgres_extended_query() -> Result<()> {
+    let (_handler, port) = start_handler(SessionManagerBuilder::create().build()?).await?;
+    let mut stream = connect(port, "root").await?;
+    receive_until_ready(&mut stream).await?;
+
+    // Parse, bind the text parameter 3, describe and execute two rows at a time.
+    let mut parse = cstr("s1");
+    parse.extend(cstr("SELECT number FROM numbers($1)"));
+    parse.extend_from_slice(&[0, 1, 0, 0, 0, 20]);
+    send(&mut stream, b'P', &parse).await?;
+
+    let mut bind = cstr("p1");
+    bind.extend(cstr("s1"));
+    bind.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 1, b'3', 0, 0]);
+    send(&mut stream, b'B', &bind).await?;
+    send(&mut stream, b'D', &[&[b'P'][..], &cstr("p1")[..]].concat()).await?;
+    let mut execute = cstr("p1");
+    execute.extend_from_slice(&2_i32.to_be_bytes());
+    send(&mut stream, b'E', &execute).await?;
+    send(&mut stream, b'E', &execute).await?;
+    send(&mut stream, b'S', &[]).await?;
+
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(tags(&messages), "12TDDsDCZ");
+    assert_eq!(data_row_value(&messages[6].1), "2");
+    assert_eq!(messages[7].1, cstr("SELECT 3"));
+
+    // The messages after an error are skipped until Sync.
+    let mut bind = cstr("p2");
+    bind.extend(cstr("unknown"));
+    bind.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+    send(&mut stream, b'B', &bind).await?;
+    send(&mut stream, b'E', &execute).await?;
+    send(&mut stream, b'S', &[]).await?;
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(tags(&messages), "EZ");
+
+    // The statement is still there after the error.
+    send(&mut stream, b'D', &[&[b'S'][..], &cstr("s1")[..]].concat()).await?;
+    send(&mut stream, b'S', &[]).await?;
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(tags(&messages), "tTZ");
+    assert_eq!(messages[0].1, vec![0, 1, 0, 0, 0, 20]);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_postgres_md5_auth() -> Result<()> {
+    let sessions = SessionManagerBuilder::create()
+        .postgres_handler_auth_method("md5")
+        .build()?;
+    let user = User::new("pg-user", "%", "pg-pwd", AuthType::PlainText);
+    sessions.get_user_manager().add_user(user.into()).await?;
+    let (_handler, port) = start_handler(sessions).await?;
+
+    for (password, expect) in [("pg-pwd", b'R'), ("wrong-pwd", b'E')] {
+        let mut stream = connect(port, "pg-user").await?;
+        let (tag, body) = receive(&mut stream).await?;
+        assert_eq!(tag, b'R');
+        assert_eq!(&body[..4], &[0, 0, 0, 5]);
+
+        let salt = [body[4], body[5], body[6], body[7]];
+        let response = md5_password("pg-user", password.as_bytes(), &salt);
+        send(&mut stream, b'p', &cstr(&response)).await?;
+
+        let (tag, body) = receive(&mut stream).await?;
+        assert_eq!(tag, expect);
+        match tag {
+            b'R' => assert_eq!(body, vec![0, 0, 0, 0]),
+            _ => assert_eq!(error_code(&body), "28P01"),
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_postgres_auth_by_client_address() -> Result<()> {
+    let sessions = SessionManagerBuilder::create()
+        .postgres_handler_auth_method("md5")
+        .build()?;
+    let user = User::new("pg-local", "localhost", "pg-pwd", AuthType::PlainText);
+    sessions.get_user_manager().add_user(user.into()).await?;
+    let user = User::new("pg-remote", "10.0.0.1", "pg-pwd", AuthType::PlainText);
+    sessions.get_user_manager().add_user(user.into()).await?;
+    let (_handler, port) = start_handler(sessions).await?;
+
+    // the handler is connected from 127.0.0.1, which is localhost but not 10.0.0.1
+    for (user, expect) in [("pg-local", b'R'), ("pg-remote", b'E')] {
+        let mut stream = connect(port, user).await?;
+        let (tag, body) = receive(&mut stream).await?;
+        assert_eq!(tag, expect);
+        if tag == b'R' {
+            let salt = [body[4], body[5], body[6], body[7]];
+            send(
+                &mut stream,
+                b'p',
+                &cstr(&md5_password(user, b"pg-pwd", &salt)),
+            )
+            .await?;
+            let messages = receive_until_ready(&mut stream).await?;
+            assert_eq!(messages[0], (b'R', vec![0, 0, 0, 0]));
+        }
+    }
+    Ok(())
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_postgres_scram_auth() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let user = User::new("pg-user", "%", "pg-pwd", AuthType::PlainText);
+    sessions.get_user_manager().add_user(user.into()).await?;
+    let (_handler, port) = start_handler(sessions).await?;
+
+    scram_auth(port, "pg-user", b"pg-pwd").await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_postgres_hashed_password_auth() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let mut user: UserInfo = User::new("pg-user", "%", "pg-pwd", AuthType::Sha256).into();
+    user.scram_verifier = Some(scram_sha256_verifier(b"pg-pwd"));
+    sessions.get_user_manager().add_user(user).await?;
+    let user = User::new("pg-user-1", "%", "pg-pwd", AuthType::DoubleSha1);
+    sessions.get_user_manager().add_user(user.into()).await?;
+    let (_handler, port) = start_handler(sessions).await?;
+
+    scram_auth(port, "pg-user", b"pg-pwd").await?;
+
+    // Without a verifier the cleartext password is not asked.
+    let mut stream = connect(port, "pg-user-1").await?;
+    let (tag, body) = receive(&mut stream).await?;
+    assert_eq!(tag, b'E');
+    assert_eq!(error_code(&body), "28P01");
+    Ok(())
+}
+
+async fn scram_auth(port: u16, user: &str, password: &[u8]) -> Result<()> {
+    let mut stream = connect(port, user).await?;
+    let (tag, body) = receive(&mut stream).await?;
+    assert_eq!(tag, b'R');
+    assert_eq!(
+        body,
+        [&[0, 0, 0, 10][..], &b"SCRAM-SHA-256\0\0"[..]].concat()
+    );
+
+    let client_first_bare = "n=,r=client-nonce";
+    let mut initial_response = cstr("SCRAM-SHA-256");
+    let client_first = format!("n,,{}", client_first_bare);
+    initial_response.extend_from_slice(&(client_first.len() as i32).to_be_bytes());
+    initial_response.extend_from_slice(client_first.as_bytes());
+    send(&mut stream, b'p', &initial_response).await?;
+
+    let (tag, body) = receive(&mut stream).await?;
+    assert_eq!((tag, &body[..4]), (b'R', &[0, 0, 0, 11][..]));
+    let server_first = String::from_utf8(body[4..].to_vec()).unwrap();
+    let attr = |name: &str| {
+        server_first
+            .split(',')
+            .find_map(|attr| attr.strip_prefix(name))
+            .unwrap()
+            .to_string()
+    };
+    let (nonce, salt, iterations) = (attr("r="), attr("s="), attr("i="));
+    assert!(nonce.starts_with("client-nonce"));
+
+    // Hi(password, salt, i) of RFC 5802
+    let salt = base64::decode(salt).unwrap();
+    let mut u = hmac_sha256(password, &[&salt[..], &[0, 0, 0, 1][..]].concat());
+    let mut salted_password = u.clone();
+    for _ in 1..iterations.parse::<u32>().unwrap() {
+        u = hmac_sha256(password, &u);
+        for (s, u) in salted_password.iter_mut().zip(u.iter()) {
+            *s ^= u;
+        }
+    }
+
+    let without_proof = format!("c=biws,r={}", nonce);
+    let auth_message = format!("{},{},{}", client_first_bare, server_first, without_proof);
+    let client_key = hmac_sha256(&salted_password, b"Client Key");
+    let client_signature = hmac_sha256(&Sha256::digest(&client_key), auth_message.as_bytes());
+    let proof = client_key
+        .iter()
+        .zip(client_signature.iter())
+        .map(|(k, s)| k ^ s)
+        .collect::<Vec<_>>();
+    let client_final = format!("{},p={}", without_proof, base64::encode(proof));
+    send(&mut stream, b'p', client_final.as_bytes()).await?;
+
+    let server_key = hmac_sha256(&salted_password, b"Server Key");
+    let server_signature = hmac_sha256(&server_key, auth_message.as_bytes());
+    let (tag, body) = receive(&mut stream).await?;
+    assert_eq!((tag, &body[..4]), (b'R', &[0, 0, 0, 12][..]));
+    assert_eq!(
+        body[4..].to_vec(),
+        format!("v={}", base64::encode(server_signature)).into_bytes()
+    );
+
+    let messages = receive_until_ready(&mut stream).await?;
+    assert_eq!(messages[0], (b'R', vec![0, 0, 0, 0]));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_postgres_reject_connection() -> Result<()> {
+    let (_handler, port) =
+        start_handler(SessionManagerBuilder::create().max_sessions(1).build()?).await?;
+    let mut stream = connect(port, "root").await?;
+    receive_until_ready(&mut stream).await?;
+
+    let mut rejected = connect(port, "root").await?;
+    let (tag, body) = receive(&mut rejected).await?;
+    assert_eq!(tag, b'E');
+    assert_eq!(error_code(&body), "53300");
+    Ok(())
+}
+
+#[test]
+fn test_split_statements() {
+    assert_eq!(split_statements("SELECT 1; SELECT ';'\n;"), vec![
+        "SELECT 1",
+        "SELECT ';'"
+    ]);
+    assert!(split_statements(" ; ").is_empty());
+}
+
+#[test]
+fn test_bind_parameters() -> Result<()> {
+    let literals = [
+        param_literal(&Some(b"42".to_vec()), 20, 0)?,
+        param_literal(&Some(b"it's".to_vec()), 25, 0)?,
+        param_literal(&None, 25, 0)?,
+        param_literal(&Some(7_i32.to_be_bytes().to_vec()), 23, 1)?,
+    ];
+    let query = bind_parameters("SELECT $1, $2, '$1', $3, $4", |index| {
+        Ok(literals[index - 1].clone())
+    })?;
+    assert_eq!(query, "SELECT 42, 'it''s', '$1', NULL, 7");
+
+    assert!(param_literal(&Some(b"1; DROP TABLE t".to_vec()), 20, 0).is_err());
+    assert!(bind_parameters("SELECT $2", |_| Ok(String::new())).is_ok());
+    Ok(())
+}
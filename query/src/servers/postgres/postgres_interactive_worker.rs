@@ -0,0 +1,843 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::io::Cursor;
+use std::net::SocketAddr;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use common_base::tokio::io::AsyncRead;
+use common_base::tokio::io::AsyncWrite;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_types::AuthType;
+use common_planners::PlanNode;
+use common_streams::SendableDataBlockStream;
+use common_streams::SourceStream;
+use common_streams::ValueSource;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use rand::Rng;
+
+use crate::interpreters::InterpreterFactory;
+use crate::servers::postgres::postgres_auth::constant_time_eq;
+use crate::servers::postgres::postgres_auth::md5_password;
+use crate::servers::postgres::postgres_auth::PostgresAuthMethod;
+use crate::servers::postgres::postgres_auth::ScramSha256;
+use crate::servers::postgres::postgres_auth::SCRAM_SHA_256;
+use crate::servers::postgres::postgres_protocol::BackendMessage;
+use crate::servers::postgres::postgres_protocol::ErrorFields;
+use crate::servers::postgres::postgres_protocol::FrontendMessage;
+use crate::servers::postgres::postgres_protocol::MessageReader;
+use crate::servers::postgres::postgres_protocol::PostgresStream;
+use crate::servers::postgres::postgres_protocol::StartupMessage;
+use crate::servers::postgres::postgres_protocol::FORMAT_BINARY;
+use crate::servers::postgres::postgres_protocol::FORMAT_TEXT;
+use crate::servers::postgres::postgres_protocol::PROTOCOL_VERSION;
+use crate::servers::postgres::postgres_types::encode_value;
+use crate::servers::postgres::postgres_types::field_description;
+use crate::servers::postgres::postgres_types::BOOL_OID;
+use crate::servers::postgres::postgres_types::DATE_OID;
+use crate::servers::postgres::postgres_types::FLOAT4_OID;
+use crate::servers::postgres::postgres_types::FLOAT8_OID;
+use crate::servers::postgres::postgres_types::INT2_OID;
+use crate::servers::postgres::postgres_types::INT4_OID;
+use crate::servers::postgres::postgres_types::INT8_OID;
+use crate::servers::postgres::postgres_types::NUMERIC_OID;
+use crate::servers::postgres::postgres_types::TEXT_OID;
+use crate::servers::postgres::postgres_types::TIMESTAMP_OID;
+use crate::sessions::SessionRef;
+use crate::sql::PlanParser;
+
+// The version reported to the clients, some of them check it before using a feature.
+const SERVER_VERSION: &str = "12.0";
+
+type EncodedRow = Vec<Option<Vec<u8>>>;
+
+struct PreparedStatement {
+    query: String,
+    param_types: Vec<u32>,
+}
+
+struct Portal {
+    query: String,
+    result_formats: Vec<i16>,
+    // The rows left by an execute with a row limit, and the tag sent once they are all sent.
+    suspended: Option<(VecDeque<EncodedRow>, String)>,
+}
+
+struct QueryResult {
+    schema: Option<DataSchemaRef>,
+    blocks: Vec<DataBlock>,
+    tag: String,
+}
+
+pub struct PostgresInteractiveWorker<S> {
+    session: SessionRef,
+    stream: PostgresStream<S>,
+    auth_method: PostgresAuthMethod,
+    client_addr: String,
+    statements: HashMap<String, PreparedStatement>,
+    portals: HashMap<String, Portal>,
+    // After an error of the extended query protocol the messages are discarded until Sync.
+    skip_until_sync: bool,
+}
+
+impl<S> PostgresInteractiveWorker<S>
+where S: AsyncRead + AsyncWrite + Unpin
+{
+    pub fn create(
+        session: SessionRef,
+        stream: S,
+        auth_method: PostgresAuthMethod,
+        client_addr: String,
+    ) -> PostgresInteractiveWorker<S> {
+        PostgresInteractiveWorker {
+            session,
+            stream: PostgresStream::create(stream),
+            auth_method,
+            client_addr,
+            statements: HashMap::new(),
+            portals: HashMap::new(),
+            skip_until_sync: false,
+        }
+    }
+
+    pub async fn run(mut self) -> Result<()> {
+        if !self.startup().await? {
+            return Ok(());
+        }
+
+        while let Some(message) = self.stream.read_message().await? {
+            if self.session.is_aborting() {
+                self.write_error(&ErrorCode::AbortedSession(
+                    "Aborting this connection. because we are try aborting server.",
+                ));
+                return self.stream.flush().await;
+            }
+
+            match message {
+                FrontendMessage::Terminate => break,
+                FrontendMessage::Query(query) => {
+                    self.on_query(&query).await;
+                    self.write_ready_for_query();
+                    self.stream.flush().await?;
+                }
+                FrontendMessage::Sync => {
+                    self.skip_until_sync = false;
+                    self.write_ready_for_query();
+                    self.stream.flush().await?;
+                }
+                FrontendMessage::Flush => self.stream.flush().await?,
+                _ if self.skip_until_sync => {}
+                message => {
+                    if let Err(cause) = self.on_extended_query(message).await {
+                        self.write_error(&cause);
+                        self.skip_until_sync = true;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns false if the connection is to be closed.
+    async fn startup(&mut self) -> Result<bool> {
+        let params = loop {
+            match self.stream.read_startup().await? {
+                StartupMessage::SslRequest | StartupMessage::GssEncRequest => {
+                    self.stream.write_unsupported_encryption().await?
+                }
+                // The queries cannot be cancelled yet, the request is ignored.
+                StartupMessage::CancelRequest { .. } => return Ok(false),
+                StartupMessage::Startup { version, params } => {
+                    if version >> 16 != PROTOCOL_VERSION >> 16 {
+                        self.write_error(&ErrorCode::BadBytes(format!(
+                            "Unsupported frontend protocol {}.{}",
+                            version >> 16,
+                            version & 0xFFFF
+                        )));
+                        self.stream.flush().await?;
+                        return Ok(false);
+                    }
+                    break params;
+                }
+            }
+        };
+
+        let user_name = params.get("user").cloned().unwrap_or_default();
+        if let Err(cause) = self.authenticate(&user_name).await {
+            log::error!(
+                "PostgreSQL handler authenticate failed, \
+                user_name: {}, \
+                client_address: {}, \
+                failure_cause: {}",
+                user_name,
+                self.client_addr,
+                cause
+            );
+            self.write_error(&cause);
+            self.stream.flush().await?;
+            return Ok(false);
+        }
+
+        if let Some(database) = params.get("database") {
+            let context = self.session.create_context().await?;
+            if let Err(cause) = context.set_current_database(database.clone()) {
+                // The clients default the database to the user name, which may not exist.
+                if database != &user_name {
+                    self.write_error(&cause);
+                    self.stream.flush().await?;
+                    return Ok(false);
+                }
+            }
+        }
+
+        let parameters = [
+            ("server_version", SERVER_VERSION),
+            ("server_encoding", "UTF8"),
+            ("client_encoding", "UTF8"),
+            ("DateStyle", "ISO, MDY"),
+            ("TimeZone", "UTC"),
+            ("integer_datetimes", "on"),
+            ("standard_conforming_strings", "on"),
+        ];
+        for (name, value) in parameters {
+            self.stream
+                .write(BackendMessage::ParameterStatus(name, value.to_string()));
+        }
+
+        self.stream.write(BackendMessage::BackendKeyData(
+            rand::random(),
+            rand::random(),
+        ));
+        self.write_ready_for_query();
+        self.stream.flush().await?;
+        Ok(true)
+    }
+
+    async fn authenticate(&mut self, user_name: &str) -> Result<()> {
+        let user_manager = self.session.get_user_manager();
+        let client_ip = match self.client_addr.parse::<SocketAddr>() {
+            Ok(addr) => addr.ip().to_string(),
+            Err(_) => self.client_addr.clone(),
+        };
+        let user_info = user_manager
+            .get_user_by_address(user_name, &client_ip)
+            .await?;
+
+        let authed = match user_info.auth_type {
+            AuthType::None => true,
+            AuthType::PlainText => match self.auth_method {
+                PostgresAuthMethod::Md5 => {
+                    let salt = rand::thread_rng().gen::<[u8; 4]>();
+                    self.stream
+                        .write(BackendMessage::AuthenticationMd5Password(salt));
+                    self.stream.flush().await?;
+                    let response = self.read_password().await?;
+                    let expected = md5_password(user_name, &user_info.password, &salt);
+                    constant_time_eq(&response, expected.as_bytes())
+                }
+                PostgresAuthMethod::ScramSha256 => {
+                    let scram = ScramSha256::create(&user_info.password);
+                    self.authenticate_scram(scram).await?;
+                    true
+                }
+            },
+            // The cleartext password is never asked since the connection is not encrypted,
+            // these users are authenticated against the SCRAM verifier kept with them.
+            AuthType::DoubleSha1 | AuthType::Sha256 => match &user_info.scram_verifier {
+                Some(verifier) => {
+                    let scram = ScramSha256::from_verifier(verifier)?;
+                    self.authenticate_scram(scram).await?;
+                    true
+                }
+                None => {
+                    return Err(ErrorCode::AuthenticateFailure(format!(
+                        "No SCRAM-SHA-256 verifier of user {}, alter its password to log in",
+                        user_name
+                    )))
+                }
+            },
+        };
+
+        if !authed {
+            return Err(ErrorCode::AuthenticateFailure(format!(
+                "Password authentication failed for user {}",
+                user_name
+            )));
+        }
+
+        self.session.set_current_user(user_info);
+        self.stream.write(BackendMessage::AuthenticationOk);
+        Ok(())
+    }
+
+    async fn authenticate_scram(&mut self, mut scram: ScramSha256) -> Result<()> {
+        self.stream
+            .write(BackendMessage::AuthenticationSasl(vec![SCRAM_SHA_256]));
+        self.stream.flush().await?;
+
+        // SASLInitialResponse: the mechanism, then the length and the client-first-message.
+        let initial_response = self.read_password_message().await?;
+        let mut reader = MessageReader::create(&initial_response);
+        let mechanism = reader.read_cstr()?;
+        if mechanism != SCRAM_SHA_256 {
+            return Err(ErrorCode::AuthenticateFailure(format!(
+                "Unsupported SASL mechanism {}",
+                mechanism
+            )));
+        }
+        let len = reader.read_i32()?;
+        let client_first = reader.read_bytes(len.max(0) as usize)?;
+
+        let server_first = scram.server_first(client_first)?;
+        self.stream
+            .write(BackendMessage::AuthenticationSaslContinue(server_first));
+        self.stream.flush().await?;
+
+        let client_final = self.read_password_message().await?;
+        let server_final = scram.server_final(&client_final)?;
+        self.stream
+            .write(BackendMessage::AuthenticationSaslFinal(server_final));
+        Ok(())
+    }
+
+    async fn read_password_message(&mut self) -> Result<Vec<u8>> {
+        match self.stream.read_message().await? {
+            Some(FrontendMessage::Password(body)) => Ok(body),
+            Some(_) => Err(ErrorCode::AuthenticateFailure(
+                "Expected a password message",
+            )),
+            None => Err(ErrorCode::AuthenticateFailure(
+                "The connection is closed during authentication",
+            )),
+        }
+    }
+
+    /// The cleartext and MD5 passwords are null-terminated strings.
+    async fn read_password(&mut self) -> Result<Vec<u8>> {
+        let mut password = self.read_password_message().await?;
+        if password.last() == Some(&0) {
+            password.pop();
+        }
+        Ok(password)
+    }
+
+    async fn on_query(&mut self, query: &str) {
+        let statements = split_statements(query);
+        if statements.is_empty() {
+            self.stream.write(BackendMessage::EmptyQueryResponse);
+            return;
+        }
+
+        // The statements after a failed one are not executed.
+        for statement in statements {
+            let result = match self.execute(statement).await {
+                Ok(result) => result,
+                Err(cause) => {
+                    self.write_error(
+                        &cause.add_message_back(format!("(while in query {})", statement)),
+                    );
+                    return;
+                }
+            };
+
+            if let Some(schema) = &result.schema {
+                self.write_row_description(schema, &[]);
+            }
+            match encode_rows(&result.blocks, &[]) {
+                Ok(rows) => {
+                    for row in rows {
+                        self.stream.write(BackendMessage::DataRow(row));
+                    }
+                    self.stream
+                        .write(BackendMessage::CommandComplete(result.tag));
+                }
+                Err(cause) => {
+                    self.write_error(&cause);
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn on_extended_query(&mut self, message: FrontendMessage) -> Result<()> {
+        match message {
+            FrontendMessage::Parse {
+                name,
+                query,
+                param_types,
+            } => {
+                self.statements
+                    .insert(name, PreparedStatement { query, param_types });
+                self.stream.write(BackendMessage::ParseComplete);
+            }
+            FrontendMessage::Bind {
+                portal,
+                statement,
+                param_formats,
+                params,
+                result_formats,
+            } => {
+                let prepared = self.get_statement(&statement)?;
+                let mut literals = Vec::with_capacity(params.len());
+                for (index, param) in params.iter().enumerate() {
+                    let format = column_format(&param_formats, index);
+                    let type_oid = prepared.param_types.get(index).copied().unwrap_or(0);
+                    literals.push(param_literal(param, type_oid, format)?);
+                }
+                let query = bind_parameters(&prepared.query, |index| {
+                    literals.get(index - 1).cloned().ok_or_else(|| {
+                        ErrorCode::BadArguments(format!("No value for the parameter ${}", index))
+                    })
+                })?;
+
+                self.portals.insert(portal, Portal {
+                    query,
+                    result_formats,
+                    suspended: None,
+                });
+                self.stream.write(BackendMessage::BindComplete);
+            }
+            FrontendMessage::Describe { kind: b'S', name } => {
+                let prepared = self.get_statement(&name)?;
+                let mut param_types = prepared.param_types.clone();
+                param_types.resize(parameters_count(&prepared.query), 0);
+                let param_types = param_types
+                    .into_iter()
+                    .map(|oid| if oid == 0 { TEXT_OID } else { oid })
+                    .collect();
+                let query = bind_parameters(&prepared.query, |_| Ok(String::from("NULL")))?;
+
+                self.stream
+                    .write(BackendMessage::ParameterDescription(param_types));
+                self.describe(&query, &[]).await?;
+            }
+            FrontendMessage::Describe { name, .. } => {
+                let portal = self.get_portal(&name)?;
+                let (query, result_formats) = (portal.query.clone(), portal.result_formats.clone());
+                self.describe(&query, &result_formats).await?;
+            }
+            FrontendMessage::Execute { portal, max_rows } => {
+                self.on_execute(&portal, max_rows).await?;
+            }
+            FrontendMessage::Close { kind, name } => {
+                match kind {
+                    b'S' => self.statements.remove(&name).map(|_| ()),
+                    _ => self.portals.remove(&name).map(|_| ()),
+                };
+                self.stream.write(BackendMessage::CloseComplete);
+            }
+            FrontendMessage::Password(_) => {
+                return Err(ErrorCode::BadBytes("Unexpected password message"));
+            }
+            message => {
+                return Err(ErrorCode::UnImplement(format!(
+                    "Unsupported PostgreSQL message {:?}",
+                    message
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn on_execute(&mut self, name: &str, max_rows: i32) -> Result<()> {
+        let portal = self.get_portal(name)?;
+        let (mut rows, tag) = match portal.suspended.take() {
+            Some(suspended) => suspended,
+            None => {
+                let query = portal.query.clone();
+                let result_formats = portal.result_formats.clone();
+                if split_statements(&query).is_empty() {
+                    self.stream.write(BackendMessage::EmptyQueryResponse);
+                    return Ok(());
+                }
+                let result = self.execute(&query).await?;
+                (encode_rows(&result.blocks, &result_formats)?, result.tag)
+            }
+        };
+
+        let limit = if max_rows > 0 {
+            max_rows as usize
+        } else {
+            rows.len()
+        };
+        for row in rows.drain(..limit.min(rows.len())) {
+            self.stream.write(BackendMessage::DataRow(row));
+        }
+
+        if rows.is_empty() {
+            self.stream.write(BackendMessage::CommandComplete(tag));
+        } else {
+            self.get_portal(name)?.suspended = Some((rows, tag));
+            self.stream.write(BackendMessage::PortalSuspended);
+        }
+        Ok(())
+    }
+
+    /// Writes the columns of the query without executing it.
+    async fn describe(&mut self, query: &str, result_formats: &[i16]) -> Result<()> {
+        let statements = split_statements(query);
+        let plan = match statements.first() {
+            None => None,
+            Some(statement) => {
+                let context = self.session.create_context().await?;
+                Some(PlanParser::create(context).build_from_sql(statement)?)
+            }
+        };
+
+        match plan {
+            Some(plan) if returns_rows(&plan) => {
+                self.write_row_description(&plan.schema(), result_formats)
+            }
+            _ => self.stream.write(BackendMessage::NoData),
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, query: &str) -> Result<QueryResult> {
+        log::debug!("{}", query);
+
+        let context = self.session.create_context().await?;
+        context.attach_query_str(query);
+        let plan = PlanParser::create(context.clone()).build_from_sql(query)?;
+
+        match plan {
+            // The values are read here to count the inserted rows.
+            PlanNode::InsertInto(mut plan) if plan.values_opt.is_some() => {
+                let values = plan.values_opt.take().unwrap_or_default();
+                let block_size = context.get_settings().get_max_block_size()? as usize;
+                let source = ValueSource::new(Cursor::new(values), plan.schema(), block_size);
+                let stream = SourceStream::new(Box::new(source)).execute().await?;
+
+                let rows = Arc::new(AtomicUsize::new(0));
+                let counter = rows.clone();
+                let stream: SendableDataBlockStream = Box::pin(stream.map(move |block| {
+                    if let Ok(block) = &block {
+                        counter.fetch_add(block.num_rows(), Ordering::Relaxed);
+                    }
+                    block
+                }));
+
+                let interpreter = InterpreterFactory::get(context, PlanNode::InsertInto(plan))?;
+                let data_stream = interpreter.execute(Some(stream)).await?;
+                data_stream.try_collect::<Vec<DataBlock>>().await?;
+                Ok(QueryResult {
+                    schema: None,
+                    blocks: vec![],
+                    tag: format!("INSERT 0 {}", rows.load(Ordering::Relaxed)),
+                })
+            }
+            plan => {
+                let schema = match returns_rows(&plan) {
+                    true => Some(plan.schema()),
+                    false => None,
+                };
+                let interpreter = InterpreterFactory::get(context, plan)?;
+                let data_stream = interpreter.execute(None).await?;
+                let blocks = data_stream.try_collect::<Vec<DataBlock>>().await?;
+
+                let tag = match &schema {
+                    Some(_) => {
+                        let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+                        format!("SELECT {}", rows)
+                    }
+                    None => command_tag(query),
+                };
+                Ok(QueryResult {
+                    schema,
+                    blocks,
+                    tag,
+                })
+            }
+        }
+    }
+
+    fn get_statement(&self, name: &str) -> Result<&PreparedStatement> {
+        self.statements.get(name).ok_or_else(|| {
+            ErrorCode::BadArguments(format!("Unknown prepared statement {:?}", name))
+        })
+    }
+
+    fn get_portal(&mut self, name: &str) -> Result<&mut Portal> {
+        self.portals
+            .get_mut(name)
+            .ok_or_else(|| ErrorCode::BadArguments(format!("Unknown portal {:?}", name)))
+    }
+
+    fn write_row_description(&mut self, schema: &DataSchemaRef, result_formats: &[i16]) {
+        let fields = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| field_description(field, column_format(result_formats, index)))
+            .collect();
+        self.stream.write(BackendMessage::RowDescription(fields));
+    }
+
+    fn write_ready_for_query(&mut self) {
+        let status = match self.session.get_transaction() {
+            Some(_) => b'T',
+            None => b'I',
+        };
+        self.stream.write(BackendMessage::ReadyForQuery(status));
+    }
+
+    fn write_error(&mut self, cause: &ErrorCode) {
+        self.stream
+            .write(BackendMessage::ErrorResponse(ErrorFields {
+                severity: "ERROR",
+                code: sqlstate(cause),
+                message: cause.message(),
+            }));
+    }
+}
+
+/// The SQLSTATE of the errors, the ones not listed here are internal errors.
+fn sqlstate(cause: &ErrorCode) -> &'static str {
+    let states = [
+        (ErrorCode::SyntaxException("").code(), "42601"),
+        (ErrorCode::UnknownTable("").code(), "42P01"),
+        (ErrorCode::UnknownDatabase("").code(), "3D000"),
+        (ErrorCode::UnknownUser("").code(), "28000"),
+        (ErrorCode::AuthenticateFailure("").code(), "28P01"),
+        (ErrorCode::TooManyUserConnections("").code(), "53300"),
+        (ErrorCode::AbortedSession("").code(), "57P01"),
+        (ErrorCode::UnImplement("").code(), "0A000"),
+    ];
+
+    states
+        .iter()
+        .find(|(code, _)| *code == cause.code())
+        .map(|(_, state)| *state)
+        .unwrap_or("XX000")
+}
+
+/// The format codes are either empty for all text, a single one for all the columns, or one
+/// per column.
+fn column_format(formats: &[i16], index: usize) -> i16 {
+    match formats.len() {
+        0 => FORMAT_TEXT,
+        1 => formats[0],
+        _ => formats.get(index).copied().unwrap_or(FORMAT_TEXT),
+    }
+}
+
+fn encode_rows(blocks: &[DataBlock], formats: &[i16]) -> Result<VecDeque<EncodedRow>> {
+    let mut rows = VecDeque::new();
+    for block in blocks {
+        let fields = block.schema().fields();
+        for row_index in 0..block.num_rows() {
+            let mut row = Vec::with_capacity(fields.len());
+            for (col_index, field) in fields.iter().enumerate() {
+                let value = block.column(col_index).try_get(row_index)?;
+                let format = column_format(formats, col_index);
+                row.push(encode_value(field.data_type(), value, format)?);
+            }
+            rows.push_back(row);
+        }
+    }
+    Ok(rows)
+}
+
+fn returns_rows(plan: &PlanNode) -> bool {
+    matches!(
+        plan,
+        PlanNode::Select(_)
+            | PlanNode::Explain(_)
+            | PlanNode::DescribeTable(_)
+            | PlanNode::ShowCreateTable(_)
+    )
+}
+
+/// The tag of CommandComplete for the statements without rows, such as `CREATE TABLE`.
+fn command_tag(query: &str) -> String {
+    let mut words = query
+        .split_whitespace()
+        .map(|word| word.to_ascii_uppercase());
+    let first = words.next().unwrap_or_default();
+    match (first.as_str(), words.next()) {
+        ("CREATE" | "DROP" | "ALTER", Some(object)) => format!("{} {}", first, object),
+        _ => first,
+    }
+}
+
+/// Visits the text of a query outside of the quoted strings and identifiers.
+fn for_each_unquoted(query: &str, mut f: impl FnMut(usize, char, bool)) {
+    let mut quote = None;
+    for (pos, c) in query.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"' | '`') => {
+                quote = Some(c);
+                f(pos, c, false);
+            }
+            (Some(q), _) if q == c => {
+                quote = None;
+                f(pos, c, false);
+            }
+            (Some(_), _) => f(pos, c, false),
+            (None, _) => f(pos, c, true),
+        }
+    }
+}
+
+/// Splits the statements of a simple query, the empty ones are skipped.
+pub(crate) fn split_statements(query: &str) -> Vec<&str> {
+    let mut statements = vec![];
+    let mut start = 0;
+    for_each_unquoted(query, |pos, c, unquoted| {
+        if unquoted && c == ';' {
+            statements.push(&query[start..pos]);
+            start = pos + 1;
+        }
+    });
+    statements.push(&query[start..]);
+
+    statements
+        .into_iter()
+        .map(|statement| statement.trim())
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+fn parameters_count(query: &str) -> usize {
+    let mut count = 0;
+    let _ = bind_parameters(query, |index| {
+        count = count.max(index);
+        Ok(String::new())
+    });
+    count
+}
+
+/// Replaces the parameters `$1`, `$2`... of a query with the literals of their values.
+pub(crate) fn bind_parameters(
+    query: &str,
+    mut literal: impl FnMut(usize) -> Result<String>,
+) -> Result<String> {
+    let mut parameters = vec![];
+    let mut parameter_start = None;
+    for_each_unquoted(query, |pos, c, unquoted| match (parameter_start, c) {
+        (Some(_), '0'..='9') if unquoted => {}
+        _ => {
+            if let Some(start) = parameter_start.take() {
+                if pos > start + 1 {
+                    parameters.push((start, pos));
+                }
+            }
+            if unquoted && c == '$' {
+                parameter_start = Some(pos);
+            }
+        }
+    });
+    if let Some(start) = parameter_start {
+        if query.len() > start + 1 {
+            parameters.push((start, query.len()));
+        }
+    }
+
+    let mut bound = String::with_capacity(query.len());
+    let mut last = 0;
+    for (start, end) in parameters {
+        let index = query[start + 1..end].parse::<usize>().map_err(|_| {
+            ErrorCode::BadArguments(format!("Invalid parameter {}", &query[start..end]))
+        })?;
+        bound.push_str(&query[last..start]);
+        bound.push_str(&literal(index)?);
+        last = end;
+    }
+    bound.push_str(&query[last..]);
+    Ok(bound)
+}
+
+fn quote_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn fixed_size<const N: usize>(value: &[u8]) -> Result<[u8; N]> {
+    value.try_into().map_err(|_| {
+        ErrorCode::BadBytes(format!(
+            "Expected a binary parameter of {} bytes, but got {} bytes",
+            N,
+            value.len()
+        ))
+    })
+}
+
+/// The SQL literal of a parameter value.
+pub(crate) fn param_literal(value: &Option<Vec<u8>>, type_oid: u32, format: i16) -> Result<String> {
+    let value = match value {
+        None => return Ok(String::from("NULL")),
+        Some(value) => value,
+    };
+
+    match format {
+        FORMAT_TEXT => {
+            let text = String::from_utf8(value.clone())
+                .map_err(|_| ErrorCode::BadBytes("The parameter is not UTF-8"))?;
+            match type_oid {
+                BOOL_OID => match text.to_ascii_lowercase().as_str() {
+                    "t" | "true" | "1" | "y" | "yes" | "on" => Ok(String::from("true")),
+                    "f" | "false" | "0" | "n" | "no" | "off" => Ok(String::from("false")),
+                    _ => Err(ErrorCode::BadArguments(format!(
+                        "Invalid boolean parameter {}",
+                        text
+                    ))),
+                },
+                INT2_OID | INT4_OID | INT8_OID | FLOAT4_OID | FLOAT8_OID | NUMERIC_OID => {
+                    match text.trim().parse::<f64>() {
+                        Ok(_) => Ok(text.trim().to_string()),
+                        Err(_) => Err(ErrorCode::BadArguments(format!(
+                            "Invalid numeric parameter {}",
+                            text
+                        ))),
+                    }
+                }
+                _ => Ok(quote_string(&text)),
+            }
+        }
+        FORMAT_BINARY => match type_oid {
+            BOOL_OID => Ok((fixed_size::<1>(value)?[0] != 0).to_string()),
+            INT2_OID => Ok(i16::from_be_bytes(fixed_size(value)?).to_string()),
+            INT4_OID => Ok(i32::from_be_bytes(fixed_size(value)?).to_string()),
+            INT8_OID => Ok(i64::from_be_bytes(fixed_size(value)?).to_string()),
+            FLOAT4_OID => Ok(f32::from_be_bytes(fixed_size(value)?).to_string()),
+            FLOAT8_OID => Ok(f64::from_be_bytes(fixed_size(value)?).to_string()),
+            // The unspecified type is taken as text.
+            0 | TEXT_OID => {
+                let text = String::from_utf8(value.clone())
+                    .map_err(|_| ErrorCode::BadBytes("The parameter is not UTF-8"))?;
+                Ok(quote_string(&text))
+            }
+            DATE_OID | TIMESTAMP_OID | NUMERIC_OID => Err(ErrorCode::UnImplement(format!(
+                "Unsupported binary parameter of type {}, expect text format",
+                type_oid
+            ))),
+            _ => Err(ErrorCode::UnImplement(format!(
+                "Unsupported binary parameter of type {}",
+                type_oid
+            ))),
+        },
+        _ => Err(ErrorCode::BadArguments(format!(
+            "Unknown format code {}",
+            format
+        ))),
+    }
+}
@@ -0,0 +1,462 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::BytesMut;
+use common_base::tokio::io::AsyncRead;
+use common_base::tokio::io::AsyncReadExt;
+use common_base::tokio::io::AsyncWrite;
+use common_base::tokio::io::AsyncWriteExt;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+pub const PROTOCOL_VERSION: i32 = 196608;
+const CANCEL_REQUEST_CODE: i32 = 80877102;
+const SSL_REQUEST_CODE: i32 = 80877103;
+const GSSENC_REQUEST_CODE: i32 = 80877104;
+
+const MAX_STARTUP_SIZE: usize = 10 * 1024;
+const MAX_MESSAGE_SIZE: usize = 1 << 30;
+
+pub const FORMAT_TEXT: i16 = 0;
+pub const FORMAT_BINARY: i16 = 1;
+
+/// The first message of a connection, it has no type byte.
+#[derive(Debug, PartialEq)]
+pub enum StartupMessage {
+    SslRequest,
+    GssEncRequest,
+    CancelRequest {
+        process_id: i32,
+        secret_key: i32,
+    },
+    Startup {
+        version: i32,
+        params: HashMap<String, String>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FrontendMessage {
+    Query(String),
+    Parse {
+        name: String,
+        query: String,
+        param_types: Vec<u32>,
+    },
+    Bind {
+        portal: String,
+        statement: String,
+        param_formats: Vec<i16>,
+        params: Vec<Option<Vec<u8>>>,
+        result_formats: Vec<i16>,
+    },
+    /// `S` for a prepared statement, `P` for a portal.
+    Describe {
+        kind: u8,
+        name: String,
+    },
+    Execute {
+        portal: String,
+        max_rows: i32,
+    },
+    /// `S` for a prepared statement, `P` for a portal.
+    Close {
+        kind: u8,
+        name: String,
+    },
+    Sync,
+    Flush,
+    Terminate,
+    /// The body of the password, SASL initial response and SASL response messages, they are
+    /// told apart by the state of the authentication.
+    Password(Vec<u8>),
+    Unsupported(u8),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDescription {
+    pub name: String,
+    pub type_oid: u32,
+    pub type_size: i16,
+    pub format: i16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorFields {
+    pub severity: &'static str,
+    pub code: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendMessage {
+    AuthenticationOk,
+    AuthenticationMd5Password([u8; 4]),
+    AuthenticationSasl(Vec<&'static str>),
+    AuthenticationSaslContinue(Vec<u8>),
+    AuthenticationSaslFinal(Vec<u8>),
+    ParameterStatus(&'static str, String),
+    BackendKeyData(i32, i32),
+    /// `I` if idle, `T` in a transaction, `E` in a failed transaction.
+    ReadyForQuery(u8),
+    RowDescription(Vec<FieldDescription>),
+    DataRow(Vec<Option<Vec<u8>>>),
+    CommandComplete(String),
+    EmptyQueryResponse,
+    ParseComplete,
+    BindComplete,
+    CloseComplete,
+    NoData,
+    PortalSuspended,
+    ParameterDescription(Vec<u32>),
+    ErrorResponse(ErrorFields),
+}
+
+/// Reads the fields of a message body.
+pub struct MessageReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> MessageReader<'a> {
+    pub fn create(buf: &'a [u8]) -> MessageReader<'a> {
+        MessageReader { buf }
+    }
+
+    fn truncated() -> ErrorCode {
+        ErrorCode::BadBytes("Truncated PostgreSQL message")
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8> {
+        match self.buf.remaining() >= 1 {
+            true => Ok(self.buf.get_u8()),
+            false => Err(Self::truncated()),
+        }
+    }
+
+    pub fn read_i16(&mut self) -> Result<i16> {
+        match self.buf.remaining() >= 2 {
+            true => Ok(self.buf.get_i16()),
+            false => Err(Self::truncated()),
+        }
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32> {
+        match self.buf.remaining() >= 4 {
+            true => Ok(self.buf.get_i32()),
+            false => Err(Self::truncated()),
+        }
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(Self::truncated());
+        }
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(bytes)
+    }
+
+    /// Reads a null terminated string.
+    pub fn read_cstr(&mut self) -> Result<String> {
+        match self.buf.iter().position(|b| *b == 0) {
+            None => Err(Self::truncated()),
+            Some(pos) => {
+                let value = String::from_utf8(self.buf[..pos].to_vec())
+                    .map_err(|_| ErrorCode::BadBytes("Invalid UTF-8 in PostgreSQL message"))?;
+                self.buf = &self.buf[pos + 1..];
+                Ok(value)
+            }
+        }
+    }
+
+    fn read_i16_array(&mut self) -> Result<Vec<i16>> {
+        let len = self.read_i16()?;
+        (0..len).map(|_| self.read_i16()).collect()
+    }
+}
+
+impl StartupMessage {
+    pub fn decode(body: &[u8]) -> Result<StartupMessage> {
+        let mut reader = MessageReader::create(body);
+        match reader.read_i32()? {
+            SSL_REQUEST_CODE => Ok(StartupMessage::SslRequest),
+            GSSENC_REQUEST_CODE => Ok(StartupMessage::GssEncRequest),
+            CANCEL_REQUEST_CODE => Ok(StartupMessage::CancelRequest {
+                process_id: reader.read_i32()?,
+                secret_key: reader.read_i32()?,
+            }),
+            version => {
+                let mut params = HashMap::new();
+                loop {
+                    let name = reader.read_cstr()?;
+                    if name.is_empty() {
+                        break;
+                    }
+                    params.insert(name, reader.read_cstr()?);
+                }
+                Ok(StartupMessage::Startup { version, params })
+            }
+        }
+    }
+}
+
+impl FrontendMessage {
+    pub fn decode(tag: u8, body: &[u8]) -> Result<FrontendMessage> {
+        let mut reader = MessageReader::create(body);
+        let message = match tag {
+            b'Q' => FrontendMessage::Query(reader.read_cstr()?),
+            b'P' => FrontendMessage::Parse {
+                name: reader.read_cstr()?,
+                query: reader.read_cstr()?,
+                param_types: {
+                    let len = reader.read_i16()?;
+                    (0..len)
+                        .map(|_| reader.read_i32().map(|oid| oid as u32))
+                        .collect::<Result<Vec<_>>>()?
+                },
+            },
+            b'B' => {
+                let portal = reader.read_cstr()?;
+                let statement = reader.read_cstr()?;
+                let param_formats = reader.read_i16_array()?;
+                let len = reader.read_i16()?;
+                let mut params = Vec::with_capacity(len.max(0) as usize);
+                for _ in 0..len {
+                    let param = match reader.read_i32()? {
+                        len if len < 0 => None,
+                        len => Some(reader.read_bytes(len as usize)?.to_vec()),
+                    };
+                    params.push(param);
+                }
+                let result_formats = reader.read_i16_array()?;
+                FrontendMessage::Bind {
+                    portal,
+                    statement,
+                    param_formats,
+                    params,
+                    result_formats,
+                }
+            }
+            b'D' => FrontendMessage::Describe {
+                kind: reader.read_u8()?,
+                name: reader.read_cstr()?,
+            },
+            b'E' => FrontendMessage::Execute {
+                portal: reader.read_cstr()?,
+                max_rows: reader.read_i32()?,
+            },
+            b'C' => FrontendMessage::Close {
+                kind: reader.read_u8()?,
+                name: reader.read_cstr()?,
+            },
+            b'S' => FrontendMessage::Sync,
+            b'H' => FrontendMessage::Flush,
+            b'X' => FrontendMessage::Terminate,
+            b'p' => FrontendMessage::Password(body.to_vec()),
+            tag => FrontendMessage::Unsupported(tag),
+        };
+        Ok(message)
+    }
+}
+
+fn put_cstr(buf: &mut BytesMut, value: &str) {
+    buf.put_slice(value.as_bytes());
+    buf.put_u8(0);
+}
+
+impl BackendMessage {
+    fn tag(&self) -> u8 {
+        match self {
+            BackendMessage::AuthenticationOk
+            | BackendMessage::AuthenticationMd5Password(_)
+            | BackendMessage::AuthenticationSasl(_)
+            | BackendMessage::AuthenticationSaslContinue(_)
+            | BackendMessage::AuthenticationSaslFinal(_) => b'R',
+            BackendMessage::ParameterStatus(..) => b'S',
+            BackendMessage::BackendKeyData(..) => b'K',
+            BackendMessage::ReadyForQuery(_) => b'Z',
+            BackendMessage::RowDescription(_) => b'T',
+            BackendMessage::DataRow(_) => b'D',
+            BackendMessage::CommandComplete(_) => b'C',
+            BackendMessage::EmptyQueryResponse => b'I',
+            BackendMessage::ParseComplete => b'1',
+            BackendMessage::BindComplete => b'2',
+            BackendMessage::CloseComplete => b'3',
+            BackendMessage::NoData => b'n',
+            BackendMessage::PortalSuspended => b's',
+            BackendMessage::ParameterDescription(_) => b't',
+            BackendMessage::ErrorResponse(_) => b'E',
+        }
+    }
+
+    fn encode_body(&self, buf: &mut BytesMut) {
+        match self {
+            BackendMessage::AuthenticationOk => buf.put_i32(0),
+            BackendMessage::AuthenticationMd5Password(salt) => {
+                buf.put_i32(5);
+                buf.put_slice(salt);
+            }
+            BackendMessage::AuthenticationSasl(mechanisms) => {
+                buf.put_i32(10);
+                for mechanism in mechanisms {
+                    put_cstr(buf, mechanism);
+                }
+                buf.put_u8(0);
+            }
+            BackendMessage::AuthenticationSaslContinue(data) => {
+                buf.put_i32(11);
+                buf.put_slice(data);
+            }
+            BackendMessage::AuthenticationSaslFinal(data) => {
+                buf.put_i32(12);
+                buf.put_slice(data);
+            }
+            BackendMessage::ParameterStatus(name, value) => {
+                put_cstr(buf, name);
+                put_cstr(buf, value);
+            }
+            BackendMessage::BackendKeyData(process_id, secret_key) => {
+                buf.put_i32(*process_id);
+                buf.put_i32(*secret_key);
+            }
+            BackendMessage::ReadyForQuery(status) => buf.put_u8(*status),
+            BackendMessage::RowDescription(fields) => {
+                buf.put_i16(fields.len() as i16);
+                for field in fields {
+                    put_cstr(buf, &field.name);
+                    // table oid and column attribute number
+                    buf.put_i32(0);
+                    buf.put_i16(0);
+                    buf.put_u32(field.type_oid);
+                    buf.put_i16(field.type_size);
+                    // type modifier
+                    buf.put_i32(-1);
+                    buf.put_i16(field.format);
+                }
+            }
+            BackendMessage::DataRow(values) => {
+                buf.put_i16(values.len() as i16);
+                for value in values {
+                    match value {
+                        None => buf.put_i32(-1),
+                        Some(value) => {
+                            buf.put_i32(value.len() as i32);
+                            buf.put_slice(value);
+                        }
+                    }
+                }
+            }
+            BackendMessage::CommandComplete(tag) => put_cstr(buf, tag),
+            BackendMessage::ParameterDescription(types) => {
+                buf.put_i16(types.len() as i16);
+                for oid in types {
+                    buf.put_u32(*oid);
+                }
+            }
+            BackendMessage::ErrorResponse(fields) => {
+                buf.put_u8(b'S');
+                put_cstr(buf, fields.severity);
+                buf.put_u8(b'V');
+                put_cstr(buf, fields.severity);
+                buf.put_u8(b'C');
+                put_cstr(buf, fields.code);
+                buf.put_u8(b'M');
+                put_cstr(buf, &fields.message);
+                buf.put_u8(0);
+            }
+            BackendMessage::EmptyQueryResponse
+            | BackendMessage::ParseComplete
+            | BackendMessage::BindComplete
+            | BackendMessage::CloseComplete
+            | BackendMessage::NoData
+            | BackendMessage::PortalSuspended => {}
+        }
+    }
+
+    pub fn encode(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.tag());
+        let len_pos = buf.len();
+        buf.put_i32(0);
+        self.encode_body(buf);
+        let len = (buf.len() - len_pos) as i32;
+        buf[len_pos..len_pos + 4].copy_from_slice(&len.to_be_bytes());
+    }
+}
+
+/// The messages of a connection, the backend messages are buffered until `flush`.
+pub struct PostgresStream<S> {
+    stream: S,
+    write_buf: BytesMut,
+}
+
+impl<S> PostgresStream<S>
+where S: AsyncRead + AsyncWrite + Unpin
+{
+    pub fn create(stream: S) -> PostgresStream<S> {
+        PostgresStream {
+            stream,
+            write_buf: BytesMut::new(),
+        }
+    }
+
+    async fn read_body(&mut self, max_size: usize) -> Result<Vec<u8>> {
+        let len = self.stream.read_i32().await? as usize;
+        if !(4..=max_size).contains(&len) {
+            return Err(ErrorCode::BadBytes(format!(
+                "Invalid PostgreSQL message length {}",
+                len
+            )));
+        }
+        let mut body = vec![0; len - 4];
+        self.stream.read_exact(&mut body).await?;
+        Ok(body)
+    }
+
+    pub async fn read_startup(&mut self) -> Result<StartupMessage> {
+        let body = self.read_body(MAX_STARTUP_SIZE).await?;
+        StartupMessage::decode(&body)
+    }
+
+    /// None if the client closed the connection.
+    pub async fn read_message(&mut self) -> Result<Option<FrontendMessage>> {
+        let mut tag = [0; 1];
+        if self.stream.read(&mut tag).await? == 0 {
+            return Ok(None);
+        }
+        let body = self.read_body(MAX_MESSAGE_SIZE).await?;
+        FrontendMessage::decode(tag[0], &body).map(Some)
+    }
+
+    /// Answers the SSL and GSSAPI encryption requests, they are not supported.
+    pub async fn write_unsupported_encryption(&mut self) -> Result<()> {
+        self.stream.write_all(b"N").await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    pub fn write(&mut self, message: BackendMessage) {
+        message.encode(&mut self.write_buf);
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        self.stream.write_all(&self.write_buf).await?;
+        self.stream.flush().await?;
+        self.write_buf.clear();
+        Ok(())
+    }
+}
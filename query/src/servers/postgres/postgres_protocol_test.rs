@@ -0,0 +1,178 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BufMut;
+use bytes::BytesMut;
+use common_base::tokio;
+use common_base::tokio::io::AsyncReadExt;
+use common_base::tokio::io::AsyncWriteExt;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::servers::postgres::postgres_protocol::BackendMessage;
+use crate::servers::postgres::postgres_protocol::ErrorFields;
+use crate::servers::postgres::postgres_protocol::FieldDescription;
+use crate::servers::postgres::postgres_protocol::FrontendMessage;
+use crate::servers::postgres::postgres_protocol::PostgresStream;
+use crate::servers::postgres::postgres_protocol::StartupMessage;
+use crate::servers::postgres::postgres_protocol::PROTOCOL_VERSION;
+
+#[test]
+fn test_decode_startup() -> Result<()> {
+    let mut body = BytesMut::new();
+    body.put_i32(PROTOCOL_VERSION);
+    body.put_slice(b"user\0root\0database\0default\0\0");
+    match StartupMessage::decode(&body)? {
+        StartupMessage::Startup { version, params } => {
+            assert_eq!(version, PROTOCOL_VERSION);
+            assert_eq!(params.get("user"), Some(&"root".to_string()));
+            assert_eq!(params.get("database"), Some(&"default".to_string()));
+        }
+        message => panic!("unexpected startup message {:?}", message),
+    }
+
+    let mut body = BytesMut::new();
+    body.put_i32(80877103);
+    assert_eq!(StartupMessage::decode(&body)?, StartupMessage::SslRequest);
+
+    let mut body = BytesMut::new();
+    body.put_i32(80877102);
+    body.put_i32(1);
+    body.put_i32(2);
+    assert_eq!(
+        StartupMessage::decode(&body)?,
+        StartupMessage::CancelRequest {
+            process_id: 1,
+            secret_key: 2
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_decode_extended_query() -> Result<()> {
+    let mut body = BytesMut::new();
+    body.put_slice(b"s1\0SELECT $1\0");
+    body.put_i16(1);
+    body.put_i32(23);
+    assert_eq!(
+        FrontendMessage::decode(b'P', &body)?,
+        FrontendMessage::Parse {
+            name: "s1".to_string(),
+            query: "SELECT $1".to_string(),
+            param_types: vec![23],
+        }
+    );
+
+    let mut body = BytesMut::new();
+    body.put_slice(b"p1\0s1\0");
+    body.put_i16(1);
+    body.put_i16(0);
+    body.put_i16(2);
+    body.put_i32(2);
+    body.put_slice(b"42");
+    body.put_i32(-1);
+    body.put_i16(1);
+    body.put_i16(1);
+    assert_eq!(
+        FrontendMessage::decode(b'B', &body)?,
+        FrontendMessage::Bind {
+            portal: "p1".to_string(),
+            statement: "s1".to_string(),
+            param_formats: vec![0],
+            params: vec![Some(b"42".to_vec()), None],
+            result_formats: vec![1],
+        }
+    );
+
+    let mut body = BytesMut::new();
+    body.put_slice(b"p1\0");
+    body.put_i32(10);
+    assert_eq!(
+        FrontendMessage::decode(b'E', &body)?,
+        FrontendMessage::Execute {
+            portal: "p1".to_string(),
+            max_rows: 10,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_decode_truncated() -> Result<()> {
+    match FrontendMessage::decode(b'Q', b"SELECT 1") {
+        Ok(_) => panic!("the query must be null terminated"),
+        Err(e) => assert_eq!(e.code(), ErrorCode::BadBytes("").code()),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_encode_backend_messages() {
+    let mut buf = BytesMut::new();
+    BackendMessage::RowDescription(vec![FieldDescription {
+        name: "a".to_string(),
+        type_oid: 23,
+        type_size: 4,
+        format: 0,
+    }])
+    .encode(&mut buf);
+    assert_eq!(buf.as_ref(), &[
+        b'T', 0, 0, 0, 26, 0, 1, b'a', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 23, 0, 4, 255, 255, 255, 255,
+        0, 0
+    ]);
+
+    let mut buf = BytesMut::new();
+    BackendMessage::DataRow(vec![Some(b"1".to_vec()), None]).encode(&mut buf);
+    assert_eq!(buf.as_ref(), &[
+        b'D', 0, 0, 0, 15, 0, 2, 0, 0, 0, 1, b'1', 255, 255, 255, 255
+    ]);
+
+    let mut buf = BytesMut::new();
+    BackendMessage::ErrorResponse(ErrorFields {
+        severity: "ERROR",
+        code: "42601",
+        message: "bad".to_string(),
+    })
+    .encode(&mut buf);
+    assert_eq!(
+        buf.as_ref(),
+        &b"E\0\0\0\x1fSERROR\0VERROR\0C42601\0Mbad\0\0"[..]
+    );
+}
+
+#[tokio::test]
+async fn test_postgres_stream() -> Result<()> {
+    let (client, server) = tokio::io::duplex(1024);
+    let (mut client_reader, mut client_writer) = tokio::io::split(client);
+    let mut stream = PostgresStream::create(server);
+
+    client_writer.write_all(b"Q\0\0\0\x0dSELECT 1\0").await?;
+    assert_eq!(
+        stream.read_message().await?,
+        Some(FrontendMessage::Query("SELECT 1".to_string()))
+    );
+
+    stream.write(BackendMessage::EmptyQueryResponse);
+    stream.write(BackendMessage::ReadyForQuery(b'I'));
+    stream.flush().await?;
+    let mut buf = [0; 11];
+    client_reader.read_exact(&mut buf).await?;
+    assert_eq!(&buf, b"I\0\0\0\x04Z\0\0\0\x05I");
+
+    drop(client_writer);
+    drop(client_reader);
+    assert_eq!(stream.read_message().await?, None);
+    Ok(())
+}
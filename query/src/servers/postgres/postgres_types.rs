@@ -0,0 +1,207 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono_tz::Tz;
+use common_datavalues::DataField;
+use common_datavalues::DataType;
+use common_datavalues::DataValue;
+use common_datavalues::DateConverter;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::servers::postgres::postgres_protocol::FieldDescription;
+use crate::servers::postgres::postgres_protocol::FORMAT_BINARY;
+use crate::servers::postgres::postgres_protocol::FORMAT_TEXT;
+
+pub const BOOL_OID: u32 = 16;
+pub const INT8_OID: u32 = 20;
+pub const INT2_OID: u32 = 21;
+pub const INT4_OID: u32 = 23;
+pub const TEXT_OID: u32 = 25;
+pub const FLOAT4_OID: u32 = 700;
+pub const FLOAT8_OID: u32 = 701;
+pub const DATE_OID: u32 = 1082;
+pub const TIMESTAMP_OID: u32 = 1114;
+pub const NUMERIC_OID: u32 = 1700;
+
+// 2000-01-01, the epoch of the binary dates and timestamps.
+const POSTGRES_EPOCH_DAYS: i64 = 10957;
+const POSTGRES_EPOCH_SECONDS: i64 = 946684800;
+
+/// The unsigned integers are mapped to the next wider signed type, UInt64 to numeric.
+pub fn type_oid(data_type: &DataType) -> u32 {
+    match data_type {
+        DataType::Boolean => BOOL_OID,
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 => INT2_OID,
+        DataType::Int32 | DataType::UInt16 => INT4_OID,
+        DataType::Int64 | DataType::UInt32 => INT8_OID,
+        DataType::UInt64 => NUMERIC_OID,
+        DataType::Float32 => FLOAT4_OID,
+        DataType::Float64 => FLOAT8_OID,
+        DataType::Date16 | DataType::Date32 => DATE_OID,
+        DataType::DateTime32(_) => TIMESTAMP_OID,
+        _ => TEXT_OID,
+    }
+}
+
+fn type_size(oid: u32) -> i16 {
+    match oid {
+        BOOL_OID => 1,
+        INT2_OID => 2,
+        INT4_OID | FLOAT4_OID | DATE_OID => 4,
+        INT8_OID | FLOAT8_OID | TIMESTAMP_OID => 8,
+        _ => -1,
+    }
+}
+
+pub fn field_description(field: &DataField, format: i16) -> FieldDescription {
+    let type_oid = type_oid(field.data_type());
+    FieldDescription {
+        name: field.name().clone(),
+        type_oid,
+        type_size: type_size(type_oid),
+        format,
+    }
+}
+
+fn encode_int(v: i64, oid: u32, format: i16) -> Vec<u8> {
+    match (format, oid) {
+        (FORMAT_TEXT, _) => v.to_string().into_bytes(),
+        (_, INT2_OID) => (v as i16).to_be_bytes().to_vec(),
+        (_, INT4_OID) => (v as i32).to_be_bytes().to_vec(),
+        _ => v.to_be_bytes().to_vec(),
+    }
+}
+
+fn encode_float(v: f64, oid: u32, format: i16) -> Vec<u8> {
+    match (format, oid) {
+        (FORMAT_TEXT, _) if v.is_infinite() && v > 0.0 => b"Infinity".to_vec(),
+        (FORMAT_TEXT, _) if v.is_infinite() => b"-Infinity".to_vec(),
+        (FORMAT_TEXT, FLOAT4_OID) => (v as f32).to_string().into_bytes(),
+        (FORMAT_TEXT, _) => v.to_string().into_bytes(),
+        (_, FLOAT4_OID) => (v as f32).to_be_bytes().to_vec(),
+        _ => v.to_be_bytes().to_vec(),
+    }
+}
+
+/// The binary numeric: the digits in base 10000 from the most significant one, the weight is
+/// the exponent of the first digit.
+fn encode_numeric(v: u64, format: i16) -> Vec<u8> {
+    if format == FORMAT_TEXT {
+        return v.to_string().into_bytes();
+    }
+
+    let mut digits = vec![];
+    let mut rest = v;
+    while rest > 0 {
+        digits.push((rest % 10000) as i16);
+        rest /= 10000;
+    }
+    digits.reverse();
+    let weight = digits.len() as i16 - 1;
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    let mut buf = Vec::with_capacity(8 + digits.len() * 2);
+    buf.extend_from_slice(&(digits.len() as i16).to_be_bytes());
+    buf.extend_from_slice(&weight.max(0).to_be_bytes());
+    // positive sign and no decimal digits
+    buf.extend_from_slice(&0_i16.to_be_bytes());
+    buf.extend_from_slice(&0_i16.to_be_bytes());
+    for digit in digits {
+        buf.extend_from_slice(&digit.to_be_bytes());
+    }
+    buf
+}
+
+fn encode_date(days: i64, format: i16) -> Vec<u8> {
+    match format {
+        FORMAT_TEXT => {
+            let utc: Tz = "UTC".parse().unwrap();
+            days.to_date(&utc).naive_local().to_string().into_bytes()
+        }
+        _ => ((days - POSTGRES_EPOCH_DAYS) as i32).to_be_bytes().to_vec(),
+    }
+}
+
+fn encode_timestamp(seconds: i64, tz: &Option<String>, format: i16) -> Result<Vec<u8>> {
+    let tz = tz.clone().unwrap_or_else(|| "UTC".to_string());
+    let tz: Tz = tz
+        .parse()
+        .map_err(|_| ErrorCode::BadDataValueType(format!("Unknown time zone {}", tz)))?;
+    let date_time = seconds.to_date_time(&tz).naive_local();
+    Ok(match format {
+        FORMAT_TEXT => date_time.to_string().into_bytes(),
+        _ => ((date_time.timestamp() - POSTGRES_EPOCH_SECONDS) * 1_000_000)
+            .to_be_bytes()
+            .to_vec(),
+    })
+}
+
+/// Encodes a value of a column, None for NULL.
+pub fn encode_value(
+    data_type: &DataType,
+    value: DataValue,
+    format: i16,
+) -> Result<Option<Vec<u8>>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    if format != FORMAT_TEXT && format != FORMAT_BINARY {
+        return Err(ErrorCode::BadArguments(format!(
+            "Unknown format code {}",
+            format
+        )));
+    }
+
+    let oid = type_oid(data_type);
+    let encoded = match (data_type, value) {
+        (DataType::Boolean, DataValue::Boolean(Some(v))) => match format {
+            FORMAT_TEXT => {
+                if v {
+                    b"t".to_vec()
+                } else {
+                    b"f".to_vec()
+                }
+            }
+            _ => vec![v as u8],
+        },
+        (DataType::Int8, DataValue::Int8(Some(v))) => encode_int(v as i64, oid, format),
+        (DataType::Int16, DataValue::Int16(Some(v))) => encode_int(v as i64, oid, format),
+        (DataType::Int32, DataValue::Int32(Some(v))) => encode_int(v as i64, oid, format),
+        (DataType::Int64, DataValue::Int64(Some(v))) => encode_int(v, oid, format),
+        (DataType::UInt8, DataValue::UInt8(Some(v))) => encode_int(v as i64, oid, format),
+        (DataType::UInt16, DataValue::UInt16(Some(v))) => encode_int(v as i64, oid, format),
+        (DataType::UInt32, DataValue::UInt32(Some(v))) => encode_int(v as i64, oid, format),
+        (DataType::UInt64, DataValue::UInt64(Some(v))) => encode_numeric(v, format),
+        (DataType::Float32, DataValue::Float32(Some(v))) => encode_float(v as f64, oid, format),
+        (DataType::Float64, DataValue::Float64(Some(v))) => encode_float(v, oid, format),
+        (DataType::Date16, DataValue::UInt16(Some(v))) => encode_date(v as i64, format),
+        (DataType::Date32, DataValue::Int32(Some(v))) => encode_date(v as i64, format),
+        (DataType::DateTime32(tz), DataValue::UInt32(Some(v))) => {
+            encode_timestamp(v as i64, tz, format)?
+        }
+        (DataType::String, DataValue::String(Some(v))) => v,
+        // The other types are sent as text, which is also the binary format of text.
+        (_, v) if oid == TEXT_OID => v.to_string().into_bytes(),
+        (_, v) => {
+            return Err(ErrorCode::BadDataValueType(format!(
+                "Unsupported value {:?} of column type {:?}",
+                v, data_type
+            )))
+        }
+    };
+    Ok(Some(encoded))
+}
@@ -0,0 +1,125 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::DataType;
+use common_datavalues::DataValue;
+use common_exception::Result;
+use pretty_assertions::assert_eq;
+
+use crate::servers::postgres::postgres_types::encode_value;
+use crate::servers::postgres::postgres_types::type_oid;
+
+fn text(data_type: DataType, value: DataValue) -> Result<String> {
+    let encoded = encode_value(&data_type, value, 0)?.unwrap();
+    Ok(String::from_utf8(encoded).unwrap())
+}
+
+fn binary(data_type: DataType, value: DataValue) -> Result<Vec<u8>> {
+    Ok(encode_value(&data_type, value, 1)?.unwrap())
+}
+
+#[test]
+fn test_type_oid() {
+    assert_eq!(type_oid(&DataType::Boolean), 16);
+    assert_eq!(type_oid(&DataType::UInt8), 21);
+    assert_eq!(type_oid(&DataType::UInt16), 23);
+    assert_eq!(type_oid(&DataType::UInt32), 20);
+    assert_eq!(type_oid(&DataType::UInt64), 1700);
+    assert_eq!(type_oid(&DataType::Float64), 701);
+    assert_eq!(type_oid(&DataType::String), 25);
+    assert_eq!(type_oid(&DataType::Date16), 1082);
+    assert_eq!(type_oid(&DataType::DateTime32(None)), 1114);
+}
+
+#[test]
+fn test_encode_text() -> Result<()> {
+    assert_eq!(
+        text(DataType::Boolean, DataValue::Boolean(Some(true)))?,
+        "t"
+    );
+    assert_eq!(text(DataType::Int8, DataValue::Int8(Some(-1)))?, "-1");
+    assert_eq!(
+        text(DataType::UInt64, DataValue::UInt64(Some(u64::MAX)))?,
+        "18446744073709551615"
+    );
+    assert_eq!(
+        text(DataType::Float64, DataValue::Float64(Some(f64::INFINITY)))?,
+        "Infinity"
+    );
+    assert_eq!(
+        text(DataType::Date16, DataValue::UInt16(Some(10957)))?,
+        "2000-01-01"
+    );
+    assert_eq!(
+        text(
+            DataType::DateTime32(None),
+            DataValue::UInt32(Some(946684801))
+        )?,
+        "2000-01-01 00:00:01"
+    );
+    assert_eq!(
+        text(DataType::String, DataValue::String(Some(b"abc".to_vec())))?,
+        "abc"
+    );
+    assert_eq!(
+        encode_value(&DataType::Int32, DataValue::Int32(None), 0)?,
+        None
+    );
+    Ok(())
+}
+
+#[test]
+fn test_encode_binary() -> Result<()> {
+    assert_eq!(
+        binary(DataType::Boolean, DataValue::Boolean(Some(true)))?,
+        vec![1]
+    );
+    assert_eq!(binary(DataType::UInt8, DataValue::UInt8(Some(255)))?, vec![
+        0, 255
+    ]);
+    assert_eq!(binary(DataType::Int32, DataValue::Int32(Some(-2)))?, vec![
+        255, 255, 255, 254
+    ]);
+    assert_eq!(
+        binary(DataType::Float32, DataValue::Float32(Some(1.0)))?,
+        1.0_f32.to_be_bytes().to_vec()
+    );
+    assert_eq!(
+        binary(DataType::Date32, DataValue::Int32(Some(10958)))?,
+        vec![0, 0, 0, 1]
+    );
+    assert_eq!(
+        binary(
+            DataType::DateTime32(None),
+            DataValue::UInt32(Some(946684801))
+        )?,
+        1_000_000_i64.to_be_bytes().to_vec()
+    );
+
+    // ndigits, weight, sign, dscale, then the digits in base 10000
+    assert_eq!(
+        binary(DataType::UInt64, DataValue::UInt64(Some(123456789)))?,
+        vec![0, 3, 0, 2, 0, 0, 0, 0, 0, 1, 9, 41, 26, 133]
+    );
+    assert_eq!(
+        binary(DataType::UInt64, DataValue::UInt64(Some(10000)))?,
+        vec![0, 1, 0, 1, 0, 0, 0, 0, 0, 1]
+    );
+    assert_eq!(binary(DataType::UInt64, DataValue::UInt64(Some(0)))?, vec![
+        0, 0, 0, 0, 0, 0, 0, 0
+    ]);
+
+    assert!(encode_value(&DataType::Int32, DataValue::Int32(Some(1)), 2).is_err());
+    Ok(())
+}
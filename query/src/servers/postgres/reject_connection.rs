@@ -0,0 +1,47 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio::net::TcpStream;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::servers::postgres::postgres_protocol::BackendMessage;
+use crate::servers::postgres::postgres_protocol::ErrorFields;
+use crate::servers::postgres::postgres_protocol::PostgresStream;
+use crate::servers::postgres::postgres_protocol::StartupMessage;
+
+pub struct RejectPostgresConnection;
+
+impl RejectPostgresConnection {
+    /// Answers the startup message of the client with a too_many_connections error.
+    pub async fn reject(stream: TcpStream, error: ErrorCode) -> Result<()> {
+        let mut stream = PostgresStream::create(stream);
+        loop {
+            match stream.read_startup().await? {
+                StartupMessage::SslRequest | StartupMessage::GssEncRequest => {
+                    stream.write_unsupported_encryption().await?
+                }
+                StartupMessage::CancelRequest { .. } => return Ok(()),
+                StartupMessage::Startup { .. } => break,
+            }
+        }
+
+        stream.write(BackendMessage::ErrorResponse(ErrorFields {
+            severity: "FATAL",
+            code: "53300",
+            message: error.message(),
+        }));
+        stream.flush().await
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::future::Future;
 use std::sync::atomic::Ordering;
@@ -29,9 +30,11 @@ use common_context::TableIOContext;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_infallible::RwLock;
+use common_management::UserInfo;
 use common_planners::Part;
 use common_planners::Partitions;
 use common_planners::PlanNode;
+use common_planners::PruningStatistics;
 use common_planners::ReadDataSourcePlan;
 use common_planners::Statistics;
 use common_streams::AbortStream;
@@ -43,10 +46,13 @@ use crate::catalogs::Table;
 use crate::clusters::ClusterRef;
 use crate::configs::Config;
 use crate::datasources::common::ContextDalBuilder;
+use crate::datasources::table::fuse::TableCache;
+use crate::pipelines::transforms::CteMaterializerRef;
 use crate::servers::http::v1::query::HttpQueryHandle;
 use crate::sessions::context_shared::DatabendQueryContextShared;
 use crate::sessions::SessionManagerRef;
 use crate::sessions::Settings;
+use crate::sessions::Transaction;
 
 pub struct DatabendQueryContext {
     statistics: Arc<RwLock<Statistics>>,
@@ -104,6 +110,15 @@ impl DatabendQueryContext {
             .add_total_rows_approx(total_rows);
     }
 
+    /// Accumulates the partitions pruned by the scans of the query, e.g. by the indexes of fuse tables.
+    pub fn add_pruning_statistics(&self, pruning: &PruningStatistics) {
+        self.shared.add_pruning_statistics(pruning);
+    }
+
+    pub fn get_pruning_statistics(&self) -> PruningStatistics {
+        self.shared.get_pruning_statistics()
+    }
+
     // Steal n partitions from the partition pool by the pipeline worker.
     // This also can steal the partitions from distributed node.
     pub fn try_get_partitions(&self, num: usize) -> Result<Partitions> {
@@ -195,6 +210,11 @@ impl DatabendQueryContext {
         self.shared.get_current_database()
     }
 
+    /// The user authenticated by the handler of the session, None if not authenticated.
+    pub fn get_current_user(&self) -> Option<UserInfo> {
+        self.shared.get_current_user()
+    }
+
     pub fn set_current_database(&self, new_database_name: String) -> Result<()> {
         let rt = self.shared.try_get_runtime()?;
         let cata = self.get_catalog();
@@ -228,6 +248,16 @@ impl DatabendQueryContext {
         self.shared.get_settings()
     }
 
+    /// Updates the settings for this query only, the settings of the session are unchanged.
+    pub fn apply_query_settings(&self, settings: &HashMap<String, String>) -> Result<()> {
+        let query_settings = self.get_settings().copy();
+        for (name, value) in settings {
+            query_settings.update_settings(name, value.clone())?;
+        }
+        *self.shared.query_settings.write() = Some(query_settings);
+        Ok(())
+    }
+
     pub fn get_config(&self) -> Config {
         self.shared.conf.clone()
     }
@@ -237,6 +267,19 @@ impl DatabendQueryContext {
         format!("_subquery_{}", index)
     }
 
+    pub fn get_cte_id(&self, name: &str) -> String {
+        let index = self.shared.cte_index.fetch_add(1, Ordering::Relaxed);
+        format!("_cte_{}_{}", name, index)
+    }
+
+    pub fn get_cte_materializer(
+        &self,
+        cte_id: &str,
+        create: impl FnOnce() -> CteMaterializerRef,
+    ) -> CteMaterializerRef {
+        self.shared.get_cte_materializer(cte_id, create)
+    }
+
     pub fn attach_query_str(&self, query: &str) {
         self.shared.attach_query_str(query);
     }
@@ -249,6 +292,23 @@ impl DatabendQueryContext {
         self.shared.session.get_sessions_manager()
     }
 
+    pub fn get_table_cache(&self) -> Arc<TableCache> {
+        self.shared.session.get_sessions_manager().get_table_cache()
+    }
+
+    pub fn begin_transaction(&self) -> Result<()> {
+        self.shared.session.begin_transaction()
+    }
+
+    /// The explicit transaction in progress of the session, if any.
+    pub fn get_transaction(&self) -> Option<Arc<Transaction>> {
+        self.shared.session.get_transaction()
+    }
+
+    pub fn take_transaction(&self) -> Option<Arc<Transaction>> {
+        self.shared.session.take_transaction()
+    }
+
     pub fn get_shared_runtime(&self) -> Result<Arc<Runtime>> {
         self.shared.try_get_runtime()
     }
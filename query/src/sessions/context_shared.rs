@@ -24,7 +24,9 @@ use common_base::Runtime;
 use common_exception::Result;
 use common_infallible::Mutex;
 use common_infallible::RwLock;
+use common_management::UserInfo;
 use common_planners::PlanNode;
+use common_planners::PruningStatistics;
 use futures::future::AbortHandle;
 use uuid::Uuid;
 
@@ -33,6 +35,7 @@ use crate::catalogs::Catalog;
 use crate::catalogs::Table;
 use crate::clusters::ClusterRef;
 use crate::configs::Config;
+use crate::pipelines::transforms::CteMaterializerRef;
 use crate::servers::http::v1::query::HttpQueryHandle;
 use crate::sessions::Session;
 use crate::sessions::Settings;
@@ -58,10 +61,15 @@ pub struct DatabendQueryContextShared {
     pub(in crate::sessions) sources_abort_handle: Arc<RwLock<Vec<AbortHandle>>>,
     pub(in crate::sessions) ref_count: Arc<AtomicUsize>,
     pub(in crate::sessions) subquery_index: Arc<AtomicUsize>,
+    pub(in crate::sessions) cte_index: Arc<AtomicUsize>,
     pub(in crate::sessions) running_query: Arc<RwLock<Option<String>>>,
     pub(in crate::sessions) http_query: Arc<RwLock<Option<HttpQueryHandle>>>,
     pub(in crate::sessions) running_plan: Arc<RwLock<Option<PlanNode>>>,
+    pub(in crate::sessions) pruning: Arc<RwLock<PruningStatistics>>,
     pub(in crate::sessions) tables_refs: Arc<Mutex<HashMap<DatabaseAndTable, Arc<dyn Table>>>>,
+    pub(in crate::sessions) cte_materializers: Arc<Mutex<HashMap<String, CteMaterializerRef>>>,
+    // the settings of this query only, instead of the ones of the session
+    pub(in crate::sessions) query_settings: Arc<RwLock<Option<Arc<Settings>>>>,
 }
 
 impl DatabendQueryContextShared {
@@ -80,10 +88,14 @@ impl DatabendQueryContextShared {
             sources_abort_handle: Arc::new(RwLock::new(Vec::new())),
             ref_count: Arc::new(AtomicUsize::new(0)),
             subquery_index: Arc::new(AtomicUsize::new(1)),
+            cte_index: Arc::new(AtomicUsize::new(1)),
             running_query: Arc::new(RwLock::new(None)),
             http_query: Arc::new(RwLock::new(None)),
             running_plan: Arc::new(RwLock::new(None)),
+            pruning: Arc::new(RwLock::new(PruningStatistics::default())),
             tables_refs: Arc::new(Mutex::new(HashMap::new())),
+            cte_materializers: Arc::new(Mutex::new(HashMap::new())),
+            query_settings: Arc::new(RwLock::new(None)),
         })
     }
 
@@ -114,8 +126,15 @@ impl DatabendQueryContextShared {
         self.session.set_current_database(new_database_name);
     }
 
+    pub fn get_current_user(&self) -> Option<UserInfo> {
+        self.session.get_current_user()
+    }
+
     pub fn get_settings(&self) -> Arc<Settings> {
-        self.session.get_settings()
+        match &*self.query_settings.read() {
+            Some(settings) => settings.clone(),
+            None => self.session.get_settings(),
+        }
     }
 
     pub fn get_catalog(&self) -> Arc<DatabaseCatalog> {
@@ -137,6 +156,7 @@ impl DatabendQueryContextShared {
                 let table = table.to_string();
                 let t = (async move { catalog.get_table(&database, &table).await })
                     .wait_in(&rt, Some(Duration::from_millis(5000)))??;
+                let t = self.with_staged_changes(t)?;
 
                 entry.insert(t).clone()
             }
@@ -145,6 +165,38 @@ impl DatabendQueryContextShared {
         Ok(ent)
     }
 
+    /// Inside a transaction, the statements work on top of the changes the transaction has
+    /// staged for the table, rather than on what has been committed.
+    fn with_staged_changes(&self, table: Arc<dyn Table>) -> Result<Arc<dyn Table>> {
+        let staged = match self.session.get_transaction() {
+            None => None,
+            Some(txn) => txn.get_staged_table(table.get_id()),
+        };
+
+        match staged {
+            None => Ok(table),
+            Some(staged) => {
+                let mut table_info = table.get_table_info().clone();
+                table_info.ident.version = staged.table_version;
+                table_info.meta.options.extend(staged.options);
+                self.get_catalog().build_table(&table_info)
+            }
+        }
+    }
+
+    /// All the scans of the same CTE in the query share one materializer.
+    pub fn get_cte_materializer(
+        &self,
+        cte_id: &str,
+        create: impl FnOnce() -> CteMaterializerRef,
+    ) -> CteMaterializerRef {
+        let mut cte_materializers = self.cte_materializers.lock();
+        cte_materializers
+            .entry(cte_id.to_string())
+            .or_insert_with(create)
+            .clone()
+    }
+
     /// Init runtime when first get
     pub fn try_get_runtime(&self) -> Result<Arc<Runtime>> {
         let mut query_runtime = self.runtime.write();
@@ -175,6 +227,14 @@ impl DatabendQueryContextShared {
         *running_plan = Some(plan.clone());
     }
 
+    pub fn add_pruning_statistics(&self, pruning: &PruningStatistics) {
+        self.pruning.write().merge(pruning);
+    }
+
+    pub fn get_pruning_statistics(&self) -> PruningStatistics {
+        self.pruning.read().clone()
+    }
+
     pub fn add_source_abort_handle(&self, handle: AbortHandle) {
         let mut sources_abort_handle = self.sources_abort_handle.write();
         sources_abort_handle.push(handle);
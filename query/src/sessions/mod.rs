@@ -26,6 +26,7 @@ mod session_test;
 mod sessions;
 mod sessions_info;
 mod settings;
+mod transaction;
 
 pub use context::DatabendQueryContext;
 pub use context::DatabendQueryContextRef;
@@ -36,3 +37,5 @@ pub use session_ref::SessionRef;
 pub use sessions::SessionManager;
 pub use sessions::SessionManagerRef;
 pub use settings::Settings;
+pub use transaction::StagedTable;
+pub use transaction::Transaction;
@@ -16,26 +16,33 @@ use std::net::SocketAddr;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 
+use common_dal::DataAccessorBuilder;
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_infallible::Mutex;
 use common_macros::MallocSizeOf;
+use common_management::UserInfo;
 use common_mem_allocator::malloc_size;
 use futures::channel::oneshot::Sender;
 use futures::channel::*;
 
 use crate::catalogs::impls::DatabaseCatalog;
 use crate::configs::Config;
+use crate::datasources::common::ContextDalBuilder;
 use crate::sessions::context_shared::DatabendQueryContextShared;
 use crate::sessions::DatabendQueryContext;
 use crate::sessions::DatabendQueryContextRef;
 use crate::sessions::SessionManagerRef;
 use crate::sessions::Settings;
+use crate::sessions::Transaction;
 use crate::users::UserManagerRef;
 
 #[derive(MallocSizeOf)]
 pub(in crate::sessions) struct MutableStatus {
     pub(in crate::sessions) abort: bool,
     pub(in crate::sessions) current_database: String,
+    #[ignore_malloc_size_of = "insignificant"]
+    pub(in crate::sessions) current_user: Option<UserInfo>,
     pub(in crate::sessions) session_settings: Arc<Settings>,
     #[ignore_malloc_size_of = "insignificant"]
     pub(in crate::sessions) client_host: Option<SocketAddr>,
@@ -43,6 +50,8 @@ pub(in crate::sessions) struct MutableStatus {
     pub(in crate::sessions) io_shutdown_tx: Option<Sender<Sender<()>>>,
     #[ignore_malloc_size_of = "insignificant"]
     pub(in crate::sessions) context_shared: Option<Arc<DatabendQueryContextShared>>,
+    #[ignore_malloc_size_of = "insignificant"]
+    pub(in crate::sessions) transaction: Option<Arc<Transaction>>,
 }
 
 #[derive(Clone, MallocSizeOf)]
@@ -73,10 +82,12 @@ impl Session {
             mutable_state: Arc::new(Mutex::new(MutableStatus {
                 abort: false,
                 current_database: String::from("default"),
+                current_user: None,
                 session_settings: Settings::try_create()?,
                 client_host: None,
                 io_shutdown_tx: None,
                 context_shared: None,
+                transaction: None,
             })),
         }))
     }
@@ -113,6 +124,11 @@ impl Session {
         self.kill(/* shutdown io stream */);
     }
 
+    /// Whether a query is running in the session.
+    pub fn is_running_query(self: &Arc<Self>) -> bool {
+        self.mutable_state.lock().context_shared.is_some()
+    }
+
     pub fn force_kill_query(self: &Arc<Self>) {
         let mut mutable_state = self.mutable_state.lock();
 
@@ -178,10 +194,68 @@ impl Session {
         inner.current_database.clone()
     }
 
+    /// Attaches the user authenticated by the handler of the session.
+    pub fn set_current_user(self: &Arc<Self>, user: UserInfo) {
+        self.mutable_state.lock().current_user = Some(user);
+    }
+
+    /// The authenticated user of the session, None if not authenticated yet.
+    pub fn get_current_user(self: &Arc<Self>) -> Option<UserInfo> {
+        self.mutable_state.lock().current_user.clone()
+    }
+
     pub fn get_settings(self: &Arc<Self>) -> Arc<Settings> {
         self.mutable_state.lock().session_settings.clone()
     }
 
+    pub fn begin_transaction(self: &Arc<Self>) -> Result<()> {
+        let mut inner = self.mutable_state.lock();
+        if inner.transaction.is_some() {
+            return Err(ErrorCode::BadArguments(
+                "There is already a transaction in progress",
+            ));
+        }
+        inner.transaction = Some(Arc::new(Transaction::default()));
+        Ok(())
+    }
+
+    /// The transaction in progress, if any.
+    pub fn get_transaction(self: &Arc<Self>) -> Option<Arc<Transaction>> {
+        self.mutable_state.lock().transaction.clone()
+    }
+
+    /// Ends the transaction in progress, it is up to the caller to commit or discard it.
+    pub fn take_transaction(self: &Arc<Self>) -> Option<Arc<Transaction>> {
+        self.mutable_state.lock().transaction.take()
+    }
+
+    /// Rolls back the transaction in progress, if any, when the session is closed without
+    /// ending it. The objects written by the transaction are removed in the background.
+    pub fn rollback_transaction(self: &Arc<Self>) {
+        let txn = match self.take_transaction() {
+            None => return,
+            Some(txn) => txn,
+        };
+
+        let id = self.id.clone();
+        let table_cache = self.sessions.get_table_cache();
+        let dal_builder = ContextDalBuilder::new(self.config.storage.clone());
+        common_base::tokio::spawn(async move {
+            let discarded = match dal_builder.build() {
+                Ok(da) => txn.discard(da, table_cache).await,
+                Err(cause) => Err(cause),
+            };
+            // the leftovers are removed by purge
+            if let Err(cause) = discarded {
+                log::warn!(
+                    "Failed to roll back the transaction of the closed session {}: {}",
+                    id,
+                    cause
+                );
+            }
+        });
+    }
+
     pub fn get_sessions_manager(self: &Arc<Self>) -> SessionManagerRef {
         self.sessions.clone()
     }
@@ -15,6 +15,8 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use common_planners::PruningStatistics;
+
 use crate::sessions::session::MutableStatus;
 use crate::sessions::Session;
 use crate::sessions::Settings;
@@ -29,6 +31,8 @@ pub struct ProcessInfo {
     pub client_address: Option<SocketAddr>,
     pub session_extra_info: Option<String>,
     pub memory_usage: u64,
+    /// Partitions pruned by the running query, None if idle
+    pub pruning: Option<PruningStatistics>,
 }
 
 impl Session {
@@ -48,6 +52,11 @@ impl Session {
             }
         }
 
+        let pruning = status
+            .context_shared
+            .as_ref()
+            .map(|shared| shared.get_pruning_statistics());
+
         ProcessInfo {
             id: self.id.clone(),
             typ: self.typ.clone(),
@@ -57,6 +66,7 @@ impl Session {
             client_address: status.client_host,
             session_extra_info: self.process_extra_info(status),
             memory_usage,
+            pruning,
         }
     }
 
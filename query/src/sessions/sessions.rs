@@ -32,6 +32,7 @@ use crate::catalogs::impls::DatabaseCatalog;
 use crate::clusters::ClusterDiscovery;
 use crate::clusters::ClusterDiscoveryRef;
 use crate::configs::Config;
+use crate::datasources::table::fuse::TableCache;
 use crate::servers::http::v1::query::HttpQueryManager;
 use crate::servers::http::v1::query::HttpQueryManagerRef;
 use crate::sessions::session::Session;
@@ -45,6 +46,7 @@ pub struct SessionManager {
     pub(in crate::sessions) catalog: Arc<DatabaseCatalog>,
     pub(in crate::sessions) user: UserManagerRef,
     pub(in crate::sessions) http_query_manager: HttpQueryManagerRef,
+    pub(in crate::sessions) table_cache: Arc<TableCache>,
 
     pub(in crate::sessions) max_sessions: usize,
     pub(in crate::sessions) active_sessions: Arc<RwLock<HashMap<String, Arc<Session>>>>,
@@ -64,6 +66,9 @@ impl SessionManager {
 
         let http_query_manager = HttpQueryManager::create_global(conf.clone()).await?;
 
+        // Caches of the fuse tables, shared by all the sessions.
+        let table_cache = TableCache::create_global(&conf)?;
+
         let max_active_sessions = conf.query.max_active_sessions as usize;
         Ok(Arc::new(SessionManager {
             catalog,
@@ -71,6 +76,7 @@ impl SessionManager {
             discovery,
             user,
             http_query_manager,
+            table_cache,
             max_sessions: max_active_sessions,
             active_sessions: Arc::new(RwLock::new(HashMap::with_capacity(max_active_sessions))),
         }))
@@ -88,6 +94,10 @@ impl SessionManager {
         self.http_query_manager.clone()
     }
 
+    pub fn get_table_cache(self: &Arc<Self>) -> Arc<TableCache> {
+        self.table_cache.clone()
+    }
+
     // Get the user api provider.
     pub fn get_user_manager(self: &Arc<Self>) -> UserManagerRef {
         self.user.clone()
@@ -31,7 +31,11 @@ impl Settings {
         ("max_threads", u64, 16, "The maximum number of threads to execute the request. By default, it is determined automatically."),
         ("flight_client_timeout", u64, 60, "Max duration the flight client request is allowed to take in seconds. By default, it is 60 seconds"),
         ("min_distributed_rows", u64, 100000000, "Minimum distributed read rows. In cluster mode, when read rows exceeds this value, the local table converted to distributed query."),
-        ("min_distributed_bytes", u64, 500 * 1024 * 1024, "Minimum distributed read bytes. In cluster mode, when read bytes exceeds this value, the local table converted to distributed query.")
+        ("min_distributed_bytes", u64, 500 * 1024 * 1024, "Minimum distributed read bytes. In cluster mode, when read bytes exceeds this value, the local table converted to distributed query."),
+        ("auto_compact_segments_threshold", u64, 0, "Compact the fuse table in the background after insertion, when the number of its segments exceeds this value. By default, it is 0 (disabled)."),
+        ("retention_snapshots", u64, 1, "The number of the latest snapshots of a fuse table kept by purging. By default, it is 1 (only the current snapshot)."),
+        ("retention_period_seconds", u64, 0, "The snapshots of a fuse table generated within this period are kept by purging, in seconds. By default, it is 0."),
+        ("purge_grace_period_seconds", u64, 3600, "The objects of a fuse table modified within this period are not removed by purging, since they might be written by ongoing operations, in seconds. By default, it is 3600.")
     }
 
     pub fn try_create() -> Result<Arc<Settings>> {
@@ -45,6 +49,13 @@ impl Settings {
         Ok(settings)
     }
 
+    /// A copy of the settings, updating which leaves these settings unchanged.
+    pub fn copy(&self) -> Arc<Settings> {
+        Arc::new(Settings {
+            inner: self.inner.copy(),
+        })
+    }
+
     pub fn iter(&self) -> SettingsIterator {
         SettingsIterator {
             settings: self.inner.get_settings(),
@@ -66,6 +77,12 @@ impl SettingsBase {
         }
     }
 
+    pub fn copy(&self) -> Self {
+        SettingsBase {
+            settings: Arc::new(RwLock::new(self.settings.read().clone())),
+        }
+    }
+
     // TODO, to use macro generate this codes
     #[allow(unused)]
     pub fn try_set_u64(&self, key: &'static str, val: u64, desc: &str) -> Result<()> {
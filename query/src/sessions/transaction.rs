@@ -0,0 +1,105 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_dal::DataAccessor;
+use common_exception::Result;
+use common_infallible::Mutex;
+use common_meta_types::MetaId;
+use common_meta_types::MetaVersion;
+use common_meta_types::TableOptionsUpsert;
+
+use crate::datasources::table::fuse::TableCache;
+
+/// Changes of the tables touched by an explicit transaction (BEGIN ... COMMIT).
+///
+/// Instead of being committed one by one, the new table options (e.g. the snapshot location
+/// of a fuse table) are staged here, and committed together on COMMIT, on condition that none
+/// of the tables has been changed by others since the transaction touched it.
+#[derive(Default)]
+pub struct Transaction {
+    staged: Mutex<HashMap<MetaId, StagedTable>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct StagedTable {
+    /// Version of the table when the transaction touched it for the first time.
+    pub table_version: MetaVersion,
+    /// Options to upsert on COMMIT.
+    pub options: HashMap<String, String>,
+    /// Objects written by the transaction, which are removed if it is rolled back.
+    pub objects: Vec<String>,
+}
+
+impl Transaction {
+    /// Stages an option of a table, `table_version` is only recorded the first time the table
+    /// is staged, the later statements work on top of the staged options.
+    pub fn stage(
+        &self,
+        table_id: MetaId,
+        table_version: MetaVersion,
+        key: &str,
+        value: String,
+        objects: Vec<String>,
+    ) {
+        let mut staged = self.staged.lock();
+        let table = staged.entry(table_id).or_insert_with(|| StagedTable {
+            table_version,
+            options: HashMap::new(),
+            objects: vec![],
+        });
+        table.options.insert(key.to_string(), value);
+        table.objects.extend(objects);
+    }
+
+    pub fn get_staged_table(&self, table_id: MetaId) -> Option<StagedTable> {
+        self.staged.lock().get(&table_id).cloned()
+    }
+
+    /// The upserts to commit all of the staged tables at once.
+    pub fn get_upserts(&self) -> Vec<TableOptionsUpsert> {
+        self.staged
+            .lock()
+            .iter()
+            .map(|(table_id, table)| TableOptionsUpsert {
+                table_id: *table_id,
+                table_version: table.table_version,
+                options: table.options.clone(),
+            })
+            .collect()
+    }
+
+    pub fn get_staged_objects(&self) -> Vec<String> {
+        self.staged
+            .lock()
+            .values()
+            .flat_map(|table| table.objects.iter().cloned())
+            .collect()
+    }
+
+    /// Removes the objects written by the transaction on rollback, none of them is referenced.
+    pub async fn discard(
+        &self,
+        da: Arc<dyn DataAccessor>,
+        table_cache: Arc<TableCache>,
+    ) -> Result<()> {
+        for location in self.get_staged_objects() {
+            da.delete(&location).await?;
+            table_cache.evict(&location);
+        }
+        Ok(())
+    }
+}
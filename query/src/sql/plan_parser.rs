@@ -13,28 +13,43 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::sync::Arc;
 
+use chrono::DateTime;
+use chrono::NaiveDateTime;
+use chrono::Utc;
+use common_datablocks::DataBlock;
 use common_datavalues::prelude::*;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_functions::aggregates::AggregateFunctionFactory;
+use common_infallible::RwLock;
 use common_meta_types::TableMeta;
+use common_planners::col;
 use common_planners::expand_aggregate_arg_exprs;
 use common_planners::expand_wildcard;
+use common_planners::expand_window_arg_exprs;
 use common_planners::expr_as_column_expr;
 use common_planners::extract_aliases;
 use common_planners::find_aggregate_exprs;
+use common_planners::find_column_exprs;
 use common_planners::find_columns_not_satisfy_exprs;
+use common_planners::find_window_exprs;
 use common_planners::rebase_expr;
 use common_planners::rebase_expr_from_input;
 use common_planners::resolve_aliases_to_exprs;
 use common_planners::sort_to_inner_expr;
 use common_planners::unwrap_alias_exprs;
+use common_planners::AlterTableOperation;
+use common_planners::AlterTablePlan;
 use common_planners::AlterUserPlan;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
 use common_planners::CreateUserPlan;
+use common_planners::CteScanPlan;
+use common_planners::DeletePlan;
 use common_planners::DescribeTablePlan;
 use common_planners::DropDatabasePlan;
 use common_planners::DropTablePlan;
@@ -43,33 +58,55 @@ use common_planners::Expression;
 use common_planners::Extras;
 use common_planners::GrantPrivilegePlan;
 use common_planners::InsertIntoPlan;
+use common_planners::JoinPlan;
+use common_planners::JoinType;
 use common_planners::KillPlan;
+use common_planners::OptimizeTablePlan;
 use common_planners::PlanBuilder;
 use common_planners::PlanNode;
+use common_planners::PlanRewriter;
 use common_planners::SelectPlan;
+use common_planners::SetOperator;
 use common_planners::SettingPlan;
 use common_planners::ShowCreateTablePlan;
+use common_planners::TransactionPlan;
 use common_planners::TruncateTablePlan;
+use common_planners::UpdatePlan;
 use common_planners::UseDatabasePlan;
 use common_planners::VarValue;
+use common_planners::WindowFrame;
+use common_planners::WindowFrameBound;
+use common_planners::WindowFrameUnits;
 use common_tracing::tracing;
 use nom::FindSubstring;
+use sqlparser::ast::Assignment;
+use sqlparser::ast::ColumnOption;
 use sqlparser::ast::FunctionArg;
 use sqlparser::ast::Ident;
+use sqlparser::ast::JoinConstraint;
+use sqlparser::ast::JoinOperator;
 use sqlparser::ast::ObjectName;
 use sqlparser::ast::OrderByExpr;
 use sqlparser::ast::Query;
+use sqlparser::ast::SetExpr;
 use sqlparser::ast::Statement;
 use sqlparser::ast::TableFactor;
+use sqlparser::ast::TableWithJoins;
 use sqlparser::ast::UnaryOperator;
+use sqlparser::ast::With;
 
 use crate::catalogs::Catalog;
+use crate::catalogs::NavigationPoint;
 use crate::catalogs::ToReadDataSourcePlan;
+use crate::datasources::table::fuse::util::TBL_OPT_KEY_CLUSTER_KEYS;
 use crate::functions::ContextFunction;
+use crate::pipelines::transforms::ExpressionExecutor;
 use crate::sessions::DatabendQueryContextRef;
 use crate::sql::sql_statement::DfCreateTable;
 use crate::sql::sql_statement::DfDropDatabase;
 use crate::sql::sql_statement::DfUseDatabase;
+use crate::sql::DfAlterTable;
+use crate::sql::DfAlterTableOperation;
 use crate::sql::DfAlterUser;
 use crate::sql::DfCreateDatabase;
 use crate::sql::DfCreateUser;
@@ -79,21 +116,31 @@ use crate::sql::DfExplain;
 use crate::sql::DfGrantStatement;
 use crate::sql::DfHint;
 use crate::sql::DfKillStatement;
+use crate::sql::DfOptimizeTable;
 use crate::sql::DfParser;
 use crate::sql::DfShowCreateTable;
 use crate::sql::DfShowDatabases;
 use crate::sql::DfShowTables;
 use crate::sql::DfStatement;
+use crate::sql::DfTransaction;
 use crate::sql::DfTruncateTable;
 use crate::sql::SQLCommon;
 
 pub struct PlanParser {
     ctx: DatabendQueryContextRef,
+    // The scopes of the common table expressions, the innermost scope is the last one.
+    ctes: RwLock<Vec<HashMap<String, CteScanPlan>>>,
+    // The reference count of each CTE, by the cte_id.
+    cte_references: RwLock<HashMap<String, usize>>,
 }
 
 impl PlanParser {
     pub fn create(ctx: DatabendQueryContextRef) -> Self {
-        Self { ctx }
+        Self {
+            ctx,
+            ctes: RwLock::new(vec![]),
+            cte_references: RwLock::new(HashMap::new()),
+        }
     }
 
     pub fn build_from_sql(&self, query: &str) -> Result<PlanNode> {
@@ -134,6 +181,8 @@ impl PlanParser {
             DfStatement::DescribeTable(v) => self.sql_describe_table_to_plan(v),
             DfStatement::DropTable(v) => self.sql_drop_table_to_plan(v),
             DfStatement::TruncateTable(v) => self.sql_truncate_table_to_plan(v),
+            DfStatement::OptimizeTable(v) => self.sql_optimize_table_to_plan(v),
+            DfStatement::AlterTable(v) => self.sql_alter_table_to_plan(v),
             DfStatement::UseDatabase(v) => self.sql_use_database_to_plan(v),
             DfStatement::ShowCreateTable(v) => self.sql_show_create_table_to_plan(v),
             DfStatement::ShowTables(df) => {
@@ -180,6 +229,7 @@ impl PlanParser {
             }
             DfStatement::AlterUser(v) => self.sql_alter_user_to_plan(v),
             DfStatement::GrantPrivilege(v) => self.sql_grant_privilege_to_plan(v),
+            DfStatement::Transaction(v) => self.sql_transaction_to_plan(v),
         }
     }
 
@@ -202,6 +252,16 @@ impl PlanParser {
                 self.insert_to_plan(table_name, columns, source, &format_sql)
             }
 
+            Statement::Delete {
+                table_name,
+                selection,
+            } => self.delete_to_plan(table_name, selection),
+            Statement::Update {
+                table_name,
+                assignments,
+                selection,
+            } => self.update_to_plan(table_name, assignments, selection),
+
             _ => Result::Err(ErrorCode::SyntaxException(format!(
                 "Unsupported statement {:?}",
                 statement
@@ -327,6 +387,26 @@ impl PlanParser {
         }
 
         let schema = DataSchemaRefExt::create(fields);
+
+        // the cluster keys are kept in the options, which are validated by the fuse table
+        if !create.cluster_keys.is_empty() {
+            if !create.engine.eq_ignore_ascii_case("FUSE") {
+                return Result::Err(ErrorCode::SyntaxException(format!(
+                    "CLUSTER BY is not supported by the engine {}",
+                    create.engine
+                )));
+            }
+            let cluster_keys = create
+                .cluster_keys
+                .iter()
+                .map(|expr| self.sql_to_rex(expr, &schema, None))
+                .collect::<Result<Vec<_>>>()?;
+            options.insert(
+                TBL_OPT_KEY_CLUSTER_KEYS.to_string(),
+                serde_json::to_string(&cluster_keys)?,
+            );
+        }
+
         Ok(PlanNode::CreateTable(CreateTablePlan {
             if_not_exists: create.if_not_exists,
             db,
@@ -369,6 +449,13 @@ impl PlanParser {
         }))
     }
 
+    #[tracing::instrument(level = "info", skip(self, transaction), fields(ctx.id = self.ctx.get_id().as_str()))]
+    pub fn sql_transaction_to_plan(&self, transaction: &DfTransaction) -> Result<PlanNode> {
+        Ok(PlanNode::Transaction(TransactionPlan {
+            operation: transaction.operation.clone(),
+        }))
+    }
+
     #[tracing::instrument(level = "info", skip(self, show_create), fields(ctx.id = self.ctx.get_id().as_str()))]
     pub fn sql_show_create_table_to_plan(
         &self,
@@ -462,6 +549,102 @@ impl PlanParser {
         Ok(PlanNode::TruncateTable(TruncateTablePlan { db, table }))
     }
 
+    // DfOptimizeTable to plan.
+    #[tracing::instrument(level = "info", skip(self, optimize), fields(ctx.id = self.ctx.get_id().as_str()))]
+    pub fn sql_optimize_table_to_plan(&self, optimize: &DfOptimizeTable) -> Result<PlanNode> {
+        let mut db = self.ctx.get_current_database();
+        if optimize.name.0.is_empty() {
+            return Result::Err(ErrorCode::SyntaxException(
+                "OptimizeTable table name is empty",
+            ));
+        }
+        let mut table = optimize.name.0[0].value.clone();
+        if optimize.name.0.len() > 1 {
+            db = table;
+            table = optimize.name.0[1].value.clone();
+        }
+
+        Ok(PlanNode::OptimizeTable(OptimizeTablePlan {
+            db,
+            table,
+            operation: optimize.operation,
+        }))
+    }
+
+    // DfAlterTable to plan.
+    #[tracing::instrument(level = "info", skip(self, alter), fields(ctx.id = self.ctx.get_id().as_str()))]
+    pub fn sql_alter_table_to_plan(&self, alter: &DfAlterTable) -> Result<PlanNode> {
+        let mut db = self.ctx.get_current_database();
+        if alter.name.0.is_empty() {
+            return Result::Err(ErrorCode::SyntaxException("AlterTable table name is empty"));
+        }
+        let mut table = alter.name.0[0].value.clone();
+        if alter.name.0.len() > 1 {
+            db = table;
+            table = alter.name.0[1].value.clone();
+        }
+
+        let operation = match &alter.operation {
+            DfAlterTableOperation::AddColumn(column) => {
+                let data_type = SQLCommon::make_data_type(&column.data_type)?;
+                let default_value = column
+                    .options
+                    .iter()
+                    .find_map(|opt| match &opt.option {
+                        ColumnOption::Default(expr) => Some(expr),
+                        _ => None,
+                    })
+                    .map(|expr| self.sql_to_const_value(expr, &data_type))
+                    .transpose()?;
+                AlterTableOperation::AddColumn {
+                    field: DataField::new(&column.name.value, data_type, false),
+                    default_value,
+                }
+            }
+            DfAlterTableOperation::DropColumn(name) => AlterTableOperation::DropColumn {
+                name: name.value.clone(),
+            },
+            DfAlterTableOperation::RenameColumn { old_name, new_name } => {
+                AlterTableOperation::RenameColumn {
+                    old_name: old_name.value.clone(),
+                    new_name: new_name.value.clone(),
+                }
+            }
+        };
+
+        Ok(PlanNode::AlterTable(AlterTablePlan {
+            db,
+            table,
+            operation,
+        }))
+    }
+
+    /// Evaluates a constant expression, e.g. the DEFAULT of a column, into a value of `data_type`.
+    fn sql_to_const_value(
+        &self,
+        expr: &sqlparser::ast::Expr,
+        data_type: &DataType,
+    ) -> Result<DataValue> {
+        let expr = Expression::Cast {
+            expr: Box::new(self.sql_to_rex(expr, &DataSchema::empty(), None)?),
+            data_type: data_type.clone(),
+        };
+
+        // constants are evaluated against a block of a single (dummy) row
+        let input_schema =
+            DataSchemaRefExt::create(vec![DataField::new("_dummy", DataType::UInt8, false)]);
+        let output_schema = DataSchemaRefExt::create(vec![expr.to_data_field(&input_schema)?]);
+        let executor = ExpressionExecutor::try_create(
+            "constant expression executor",
+            input_schema.clone(),
+            output_schema,
+            vec![expr],
+            false,
+        )?;
+        let block = DataBlock::create_by_array(input_schema, vec![Series::new(vec![0u8])]);
+        executor.execute(&block)?.column(0).try_get(0)
+    }
+
     #[tracing::instrument(level = "info", skip(self, table_name, columns, source), fields(ctx.id = self.ctx.get_id().as_str()))]
     fn insert_to_plan(
         &self,
@@ -511,16 +694,129 @@ impl PlanParser {
         Ok(PlanNode::InsertInto(plan_node))
     }
 
+    #[tracing::instrument(level = "info", skip(self, table_name, selection), fields(ctx.id = self.ctx.get_id().as_str()))]
+    fn delete_to_plan(
+        &self,
+        table_name: &ObjectName,
+        selection: &Option<sqlparser::ast::Expr>,
+    ) -> Result<PlanNode> {
+        let mut db_name = self.ctx.get_current_database();
+        let mut tbl_name = table_name.0[0].value.clone();
+
+        if table_name.0.len() > 1 {
+            db_name = tbl_name;
+            tbl_name = table_name.0[1].value.clone();
+        }
+
+        let table = self.ctx.get_table(&db_name, &tbl_name)?;
+        let table_schema = table.schema();
+        let tbl_id = table.get_id();
+
+        let selection = selection
+            .as_ref()
+            .map(|expr| self.mutation_expr_to_rex(expr, &table_schema, "DELETE"))
+            .transpose()?;
+
+        Ok(PlanNode::Delete(DeletePlan {
+            db_name,
+            tbl_name,
+            tbl_id,
+            table_schema,
+            selection,
+        }))
+    }
+
+    #[tracing::instrument(level = "info", skip(self, table_name, assignments, selection), fields(ctx.id = self.ctx.get_id().as_str()))]
+    fn update_to_plan(
+        &self,
+        table_name: &ObjectName,
+        assignments: &[Assignment],
+        selection: &Option<sqlparser::ast::Expr>,
+    ) -> Result<PlanNode> {
+        let mut db_name = self.ctx.get_current_database();
+        let mut tbl_name = table_name.0[0].value.clone();
+
+        if table_name.0.len() > 1 {
+            db_name = tbl_name;
+            tbl_name = table_name.0[1].value.clone();
+        }
+
+        let table = self.ctx.get_table(&db_name, &tbl_name)?;
+        let table_schema = table.schema();
+        let tbl_id = table.get_id();
+
+        let mut updated_columns = HashSet::with_capacity(assignments.len());
+        let mut update_list = Vec::with_capacity(assignments.len());
+        for assignment in assignments {
+            let column = assignment.id.value.clone();
+            let field = table_schema.field_with_name(&column).map_err(|_| {
+                ErrorCode::SyntaxException(format!(
+                    "Unknown column {} in table {}.{}",
+                    column, db_name, tbl_name
+                ))
+            })?;
+            if !updated_columns.insert(column.clone()) {
+                return Result::Err(ErrorCode::SyntaxException(format!(
+                    "Multiple assignments to the same column {}",
+                    column
+                )));
+            }
+
+            let value = self.mutation_expr_to_rex(&assignment.value, &table_schema, "UPDATE")?;
+            let value = Expression::Cast {
+                expr: Box::new(value),
+                data_type: field.data_type().clone(),
+            };
+            update_list.push((column, value));
+        }
+
+        let selection = selection
+            .as_ref()
+            .map(|expr| self.mutation_expr_to_rex(expr, &table_schema, "UPDATE"))
+            .transpose()?;
+
+        Ok(PlanNode::Update(UpdatePlan {
+            db_name,
+            tbl_name,
+            tbl_id,
+            table_schema,
+            assignments: update_list,
+            selection,
+        }))
+    }
+
+    /// Expressions of DELETE and UPDATE are evaluated row by row against the table.
+    fn mutation_expr_to_rex(
+        &self,
+        expr: &sqlparser::ast::Expr,
+        table_schema: &DataSchema,
+        clause: &str,
+    ) -> Result<Expression> {
+        let expr = self.sql_to_rex(expr, table_schema, None)?;
+        if !find_aggregate_exprs(&[expr.clone()]).is_empty() {
+            return Result::Err(ErrorCode::SyntaxException(format!(
+                "Aggregate function is not allowed in {}: {:?}",
+                clause, expr
+            )));
+        }
+        Self::check_no_window_exprs(&[expr.clone()], clause)?;
+        Ok(expr)
+    }
+
     /// Generate a logic plan from an SQL query
     pub fn query_to_plan(&self, query: &sqlparser::ast::Query) -> Result<PlanNode> {
-        if query.with.is_some() {
-            return Result::Err(ErrorCode::UnImplement("CTE is not yet implement"));
+        match &query.with {
+            None => self.query_body_to_plan(query),
+            Some(with) => self.with_to_plan(with, query),
         }
+    }
 
+    fn query_body_to_plan(&self, query: &sqlparser::ast::Query) -> Result<PlanNode> {
         match &query.body {
-            sqlparser::ast::SetExpr::Select(s) => {
+            SetExpr::Select(s) => {
                 self.select_to_plan(s.as_ref(), &query.limit, &query.offset, &query.order_by)
             }
+            SetExpr::SetOperation { .. } => self.set_operation_to_plan(query),
             _ => Result::Err(ErrorCode::UnImplement(format!(
                 "Query {} is not yet implemented",
                 query.body
@@ -528,6 +824,165 @@ impl PlanParser {
         }
     }
 
+    /// Generate a logic plan from a query with common table expressions.
+    /// Each CTE is visible to the CTEs after it and to the query body. A CTE referenced
+    /// more than once is scanned by CteScan and materialized once, the others are inlined.
+    /// For example:
+    /// "with t as (select number from numbers(10)) select * from t a join t b on a.number = b.number"
+    fn with_to_plan(&self, with: &With, query: &sqlparser::ast::Query) -> Result<PlanNode> {
+        if with.recursive {
+            return Result::Err(ErrorCode::UnImplement(
+                "Recursive CTE is not yet implemented",
+            ));
+        }
+
+        self.ctes.write().push(HashMap::new());
+        let plan = self.with_scope_to_plan(with, query);
+        let ctes = self.ctes.write().pop().unwrap_or_default();
+        let plan = plan?;
+
+        let cte_references = self.cte_references.read();
+        let inlined = ctes
+            .values()
+            .filter(|cte| cte_references.get(&cte.cte_id).copied().unwrap_or(0) <= 1)
+            .map(|cte| cte.cte_id.clone())
+            .collect::<HashSet<_>>();
+
+        match inlined.is_empty() {
+            true => Ok(plan),
+            false => CteInliner { inlined }.rewrite_plan_node(&plan),
+        }
+    }
+
+    fn with_scope_to_plan(&self, with: &With, query: &sqlparser::ast::Query) -> Result<PlanNode> {
+        for cte in &with.cte_tables {
+            let name = cte.alias.name.value.clone();
+            let duplicated = match self.ctes.read().last() {
+                Some(scope) => scope.contains_key(&name),
+                None => false,
+            };
+            if duplicated {
+                return Result::Err(ErrorCode::SyntaxException(format!(
+                    "Duplicate CTE name '{}'",
+                    name
+                )));
+            }
+
+            let mut plan = self.query_to_plan(&cte.query)?;
+            if !cte.alias.columns.is_empty() {
+                let schema = plan.schema();
+                if schema.fields().len() != cte.alias.columns.len() {
+                    return Result::Err(ErrorCode::BadArguments(format!(
+                        "CTE '{}' has {} columns, but {} column names are specified",
+                        name,
+                        schema.fields().len(),
+                        cte.alias.columns.len()
+                    )));
+                }
+
+                let exprs = schema
+                    .fields()
+                    .iter()
+                    .zip(cte.alias.columns.iter())
+                    .map(|(field, column)| col(field.name()).alias(&column.value))
+                    .collect::<Vec<_>>();
+                plan = PlanBuilder::from(&plan).project(&exprs)?.build()?;
+            }
+
+            let cte_plan = CteScanPlan {
+                cte_id: self.ctx.get_cte_id(&name),
+                name: name.clone(),
+                input: Arc::new(plan),
+            };
+            if let Some(scope) = self.ctes.write().last_mut() {
+                scope.insert(name, cte_plan);
+            }
+        }
+
+        self.query_body_to_plan(query)
+    }
+
+    fn find_cte(&self, name: &str) -> Option<CteScanPlan> {
+        let ctes = self.ctes.read();
+        let cte_plan = ctes.iter().rev().find_map(|scope| scope.get(name))?;
+
+        let mut cte_references = self.cte_references.write();
+        *cte_references.entry(cte_plan.cte_id.clone()).or_insert(0) += 1;
+        Some(cte_plan.clone())
+    }
+
+    /// Generate a logic plan from an SQL set operation, the ORDER BY and LIMIT are applied to the result.
+    /// For example:
+    /// "select number from numbers(10) union all select number from numbers(5) order by number limit 3"
+    fn set_operation_to_plan(&self, query: &sqlparser::ast::Query) -> Result<PlanNode> {
+        let plan = self.set_expr_to_plan(&query.body)?;
+
+        let order_by_exprs = query
+            .order_by
+            .iter()
+            .map(|e| -> Result<Expression> {
+                let expr = self.sql_to_rex(&e.expr, &plan.schema(), None)?;
+                Ok(Expression::Sort {
+                    expr: Box::new(expr.clone()),
+                    asc: e.asc.unwrap_or(true),
+                    nulls_first: e.nulls_first.unwrap_or(true),
+                    origin_expr: Box::new(expr),
+                })
+            })
+            .collect::<Result<Vec<Expression>>>()?;
+
+        let plan = self.sort(&plan, &order_by_exprs)?;
+        let plan = self.limit(&plan, &query.limit, &query.offset, None)?;
+
+        Ok(PlanNode::Select(SelectPlan {
+            input: Arc::new(plan),
+        }))
+    }
+
+    fn set_expr_to_plan(&self, set_expr: &SetExpr) -> Result<PlanNode> {
+        let plan = match set_expr {
+            SetExpr::Select(s) => self.select_to_plan(s.as_ref(), &None, &None, &[])?,
+            SetExpr::Query(q) => self.query_to_plan(q)?,
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                let op = match (op, all) {
+                    (sqlparser::ast::SetOperator::Union, true) => SetOperator::UnionAll,
+                    (sqlparser::ast::SetOperator::Union, false) => SetOperator::Union,
+                    (sqlparser::ast::SetOperator::Intersect, false) => SetOperator::Intersect,
+                    (sqlparser::ast::SetOperator::Except, false) => SetOperator::Except,
+                    (op, _) => {
+                        return Result::Err(ErrorCode::UnImplement(format!(
+                            "Set operation {} ALL is not yet implemented",
+                            op
+                        )))
+                    }
+                };
+
+                let left = self.set_expr_to_plan(left)?;
+                let right = self.set_expr_to_plan(right)?;
+                PlanBuilder::from(&left)
+                    .set_operation(&right, op)
+                    .and_then(|builder| builder.build())?
+            }
+            _ => {
+                return Result::Err(ErrorCode::UnImplement(format!(
+                    "Query {} is not yet implemented",
+                    set_expr
+                )))
+            }
+        };
+
+        // The inputs of set operation are not wrapped by SelectPlan.
+        match plan {
+            PlanNode::Select(select) => Ok(select.input.as_ref().clone()),
+            plan => Ok(plan),
+        }
+    }
+
     /// Generate a logic plan from an SQL select
     /// For example:
     /// "select sum(number+1)+2, number%3 as id from numbers(10) where number>1 group by id having id>1 order by id desc limit 3"
@@ -542,7 +997,7 @@ impl PlanParser {
         // Filter expression
         // In example: Filter=(number > 1)
         let plan = self
-            .plan_tables_with_joins(&select.from)
+            .plan_tables_with_joins(&select.from, Some(select))
             .and_then(|input| self.filter(&input, &select.selection, Some(select)))?;
 
         // Projection expression
@@ -570,6 +1025,7 @@ impl PlanParser {
                     .and_then(|expr| resolve_aliases_to_exprs(&expr, &aliases))
             })
             .collect::<Result<Vec<_>>>()?;
+        Self::check_no_window_exprs(&group_by_exprs, "GROUP BY")?;
 
         // Having Expression after against aliases
         // In example: Having=((number % 3) > 1)
@@ -579,6 +1035,7 @@ impl PlanParser {
             .map::<Result<Expression>, _>(|having_expr| {
                 let having_expr = self.sql_to_rex(having_expr, &plan.schema(), Some(select))?;
                 let having_expr = resolve_aliases_to_exprs(&having_expr, &aliases)?;
+                Self::check_no_window_exprs(&[having_expr.clone()], "HAVING")?;
 
                 Ok(having_expr)
             })
@@ -678,6 +1135,17 @@ impl PlanParser {
             (plan, having_expr_opt)
         };
 
+        // The window functions are evaluated after the aggregation and HAVING.
+        // In example: rank() over (order by number)
+        let window_exprs = find_window_exprs(&expression_with_sort);
+        let (plan, having_expr_post_aggr_opt) = match window_exprs.is_empty() {
+            true => (plan, having_expr_post_aggr_opt),
+            false => {
+                let plan = self.having(&plan, having_expr_post_aggr_opt)?;
+                (self.window(&plan, &window_exprs)?, None)
+            }
+        };
+
         let stage_phase = if order_by_exprs.is_empty() {
             "Before Projection"
         } else {
@@ -721,13 +1189,42 @@ impl PlanParser {
         }
     }
 
-    fn plan_tables_with_joins(&self, from: &[sqlparser::ast::TableWithJoins]) -> Result<PlanNode> {
-        match from.len() {
-            0 => self.plan_with_dummy_source(),
-            1 => self.plan_table_with_joins(&from[0]),
-            // Such as SELECT * FROM t1, t2;
-            // It's not `JOIN` clause.
-            _ => Result::Err(ErrorCode::SyntaxException("Cannot SELECT multiple tables")),
+    fn plan_tables_with_joins(
+        &self,
+        from: &[TableWithJoins],
+        select: Option<&sqlparser::ast::Select>,
+    ) -> Result<PlanNode> {
+        match from {
+            [] => self.plan_with_dummy_source(),
+            [t] if t.joins.is_empty() && !matches!(t.relation, TableFactor::NestedJoin(_)) => {
+                self.create_relation(&t.relation)
+            }
+            _ => {
+                // The columns which exist in more than one relation are qualified
+                // as `table.column` before joining, so they can be told apart.
+                let mut relations = Vec::new();
+                for t in from {
+                    self.collect_relations(t, &mut relations)?;
+                }
+
+                let duplicated_columns = Self::duplicated_columns(&relations);
+                let mut relations = relations
+                    .into_iter()
+                    .map(|(qualifier, plan)| {
+                        Self::qualify_relation(qualifier, plan, &duplicated_columns)
+                    })
+                    .collect::<Result<VecDeque<_>>>()?;
+
+                // Such as SELECT * FROM t1, t2, it's a cross join of all the tables.
+                let mut plan = self.plan_table_with_joins(&from[0], &mut relations, select)?;
+                for t in &from[1..] {
+                    let right = self.plan_table_with_joins(t, &mut relations, select)?;
+                    plan = PlanBuilder::from(&plan)
+                        .join(&right, JoinType::Cross, &[], &[], None)
+                        .and_then(|builder| builder.build())?;
+                }
+                Ok(plan)
+            }
         }
     }
 
@@ -747,13 +1244,249 @@ impl PlanParser {
         Ok(dummy_read_plan)
     }
 
-    fn plan_table_with_joins(&self, t: &sqlparser::ast::TableWithJoins) -> Result<PlanNode> {
-        self.create_relation(&t.relation)
+    fn plan_table_with_joins(
+        &self,
+        t: &TableWithJoins,
+        relations: &mut VecDeque<PlanNode>,
+        select: Option<&sqlparser::ast::Select>,
+    ) -> Result<PlanNode> {
+        let mut plan = self.plan_table_factor(&t.relation, relations, select)?;
+        for join in &t.joins {
+            let right = self.plan_table_factor(&join.relation, relations, select)?;
+            plan = self.join(&plan, &right, &join.join_operator, select)?;
+        }
+        Ok(plan)
+    }
+
+    fn plan_table_factor(
+        &self,
+        relation: &TableFactor,
+        relations: &mut VecDeque<PlanNode>,
+        select: Option<&sqlparser::ast::Select>,
+    ) -> Result<PlanNode> {
+        match relation {
+            TableFactor::NestedJoin(t) => self.plan_table_with_joins(t, relations, select),
+            _ => relations
+                .pop_front()
+                .ok_or_else(|| ErrorCode::LogicalError("Relations of the join are exhausted")),
+        }
+    }
+
+    /// Collect the relations of the FROM clause in the order of appearance,
+    /// with the name which is used to qualify their columns.
+    fn collect_relations(
+        &self,
+        t: &TableWithJoins,
+        relations: &mut Vec<(Option<String>, PlanNode)>,
+    ) -> Result<()> {
+        let factors = std::iter::once(&t.relation).chain(t.joins.iter().map(|j| &j.relation));
+        for relation in factors {
+            match relation {
+                TableFactor::NestedJoin(t) => self.collect_relations(t, relations)?,
+                _ => relations.push((
+                    Self::relation_qualifier(relation),
+                    self.create_relation(relation)?,
+                )),
+            }
+        }
+        Ok(())
+    }
+
+    fn relation_qualifier(relation: &TableFactor) -> Option<String> {
+        match relation {
+            TableFactor::Table { name, alias, .. } => match alias {
+                Some(alias) => Some(alias.name.value.clone()),
+                None => name.0.last().map(|ident| ident.value.clone()),
+            },
+            TableFactor::Derived { alias, .. } => alias.as_ref().map(|a| a.name.value.clone()),
+            _ => None,
+        }
+    }
+
+    fn duplicated_columns(relations: &[(Option<String>, PlanNode)]) -> HashSet<String> {
+        let mut seen = HashSet::new();
+        let mut duplicated = HashSet::new();
+        for (_, plan) in relations {
+            for field in plan.schema().fields() {
+                if !seen.insert(field.name().clone()) {
+                    duplicated.insert(field.name().clone());
+                }
+            }
+        }
+        duplicated
+    }
+
+    /// Rename the duplicated columns of the relation to `qualifier.column`.
+    fn qualify_relation(
+        qualifier: Option<String>,
+        plan: PlanNode,
+        duplicated_columns: &HashSet<String>,
+    ) -> Result<PlanNode> {
+        let schema = plan.schema();
+        let need_qualify = schema
+            .fields()
+            .iter()
+            .any(|f| duplicated_columns.contains(f.name()));
+
+        match qualifier {
+            Some(qualifier) if need_qualify => {
+                let exprs = schema
+                    .fields()
+                    .iter()
+                    .map(|f| match duplicated_columns.contains(f.name()) {
+                        true => col(f.name()).alias(&format!("{}.{}", qualifier, f.name())),
+                        false => col(f.name()),
+                    })
+                    .collect::<Vec<_>>();
+
+                PlanBuilder::from(&plan)
+                    .project(&exprs)
+                    .and_then(|builder| builder.build())
+            }
+            _ => Ok(plan),
+        }
+    }
+
+    fn join(
+        &self,
+        left: &PlanNode,
+        right: &PlanNode,
+        operator: &JoinOperator,
+        select: Option<&sqlparser::ast::Select>,
+    ) -> Result<PlanNode> {
+        let (join_type, constraint) = match operator {
+            JoinOperator::Inner(constraint) => (JoinType::Inner, Some(constraint)),
+            JoinOperator::LeftOuter(constraint) => (JoinType::Left, Some(constraint)),
+            JoinOperator::RightOuter(constraint) => (JoinType::Right, Some(constraint)),
+            JoinOperator::FullOuter(constraint) => (JoinType::Full, Some(constraint)),
+            JoinOperator::CrossJoin => (JoinType::Cross, None),
+            _ => {
+                return Result::Err(ErrorCode::UnImplement(format!(
+                    "Unsupported join operator: {:?}",
+                    operator
+                )))
+            }
+        };
+
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        let conditions = match constraint {
+            None => vec![],
+            Some(JoinConstraint::On(expr)) => {
+                let schema = JoinPlan::join_schema(join_type, &left_schema, &right_schema);
+                let expr = self.sql_to_rex(expr, &schema, select)?;
+                Self::split_conjunctions(&expr)
+            }
+            Some(JoinConstraint::Using(idents)) => idents
+                .iter()
+                .map(|ident| {
+                    let left_column = Self::resolve_using_column(&left_schema, &ident.value)?;
+                    let right_column = Self::resolve_using_column(&right_schema, &ident.value)?;
+                    Ok(left_column.eq(right_column))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Some(other) => {
+                return Result::Err(ErrorCode::UnImplement(format!(
+                    "Unsupported join constraint: {:?}",
+                    other
+                )))
+            }
+        };
+
+        // Split the conditions into the equi-join keys and the residual filter.
+        let mut left_keys = vec![];
+        let mut right_keys = vec![];
+        let mut residual: Option<Expression> = None;
+        for condition in conditions {
+            match Self::equi_join_keys(&condition, &left_schema, &right_schema) {
+                Some((left_key, right_key)) => {
+                    left_keys.push(left_key);
+                    right_keys.push(right_key);
+                }
+                None => {
+                    residual = match residual {
+                        None => Some(condition),
+                        Some(residual) => Some(residual.and(condition)),
+                    }
+                }
+            }
+        }
+
+        PlanBuilder::from(left)
+            .join(right, join_type, &left_keys, &right_keys, residual)
+            .and_then(|builder| builder.build())
+    }
+
+    fn split_conjunctions(expr: &Expression) -> Vec<Expression> {
+        match expr {
+            Expression::BinaryExpression { op, left, right } if op.to_lowercase() == "and" => {
+                let mut conjunctions = Self::split_conjunctions(left);
+                conjunctions.extend(Self::split_conjunctions(right));
+                conjunctions
+            }
+            _ => vec![expr.clone()],
+        }
+    }
+
+    /// Returns the (left key, right key) if the condition is `left_expr = right_expr`.
+    fn equi_join_keys(
+        condition: &Expression,
+        left_schema: &DataSchemaRef,
+        right_schema: &DataSchemaRef,
+    ) -> Option<(Expression, Expression)> {
+        let belongs_to = |expr: &Expression, schema: &DataSchemaRef| {
+            let columns = find_column_exprs(&[expr.clone()]);
+            !columns.is_empty()
+                && columns.iter().all(|c| match c {
+                    Expression::Column(name) => schema.field_with_name(name).is_ok(),
+                    _ => false,
+                })
+        };
+
+        match condition {
+            Expression::BinaryExpression { op, left, right } if op == "=" => {
+                if belongs_to(left, left_schema) && belongs_to(right, right_schema) {
+                    Some((left.as_ref().clone(), right.as_ref().clone()))
+                } else if belongs_to(left, right_schema) && belongs_to(right, left_schema) {
+                    Some((right.as_ref().clone(), left.as_ref().clone()))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn resolve_using_column(schema: &DataSchemaRef, name: &str) -> Result<Expression> {
+        let qualified_suffix = format!(".{}", name);
+        let candidates = schema
+            .fields()
+            .iter()
+            .filter(|f| f.name() == name || f.name().ends_with(&qualified_suffix))
+            .collect::<Vec<_>>();
+
+        match candidates.as_slice() {
+            [field] => Ok(col(field.name())),
+            [] => Result::Err(ErrorCode::SyntaxException(format!(
+                "Unknown column '{}' in USING clause",
+                name
+            ))),
+            _ => Result::Err(ErrorCode::SyntaxException(format!(
+                "Column '{}' in USING clause is ambiguous",
+                name
+            ))),
+        }
     }
 
     fn create_relation(&self, relation: &sqlparser::ast::TableFactor) -> Result<PlanNode> {
         match relation {
             TableFactor::Table { name, args, .. } => {
+                if name.0.len() == 1 && args.is_empty() {
+                    if let Some(cte_plan) = self.find_cte(&name.0[0].value) {
+                        return Ok(PlanNode::CteScan(cte_plan));
+                    }
+                }
+
                 let mut db_name = self.ctx.get_current_database();
                 let mut table_name = name.to_string();
                 if name.0.len() == 2 {
@@ -762,8 +1495,14 @@ impl PlanParser {
                 }
                 let table;
 
-                // only table functions has table args
-                if !args.is_empty() {
+                // only table functions has table args, except the time travel clause
+                if let Some(point) = Self::navigation_point(args)? {
+                    let io_ctx = self.ctx.get_cluster_table_io_context()?;
+                    table = self
+                        .ctx
+                        .get_table(&db_name, &table_name)?
+                        .navigate_to(Arc::new(io_ctx), &point)?;
+                } else if !args.is_empty() {
                     if name.0.len() >= 2 {
                         return Result::Err(ErrorCode::BadArguments(
                             "Currently table can't have arguments",
@@ -805,16 +1544,243 @@ impl PlanParser {
             }
             TableFactor::Derived { subquery, .. } => self.query_to_plan(subquery),
             TableFactor::NestedJoin(table_with_joins) => {
-                self.plan_table_with_joins(table_with_joins)
+                self.plan_tables_with_joins(std::slice::from_ref(table_with_joins), None)
             }
             TableFactor::TableFunction { .. } => {
                 Result::Err(ErrorCode::UnImplement("Unsupported table function"))
             }
         }
     }
+    /// The time travel clause `AT (SNAPSHOT => '<id>')` or `AT (TIMESTAMP => '<time>')`,
+    /// which is passed in as a named table argument, see `DfParser::new_with_dialect`.
+    fn navigation_point(args: &[FunctionArg]) -> Result<Option<NavigationPoint>> {
+        let (name, arg) = match args {
+            [FunctionArg::Named { name, arg }] => (name.value.to_uppercase(), arg),
+            _ => return Ok(None),
+        };
+        if name != "SNAPSHOT" && name != "TIMESTAMP" {
+            return Ok(None);
+        }
+
+        let value = match arg {
+            sqlparser::ast::Expr::Value(sqlparser::ast::Value::SingleQuotedString(value)) => value,
+            _ => {
+                return Result::Err(ErrorCode::BadArguments(format!(
+                    "{} of AT clause must be a string literal, but got {}",
+                    name, arg
+                )))
+            }
+        };
+
+        match name.as_str() {
+            "SNAPSHOT" => Ok(Some(NavigationPoint::SnapshotID(value.clone()))),
+            _ => {
+                // Timestamps without time zone are taken as UTC.
+                let time_point = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+                    .map(|naive| DateTime::<Utc>::from_utc(naive, Utc))
+                    .or_else(|_| {
+                        DateTime::parse_from_rfc3339(value).map(|time| time.with_timezone(&Utc))
+                    })
+                    .map_err(|_| {
+                        ErrorCode::BadArguments(format!(
+                            "Invalid TIMESTAMP of AT clause: {}",
+                            value
+                        ))
+                    })?;
+                Ok(Some(NavigationPoint::TimePoint(time_point)))
+            }
+        }
+    }
+
+    /// Generate a window function from an SQL function with OVER clause.
+    /// For example: "rank() over (partition by number % 3 order by number)"
+    fn sql_window_function_to_rex(
+        &self,
+        function: &sqlparser::ast::Function,
+        args: Vec<Expression>,
+        window_spec: &sqlparser::ast::WindowSpec,
+        schema: &DataSchema,
+        select: Option<&sqlparser::ast::Select>,
+    ) -> Result<Expression> {
+        let op = function.name.to_string();
+        if function.distinct {
+            return Result::Err(ErrorCode::UnImplement(format!(
+                "DISTINCT in window function {} is not yet implemented",
+                op
+            )));
+        }
+
+        if !find_window_exprs(&args).is_empty() {
+            return Result::Err(ErrorCode::SyntaxException(format!(
+                "Window function calls cannot be nested: {}",
+                op
+            )));
+        }
+
+        let expect_args = |min: usize, max: usize| match args.len() {
+            n if n >= min && n <= max => Ok(()),
+            n => Result::Err(ErrorCode::NumberArgumentsNotMatch(format!(
+                "Window function {} expect {} to {} arguments, but got {}",
+                op, min, max, n
+            ))),
+        };
+
+        let (params, args) = match op.to_lowercase().as_str() {
+            "row_number" | "rank" | "dense_rank" => {
+                expect_args(0, 0)?;
+                (vec![], args)
+            }
+            "first_value" | "last_value" => {
+                expect_args(1, 1)?;
+                (vec![], args)
+            }
+            // The offset and the default value of lag/lead must be constants.
+            "lag" | "lead" => {
+                expect_args(1, 3)?;
+                let mut args = args;
+                let params = args
+                    .split_off(1)
+                    .into_iter()
+                    .map(|arg| match arg {
+                        Expression::Literal { value, .. } => Ok(value),
+                        other => Result::Err(ErrorCode::SyntaxException(format!(
+                            "The offset and default of window function {} must be constants, but got: {:?}",
+                            op, other
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                (params, args)
+            }
+            _ if AggregateFunctionFactory::instance().check(&op) => {
+                let args = args
+                    .into_iter()
+                    .map(|arg| match arg {
+                        Expression::Wildcard => common_planners::lit(0i64),
+                        arg => arg,
+                    })
+                    .collect::<Vec<_>>();
+                let params = function
+                    .params
+                    .iter()
+                    .map(|v| match Self::value_to_rex(v) {
+                        Ok(Expression::Literal { value, .. }) => Ok(value),
+                        expr => Result::Err(ErrorCode::SyntaxException(format!(
+                            "Unsupported value expression: {:?}, must be datavalue",
+                            expr
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                (params, args)
+            }
+            _ => {
+                return Result::Err(ErrorCode::UnknownFunction(format!(
+                    "Unsupported window function: {}",
+                    op
+                )))
+            }
+        };
+
+        let partition_by = window_spec
+            .partition_by
+            .iter()
+            .map(|expr| self.sql_to_rex(expr, schema, select))
+            .collect::<Result<Vec<_>>>()?;
+
+        let order_by = window_spec
+            .order_by
+            .iter()
+            .map(|e| -> Result<Expression> {
+                let expr = self.sql_to_rex(&e.expr, schema, select)?;
+                Ok(Expression::Sort {
+                    expr: Box::new(expr.clone()),
+                    asc: e.asc.unwrap_or(true),
+                    nulls_first: e.nulls_first.unwrap_or(true),
+                    origin_expr: Box::new(expr),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let window_frame = window_spec
+            .window_frame
+            .as_ref()
+            .map(|frame| Self::window_frame(frame, order_by.len()))
+            .transpose()?;
+
+        Ok(Expression::WindowFunction {
+            op,
+            params,
+            args,
+            partition_by,
+            order_by,
+            window_frame,
+        })
+    }
+
+    fn window_frame(
+        frame: &sqlparser::ast::WindowFrame,
+        order_by_len: usize,
+    ) -> Result<WindowFrame> {
+        let units = match frame.units {
+            sqlparser::ast::WindowFrameUnits::Rows => WindowFrameUnits::Rows,
+            sqlparser::ast::WindowFrameUnits::Range => WindowFrameUnits::Range,
+            sqlparser::ast::WindowFrameUnits::Groups => {
+                return Result::Err(ErrorCode::UnImplement(
+                    "GROUPS window frame is not yet implemented",
+                ))
+            }
+        };
+
+        let bound = |bound: &sqlparser::ast::WindowFrameBound| match bound {
+            sqlparser::ast::WindowFrameBound::CurrentRow => WindowFrameBound::CurrentRow,
+            sqlparser::ast::WindowFrameBound::Preceding(n) => WindowFrameBound::Preceding(*n),
+            sqlparser::ast::WindowFrameBound::Following(n) => WindowFrameBound::Following(*n),
+        };
+
+        // "ROWS 1 PRECEDING" is short for "ROWS BETWEEN 1 PRECEDING AND CURRENT ROW"
+        let start_bound = bound(&frame.start_bound);
+        let end_bound = match &frame.end_bound {
+            Some(end_bound) => bound(end_bound),
+            None => WindowFrameBound::CurrentRow,
+        };
+
+        let window_frame = WindowFrame {
+            units,
+            start_bound,
+            end_bound,
+        };
+
+        let invalid_bound = matches!(start_bound, WindowFrameBound::Following(None))
+            || matches!(end_bound, WindowFrameBound::Preceding(None));
+        if invalid_bound {
+            return Result::Err(ErrorCode::SyntaxException(format!(
+                "Invalid window frame: {}",
+                window_frame
+            )));
+        }
+
+        let has_offset = |bound: WindowFrameBound| {
+            matches!(
+                bound,
+                WindowFrameBound::Preceding(Some(_)) | WindowFrameBound::Following(Some(_))
+            )
+        };
+        if units == WindowFrameUnits::Range
+            && (has_offset(start_bound) || has_offset(end_bound))
+            && order_by_len != 1
+        {
+            return Result::Err(ErrorCode::SyntaxException(format!(
+                "Window frame {} requires exactly one ORDER BY expression",
+                window_frame
+            )));
+        }
+
+        Ok(window_frame)
+    }
+
     fn process_compound_ident(
         &self,
         ids: &[Ident],
+        schema: &DataSchema,
         select: Option<&sqlparser::ast::Select>,
     ) -> Result<Expression> {
         let mut var_names = vec![];
@@ -830,62 +1796,56 @@ impl PlanParser {
 
         let table_name = &var_names[0];
         let from = &select.unwrap().from;
+        if from.is_empty() {
+            return Err(ErrorCode::SyntaxException(
+                "Missing table in the select clause",
+            ));
+        }
+
+        // The column is qualified by the table name if it's ambiguous in a join.
+        let qualified_name = var_names.join(".");
+        if schema.field_with_name(&qualified_name).is_ok() {
+            return Ok(Expression::Column(qualified_name));
+        }
+
         let obj_table_name = ObjectName(vec![Ident::new(table_name)]);
+        let mut relations = vec![];
+        for table_with_joins in from {
+            Self::collect_table_factors(table_with_joins, &mut relations);
+        }
 
-        match from.len() {
-            0 => Err(ErrorCode::SyntaxException(
-                "Missing table in the select clause",
-            )),
-            1 => match &from[0].relation {
-                TableFactor::Table {
-                    name,
-                    alias,
-                    args: _,
-                    with_hints: _,
-                } => {
-                    if *name == obj_table_name {
-                        return Ok(Expression::Column(var_names.pop().unwrap()));
-                    }
-                    match alias {
-                        Some(a) => {
-                            if a.name == ids[0] {
-                                Ok(Expression::Column(var_names.pop().unwrap()))
-                            } else {
-                                Err(ErrorCode::UnknownTable(format!(
-                                    "Unknown Table '{:?}'",
-                                    &table_name,
-                                )))
-                            }
-                        }
-                        None => Err(ErrorCode::UnknownTable(format!(
-                            "Unknown Table '{:?}'",
-                            &table_name,
-                        ))),
-                    }
-                }
-                TableFactor::Derived {
-                    lateral: _,
-                    subquery: _,
-                    alias,
-                } => match alias {
-                    Some(a) => {
-                        if a.name == ids[0] {
-                            Ok(Expression::Column(var_names.pop().unwrap()))
-                        } else {
-                            Err(ErrorCode::UnknownTable(format!(
-                                "Unknown Table '{:?}'",
-                                &table_name,
-                            )))
-                        }
-                    }
-                    None => Err(ErrorCode::UnknownTable(format!(
-                        "Unknown Table '{:?}'",
-                        &table_name,
-                    ))),
-                },
-                _ => Err(ErrorCode::SyntaxException("Cannot support Nested Join now")),
+        let matched = relations.iter().any(|relation| match relation {
+            TableFactor::Table { name, alias, .. } => match alias {
+                Some(a) => a.name == ids[0],
+                None => *name == obj_table_name,
             },
-            _ => Err(ErrorCode::SyntaxException("Cannot support JOIN clause")),
+            TableFactor::Derived { alias, .. } => match alias {
+                Some(a) => a.name == ids[0],
+                None => false,
+            },
+            _ => false,
+        });
+
+        match matched {
+            true => Ok(Expression::Column(var_names.pop().unwrap())),
+            false => Err(ErrorCode::UnknownTable(format!(
+                "Unknown Table '{:?}'",
+                &table_name,
+            ))),
+        }
+    }
+
+    fn collect_table_factors<'a>(
+        table_with_joins: &'a TableWithJoins,
+        relations: &mut Vec<&'a TableFactor>,
+    ) {
+        let factors = std::iter::once(&table_with_joins.relation)
+            .chain(table_with_joins.joins.iter().map(|j| &j.relation));
+        for relation in factors {
+            match relation {
+                TableFactor::NestedJoin(t) => Self::collect_table_factors(t, relations),
+                _ => relations.push(relation),
+            }
         }
     }
 
@@ -1027,7 +1987,7 @@ impl PlanParser {
                 })
             }
             sqlparser::ast::Expr::CompoundIdentifier(ids) => {
-                self.process_compound_ident(ids.as_slice(), select)
+                self.process_compound_ident(ids.as_slice(), schema, select)
             }
             sqlparser::ast::Expr::Function(e) => {
                 let mut args = Vec::with_capacity(e.args.len());
@@ -1055,6 +2015,10 @@ impl PlanParser {
                 }
 
                 let op = e.name.to_string();
+                if let Some(window_spec) = &e.over {
+                    return self.sql_window_function_to_rex(e, args, window_spec, schema, select);
+                }
+
                 if AggregateFunctionFactory::instance().check(&op) {
                     let args = match op.to_lowercase().as_str() {
                         "count" => args
@@ -1149,6 +2113,30 @@ impl PlanParser {
                         .or(expression.gt(high_expression))),
                 }
             }
+            sqlparser::ast::Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                // e.g. a IN (1, 2) => a = 1 OR a = 2, a NOT IN (1, 2) => a != 1 AND a != 2
+                let expression = self.sql_to_rex(expr, schema, select)?;
+                let mut list_expressions = list
+                    .iter()
+                    .map(|item| self.sql_to_rex(item, schema, select))
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter();
+                let first = list_expressions.next().ok_or_else(|| {
+                    ErrorCode::SyntaxException(format!("Empty list of the IN expression: {}", expr))
+                })?;
+                Ok(match *negated {
+                    false => list_expressions.fold(expression.eq(first), |acc, item| {
+                        acc.or(expression.eq(item))
+                    }),
+                    true => list_expressions.fold(expression.not_eq(first), |acc, item| {
+                        acc.and(expression.not_eq(item))
+                    }),
+                })
+            }
             other => Result::Err(ErrorCode::SyntaxException(format!(
                 "Unsupported expression: {}, type: {:?}",
                 expr, other
@@ -1202,6 +2190,7 @@ impl PlanParser {
             Some(ref predicate_expr) => self
                 .sql_to_rex(predicate_expr, &plan.schema(), select)
                 .and_then(|filter_expr| {
+                    Self::check_no_window_exprs(&[filter_expr.clone()], "WHERE")?;
                     PlanBuilder::from(plan)
                         .filter(filter_expr)
                         .and_then(|builder| builder.build())
@@ -1221,6 +2210,56 @@ impl PlanParser {
         Ok(plan.clone())
     }
 
+    /// Apply the window functions to the plan, the window functions with the same
+    /// PARTITION BY and ORDER BY are evaluated together.
+    fn window(&self, input: &PlanNode, window_exprs: &[Expression]) -> Result<PlanNode> {
+        // Evaluate the arguments, partition keys and order keys before window.
+        // In example: [number, (number % 3)]
+        let before_window_exprs = expand_window_arg_exprs(window_exprs);
+        let plan = self.expression(input, &before_window_exprs, "Before Window")?;
+
+        let mut window_groups: Vec<Vec<Expression>> = vec![];
+        for expr in window_exprs {
+            let expr = rebase_expr_from_input(expr, &plan.schema())?;
+            let same_window = |group: &&mut Vec<Expression>| match (&group[0], &expr) {
+                (
+                    Expression::WindowFunction {
+                        partition_by: p1,
+                        order_by: o1,
+                        ..
+                    },
+                    Expression::WindowFunction {
+                        partition_by: p2,
+                        order_by: o2,
+                        ..
+                    },
+                ) => p1 == p2 && o1 == o2,
+                _ => false,
+            };
+
+            match window_groups.iter_mut().find(same_window) {
+                Some(group) => group.push(expr),
+                None => window_groups.push(vec![expr]),
+            }
+        }
+
+        window_groups.iter().try_fold(plan, |plan, window_funcs| {
+            PlanBuilder::from(&plan)
+                .window(window_funcs)
+                .and_then(|builder| builder.build())
+        })
+    }
+
+    fn check_no_window_exprs(exprs: &[Expression], clause: &str) -> Result<()> {
+        match find_window_exprs(exprs).first() {
+            None => Ok(()),
+            Some(expr) => Result::Err(ErrorCode::SyntaxException(format!(
+                "Window function {:?} is not allowed in {}",
+                expr, clause
+            ))),
+        }
+    }
+
     /// Wrap a plan in a projection
     fn project(&self, input: &PlanNode, exprs: &[Expression]) -> Result<PlanNode> {
         let exprs = exprs
@@ -1356,3 +2395,22 @@ impl PlanParser {
             .and_then(|builder| builder.build())
     }
 }
+
+/// Replaces the scans of the CTEs referenced only once with the CTE query.
+struct CteInliner {
+    inlined: HashSet<String>,
+}
+
+impl PlanRewriter for CteInliner {
+    fn rewrite_cte_scan(&mut self, plan: &CteScanPlan) -> Result<PlanNode> {
+        let new_input = self.rewrite_plan_node(&plan.input)?;
+        match self.inlined.contains(&plan.cte_id) {
+            true => Ok(new_input),
+            false => {
+                let mut new_plan = plan.clone();
+                new_plan.set_input(&new_input);
+                Ok(PlanNode::CteScan(new_plan))
+            }
+        }
+    }
+}
@@ -177,10 +177,83 @@ fn test_plan_parser() -> Result<()> {
         },
 
         Test {
-            name: "unimplemented-cte",
+            name: "cte-passed",
             sql: "with t as ( select sum(number) n from numbers_mt(1000) )select * from t",
+            expect: "\
+            Projection: n:UInt64\
+            \n  Projection: sum(number) as n:UInt64\
+            \n    AggregatorFinal: groupBy=[[]], aggr=[[sum(number)]]\
+            \n      AggregatorPartial: groupBy=[[]], aggr=[[sum(number)]]\
+            \n        ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 1000, read_bytes: 8000]",
+            error: "",
+        },
+        Test {
+            name: "cte-multiple-references",
+            sql: "with t as (select number from numbers(10)) select a.number from t as a join t as b on a.number = b.number",
+            expect: "\
+            Projection: a.number:UInt64\
+            \n  Join: INNER, on: [a.number = b.number]\
+            \n    Projection: number as a.number:UInt64\
+            \n      CteScan: t\
+            \n        Projection: number:UInt64\
+            \n          ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]\
+            \n    Projection: number as b.number:UInt64\
+            \n      CteScan: t\
+            \n        Projection: number:UInt64\
+            \n          ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
+            error: "",
+        },
+        Test {
+            name: "cte-recursive-unimplemented",
+            sql: "with recursive t as (select 1) select * from t",
+            expect: "",
+            error: "Code: 2, displayText = Recursive CTE is not yet implemented.",
+        },
+        Test {
+            name: "cte-columns-mismatch",
+            sql: "with t(a, b) as (select number from numbers(10)) select * from t",
+            expect: "",
+            error: "Code: 6, displayText = CTE 't' has 1 columns, but 2 column names are specified.",
+        },
+        Test {
+            name: "window-passed",
+            sql: "select number, rank() over (partition by number % 3 order by number desc) as r from numbers(10)",
+            expect: "\
+            Projection: number:UInt64, rank() over (partition by (number % 3) order by number desc) as r:UInt64\
+            \n  Window: [rank() over (partition by (number % 3) order by number desc)]\
+            \n    Expression: (number % 3):UInt8, number:UInt64 (Before Window)\
+            \n      ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
+            error: "",
+        },
+        Test {
+            name: "window-in-where",
+            sql: "select number from numbers(10) where row_number() over () > 1",
+            expect: "",
+            error: "Code: 5, displayText = Window function row_number() over () is not allowed in WHERE.",
+        },
+        Test {
+            name: "window-unsupported-function",
+            sql: "select ntile(2) over (order by number) from numbers(10)",
+            expect: "",
+            error: "Code: 8, displayText = Unsupported window function: ntile.",
+        },
+        Test {
+            name: "update-passed",
+            sql: "update system.one set dummy = 1 where dummy > 0",
+            expect: "Update system.one set dummy = cast(1 as UInt8), selection: (dummy > 0)",
+            error: "",
+        },
+        Test {
+            name: "update-unknown-column",
+            sql: "update system.one set x = 1",
+            expect: "",
+            error: "Code: 5, displayText = Unknown column x in table system.one.",
+        },
+        Test {
+            name: "update-duplicate-column",
+            sql: "update system.one set dummy = 1, dummy = 2",
             expect: "",
-            error: "Code: 2, displayText = CTE is not yet implement.",
+            error: "Code: 5, displayText = Multiple assignments to the same column dummy.",
         },
         Test {
             name: "kleene-logic-null",
@@ -200,6 +273,69 @@ fn test_plan_parser() -> Result<()> {
             \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
             error: "",
         },
+        Test {
+            name: "in-list-passed",
+            sql: "select * from numbers(10) where number in (1, 3)",
+            expect: "\
+            Projection: number:UInt64\
+            \n  Filter: ((number = 1) OR (number = 3))\
+            \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
+            error: "",
+        },
+        Test {
+            name: "not-in-list-passed",
+            sql: "select * from numbers(10) where number not in (1, 3)",
+            expect: "\
+            Projection: number:UInt64\
+            \n  Filter: ((number != 1) AND (number != 3))\
+            \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
+            error: "",
+        },
+        Test {
+            name: "join-passed",
+            sql: "select a.number from numbers(10) as a join numbers(10) as b on a.number = b.number and b.number > 1",
+            expect: "\
+            Projection: a.number:UInt64\
+            \n  Join: INNER, on: [a.number = b.number], filter: (b.number > 1)\
+            \n    Projection: number as a.number:UInt64\
+            \n      ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]\
+            \n    Projection: number as b.number:UInt64\
+            \n      ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
+            error: "",
+        },
+        Test {
+            name: "join-duplicated-column",
+            sql: "select * from numbers(10) join numbers(10) on 1 = 1",
+            expect: "",
+            error: "Code: 5, displayText = Duplicate column 'numbers.number' in join, the tables should be qualified by different names.",
+        },
+        Test {
+            name: "union-passed",
+            sql: "select number from numbers(10) union select 1 order by number limit 3",
+            expect: "\
+            Limit: 3\
+            \n  Sort: number:UInt64\
+            \n    SetOperation: UNION\
+            \n      Projection: number:UInt64\
+            \n        ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]\
+            \n      Projection: cast(1 as UInt64) as number:UInt64\
+            \n        Projection: 1:UInt8\
+            \n          Expression: 1:UInt8 (Before Projection)\
+            \n            ReadDataSource: scan partitions: [1], scan schema: [dummy:UInt8], statistics: [read_rows: 1, read_bytes: 1]",
+            error: "",
+        },
+        Test {
+            name: "intersect-all-unimplemented",
+            sql: "select number from numbers(10) intersect all select number from numbers(5)",
+            expect: "",
+            error: "Code: 2, displayText = Set operation INTERSECT ALL is not yet implemented.",
+        },
+        Test {
+            name: "union-columns-mismatch",
+            sql: "select number from numbers(10) union all select 1, 2",
+            expect: "",
+            error: "Code: 6, displayText = Set operation requires the same number of columns, left: 1, right: 2.",
+        },
         Test {
             name: "show-metrics",
             sql: "show metrics",
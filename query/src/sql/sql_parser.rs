@@ -22,6 +22,8 @@ use common_meta_types::AuthType;
 use common_meta_types::UserPrivilege;
 use common_meta_types::UserPrivilegeType;
 use common_planners::ExplainType;
+use common_planners::Optimization;
+use common_planners::TransactionOperation;
 use metrics::histogram;
 use sqlparser::ast::BinaryOperator;
 use sqlparser::ast::ColumnDef;
@@ -40,6 +42,8 @@ use sqlparser::tokenizer::Token;
 use sqlparser::tokenizer::Tokenizer;
 use sqlparser::tokenizer::Whitespace;
 
+use crate::sql::DfAlterTable;
+use crate::sql::DfAlterTableOperation;
 use crate::sql::DfAlterUser;
 use crate::sql::DfCreateDatabase;
 use crate::sql::DfCreateTable;
@@ -51,6 +55,7 @@ use crate::sql::DfExplain;
 use crate::sql::DfGrantStatement;
 use crate::sql::DfHint;
 use crate::sql::DfKillStatement;
+use crate::sql::DfOptimizeTable;
 use crate::sql::DfShowCreateTable;
 use crate::sql::DfShowDatabases;
 use crate::sql::DfShowMetrics;
@@ -59,6 +64,7 @@ use crate::sql::DfShowSettings;
 use crate::sql::DfShowTables;
 use crate::sql::DfShowUsers;
 use crate::sql::DfStatement;
+use crate::sql::DfTransaction;
 use crate::sql::DfTruncateTable;
 use crate::sql::DfUseDatabase;
 
@@ -84,13 +90,41 @@ impl<'a> DfParser<'a> {
     /// Parse the specified tokens with dialect
     pub fn new_with_dialect(sql: &str, dialect: &'a dyn Dialect) -> Result<Self, ParserError> {
         let mut tokenizer = Tokenizer::new(dialect, sql);
-        let tokens = tokenizer.tokenize()?;
+        let tokens = Self::rewrite_time_travel(tokenizer.tokenize()?);
 
         Ok(DfParser {
             parser: Parser::new(tokens, dialect),
         })
     }
 
+    /// Rewrites `t AT (SNAPSHOT => '<id>')` into `t (SNAPSHOT => '<id>')`, so that the time
+    /// travel clause is parsed as named table arguments, the same goes for `TIMESTAMP`.
+    fn rewrite_time_travel(tokens: Vec<Token>) -> Vec<Token> {
+        let is_time_travel = |pos: usize| {
+            let mut rest = tokens[pos + 1..]
+                .iter()
+                .filter(|token| !matches!(token, Token::Whitespace(_)));
+            matches!(rest.next(), Some(Token::LParen))
+                && matches!(rest.next(), Some(Token::Word(w))
+                    if w.quote_style.is_none()
+                        && (w.value.eq_ignore_ascii_case("SNAPSHOT")
+                            || w.value.eq_ignore_ascii_case("TIMESTAMP")))
+                && matches!(rest.next(), Some(Token::RArrow))
+        };
+
+        let is_at = |token: &Token| {
+            matches!(token, Token::Word(w)
+                if w.quote_style.is_none() && w.value.eq_ignore_ascii_case("AT"))
+        };
+
+        tokens
+            .iter()
+            .enumerate()
+            .filter(|(pos, token)| !is_at(token) || !is_time_travel(*pos))
+            .map(|(_, token)| token.clone())
+            .collect()
+    }
+
     /// Parse a SQL statement and produce a set of statements with dialect
     pub fn parse_sql(sql: &str) -> Result<(Vec<DfStatement>, Vec<DfHint>), ErrorCode> {
         let dialect = &GenericDialect {};
@@ -223,10 +257,30 @@ impl<'a> DfParser<'a> {
                         self.parser.next_token();
                         self.parse_grant()
                     }
+                    Keyword::BEGIN => {
+                        self.parser.next_token();
+                        self.parse_transaction(TransactionOperation::Begin)
+                    }
+                    Keyword::START => {
+                        self.parser.next_token();
+                        self.parser.expect_keyword(Keyword::TRANSACTION)?;
+                        Ok(DfStatement::Transaction(DfTransaction {
+                            operation: TransactionOperation::Begin,
+                        }))
+                    }
+                    Keyword::COMMIT => {
+                        self.parser.next_token();
+                        self.parse_transaction(TransactionOperation::Commit)
+                    }
+                    Keyword::ROLLBACK => {
+                        self.parser.next_token();
+                        self.parse_transaction(TransactionOperation::Rollback)
+                    }
                     Keyword::NoKeyword => match w.value.to_uppercase().as_str() {
                         // Use database
                         "USE" => self.parse_use_database(),
                         "KILL" => self.parse_kill_query(),
+                        "OPTIMIZE" => self.parse_optimize_table(),
                         _ => self.expected("Keyword", self.parser.peek_token()),
                     },
                     _ => {
@@ -412,6 +466,7 @@ impl<'a> DfParser<'a> {
         match self.parser.next_token() {
             Token::Word(w) => match w.keyword {
                 Keyword::USER => self.parse_alter_user(),
+                Keyword::TABLE => self.parse_alter_table(),
                 _ => self.expected("alter statement", Token::Word(w)),
             },
             unexpected => self.expected("alter statement", unexpected),
@@ -505,6 +560,16 @@ impl<'a> DfParser<'a> {
         }
     }
 
+    // Parse 'BEGIN | COMMIT | ROLLBACK [TRANSACTION | WORK]'.
+    fn parse_transaction(
+        &mut self,
+        operation: TransactionOperation,
+    ) -> Result<DfStatement, ParserError> {
+        self.parser
+            .parse_one_of_keywords(&[Keyword::TRANSACTION, Keyword::WORK]);
+        Ok(DfStatement::Transaction(DfTransaction { operation }))
+    }
+
     fn parse_create_user(&mut self) -> Result<DfStatement, ParserError> {
         let if_not_exists =
             self.parser
@@ -561,6 +626,31 @@ impl<'a> DfParser<'a> {
         Ok(DfStatement::AlterUser(alter))
     }
 
+    // ALTER TABLE [db.]table ADD [COLUMN] col type [DEFAULT expr]
+    //                      | DROP [COLUMN] col
+    //                      | RENAME [COLUMN] col TO new_col
+    fn parse_alter_table(&mut self) -> Result<DfStatement, ParserError> {
+        let name = self.parser.parse_object_name()?;
+
+        let operation = if self.parser.parse_keyword(Keyword::ADD) {
+            self.parser.parse_keyword(Keyword::COLUMN);
+            DfAlterTableOperation::AddColumn(self.parse_column_def()?)
+        } else if self.parser.parse_keyword(Keyword::DROP) {
+            self.parser.parse_keyword(Keyword::COLUMN);
+            DfAlterTableOperation::DropColumn(self.parser.parse_identifier()?)
+        } else if self.parser.parse_keyword(Keyword::RENAME) {
+            self.parser.parse_keyword(Keyword::COLUMN);
+            let old_name = self.parser.parse_identifier()?;
+            self.parser.expect_keyword(Keyword::TO)?;
+            let new_name = self.parser.parse_identifier()?;
+            DfAlterTableOperation::RenameColumn { old_name, new_name }
+        } else {
+            return self.expected("ADD | DROP | RENAME", self.parser.peek_token());
+        };
+
+        Ok(DfStatement::AlterTable(DfAlterTable { name, operation }))
+    }
+
     fn get_auth_option(&mut self) -> Result<(AuthType, String), ParserError> {
         let exist_not_identified = self.parser.parse_keyword(Keyword::NOT);
         let exist_identified = self.consume_token("IDENTIFIED");
@@ -602,15 +692,18 @@ impl<'a> DfParser<'a> {
         let table_name = self.parser.parse_object_name()?;
         let (columns, _) = self.parse_columns()?;
         let engine = self.parse_table_engine()?;
+        let cluster_keys = self.parse_cluster_keys()?;
 
         let mut table_properties = vec![];
 
         // parse table options: https://dev.mysql.com/doc/refman/8.0/en/create-table.html
-        if self.consume_token("LOCATION") {
+        // e.g. LOCATION = '/data/33.csv' COMPRESSION = 'zstd'
+        while let Token::Word(w) = self.parser.peek_token() {
+            self.parser.next_token();
             self.parser.expect_token(&Token::Eq)?;
             let value = self.parse_value()?;
             table_properties.push(SqlOption {
-                name: Ident::new("LOCATION"),
+                name: Ident::new(w.value.to_uppercase()),
                 value,
             })
         }
@@ -620,6 +713,7 @@ impl<'a> DfParser<'a> {
             name: table_name,
             columns,
             engine,
+            cluster_keys,
             options: table_properties,
         };
 
@@ -637,6 +731,19 @@ impl<'a> DfParser<'a> {
         Ok(self.parser.next_token().to_string())
     }
 
+    // CLUSTER BY (expr [, expr]...)
+    fn parse_cluster_keys(&mut self) -> Result<Vec<Expr>, ParserError> {
+        if !self.consume_token("CLUSTER") {
+            return Ok(vec![]);
+        }
+
+        self.parser.expect_keyword(Keyword::BY)?;
+        self.parser.expect_token(&Token::LParen)?;
+        let keys = self.parser.parse_comma_separated(Parser::parse_expr)?;
+        self.parser.expect_token(&Token::RParen)?;
+        Ok(keys)
+    }
+
     fn parse_show_create(&mut self) -> Result<DfStatement, ParserError> {
         match self.parser.next_token() {
             Token::Word(w) => match w.keyword {
@@ -666,6 +773,29 @@ impl<'a> DfParser<'a> {
         }
     }
 
+    // OPTIMIZE TABLE [db.]table [COMPACT | PURGE | ALL | RECLUSTER]
+    fn parse_optimize_table(&mut self) -> Result<DfStatement, ParserError> {
+        if !self.consume_token("OPTIMIZE") {
+            return self.expected("Must OPTIMIZE", self.parser.peek_token());
+        }
+        self.parser.expect_keyword(Keyword::TABLE)?;
+        let name = self.parser.parse_object_name()?;
+
+        let operation = match self.parser.peek_token() {
+            Token::EOF | Token::SemiColon => Optimization::Compact,
+            _ if self.consume_token("COMPACT") => Optimization::Compact,
+            _ if self.consume_token("PURGE") => Optimization::Purge,
+            _ if self.consume_token("ALL") => Optimization::All,
+            _ if self.consume_token("RECLUSTER") => Optimization::Recluster,
+            unexpected => return self.expected("COMPACT | PURGE | ALL | RECLUSTER", unexpected),
+        };
+
+        Ok(DfStatement::OptimizeTable(DfOptimizeTable {
+            name,
+            operation,
+        }))
+    }
+
     fn parse_privileges(&mut self) -> Result<UserPrivilege, ParserError> {
         let mut privileges = UserPrivilege::empty();
         loop {
@@ -16,6 +16,8 @@ use common_exception::Result;
 use common_meta_types::AuthType;
 use common_meta_types::UserPrivilege;
 use common_meta_types::UserPrivilegeType;
+use common_planners::Optimization;
+use common_planners::TransactionOperation;
 use sqlparser::ast::*;
 
 use crate::sql::sql_statement::DfDropDatabase;
@@ -108,6 +110,7 @@ fn create_table() -> Result<()> {
         name: ObjectName(vec![Ident::new("t")]),
         columns: vec![make_column_def("c1", DataType::Int(None))],
         engine: "CSV".to_string(),
+        cluster_keys: vec![],
         options: vec![SqlOption {
             name: Ident::new("LOCATION".to_string()),
             value: Value::SingleQuotedString("/data/33.csv".into()),
@@ -126,6 +129,7 @@ fn create_table() -> Result<()> {
             make_column_def("c3", DataType::Varchar(Some(255))),
         ],
         engine: "Parquet".to_string(),
+        cluster_keys: vec![],
         options: vec![SqlOption {
             name: Ident::new("LOCATION".to_string()),
             value: Value::SingleQuotedString("foo.parquet".into()),
@@ -133,6 +137,59 @@ fn create_table() -> Result<()> {
     });
     expect_parse_ok(sql, expected)?;
 
+    // positive case: multiple table options
+    let sql = "CREATE TABLE t(c1 int) ENGINE = FUSE compression = 'zstd' row_per_block = 1000";
+    let expected = DfStatement::CreateTable(DfCreateTable {
+        if_not_exists: false,
+        name: ObjectName(vec![Ident::new("t")]),
+        columns: vec![make_column_def("c1", DataType::Int(None))],
+        engine: "FUSE".to_string(),
+        cluster_keys: vec![],
+        options: vec![
+            SqlOption {
+                name: Ident::new("COMPRESSION".to_string()),
+                value: Value::SingleQuotedString("zstd".into()),
+            },
+            SqlOption {
+                name: Ident::new("ROW_PER_BLOCK".to_string()),
+                value: Value::Number("1000".into(), false),
+            },
+        ],
+    });
+    expect_parse_ok(sql, expected)?;
+
+    // positive case: cluster keys
+    let sql = "CREATE TABLE t(c1 int, c2 date) ENGINE = FUSE CLUSTER BY (c1, c2 + 1) compression = 'zstd'";
+    let expected = DfStatement::CreateTable(DfCreateTable {
+        if_not_exists: false,
+        name: ObjectName(vec![Ident::new("t")]),
+        columns: vec![
+            make_column_def("c1", DataType::Int(None)),
+            make_column_def("c2", DataType::Date),
+        ],
+        engine: "FUSE".to_string(),
+        cluster_keys: vec![Expr::Identifier(Ident::new("c1")), Expr::BinaryOp {
+            left: Box::new(Expr::Identifier(Ident::new("c2"))),
+            op: BinaryOperator::Plus,
+            right: Box::new(Expr::Value(Value::Number("1".into(), false))),
+        }],
+        options: vec![SqlOption {
+            name: Ident::new("COMPRESSION".to_string()),
+            value: Value::SingleQuotedString("zstd".into()),
+        }],
+    });
+    expect_parse_ok(sql, expected)?;
+
+    expect_parse_err(
+        "CREATE TABLE t(c1 int) ENGINE = FUSE CLUSTER BY c1",
+        String::from("sql parser error: Expected (, found: c1"),
+    )?;
+
+    expect_parse_err(
+        "CREATE TABLE t(c1 int) ENGINE = FUSE compression 'zstd'",
+        String::from("sql parser error: Expected =, found: 'zstd'"),
+    )?;
+
     Ok(())
 }
 
@@ -281,6 +338,142 @@ fn truncate_table() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn optimize_table() -> Result<()> {
+    {
+        let sql = "OPTIMIZE TABLE t1";
+        let expected = DfStatement::OptimizeTable(DfOptimizeTable {
+            name: ObjectName(vec![Ident::new("t1")]),
+            operation: Optimization::Compact,
+        });
+        expect_parse_ok(sql, expected)?;
+    }
+
+    {
+        let sql = "OPTIMIZE TABLE db.t1 COMPACT";
+        let expected = DfStatement::OptimizeTable(DfOptimizeTable {
+            name: ObjectName(vec![Ident::new("db"), Ident::new("t1")]),
+            operation: Optimization::Compact,
+        });
+        expect_parse_ok(sql, expected)?;
+    }
+
+    {
+        let sql = "OPTIMIZE TABLE t1 PURGE";
+        let expected = DfStatement::OptimizeTable(DfOptimizeTable {
+            name: ObjectName(vec![Ident::new("t1")]),
+            operation: Optimization::Purge,
+        });
+        expect_parse_ok(sql, expected)?;
+    }
+
+    {
+        let sql = "OPTIMIZE TABLE t1 ALL";
+        let expected = DfStatement::OptimizeTable(DfOptimizeTable {
+            name: ObjectName(vec![Ident::new("t1")]),
+            operation: Optimization::All,
+        });
+        expect_parse_ok(sql, expected)?;
+    }
+
+    {
+        let sql = "OPTIMIZE TABLE t1 RECLUSTER";
+        let expected = DfStatement::OptimizeTable(DfOptimizeTable {
+            name: ObjectName(vec![Ident::new("t1")]),
+            operation: Optimization::Recluster,
+        });
+        expect_parse_ok(sql, expected)?;
+    }
+
+    expect_parse_err(
+        "OPTIMIZE TABLE t1 SHRINK",
+        String::from("sql parser error: Expected COMPACT | PURGE | ALL | RECLUSTER, found: SHRINK"),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn alter_table() -> Result<()> {
+    {
+        let sql = "ALTER TABLE t1 ADD COLUMN c INT DEFAULT 1";
+        let mut column = make_column_def("c", DataType::Int(None));
+        column.options = vec![ColumnOptionDef {
+            name: None,
+            option: ColumnOption::Default(Expr::Value(Value::Number("1".to_string(), false))),
+        }];
+        let expected = DfStatement::AlterTable(DfAlterTable {
+            name: ObjectName(vec![Ident::new("t1")]),
+            operation: DfAlterTableOperation::AddColumn(column),
+        });
+        expect_parse_ok(sql, expected)?;
+    }
+
+    {
+        let sql = "ALTER TABLE db.t1 ADD c VARCHAR";
+        let expected = DfStatement::AlterTable(DfAlterTable {
+            name: ObjectName(vec![Ident::new("db"), Ident::new("t1")]),
+            operation: DfAlterTableOperation::AddColumn(make_column_def(
+                "c",
+                DataType::Varchar(None),
+            )),
+        });
+        expect_parse_ok(sql, expected)?;
+    }
+
+    {
+        let sql = "ALTER TABLE t1 DROP COLUMN c";
+        let expected = DfStatement::AlterTable(DfAlterTable {
+            name: ObjectName(vec![Ident::new("t1")]),
+            operation: DfAlterTableOperation::DropColumn(Ident::new("c")),
+        });
+        expect_parse_ok(sql, expected)?;
+    }
+
+    {
+        let sql = "ALTER TABLE t1 RENAME COLUMN a TO b";
+        let expected = DfStatement::AlterTable(DfAlterTable {
+            name: ObjectName(vec![Ident::new("t1")]),
+            operation: DfAlterTableOperation::RenameColumn {
+                old_name: Ident::new("a"),
+                new_name: Ident::new("b"),
+            },
+        });
+        expect_parse_ok(sql, expected)?;
+    }
+
+    expect_parse_err(
+        "ALTER TABLE t1 MODIFY COLUMN c INT",
+        String::from("sql parser error: Expected ADD | DROP | RENAME, found: MODIFY"),
+    )?;
+
+    expect_parse_err(
+        "ALTER TABLE t1 RENAME COLUMN a b",
+        String::from("sql parser error: Expected TO, found: b"),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn time_travel_test() -> Result<()> {
+    // The AT keyword of the time travel clause is dropped, the rest are table arguments.
+    let (expected, _) = DfParser::parse_sql("SELECT * FROM t (SNAPSHOT => 'abc') AS x")?;
+    expect_parse_ok(
+        "SELECT * FROM t AT (SNAPSHOT => 'abc') AS x",
+        expected[0].clone(),
+    )?;
+
+    let (expected, _) =
+        DfParser::parse_sql("SELECT * FROM db.t(timestamp => '2021-11-01 00:00:00')")?;
+    expect_parse_ok(
+        "SELECT * FROM db.t at(timestamp => '2021-11-01 00:00:00')",
+        expected[0].clone(),
+    )?;
+
+    Ok(())
+}
+
 #[test]
 fn hint_test() -> Result<()> {
     {
@@ -708,3 +901,33 @@ fn grant_privilege_test() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn transaction_test() -> Result<()> {
+    let begin = DfStatement::Transaction(DfTransaction {
+        operation: TransactionOperation::Begin,
+    });
+    expect_parse_ok("BEGIN", begin.clone())?;
+    expect_parse_ok("BEGIN TRANSACTION", begin.clone())?;
+    expect_parse_ok("begin work", begin.clone())?;
+    expect_parse_ok("START TRANSACTION", begin)?;
+
+    let commit = DfStatement::Transaction(DfTransaction {
+        operation: TransactionOperation::Commit,
+    });
+    expect_parse_ok("COMMIT", commit.clone())?;
+    expect_parse_ok("COMMIT WORK", commit)?;
+
+    let rollback = DfStatement::Transaction(DfTransaction {
+        operation: TransactionOperation::Rollback,
+    });
+    expect_parse_ok("ROLLBACK", rollback.clone())?;
+    expect_parse_ok("ROLLBACK TRANSACTION;", rollback)?;
+
+    expect_parse_err(
+        "START WORK",
+        String::from("sql parser error: Expected TRANSACTION, found: WORK"),
+    )?;
+
+    Ok(())
+}
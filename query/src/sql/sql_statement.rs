@@ -15,6 +15,8 @@
 use common_meta_types::AuthType;
 use common_meta_types::UserPrivilege;
 use common_planners::ExplainType;
+use common_planners::Optimization;
+use common_planners::TransactionOperation;
 use nom::bytes::complete::tag;
 use nom::bytes::complete::take_till1;
 use nom::character::complete::digit1;
@@ -68,6 +70,8 @@ pub struct DfCreateTable {
     pub name: ObjectName,
     pub columns: Vec<ColumnDef>,
     pub engine: String,
+    /// Expressions of `CLUSTER BY (...)`, empty if not specified
+    pub cluster_keys: Vec<Expr>,
     pub options: Vec<SqlOption>,
 }
 
@@ -87,6 +91,25 @@ pub struct DfTruncateTable {
     pub name: ObjectName,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct DfOptimizeTable {
+    pub name: ObjectName,
+    pub operation: Optimization,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DfAlterTableOperation {
+    AddColumn(ColumnDef),
+    DropColumn(Ident),
+    RenameColumn { old_name: Ident, new_name: Ident },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DfAlterTable {
+    pub name: ObjectName,
+    pub operation: DfAlterTableOperation,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct DfCreateDatabase {
     pub if_not_exists: bool,
@@ -110,6 +133,11 @@ pub struct DfKillStatement {
     pub object_id: Ident,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct DfTransaction {
+    pub operation: TransactionOperation,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct DfCreateUser {
     pub if_not_exists: bool,
@@ -160,6 +188,8 @@ pub enum DfStatement {
     DescribeTable(DfDescribeTable),
     DropTable(DfDropTable),
     TruncateTable(DfTruncateTable),
+    OptimizeTable(DfOptimizeTable),
+    AlterTable(DfAlterTable),
 
     // Settings.
     ShowSettings(DfShowSettings),
@@ -181,6 +211,9 @@ pub enum DfStatement {
 
     // Grant
     GrantPrivilege(DfGrantStatement),
+
+    // Transaction
+    Transaction(DfTransaction),
 }
 
 /// Comment hints from SQL.
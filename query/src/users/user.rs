@@ -54,6 +54,7 @@ impl From<&User> for UserInfo {
             auth_type: user.auth_type.clone(),
             privileges,
             quota,
+            scram_verifier: None,
         }
     }
 }
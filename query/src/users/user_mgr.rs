@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::net::IpAddr;
 use std::sync::Arc;
 
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_management::UserInfo;
 use common_management::UserMgr;
@@ -70,6 +72,25 @@ impl UserManager {
         }
     }
 
+    // Get the user whose hostname matches the IP of the client, the exact hostname (the IP, or
+    // "localhost" for the loopback addresses) is preferred to "%".
+    pub async fn get_user_by_address(&self, user: &str, client_ip: &str) -> Result<UserInfo> {
+        let mut hostnames = vec![];
+        if let Ok(ip) = client_ip.parse::<IpAddr>() {
+            hostnames.push(ip.to_string());
+            if ip.is_loopback() {
+                hostnames.push("localhost".to_string());
+            }
+        }
+        for hostname in hostnames {
+            match self.get_user(user, &hostname).await {
+                Err(e) if e.code() == ErrorCode::UnknownUser("").code() => continue,
+                res => return res,
+            }
+        }
+        self.get_user(user, "%").await
+    }
+
     // Auth the user and password for different Auth type.
     pub async fn auth_user(&self, user: UserInfo, info: CertifiedInfo) -> Result<bool> {
         match user.auth_type {
@@ -156,12 +177,14 @@ impl UserManager {
         hostname: &str,
         new_auth_type: Option<AuthType>,
         new_password: Option<Vec<u8>>,
+        new_scram_verifier: Option<String>,
     ) -> Result<Option<u64>> {
         let update_user = self.api_provider.update_user(
             username.to_string(),
             hostname.to_string(),
             new_password,
             new_auth_type,
+            new_scram_verifier,
             None,
         );
         match update_user.await {
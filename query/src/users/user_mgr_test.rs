@@ -67,6 +67,15 @@ async fn test_user_manager() -> Result<()> {
         assert_eq!(pwd.as_bytes(), user.password);
     }
 
+    // get user by the client address, "localhost" for the loopback addresses.
+    {
+        let user_info = user_mgr.get_user_by_address(user, "127.0.0.1").await?;
+        assert_eq!(hostname, user_info.hostname);
+
+        let user_info = user_mgr.get_user_by_address(user, "192.168.1.1").await?;
+        assert_eq!(hostname2, user_info.hostname);
+    }
+
     // drop.
     {
         user_mgr.drop_user(user, hostname).await?;
@@ -110,11 +119,13 @@ async fn test_user_manager() -> Result<()> {
                 hostname,
                 Some(AuthType::Sha256),
                 Some(Vec::from(new_pwd)),
+                Some("test1-verifier".to_string()),
             )
             .await?;
         let new_user = user_mgr.get_user(user, hostname).await?;
         assert_eq!(new_user.password, Vec::from(new_pwd));
         assert_eq!(new_user.auth_type, AuthType::Sha256);
+        assert_eq!(new_user.scram_verifier, Some("test1-verifier".to_string()));
 
         let new_new_pwd = "test2";
         user_mgr
@@ -123,6 +134,7 @@ async fn test_user_manager() -> Result<()> {
                 hostname,
                 Some(AuthType::Sha256),
                 Some(Vec::from(new_new_pwd)),
+                None,
             )
             .await?;
         let new_new_user = user_mgr.get_user(user, hostname).await?;
@@ -134,6 +146,7 @@ async fn test_user_manager() -> Result<()> {
                 hostname,
                 Some(AuthType::Sha256),
                 Some(Vec::from(new_new_pwd)),
+                None,
             )
             .await;
         // ErrorCode::UnknownUser
@@ -0,0 +1,5 @@
+select a.number, b.number from numbers_mt(5) as a join numbers_mt(3) as b on a.number = b.number + 1 order by a.number;
+select a.number, b.number from numbers_mt(4) as a left join numbers_mt(2) as b on a.number = b.number order by a.number;
+select a.number, b.number from numbers_mt(2) as a right join numbers_mt(3) as b on a.number = b.number order by b.number;
+select count(*) from numbers_mt(10) as a, numbers_mt(10) as b where a.number < b.number;
+select count(*) from numbers_mt(10) as a join numbers_mt(10) as b using (number);
@@ -0,0 +1,5 @@
+select number from numbers_mt(3) union all select number from numbers_mt(2) order by number;
+select number from numbers_mt(3) union select number from numbers_mt(2) order by number;
+select number from numbers_mt(5) intersect select number + 3 from numbers_mt(5) order by number;
+select number % 3 as n from numbers_mt(10) except select 1 order by n;
+select count(*) from (select number from numbers_mt(10) union all select number + 1 from numbers_mt(10));
@@ -0,0 +1,6 @@
+with t as (select sum(number) n from numbers_mt(1000)) select * from t;
+with t(n) as (select number from numbers_mt(5)) select n from t where n > 2 order by n;
+with t as (select number from numbers_mt(3)), s as (select number + 1 as number from t) select number from s order by number;
+with t as (select number from numbers_mt(3)) select a.number, b.number from t as a join t as b on a.number = b.number order by a.number;
+with t as (select number % 3 as n from numbers_mt(10)) select count(*) from t as a, t as b where a.n = b.n;
+with t as (select number from numbers_mt(2)) select number from t union all select number + 10 from t order by number;
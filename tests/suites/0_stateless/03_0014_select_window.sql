@@ -0,0 +1,6 @@
+select number, row_number() over (partition by number % 2 order by number desc) as rn from numbers_mt(6) order by number;
+select number % 3 as n, rank() over (order by number % 3) as r, dense_rank() over (order by number % 3) as d from numbers_mt(6) order by n;
+select number, lag(number, 1, 100) over (order by number) as lg, lead(number) over (order by number) as ld from numbers_mt(4) order by number;
+select number, sum(number) over (order by number rows between unbounded preceding and current row) as s, count(*) over (partition by number % 2) as c, max(number) over (order by number range between 1 preceding and 1 following) as m from numbers_mt(5) order by number;
+select number, first_value(number) over (order by number desc) as f, last_value(number) over (order by number desc) as l from numbers_mt(3) order by number;
+select number from numbers_mt(3) where row_number() over () > 1; -- {ErrorCode 5}
@@ -0,0 +1,16 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE IF NOT EXISTS t(a int, b varchar) Engine = fuse;
+INSERT INTO t(a,b) VALUES(1, 'v1'),(2, 'v2'),(3, 'v3');
+INSERT INTO t(a,b) VALUES(4, 'v4'),(5, 'v5');
+DELETE FROM t WHERE a = 2 OR a = 5;
+SELECT * FROM t ORDER BY a;
+DELETE FROM t WHERE a > 100;
+SELECT count(*) FROM t;
+DELETE FROM t;
+SELECT count(*) FROM t;
+
+DROP TABLE t;
+DROP DATABASE db1;
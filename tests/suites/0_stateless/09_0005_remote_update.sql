@@ -0,0 +1,19 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE IF NOT EXISTS t(a int, b varchar) Engine = fuse;
+INSERT INTO t(a,b) VALUES(1, 'v1'),(2, 'v2'),(3, 'v3');
+INSERT INTO t(a,b) VALUES(4, 'v4'),(5, 'v5');
+UPDATE t SET b = 'x' WHERE a = 2 OR a = 5;
+SELECT * FROM t ORDER BY a;
+UPDATE t SET a = a + 10, b = 'w' WHERE a > 3;
+SELECT * FROM t ORDER BY a;
+UPDATE t SET b = 'y' WHERE a > 100;
+SELECT count(*) FROM t WHERE b = 'y';
+UPDATE t SET b = 'z';
+SELECT count(*) FROM t WHERE b = 'z';
+UPDATE t SET c = 1; -- {ErrorCode 5}
+
+DROP TABLE t;
+DROP DATABASE db1;
@@ -0,0 +1,17 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE IF NOT EXISTS t(a int) Engine = fuse;
+SELECT * FROM t AT (TIMESTAMP => '2100-01-01 00:00:00'); -- {ErrorCode 58}
+INSERT INTO t VALUES(1),(2);
+INSERT INTO t VALUES(3);
+SELECT count(*) FROM t AT (TIMESTAMP => '2100-01-01 00:00:00');
+SELECT count(*) FROM db1.t AT (TIMESTAMP => '2100-01-01T00:00:00Z') AS x WHERE x.a > 1;
+SELECT * FROM t AT (TIMESTAMP => '2000-01-01 00:00:00'); -- {ErrorCode 58}
+SELECT * FROM t AT (SNAPSHOT => '00000000-0000-0000-0000-000000000000'); -- {ErrorCode 58}
+SELECT * FROM t AT (SNAPSHOT => 'not_a_snapshot'); -- {ErrorCode 6}
+SELECT * FROM t AT (TIMESTAMP => 'yesterday'); -- {ErrorCode 6}
+
+DROP TABLE t;
+DROP DATABASE db1;
@@ -0,0 +1,14 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE IF NOT EXISTS t(a int) Engine = fuse;
+SELECT count(*) FROM fuse_snapshot('db1', 't');
+INSERT INTO t VALUES(1),(2);
+INSERT INTO t VALUES(3);
+SELECT segment_count, block_count, row_count FROM fuse_snapshot('db1', 't') ORDER BY row_count;
+SELECT * FROM fuse_snapshot('system', 'one'); -- {ErrorCode 6}
+SELECT * FROM fuse_snapshot('db1'); -- {ErrorCode 6}
+
+DROP TABLE t;
+DROP DATABASE db1;
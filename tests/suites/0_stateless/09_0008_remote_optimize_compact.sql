@@ -0,0 +1,20 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE IF NOT EXISTS t(a int) Engine = fuse;
+INSERT INTO t VALUES(1),(2);
+INSERT INTO t VALUES(3);
+INSERT INTO t VALUES(4);
+OPTIMIZE TABLE t COMPACT;
+SELECT segment_count, block_count, row_count FROM fuse_snapshot('db1', 't') ORDER BY segment_count, row_count;
+SELECT sum(a) FROM t;
+OPTIMIZE TABLE t;
+SELECT count(*) FROM fuse_snapshot('db1', 't');
+
+CREATE TABLE IF NOT EXISTS m(a int) Engine = Memory;
+OPTIMIZE TABLE m COMPACT; -- {ErrorCode 2}
+
+DROP TABLE t;
+DROP TABLE m;
+DROP DATABASE db1;
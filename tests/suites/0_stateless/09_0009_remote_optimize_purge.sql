@@ -0,0 +1,20 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE IF NOT EXISTS t(a int) Engine = fuse;
+INSERT INTO t VALUES(1),(2);
+INSERT INTO t VALUES(3);
+INSERT INTO t VALUES(4);
+OPTIMIZE TABLE t PURGE;
+SELECT count(*) FROM fuse_snapshot('db1', 't');
+SELECT sum(a) FROM t;
+
+SET retention_snapshots = 2;
+INSERT INTO t VALUES(5);
+OPTIMIZE TABLE t ALL;
+SELECT segment_count, block_count, row_count FROM fuse_snapshot('db1', 't') ORDER BY row_count, segment_count;
+SELECT sum(a) FROM t;
+
+DROP TABLE t;
+DROP DATABASE db1;
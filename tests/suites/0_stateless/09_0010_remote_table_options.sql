@@ -0,0 +1,16 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE t(a int, b varchar) Engine = fuse compression = 'zstd' dictionary_columns = 'b' row_per_block = 2;
+INSERT INTO t VALUES(1, 'x'),(2, 'y'),(3, 'x');
+SELECT block_count, row_count FROM fuse_snapshot('db1', 't');
+SELECT a, b FROM t ORDER BY a;
+
+CREATE TABLE t1(a int) Engine = fuse compression = 'gzip'; -- {ErrorCode 22}
+CREATE TABLE t1(a int) Engine = fuse dictionary_columns = 'a'; -- {ErrorCode 22}
+CREATE TABLE t1(a int) Engine = fuse row_per_block = 0; -- {ErrorCode 22}
+CREATE TABLE t1(a int) Engine = fuse foo = 'bar'; -- {ErrorCode 22}
+
+DROP TABLE t;
+DROP DATABASE db1;
@@ -0,0 +1,22 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE t(a int, b varchar) Engine = fuse;
+INSERT INTO t VALUES(1, 'x'),(2, 'y');
+
+ALTER TABLE t ADD COLUMN c int DEFAULT 10;
+INSERT INTO t VALUES(3, 'z', 30);
+SELECT a, b, c FROM t ORDER BY a;
+SELECT a FROM t WHERE c > 20;
+
+ALTER TABLE t RENAME COLUMN b TO bb;
+ALTER TABLE t DROP COLUMN a;
+SELECT bb, c FROM t ORDER BY bb;
+
+ALTER TABLE t ADD COLUMN c int; -- {ErrorCode 6}
+ALTER TABLE t DROP COLUMN a; -- {ErrorCode 6}
+ALTER TABLE t RENAME COLUMN bb TO c; -- {ErrorCode 6}
+
+DROP TABLE t;
+DROP DATABASE db1;
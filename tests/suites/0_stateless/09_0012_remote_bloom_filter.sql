@@ -0,0 +1,19 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE t(id int, name varchar) Engine = fuse bloom_filter_columns = 'id,name';
+INSERT INTO t VALUES(1, 'a'),(100, 'b');
+INSERT INTO t VALUES(2, 'c'),(99, 'd');
+INSERT INTO t VALUES(3, NULL),(98, 'e');
+SELECT id, name FROM t WHERE id = 98;
+SELECT id, name FROM t WHERE id = 50;
+SELECT id, name FROM t WHERE name = 'c';
+SELECT id FROM t WHERE id IN (2, 3) ORDER BY id;
+SELECT id FROM t WHERE id NOT IN (1, 2, 3) ORDER BY id;
+
+CREATE TABLE t1(a boolean) Engine = fuse bloom_filter_columns = 'a'; -- {ErrorCode 22}
+CREATE TABLE t1(a int) Engine = fuse bloom_filter_columns = 'b'; -- {ErrorCode 22}
+
+DROP TABLE t;
+DROP DATABASE db1;
@@ -0,0 +1,21 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE t(a int, b int) Engine = fuse CLUSTER BY (a);
+INSERT INTO t VALUES(3, 1),(1, 2);
+INSERT INTO t VALUES(2, 3),(4, 4);
+INSERT INTO t VALUES(10, 5),(9, 6);
+SELECT block_count FROM fuse_snapshot('db1', 't') LIMIT 1;
+OPTIMIZE TABLE t RECLUSTER;
+SELECT block_count, row_count, average_overlaps, average_depth FROM fuse_snapshot('db1', 't') LIMIT 1;
+SELECT a, b FROM t ORDER BY a;
+
+CREATE TABLE t1(a int) Engine = fuse CLUSTER BY (b); -- {ErrorCode 22}
+CREATE TABLE t2(a int) Engine = Memory CLUSTER BY (a); -- {ErrorCode 5}
+CREATE TABLE t3(a int) Engine = fuse;
+OPTIMIZE TABLE t3 RECLUSTER; -- {ErrorCode 6}
+
+DROP TABLE t;
+DROP TABLE t3;
+DROP DATABASE db1;
@@ -0,0 +1,29 @@
+DROP DATABASE IF EXISTS db1;
+CREATE DATABASE db1;
+USE db1;
+
+CREATE TABLE t1(a int) Engine = fuse;
+CREATE TABLE t2(a int) Engine = fuse;
+
+BEGIN;
+INSERT INTO t1 VALUES(1),(2);
+INSERT INTO t2 VALUES(3);
+INSERT INTO t1 VALUES(4);
+SELECT COUNT(*) FROM t1;
+BEGIN; -- {ErrorCode 6}
+OPTIMIZE TABLE t1 PURGE; -- {ErrorCode 2}
+COMMIT;
+SELECT a FROM t1 ORDER BY a;
+SELECT a FROM t2 ORDER BY a;
+
+START TRANSACTION;
+INSERT INTO t1 VALUES(5);
+TRUNCATE TABLE t2;
+SELECT COUNT(*) FROM t2;
+ROLLBACK;
+SELECT COUNT(*) FROM t1;
+SELECT COUNT(*) FROM t2;
+
+DROP TABLE t1;
+DROP TABLE t2;
+DROP DATABASE db1;